@@ -0,0 +1,47 @@
+//! This file was generated by `ssz-rs-codegen`; do NOT manually edit.
+use ssz_rs::prelude::*;
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct SingleFieldTestStruct {
+    pub a: u8,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct SmallTestStruct {
+    pub a: u16,
+    pub b: u16,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct FixedTestStruct {
+    pub a: u8,
+    pub b: u64,
+    pub c: u32,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct VarTestStruct {
+    pub a: u16,
+    pub b: List<u16, 1024>,
+    pub c: u8,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct ComplexTestStruct {
+    pub a: u16,
+    pub b: List<u16, 128>,
+    pub c: u8,
+    pub d: List<u8, 256>,
+    pub e: VarTestStruct,
+    pub f: Vector<FixedTestStruct, 4>,
+    pub g: Vector<VarTestStruct, 2>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct BitsStruct {
+    pub a: Bitlist<5>,
+    pub b: Bitvector<2>,
+    pub c: Bitvector<1>,
+    pub d: Bitlist<6>,
+    pub e: Bitvector<8>,
+}
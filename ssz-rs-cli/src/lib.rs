@@ -0,0 +1,360 @@
+//! A command-line tool to convert, merkleize and prove SSZ data of types known at runtime.
+//!
+//! Types are given as type expressions of a [`Schema`], e.g. `List[Checkpoint, 16]`, which may
+//! refer to the types of a schema file passed with `--schema`, or to the types of a registry, e.g.
+//! the types derived in a Rust crate added with [`Schema::insert`]:
+//!
+//! ```no_run
+//! # use ssz_rs::prelude::*;
+//! #[derive(Debug, Default, SimpleSerialize)]
+//! struct Checkpoint {
+//!     epoch: u64,
+//!     root: Node,
+//! }
+//!
+//! let mut registry = Schema::default();
+//! registry.insert("Checkpoint", Checkpoint::type_info());
+//! let args = std::env::args_os();
+//! ssz_rs_cli::run(&registry, args, &mut std::io::stdin(), &mut std::io::stdout()).unwrap();
+//! ```
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::de::DeserializeSeed;
+use ssz_rs::{
+    proofs::Proof,
+    snappy::{self, SnappyError},
+    spec_serde::DynamicSeed,
+    DeserializeError, DynamicValue, GeneralizedIndex, HashTreeRoot, MerkleizationError, Node,
+    PathElement, Prove, Schema, SchemaError, Serialize, SerializeError, Spec, SszType,
+};
+use std::{
+    ffi::OsString,
+    fmt, fs,
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+/// An error running a command.
+#[derive(Debug)]
+pub enum Error {
+    /// The command line is not valid, or asks for help.
+    Usage(clap::Error),
+    Io(io::Error),
+    Schema(SchemaError),
+    Deserialize(DeserializeError),
+    Serialize(SerializeError),
+    Merkleization(MerkleizationError),
+    Json(serde_json::Error),
+    Yaml(serde_yaml::Error),
+    Snappy(SnappyError),
+    /// The input is not a valid value of the given type.
+    InvalidValue(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usage(err) => write!(f, "{err}"),
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Schema(err) => write!(f, "{err}"),
+            Self::Deserialize(err) => write!(f, "could not decode input: {err}"),
+            Self::Serialize(err) => write!(f, "could not encode value: {err}"),
+            Self::Merkleization(err) => write!(f, "{err}"),
+            Self::Json(err) => write!(f, "invalid JSON: {err}"),
+            Self::Yaml(err) => write!(f, "invalid YAML: {err}"),
+            Self::Snappy(err) => write!(f, "{err}"),
+            Self::InvalidValue(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+macro_rules! impl_from_error {
+    ($($variant:ident($err:ty)),*) => {
+        $(
+            impl From<$err> for Error {
+                fn from(err: $err) -> Self {
+                    Self::$variant(err)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_error!(
+    Usage(clap::Error),
+    Io(io::Error),
+    Schema(SchemaError),
+    Deserialize(DeserializeError),
+    Serialize(SerializeError),
+    Merkleization(MerkleizationError),
+    Json(serde_json::Error),
+    Yaml(serde_yaml::Error),
+    Snappy(SnappyError)
+);
+
+/// The encodings of data supported by the CLI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// SSZ bytes
+    Ssz,
+    /// SSZ bytes as `ssz_snappy`, i.e. a varint length prefix followed by snappy frames, as in the
+    /// p2p req/resp domain
+    Snappy,
+    /// SSZ bytes compressed as a single snappy block, as in gossip and the consensus spec tests
+    SnappyBlock,
+    /// Hex-encoded SSZ bytes
+    Hex,
+    /// JSON in the representation of the consensus specs, see `ssz_rs::spec_serde`
+    Json,
+    /// YAML in the representation of the consensus specs, see `ssz_rs::spec_serde`
+    Yaml,
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "ssz", about = "Convert, merkleize, prove and explain SSZ data")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Args)]
+struct TypeArgs {
+    /// A file of type definitions written like those of the Python consensus specs
+    #[arg(long)]
+    schema: Option<PathBuf>,
+    /// The type of the data, e.g. `uint64` or `List[Checkpoint, 16]`
+    #[arg(long = "type")]
+    ty: String,
+}
+
+#[derive(Debug, Args)]
+struct InputArgs {
+    /// The format of the input
+    #[arg(long, value_enum, default_value_t = Format::Ssz)]
+    from: Format,
+    /// The file to read, or stdin if missing
+    input: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Convert data between formats
+    Convert {
+        #[command(flatten)]
+        ty: TypeArgs,
+        #[command(flatten)]
+        input: InputArgs,
+        /// The format of the output
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        to: Format,
+    },
+    /// Print the hash tree root of data
+    Root {
+        #[command(flatten)]
+        ty: TypeArgs,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Print a JSON proof of the data at a path, e.g. `validators.23`
+    Prove {
+        #[command(flatten)]
+        ty: TypeArgs,
+        #[command(flatten)]
+        input: InputArgs,
+        /// The path to prove, with `__len__` for the length of a list or `__selector__` for the
+        /// selector of a union
+        #[arg(long)]
+        path: String,
+    },
+    /// Verify a JSON proof from `prove` against a trusted root
+    Verify {
+        #[command(flatten)]
+        ty: TypeArgs,
+        /// The proven path
+        #[arg(long)]
+        path: String,
+        /// The trusted hash tree root
+        #[arg(long)]
+        root: String,
+        /// The file of the proof, or stdin if missing
+        proof: Option<PathBuf>,
+    },
+    /// Print an annotated hex dump of SSZ bytes, showing where any invalid data fails to decode
+    Explain {
+        #[command(flatten)]
+        ty: TypeArgs,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+}
+
+/// Run the command given by `args`, whose first element is the name of the program, with the
+/// types of `registry`.
+pub fn run<I, T>(
+    registry: &Schema,
+    args: I,
+    stdin: &mut impl Read,
+    stdout: &mut impl Write,
+) -> Result<(), Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    match Cli::try_parse_from(args)?.command {
+        Command::Convert { ty, input, to } => {
+            let ty = resolve_type(registry, &ty)?;
+            let value = decode(&ty, input.from, read_input(input.input.as_ref(), stdin)?)?;
+            stdout.write_all(&encode(&value, to)?)?;
+        }
+        Command::Root { ty, input } => {
+            let ty = resolve_type(registry, &ty)?;
+            let value = decode(&ty, input.from, read_input(input.input.as_ref(), stdin)?)?;
+            writeln!(stdout, "{}", value.hash_tree_root()?)?;
+        }
+        Command::Prove { ty, input, path } => {
+            let ty = resolve_type(registry, &ty)?;
+            let value = decode(&ty, input.from, read_input(input.input.as_ref(), stdin)?)?;
+            let (proof, root) = value.prove(&parse_path(&path))?;
+            let proof = serde_json::json!({
+                "index": proof.index,
+                "leaf": proof.leaf.to_string(),
+                "branch": proof.branch.iter().map(Node::to_string).collect::<Vec<_>>(),
+                "root": root.to_string(),
+            });
+            writeln!(stdout, "{}", serde_json::to_string_pretty(&proof)?)?;
+        }
+        Command::Verify { ty, path, root, proof } => {
+            let ty = resolve_type(registry, &ty)?;
+            let index = ty.generalized_index(&parse_path(&path))?;
+            let root = parse_node(&root)?;
+            let proof: serde_json::Value =
+                serde_json::from_slice(&read_input(proof.as_ref(), stdin)?)?;
+            let proof = parse_proof(&proof)?;
+            if proof.index != index {
+                return Err(Error::InvalidValue(format!(
+                    "the proof is for the generalized index {}, not {index}",
+                    proof.index
+                )))
+            }
+            proof.verify(root)?;
+            writeln!(stdout, "valid proof of `{path}` against {root}")?;
+        }
+        Command::Explain { ty, input } => {
+            let ty = resolve_type(registry, &ty)?;
+            let data = read_input(input.input.as_ref(), stdin)?;
+            let encoding = read_encoding(&ty, input.from, data)?;
+            write!(stdout, "{}", ty.explain(&encoding))?;
+        }
+    }
+    Ok(())
+}
+
+// Resolve the given type against any schema file, or else the registry.
+fn resolve_type(registry: &Schema, args: &TypeArgs) -> Result<SszType, Error> {
+    let Some(path) = &args.schema else { return Ok(registry.parse_type(&args.ty)?) };
+    let mut schema = Schema::parse(&fs::read_to_string(path)?)?;
+    for (name, ty) in registry.types() {
+        if schema.get(name).is_none() {
+            schema.insert(name, ty.clone());
+        }
+    }
+    Ok(schema.parse_type(&args.ty)?)
+}
+
+fn read_input(path: Option<&PathBuf>, stdin: &mut impl Read) -> Result<Vec<u8>, Error> {
+    match path {
+        Some(path) => Ok(fs::read(path)?),
+        None => {
+            let mut input = vec![];
+            stdin.read_to_end(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+fn decode(ty: &SszType, format: Format, input: Vec<u8>) -> Result<DynamicValue, Error> {
+    match format {
+        Format::Json => {
+            let json = serde_json::from_slice::<serde_json::Value>(&input)?;
+            Ok(DynamicSeed(ty).deserialize(json)?)
+        }
+        Format::Yaml => {
+            let yaml = serde_yaml::from_slice::<serde_yaml::Value>(&input)?;
+            Ok(DynamicSeed(ty).deserialize(yaml)?)
+        }
+        format => Ok(ty.deserialize(&read_encoding(ty, format, input)?)?),
+    }
+}
+
+// Read the SSZ bytes of an `input` of type `ty` in the given binary `format`.
+fn read_encoding(ty: &SszType, format: Format, input: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (min, max) = (ty.min_encoded_len(), ty.max_encoded_len());
+    let encoding = match format {
+        Format::Ssz => input,
+        Format::Snappy => snappy::decompress_framed(&input, min, max)?,
+        Format::SnappyBlock => snappy::decompress_block(&input, min, max)?,
+        Format::Hex => {
+            let text = String::from_utf8_lossy(&input);
+            let text = text.trim();
+            hex::decode(text.strip_prefix("0x").unwrap_or(text))
+                .map_err(|err| Error::InvalidValue(format!("invalid hex: {err}")))?
+        }
+        Format::Json | Format::Yaml => {
+            return Err(Error::InvalidValue(format!("expected SSZ bytes, not {format:?}")))
+        }
+    };
+    Ok(encoding)
+}
+
+fn encode(value: &DynamicValue, format: Format) -> Result<Vec<u8>, Error> {
+    let mut encoding = vec![];
+    if matches!(format, Format::Ssz | Format::Snappy | Format::SnappyBlock | Format::Hex) {
+        value.serialize(&mut encoding)?;
+    }
+    let output = match format {
+        Format::Ssz => encoding,
+        Format::Snappy => snappy::compress_framed(&encoding)?,
+        Format::SnappyBlock => snappy::compress_block(&encoding)?,
+        Format::Hex => format!("0x{}\n", hex::encode(encoding)).into_bytes(),
+        Format::Json => {
+            let mut output = serde_json::to_vec_pretty(&Spec(value))?;
+            output.push(b'\n');
+            output
+        }
+        Format::Yaml => serde_yaml::to_string(&Spec(value))?.into_bytes(),
+    };
+    Ok(output)
+}
+
+// Parse a path like `validators.23.balance`, where numbers index into collections.
+fn parse_path(path: &str) -> Vec<PathElement> {
+    path.split('.')
+        .filter(|element| !element.is_empty())
+        .map(|element| match element {
+            "__len__" => PathElement::Length,
+            "__selector__" => PathElement::Selector,
+            element => match element.parse() {
+                Ok(index) => PathElement::Index(index),
+                Err(_) => PathElement::Field(element.to_string()),
+            },
+        })
+        .collect()
+}
+
+fn parse_node(node: &str) -> Result<Node, Error> {
+    node.parse().map_err(|_| Error::InvalidValue(format!("invalid root `{node}`")))
+}
+
+fn parse_proof(proof: &serde_json::Value) -> Result<Proof, Error> {
+    let invalid = || Error::InvalidValue(format!("invalid proof `{proof}`"));
+    let index = proof["index"].as_u64().ok_or_else(invalid)? as GeneralizedIndex;
+    let leaf = parse_node(proof["leaf"].as_str().ok_or_else(invalid)?)?;
+    let branch = proof["branch"]
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|node| parse_node(node.as_str().ok_or_else(invalid)?))
+        .collect::<Result<_, _>>()?;
+    Ok(Proof { leaf, branch, index })
+}
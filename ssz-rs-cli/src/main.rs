@@ -0,0 +1,29 @@
+//! The `ssz` command-line tool; see `ssz --help`.
+mod builtin;
+
+use ssz_rs::prelude::*;
+use std::{env, io, process};
+
+// Return the types available by name besides those of any schema file, i.e. the containers of
+// the `ssz_generic` consensus spec tests.
+fn registry() -> Schema {
+    let mut registry = Schema::default();
+    registry.insert("SingleFieldTestStruct", builtin::SingleFieldTestStruct::type_info());
+    registry.insert("SmallTestStruct", builtin::SmallTestStruct::type_info());
+    registry.insert("FixedTestStruct", builtin::FixedTestStruct::type_info());
+    registry.insert("VarTestStruct", builtin::VarTestStruct::type_info());
+    registry.insert("ComplexTestStruct", builtin::ComplexTestStruct::type_info());
+    registry.insert("BitsStruct", builtin::BitsStruct::type_info());
+    registry
+}
+
+fn main() {
+    match ssz_rs_cli::run(&registry(), env::args_os(), &mut io::stdin(), &mut io::stdout()) {
+        Ok(()) => {}
+        Err(ssz_rs_cli::Error::Usage(err)) => err.exit(),
+        Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(1);
+        }
+    }
+}
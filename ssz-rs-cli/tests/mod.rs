@@ -0,0 +1,162 @@
+use ssz_rs::{prelude::*, snappy::SnappyError};
+use ssz_rs_cli::{run, Error};
+use std::path::PathBuf;
+
+#[derive(Debug, Default, SimpleSerialize)]
+struct Checkpoint {
+    epoch: u64,
+    root: Node,
+}
+
+#[derive(Debug, Default, SimpleSerialize)]
+struct Votes {
+    aggregation_bits: Bitlist<16>,
+    targets: List<Checkpoint, 4>,
+    extra: Option<U256>,
+}
+
+fn registry() -> Schema {
+    let mut registry = Schema::default();
+    registry.insert("Checkpoint", Checkpoint::type_info());
+    registry.insert("Votes", Votes::type_info());
+    registry
+}
+
+fn run_with(args: &[&str], input: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut output = vec![];
+    let args = ["ssz"].iter().chain(args);
+    run(&registry(), args, &mut &input[..], &mut output)?;
+    Ok(output)
+}
+
+fn votes() -> Votes {
+    let aggregation_bits = Bitlist::try_from(&[true, false, true, true, false][..]).unwrap();
+    let targets = vec![
+        Checkpoint { epoch: 3, root: Node::repeat_byte(0xab) },
+        Checkpoint { epoch: 4, root: Node::ZERO },
+    ];
+    Votes {
+        aggregation_bits,
+        targets: targets.try_into().unwrap(),
+        extra: Some(U256::from(u128::MAX) + U256::from(1)),
+    }
+}
+
+#[test]
+fn test_convert() {
+    let value = votes();
+    let encoding = serialize(&value).unwrap();
+
+    let json = run_with(&["convert", "--type", "Votes"], &encoding).unwrap();
+    let expected = serde_json::json!({
+        "aggregation_bits": "0x2d",
+        "targets": [
+            { "epoch": "3", "root": format!("0x{}", "ab".repeat(32)) },
+            { "epoch": "4", "root": format!("0x{}", "00".repeat(32)) },
+        ],
+        "extra": { "selector": 1, "value": "340282366920938463463374607431768211456" },
+    });
+    assert_eq!(serde_json::from_slice::<serde_json::Value>(&json).unwrap(), expected);
+
+    let args = ["convert", "--type", "Votes", "--from", "json", "--to", "ssz"];
+    assert_eq!(run_with(&args, &json).unwrap(), encoding);
+
+    let yaml =
+        run_with(&["convert", "--type", "Votes", "--from", "json", "--to", "yaml"], &json).unwrap();
+    let hex =
+        run_with(&["convert", "--type", "Votes", "--from", "yaml", "--to", "hex"], &yaml).unwrap();
+    assert_eq!(String::from_utf8(hex.clone()).unwrap(), format!("0x{}\n", hex::encode(&encoding)));
+
+    for format in ["snappy", "snappy-block"] {
+        let args = ["convert", "--type", "Votes", "--from", "hex", "--to", format];
+        let compressed = run_with(&args, &hex).unwrap();
+        let args = ["convert", "--type", "Votes", "--from", format, "--to", "ssz"];
+        assert_eq!(run_with(&args, &compressed).unwrap(), encoding);
+    }
+
+    let args = ["convert", "--type", "List[uint16, 2]", "--from", "json", "--to", "hex"];
+    assert_eq!(run_with(&args, b"[1, 2]").unwrap(), b"0x01000200\n");
+    assert!(matches!(run_with(&args, b"[1, 2, 3]"), Err(Error::Json(..))));
+    assert!(matches!(run_with(&args, b"[1, \"65536\"]"), Err(Error::Json(..))));
+    assert!(matches!(run_with(&["root", "--type", "Checkpoint"], &[0u8; 39]), Err(_)));
+    assert!(matches!(run_with(&["root", "--type", "Nope"], &[]), Err(Error::Schema(..))));
+    assert!(matches!(run_with(&["frobnicate"], &[]), Err(Error::Usage(..))));
+}
+
+#[test]
+fn test_root_and_proofs() {
+    let value = votes();
+    let encoding = serialize(&value).unwrap();
+    let root = value.hash_tree_root().unwrap();
+
+    let output = run_with(&["root", "--type", "Votes"], &encoding).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), format!("{root}\n"));
+
+    let path = "targets.1.epoch";
+    let proof = run_with(&["prove", "--type", "Votes", "--path", path], &encoding).unwrap();
+    let (expected, _) = value.prove(&["targets".into(), 1.into(), "epoch".into()]).unwrap();
+    let json = serde_json::from_slice::<serde_json::Value>(&proof).unwrap();
+    assert_eq!(json["index"], expected.index);
+    assert_eq!(json["root"], root.to_string());
+
+    let root = root.to_string();
+    let args = ["verify", "--type", "Votes", "--path", path, "--root", &root];
+    assert!(run_with(&args, &proof).is_ok());
+    let args = ["verify", "--type", "Votes", "--path", "targets.0.epoch", "--root", &root];
+    assert!(matches!(run_with(&args, &proof), Err(Error::InvalidValue(..))));
+    let other_root = Node::ZERO.to_string();
+    let args = ["verify", "--type", "Votes", "--path", path, "--root", &other_root];
+    assert!(matches!(run_with(&args, &proof), Err(Error::Merkleization(..))));
+}
+
+#[test]
+fn test_explain() {
+    let mut encoding = serialize(&votes()).unwrap();
+    let output = run_with(&["explain", "--type", "Votes"], &encoding).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("     0..126    value: Container\n"));
+    assert!(output.contains("     4..8        targets: offset -> 13 | 0d 00 00 00\n"));
+    assert!(output.contains("    93..94         selector: 1 | 01\n"));
+    assert!(!output.contains("error"));
+
+    // NOTE: the offset of `targets` points before the offset of `aggregation_bits`
+    encoding[4] = 1;
+    let hex = format!("0x{}", hex::encode(&encoding));
+    let output =
+        run_with(&["explain", "--type", "Votes", "--from", "hex"], hex.as_bytes()).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.ends_with("error at byte 4: invalid offset points to byte 1 before byte 12\n"));
+
+    let args = ["explain", "--type", "Votes", "--from", "json"];
+    assert!(matches!(run_with(&args, b"{}"), Err(Error::InvalidValue(..))));
+}
+
+#[test]
+fn test_schema_file() {
+    let project_root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..");
+    let schema = project_root.join("ssz-rs-codegen/tests/data/schema.py");
+    let schema = schema.to_str().unwrap();
+
+    let checkpoint = Checkpoint { epoch: 7, root: Node::repeat_byte(1) };
+    let encoding = serialize(&checkpoint).unwrap();
+    let args = ["root", "--schema", schema, "--type", "Target"];
+    let output = run_with(&args, &encoding).unwrap();
+    let root = checkpoint.hash_tree_root().unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), format!("{root}\n"));
+
+    // NOTE: types of the registry are available along with those of the schema
+    let args = ["root", "--schema", schema, "--type", "List[Votes, MAX_ATTESTATIONS]"];
+    let encoding = serialize(&List::<Votes, 128>::default()).unwrap();
+    assert!(run_with(&args, &encoding).is_ok());
+
+    let data = project_root.join(
+        "ssz-rs/tests/data/containers/valid/ComplexTestStruct_random_0/serialized.ssz_snappy",
+    );
+    let args = ["root", "--schema", schema, "--type", "Target", "--from", "snappy-block"];
+    let args = args.iter().copied().chain([data.to_str().unwrap()]).collect::<Vec<_>>();
+    let result = run_with(&args, &[]);
+    assert!(matches!(
+        result,
+        Err(Error::Snappy(SnappyError::InvalidLength { min: 40, max: 40, .. }))
+    ));
+}
@@ -0,0 +1,292 @@
+//! Generate Rust types implementing `SimpleSerialize` from an SSZ [`Schema`].
+//!
+//! Each container, stable container and profile of the schema becomes a `struct`, and each union
+//! becomes an `enum`, deriving `SimpleSerialize`. Unions of the form `Union[None, T]` become
+//! `Option<T>`, and any other named type becomes a type alias. Bounds are inlined as const
+//! generics, e.g. `List[uint64, MAX]` becomes `List<u64, 128>` given `MAX = 128`.
+//!
+//! ```
+//! # use ssz_rs::Schema;
+//! let schema = Schema::parse(
+//!     r#"
+//! class Checkpoint(Container):
+//!     epoch: uint64
+//!     root: Bytes32
+//! "#,
+//! )
+//! .unwrap();
+//! let source = ssz_rs_codegen::generate(&schema);
+//! assert!(source.contains("pub struct Checkpoint {\n    pub epoch: u64,"));
+//! ```
+use convert_case::{Case, Casing};
+use ssz_rs::{ProfileField, Schema, SszType};
+use std::collections::HashSet;
+
+/// The preamble of a module of generated types.
+pub const PREAMBLE: &str = "//! This file was generated by `ssz-rs-codegen`; do NOT manually edit.
+use ssz_rs::prelude::*;
+";
+
+const DERIVES: &str = "PartialEq, Eq, Debug, Default, Clone, SimpleSerialize";
+const DERIVES_WITHOUT_DEFAULT: &str = "PartialEq, Eq, Debug, Clone, SimpleSerialize";
+
+// NOTE: keywords that may also name a field in a schema
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type", "unsafe",
+    "use", "where", "while", "yield",
+];
+
+/// Generate the Rust source of the types in `schema`, in the order of their definition.
+///
+/// The source refers to items in `ssz_rs::prelude`; see [`PREAMBLE`] to generate a complete module.
+pub fn generate(schema: &Schema) -> String {
+    let mut generator = Generator { schema, items: vec![] };
+    for (name, ty) in schema.types() {
+        generator.generate_definition(name, ty);
+    }
+    generator.items.concat()
+}
+
+struct Generator<'a> {
+    schema: &'a Schema,
+    items: Vec<String>,
+}
+
+impl<'a> Generator<'a> {
+    // Return the name of the first definition of the composite type `ty`, if any.
+    //
+    // NOTE: a schema only records the structure of a type, so structurally equal types share the
+    // first name defined for them.
+    fn name_of(&self, ty: &SszType) -> Option<&'a str> {
+        if !needs_definition(ty) {
+            return None
+        }
+        self.schema.types().find(|(_, other)| *other == ty).map(|(name, _)| name)
+    }
+
+    fn generate_definition(&mut self, name: &str, ty: &SszType) {
+        match self.name_of(ty) {
+            Some(first_name) if first_name == name => self.generate_item(name, ty),
+            Some(first_name) => self.items.push(format!("\npub type {name} = {first_name};\n")),
+            None => {
+                let rust_type = self.rust_type(ty, name);
+                self.items.push(format!("\npub type {name} = {rust_type};\n"));
+            }
+        }
+    }
+
+    // Generate the `struct` or `enum` named `name` for the composite type `ty`, followed by the
+    // definitions of any unions declared inline in `ty`.
+    fn generate_item(&mut self, name: &str, ty: &SszType) {
+        let position = self.items.len();
+        let (attribute, body) = match ty {
+            SszType::Container(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(field_name, ty)| (field_name, self.field_type(ty, name, field_name)))
+                    .collect::<Vec<_>>();
+                (None, struct_body(name, &fields))
+            }
+            SszType::StableContainer(max_fields, fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(field_name, ty)| {
+                        (field_name, format!("Option<{}>", self.field_type(ty, name, field_name)))
+                    })
+                    .collect::<Vec<_>>();
+                let attribute = format!("stable_container(max_fields = {max_fields})");
+                (Some(attribute), struct_body(name, &fields))
+            }
+            SszType::Profile(max_fields, fields) => {
+                let base = self.profile_base(*max_fields, fields);
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        let rust_type = self.field_type(&field.ty, name, &field.name);
+                        let rust_type =
+                            if field.optional { format!("Option<{rust_type}>") } else { rust_type };
+                        (&field.name, rust_type)
+                    })
+                    .collect::<Vec<_>>();
+                (Some(format!("profile({base})")), struct_body(name, &fields))
+            }
+            SszType::Union(variants) => {
+                let names = self.variant_names(variants);
+                let is_compatible =
+                    variants.iter().enumerate().any(|(i, (selector, _))| *selector as usize != i);
+                let mut body = format!("pub enum {name} {{\n");
+                for ((selector, variant_type), variant_name) in variants.iter().zip(names) {
+                    match variant_type {
+                        None => body.push_str("    #[default]\n    None,\n"),
+                        Some(variant_type) => {
+                            let rust_type = self.field_type(variant_type, name, &variant_name);
+                            if is_compatible {
+                                body.push_str(&format!("    #[ssz(selector = {selector})]\n"));
+                            }
+                            body.push_str(&format!("    {variant_name}({rust_type}),\n"));
+                        }
+                    }
+                }
+                body.push_str("}\n");
+                (None, body)
+            }
+            _ => unreachable!("only composite types are defined as items"),
+        };
+        let derives = if has_default(ty) { DERIVES } else { DERIVES_WITHOUT_DEFAULT };
+        let mut item = format!("\n#[derive({derives})]\n");
+        if let Some(attribute) = attribute {
+            item.push_str(&format!("#[ssz({attribute})]\n"));
+        }
+        item.push_str(&body);
+        self.items.insert(position, item);
+    }
+
+    // Return the Rust type of the field `field_name` of the item `parent`, generating an `enum`
+    // named after both if the field is a union without a name.
+    fn field_type(&mut self, ty: &SszType, parent: &str, field_name: &str) -> String {
+        let context = format!("{parent}{}", field_name.to_case(Case::UpperCamel));
+        self.rust_type(ty, &context)
+    }
+
+    // Return the Rust type of `ty`, where `context` names any union declared inline in `ty`.
+    fn rust_type(&mut self, ty: &SszType, context: &str) -> String {
+        match ty {
+            SszType::Uint(256) => "U256".to_string(),
+            SszType::Uint(bits) => format!("u{bits}"),
+            SszType::Boolean => "bool".to_string(),
+            SszType::Vector(element_type, len) => {
+                format!("Vector<{}, {len}>", self.rust_type(element_type, context))
+            }
+            SszType::List(element_type, limit) => {
+                format!("List<{}, {limit}>", self.rust_type(element_type, context))
+            }
+            SszType::ProgressiveList(element_type) => {
+                format!("ProgressiveList<{}>", self.rust_type(element_type, context))
+            }
+            SszType::Bitvector(len) => format!("Bitvector<{len}>"),
+            SszType::Bitlist(limit) => format!("Bitlist<{limit}>"),
+            SszType::Union(variants) if option_type(variants).is_some() => {
+                let inner = option_type(variants).expect("is option");
+                format!("Option<{}>", self.rust_type(inner, context))
+            }
+            ty => match self.name_of(ty) {
+                Some(name) => name.to_string(),
+                // NOTE: only unions may be declared without a name in a schema
+                None => {
+                    self.generate_item(context, ty);
+                    context.to_string()
+                }
+            },
+        }
+    }
+
+    // Return the name of the stable container that is the base of the given profile.
+    fn profile_base(&self, max_fields: usize, fields: &[ProfileField]) -> &'a str {
+        self.schema
+            .types()
+            .find(|(_, ty)| match ty {
+                SszType::StableContainer(base_max_fields, base_fields) => {
+                    *base_max_fields == max_fields &&
+                        fields.iter().all(|field| {
+                            matches!(
+                                base_fields.get(field.index),
+                                Some((base_name, base_type))
+                                    if *base_name == field.name && *base_type == field.ty
+                            )
+                        })
+                }
+                _ => false,
+            })
+            .map(|(base, _)| base)
+            .expect("the base of a profile is defined in its schema")
+    }
+
+    // Name each variant of a union after its type, disambiguating duplicates with their selector.
+    fn variant_names(&self, variants: &[(u8, Option<SszType>)]) -> Vec<String> {
+        let names = variants
+            .iter()
+            .map(|(_, ty)| match ty {
+                None => "None".to_string(),
+                Some(ty) => match self.name_of(ty) {
+                    Some(name) => name.to_string(),
+                    None => match ty {
+                        SszType::Uint(bits) => format!("Uint{bits}"),
+                        SszType::Boolean => "Boolean".to_string(),
+                        SszType::Vector(..) => "Vector".to_string(),
+                        SszType::List(..) => "List".to_string(),
+                        SszType::ProgressiveList(..) => "ProgressiveList".to_string(),
+                        SszType::Bitvector(..) => "Bitvector".to_string(),
+                        SszType::Bitlist(..) => "Bitlist".to_string(),
+                        _ => "Union".to_string(),
+                    },
+                },
+            })
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        let duplicates = names
+            .iter()
+            .filter(|name| !seen.insert(name.as_str()))
+            .cloned()
+            .collect::<HashSet<_>>();
+        names
+            .into_iter()
+            .zip(variants)
+            .map(
+                |(name, (selector, _))| {
+                    if duplicates.contains(&name) {
+                        format!("{name}{selector}")
+                    } else {
+                        name
+                    }
+                },
+            )
+            .collect()
+    }
+}
+
+// Return if the Rust type of `ty` implements `Default`.
+fn has_default(ty: &SszType) -> bool {
+    match ty {
+        SszType::Vector(element_type, _) => has_default(element_type),
+        SszType::Container(fields) => fields.iter().all(|(_, ty)| has_default(ty)),
+        SszType::Profile(_, fields) => {
+            fields.iter().all(|field| field.optional || has_default(&field.ty))
+        }
+        // NOTE: only unions with a `None` variant have a default value
+        SszType::Union(variants) => variants.iter().any(|(_, ty)| ty.is_none()),
+        _ => true,
+    }
+}
+
+// Return if `ty` is defined as a `struct` or `enum`.
+fn needs_definition(ty: &SszType) -> bool {
+    match ty {
+        SszType::Container(..) | SszType::StableContainer(..) | SszType::Profile(..) => true,
+        SszType::Union(variants) => option_type(variants).is_none(),
+        _ => false,
+    }
+}
+
+// Return `T` if the given union is `Union[None, T]`.
+fn option_type(variants: &[(u8, Option<SszType>)]) -> Option<&SszType> {
+    match variants {
+        [(0, None), (1, Some(ty))] => Some(ty),
+        _ => None,
+    }
+}
+
+fn struct_body(name: &str, fields: &[(&String, String)]) -> String {
+    let mut body = format!("pub struct {name} {{\n");
+    for (field_name, rust_type) in fields {
+        let field_name = if KEYWORDS.contains(&field_name.as_str()) {
+            format!("r#{field_name}")
+        } else {
+            field_name.to_string()
+        };
+        body.push_str(&format!("    pub {field_name}: {rust_type},\n"));
+    }
+    body.push_str("}\n");
+    body
+}
@@ -0,0 +1,31 @@
+//! Generate a Rust module of the types in an SSZ schema file.
+//!
+//! Usage: `ssz-rs-codegen <SCHEMA> [OUTPUT]`, writing to stdout if no `OUTPUT` is given.
+use ssz_rs::Schema;
+use std::{env, fs, process};
+
+fn run(schema_path: &str, output_path: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Schema::parse(&fs::read_to_string(schema_path)?)?;
+    let source = format!("{}{}", ssz_rs_codegen::PREAMBLE, ssz_rs_codegen::generate(&schema));
+    match output_path {
+        Some(path) => fs::write(path, source)?,
+        None => print!("{source}"),
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let (schema_path, output_path) = match args.as_slice() {
+        [schema_path] => (schema_path, None),
+        [schema_path, output_path] => (schema_path, Some(output_path.as_str())),
+        _ => {
+            eprintln!("usage: ssz-rs-codegen <SCHEMA> [OUTPUT]");
+            process::exit(2);
+        }
+    };
+    if let Err(err) = run(schema_path, output_path) {
+        eprintln!("error: {err}");
+        process::exit(1);
+    }
+}
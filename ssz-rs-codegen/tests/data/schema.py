@@ -0,0 +1,33 @@
+MAX_VALIDATORS_PER_COMMITTEE = 2**11
+MAX_ATTESTATIONS = 2**7
+
+Epoch = uint64
+Root = Bytes32
+
+class Checkpoint(Container):
+    epoch: Epoch
+    root: Root
+
+class Attestation(Container):
+    aggregation_bits: Bitlist[MAX_VALIDATORS_PER_COMMITTEE]
+    target: Checkpoint
+    signature: Bytes96
+
+Target = Checkpoint
+Payload = Union[Checkpoint, List[uint8, 32], List[uint16, 16]]
+
+class Block(Container):
+    attestations: List[Attestation, MAX_ATTESTATIONS]
+    extra: Union[None, uint16, Checkpoint]
+    payload: Payload
+    type: Union[None, uint8]
+    history: ProgressiveList[Root]
+
+class Shape(StableContainer[4]):
+    side: Optional[uint16]
+    color: Optional[uint8]
+    radius: Optional[uint16]
+
+class Circle(Profile[Shape]):
+    color: Optional[uint8]
+    radius: uint16
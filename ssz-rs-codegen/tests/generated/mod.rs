@@ -0,0 +1,60 @@
+//! This file was generated by `ssz-rs-codegen`; do NOT manually edit.
+use ssz_rs::prelude::*;
+
+pub type Epoch = u64;
+
+pub type Root = Vector<u8, 32>;
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct Checkpoint {
+    pub epoch: u64,
+    pub root: Vector<u8, 32>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct Attestation {
+    pub aggregation_bits: Bitlist<2048>,
+    pub target: Checkpoint,
+    pub signature: Vector<u8, 96>,
+}
+
+pub type Target = Checkpoint;
+
+#[derive(PartialEq, Eq, Debug, Clone, SimpleSerialize)]
+pub enum Payload {
+    Checkpoint(Checkpoint),
+    List1(List<u8, 32>),
+    List2(List<u16, 16>),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, SimpleSerialize)]
+pub struct Block {
+    pub attestations: List<Attestation, 128>,
+    pub extra: BlockExtra,
+    pub payload: Payload,
+    pub r#type: Option<u8>,
+    pub history: ProgressiveList<Vector<u8, 32>>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub enum BlockExtra {
+    #[default]
+    None,
+    Uint16(u16),
+    Checkpoint(Checkpoint),
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+#[ssz(stable_container(max_fields = 4))]
+pub struct Shape {
+    pub side: Option<u16>,
+    pub color: Option<u8>,
+    pub radius: Option<u16>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+#[ssz(profile(Shape))]
+pub struct Circle {
+    pub color: Option<u8>,
+    pub radius: u16,
+}
@@ -0,0 +1,45 @@
+mod generated;
+
+use generated::*;
+use ssz_rs::prelude::*;
+
+const SCHEMA: &str = include_str!("data/schema.py");
+
+#[test]
+fn test_generated_source_is_current() {
+    let schema = Schema::parse(SCHEMA).unwrap();
+    let source = ssz_rs_codegen::PREAMBLE.to_string() + &ssz_rs_codegen::generate(&schema);
+    assert_eq!(source, include_str!("generated/mod.rs"));
+}
+
+#[test]
+fn test_generated_types_match_schema() {
+    let schema = Schema::parse(SCHEMA).unwrap();
+    let expected = [
+        ("Epoch", Epoch::type_info()),
+        ("Root", Root::type_info()),
+        ("Checkpoint", Checkpoint::type_info()),
+        ("Attestation", Attestation::type_info()),
+        ("Target", Target::type_info()),
+        ("Payload", Payload::type_info()),
+        ("Block", Block::type_info()),
+        ("Shape", Shape::type_info()),
+        ("Circle", Circle::type_info()),
+    ];
+    for (name, ty) in &expected {
+        assert_eq!(schema.get(name).unwrap(), ty, "{name}");
+    }
+    assert_eq!(schema.types().count(), expected.len());
+
+    let value = Block {
+        attestations: Default::default(),
+        extra: BlockExtra::Uint16(7),
+        payload: Payload::Checkpoint(Checkpoint { epoch: 3, ..Default::default() }),
+        r#type: Some(1),
+        history: Default::default(),
+    };
+    let encoding = serialize(&value).unwrap();
+    let dynamic_value = schema.get("Block").unwrap().deserialize(&encoding).unwrap();
+    assert_eq!(dynamic_value.hash_tree_root().unwrap(), value.hash_tree_root().unwrap());
+    assert_eq!(Block::deserialize(&encoding).unwrap(), value);
+}
@@ -4,13 +4,38 @@
 //!
 //! Refer to the `examples` in the `ssz_rs` crate for a better idea on how to use this derive macro.
 //!
-//! This proc macro supports one attribute `ssz(transparent)` to pass through calls on a wrapping
+//! This proc macro supports the attribute `ssz(transparent)` to pass through calls on a wrapping
 //! Rust enum to the underlying data. Refers to this crate's tests for example usage.
+//!
+//! The attributes `ssz(stable_container(max_fields = N))` and `ssz(profile(Base))` derive the
+//! `StableContainer[N]` and `Profile[Base]` types of EIP-7495 for a Rust struct. Every field of a
+//! stable container is an `Option`; the fields of a profile are a subset of the fields of `Base`
+//! (with the same names and in the same order) where optional fields are `Option`s.
+//!
+//! The attribute `ssz(selector = N)` on every variant of a Rust enum derives a "compatible" union,
+//! where each variant has an explicit selector in `1..=127` that need not be contiguous, so the
+//! selector of a variant can remain stable as other variants are added or removed.
+//!
+//! The attribute `ssz(serde = "spec")` on a struct or enum deriving `SimpleSerialize` (or
+//! `Serializable`) also implements `serde::Serialize` and `serde::Deserialize` in the
+//! representation of the consensus specs and the beacon API, given by `ssz_rs::spec_serde`. It can
+//! be combined with the other attributes, e.g. `ssz(stable_container(max_fields = N), serde =
+//! "spec")`, and requires the `serde` feature of `ssz_rs`.
+//!
+//! Deriving `SimpleSerialize` also derives `SszTypeInfo`, describing the SSZ type of the item at
+//! runtime; this is not supported for `ssz(transparent)` enums, which have no single SSZ type.
+mod spec_serde;
+mod stable_container;
+
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote, quote_spanned};
+use spec_serde::derive_spec_serde_impl;
+use stable_container::StableLayout;
+use std::collections::HashSet;
 use syn::{
-    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Field, Fields, Generics,
-    Ident, Meta, NestedMeta, PathArguments,
+    ext::IdentExt, parse_macro_input, parse_quote, spanned::Spanned, Attribute, Data, DataEnum,
+    DeriveInput, Field, Fields, Generics, Ident, Lit, Meta, NestedMeta, PathArguments, Variant,
+    Visibility,
 };
 
 // NOTE: copied here from `ssz_rs` crate as it is unlikely to change
@@ -32,54 +57,136 @@ fn derive_serialize_impl(data: &Data, helper_attr: Option<&HelperAttr>) -> Token
                         fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, ssz_rs::SerializeError> {
                                 self.0.serialize(buffer)
                         }
+
+                        fn serialize_into<__S: ssz_rs::Sink + ?Sized>(&self, sink: &mut __S) -> Result<usize, ssz_rs::SerializeError> {
+                                self.0.serialize_into(sink)
+                        }
+
+                        fn encoded_len(&self) -> usize {
+                                self.0.encoded_len()
+                        }
                     }
                 }
                 _ => unimplemented!(
                     "this type of struct is currently not supported by this derive macro"
                 ),
             };
-            let serialization_by_field = fields.iter().map(|f| match &f.ident {
+            let fixed_len_by_field = fields.iter().map(|f| {
+                let field_type = &f.ty;
+                quote_spanned! { f.span() =>
+                    fixed_len += ssz_rs::__internal::fixed_part_len::<#field_type>();
+                }
+            });
+            let field_count = fields.len();
+            let encoded_len_of_field = fields.iter().map(|f| match &f.ident {
+                Some(field_name) => quote_spanned! { f.span() =>
+                    ssz_rs::Serialize::encoded_len(&self.#field_name)
+                },
+                None => panic!("should have already returned an impl"),
+            });
+            let part_len_by_field = fields.iter().enumerate().map(|(i, f)| {
+                let field_type = &f.ty;
+                quote_spanned! { f.span() =>
+                    encoded_len += ssz_rs::__internal::part_len::<#field_type>(encoded_lens[#i]);
+                }
+            });
+            let fixed_part_by_field = fields.iter().enumerate().map(|(i, f)| match &f.ident {
+                Some(field_name) => quote_spanned! { f.span() =>
+                    serializer.write_fixed_with_len(&self.#field_name, encoded_lens[#i])?;
+                },
+                None => panic!("should have already returned an impl"),
+            });
+            let variable_part_by_field = fields.iter().map(|f| match &f.ident {
                 Some(field_name) => quote_spanned! { f.span() =>
-                    serializer.with_element(&self.#field_name)?;
+                    serializer.write_variable(&self.#field_name)?;
+                },
+                None => panic!("should have already returned an impl"),
+            });
+            let encoded_len_by_field = fields.iter().map(|f| match &f.ident {
+                Some(field_name) => quote_spanned! { f.span() =>
+                    encoded_len += ssz_rs::__internal::element_encoded_len(&self.#field_name);
                 },
                 None => panic!("should have already returned an impl"),
             });
 
             quote! {
                 fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, ssz_rs::SerializeError> {
-                    let mut serializer = ssz_rs::__internal::Serializer::default();
+                    ssz_rs::__internal::serialize_reserved(self, buffer)
+                }
+
+                fn serialize_into<__S: ssz_rs::Sink + ?Sized>(&self, sink: &mut __S) -> Result<usize, ssz_rs::SerializeError> {
+                    let mut fixed_len = 0;
+                    #(#fixed_len_by_field)*
+
+                    // NOTE: the length of each field is only computed once
+                    let encoded_lens: [usize; #field_count] = [#(#encoded_len_of_field),*];
+                    let mut encoded_len = 0;
+                    #(#part_len_by_field)*
 
-                    #(#serialization_by_field)*
+                    let mut serializer = ssz_rs::__internal::StreamSerializer::new(sink, fixed_len, encoded_len)?;
 
-                    serializer.serialize(buffer)
+                    #(#fixed_part_by_field)*
+                    #(#variable_part_by_field)*
+
+                    serializer.finish()
+                }
+
+                fn encoded_len(&self) -> usize {
+                    let mut encoded_len = 0;
+                    #(#encoded_len_by_field)*
+                    encoded_len
                 }
             }
         }
         Data::Enum(ref data) => {
-            let serialization_by_variant = data.variants.iter().enumerate().map(|(i, variant)| {
+            let selectors = variant_selectors(data);
+            let streaming_by_variant =
+                data.variants.iter().zip(&selectors).map(|(variant, selector)| {
+                    let variant_name = &variant.ident;
+                    match &variant.fields {
+                        Fields::Unnamed(..) => {
+                            if matches!(helper_attr, Some(&HelperAttr::Transparent)) {
+                                quote_spanned! { variant.span() =>
+                                    Self::#variant_name(value) => value.serialize_into(sink),
+                                }
+                            } else {
+                                quote_spanned! { variant.span() =>
+                                    Self::#variant_name(value) => {
+                                        let selector = #selector;
+                                        let selector_bytes = selector.serialize_into(sink)?;
+                                        let value_bytes  = value.serialize_into(sink)?;
+                                        Ok(selector_bytes + value_bytes)
+                                    }
+                                }
+                            }
+                        }
+                        Fields::Unit => {
+                            quote_spanned! { variant.span() =>
+                                Self::None => {
+                                    0u8.serialize_into(sink)
+                                }
+                            }
+                        }
+                        _ => unreachable!(),
+                    }
+                });
+            let encoded_len_by_variant = data.variants.iter().map(|variant| {
                 let variant_name = &variant.ident;
                 match &variant.fields {
                     Fields::Unnamed(..) => {
                         if matches!(helper_attr, Some(&HelperAttr::Transparent)) {
                             quote_spanned! { variant.span() =>
-                                Self::#variant_name(value) => value.serialize(buffer),
+                                Self::#variant_name(value) => value.encoded_len(),
                             }
                         } else {
                             quote_spanned! { variant.span() =>
-                                Self::#variant_name(value) => {
-                                    let selector = #i as u8;
-                                    let selector_bytes = selector.serialize(buffer)?;
-                                    let value_bytes  = value.serialize(buffer)?;
-                                    Ok(selector_bytes + value_bytes)
-                                }
+                                Self::#variant_name(value) => 1 + value.encoded_len(),
                             }
                         }
                     }
                     Fields::Unit => {
                         quote_spanned! { variant.span() =>
-                            Self::None => {
-                                0u8.serialize(buffer)
-                            }
+                            Self::None => 1,
                         }
                     }
                     _ => unreachable!(),
@@ -88,8 +195,18 @@ fn derive_serialize_impl(data: &Data, helper_attr: Option<&HelperAttr>) -> Token
 
             quote! {
                 fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, ssz_rs::SerializeError> {
+                    ssz_rs::__internal::serialize_reserved(self, buffer)
+                }
+
+                fn serialize_into<__S: ssz_rs::Sink + ?Sized>(&self, sink: &mut __S) -> Result<usize, ssz_rs::SerializeError> {
                     match self {
-                        #(#serialization_by_variant)*
+                        #(#streaming_by_variant)*
+                    }
+                }
+
+                fn encoded_len(&self) -> usize {
+                    match self {
+                        #(#encoded_len_by_variant)*
                     }
                 }
             }
@@ -115,6 +232,16 @@ fn derive_deserialize_impl(data: &Data, helper_attr: Option<&HelperAttr>) -> Tok
                             let result = <#field_type>::deserialize(&encoding)?;
                             Ok(Self(result))
                         }
+
+                        ssz_rs::__internal::std_only! {
+                            fn deserialize_reader<__R: ssz_rs::__internal::Read>(
+                                reader: &mut ssz_rs::Reader<__R>,
+                                len: Option<usize>,
+                            ) -> Result<Self, ssz_rs::DeserializeError> {
+                                let result = <#field_type as ssz_rs::Deserialize>::deserialize_reader(reader, len)?;
+                                Ok(Self(result))
+                            }
+                        }
                     }
                 }
                 _ => unimplemented!(
@@ -124,9 +251,12 @@ fn derive_deserialize_impl(data: &Data, helper_attr: Option<&HelperAttr>) -> Tok
             let deserialization_by_field = fields.iter().map(|f| {
                 let field_type = &f.ty;
                 match &f.ident {
-                    Some(_) => quote_spanned! { f.span() =>
-                        deserializer.parse::<#field_type>(encoding)?;
-                    },
+                    Some(field_name) => {
+                        let field_name = field_name.unraw().to_string();
+                        quote_spanned! { f.span() =>
+                            deserializer.parse::<#field_type>(encoding).map_err(|err| err.at(#field_name))?;
+                        }
+                    }
                     None => panic!("should have already returned an impl"),
                 }
             });
@@ -134,9 +264,40 @@ fn derive_deserialize_impl(data: &Data, helper_attr: Option<&HelperAttr>) -> Tok
             let initialization_by_field = fields.iter().enumerate().map(|(i, f)| {
                 let field_type = &f.ty;
                 match &f.ident {
-                    Some(field_name) => quote_spanned! { f.span() =>
-                        #field_name: <#field_type>::deserialize(&encoding[spans[2*#i]..spans[2*#i+1]])?,
-                    },
+                    Some(field_name) => {
+                        let name = field_name.unraw().to_string();
+                        quote_spanned! { f.span() =>
+                            #field_name: <#field_type>::deserialize(&encoding[spans[2*#i]..spans[2*#i+1]]).map_err(|err| err.at(#name))?,
+                        }
+                    }
+                    None => panic!("should have already returned an impl"),
+                }
+            });
+
+            let fixed_len_by_field = fields.iter().map(|f| {
+                let field_type = &f.ty;
+                quote_spanned! { f.span() =>
+                    fixed_len += ssz_rs::__internal::fixed_part_len::<#field_type>();
+                }
+            });
+
+            let stream_parse_by_field = fields.iter().map(|f| {
+                let field_type = &f.ty;
+                let field_name = f.ident.as_ref().expect("fields are named").unraw().to_string();
+                quote_spanned! { f.span() =>
+                    deserializer.parse::<#field_type>().map_err(|err| err.at(#field_name))?;
+                }
+            });
+
+            let stream_initialization_by_field = fields.iter().map(|f| {
+                let field_type = &f.ty;
+                match &f.ident {
+                    Some(field_name) => {
+                        let name = field_name.unraw().to_string();
+                        quote_spanned! { f.span() =>
+                            #field_name: deserializer.read::<#field_type>().map_err(|err| err.at(#name))?,
+                        }
+                    }
                     None => panic!("should have already returned an impl"),
                 }
             });
@@ -153,10 +314,30 @@ fn derive_deserialize_impl(data: &Data, helper_attr: Option<&HelperAttr>) -> Tok
                         #(#initialization_by_field)*
                     })
                 }
+
+                ssz_rs::__internal::std_only! {
+                    fn deserialize_reader<__R: ssz_rs::__internal::Read>(
+                        reader: &mut ssz_rs::Reader<__R>,
+                        len: Option<usize>,
+                    ) -> Result<Self, ssz_rs::DeserializeError> {
+                        let mut fixed_len = 0;
+                        #(#fixed_len_by_field)*
+
+                        let mut deserializer = ssz_rs::__internal::StreamDeserializer::new(reader, fixed_len, len)?;
+
+                        #(#stream_parse_by_field)*
+
+                        let result = Self {
+                            #(#stream_initialization_by_field)*
+                        };
+                        deserializer.finish()?;
+                        Ok(result)
+                    }
+                }
             }
         }
         Data::Enum(ref data) => {
-            let body = if matches!(helper_attr, Some(&HelperAttr::Transparent)) {
+            let (body, stream_impl) = if matches!(helper_attr, Some(&HelperAttr::Transparent)) {
                 let deserialization_by_variant = data.variants.iter().rev().map(|variant| {
                     // NOTE: this is "safe" as the number of legal variants fits into `u8`
                     let variant_name = &variant.ident;
@@ -174,15 +355,17 @@ fn derive_deserialize_impl(data: &Data, helper_attr: Option<&HelperAttr>) -> Tok
                         _ => unreachable!("validated to exclude this condition"),
                     }
                 });
-                quote! {
+                let body = quote! {
                     #(#deserialization_by_variant)*
                     Err(ssz_rs::DeserializeError::NoMatchingVariant)
-                }
+                };
+                // NOTE: transparent enums use the default `deserialize_reader` as they may try to
+                // decode the same encoding as several variants
+                (body, quote! {})
             } else {
+                let selectors = variant_selectors(data);
                 let deserialization_by_variant =
-                    data.variants.iter().enumerate().map(|(i, variant)| {
-                        // NOTE: this is "safe" as the number of legal variants fits into `u8`
-                        let i = i as u8;
+                    data.variants.iter().zip(&selectors).map(|(variant, selector)| {
                         let variant_name = &variant.ident;
                         match &variant.fields {
                             Fields::Unnamed(inner) => {
@@ -190,9 +373,10 @@ fn derive_deserialize_impl(data: &Data, helper_attr: Option<&HelperAttr>) -> Tok
                                 // element; qed
                                 let variant_type = &inner.unnamed[0];
                                 quote_spanned! { variant.span() =>
-                                    #i => {
+                                    #selector => {
                                         // SAFETY: index is safe because encoding isn't empty; qed
-                                        let value = <#variant_type>::deserialize(&encoding[1..])?;
+                                        let value = <#variant_type>::deserialize(&encoding[1..])
+                                            .map_err(|err| err.at(#selector as usize))?;
                                         Ok(Self::#variant_name(value))
                                     }
                                 }
@@ -213,13 +397,55 @@ fn derive_deserialize_impl(data: &Data, helper_attr: Option<&HelperAttr>) -> Tok
                             _ => unreachable!(),
                         }
                     });
-                quote! {
+                let stream_deserialization_by_variant =
+                    data.variants.iter().zip(&selectors).map(|(variant, selector)| {
+                        let variant_name = &variant.ident;
+                        match &variant.fields {
+                            Fields::Unnamed(inner) => {
+                                // SAFETY: index is safe because Punctuated always has a first
+                                // element; qed
+                                let variant_type = &inner.unnamed[0];
+                                quote_spanned! { variant.span() =>
+                                    #selector => {
+                                        let value = <#variant_type as ssz_rs::Deserialize>::deserialize_reader(reader, len)
+                                            .map_err(|err| err.at(#selector as usize))?;
+                                        Ok(Self::#variant_name(value))
+                                    }
+                                }
+                            }
+                            Fields::Unit => {
+                                quote_spanned! { variant.span() =>
+                                    0 => {
+                                        ssz_rs::__internal::read_none_value(reader, len)?;
+                                        Ok(Self::None)
+                                    },
+                                }
+                            }
+                            _ => unreachable!(),
+                        }
+                    });
+                let body = quote! {
                     // SAFETY: index is safe because encoding isn't empty; qed
                     match encoding[0] {
                         #(#deserialization_by_variant)*
                         b => Err(ssz_rs::DeserializeError::InvalidByte(b)),
                     }
-                }
+                };
+                let stream_impl = quote! {
+                    ssz_rs::__internal::std_only! {
+                        fn deserialize_reader<__R: ssz_rs::__internal::Read>(
+                            reader: &mut ssz_rs::Reader<__R>,
+                            len: Option<usize>,
+                        ) -> Result<Self, ssz_rs::DeserializeError> {
+                            let (selector, len) = ssz_rs::__internal::read_selector(reader, len)?;
+                            match selector {
+                                #(#stream_deserialization_by_variant)*
+                                b => Err(ssz_rs::DeserializeError::InvalidByte(b)),
+                            }
+                        }
+                    }
+                };
+                (body, stream_impl)
             };
 
             quote! {
@@ -233,6 +459,8 @@ fn derive_deserialize_impl(data: &Data, helper_attr: Option<&HelperAttr>) -> Tok
 
                     #body
                 }
+
+                #stream_impl
             }
         }
         Data::Union(..) => unreachable!("data was already validated to exclude union types"),
@@ -307,6 +535,76 @@ fn derive_size_hint_impl(data: &Data) -> TokenStream {
     }
 }
 
+// Expand to the minimum and maximum lengths of the encodings of the type of `data`.
+fn derive_encoded_len_bounds_impl(
+    data: &Data,
+    helper_attr: Option<&HelperAttr>,
+) -> (TokenStream, TokenStream) {
+    match data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => {
+                let min_by_field = fields.named.iter().map(|f| {
+                    let field_type = &f.ty;
+                    quote_spanned! { f.span() =>
+                        ssz_rs::__internal::min_part_len::<#field_type>()
+                    }
+                });
+                let max_by_field = fields.named.iter().map(|f| {
+                    let field_type = &f.ty;
+                    quote_spanned! { f.span() =>
+                        ssz_rs::__internal::max_part_len::<#field_type>()
+                    }
+                });
+                (
+                    quote! { 0usize #(.saturating_add(#min_by_field))* },
+                    quote! { 0usize #(.saturating_add(#max_by_field))* },
+                )
+            }
+            Fields::Unnamed(ref fields) => {
+                // SAFETY: index is safe because Punctuated always has a first element; qed
+                let field_type = &fields.unnamed[0].ty;
+                (
+                    quote! { <#field_type as ssz_rs::Serializable>::min_encoded_len() },
+                    quote! { <#field_type as ssz_rs::Serializable>::max_encoded_len() },
+                )
+            }
+            _ => unimplemented!(
+                "this type of struct is currently not supported by this derive macro"
+            ),
+        },
+        Data::Enum(ref data) => {
+            let (min_by_variant, max_by_variant): (Vec<_>, Vec<_>) = data
+                .variants
+                .iter()
+                .map(|variant| match &variant.fields {
+                    Fields::Unnamed(inner) => {
+                        // SAFETY: index is safe because Punctuated always has a first element; qed
+                        let variant_type = &inner.unnamed[0].ty;
+                        (
+                            quote_spanned! { variant.span() =>
+                                <#variant_type as ssz_rs::Serializable>::min_encoded_len()
+                            },
+                            quote_spanned! { variant.span() =>
+                                <#variant_type as ssz_rs::Serializable>::max_encoded_len()
+                            },
+                        )
+                    }
+                    Fields::Unit => (quote!(0), quote!(0)),
+                    _ => unreachable!("data was already validated to exclude named variants"),
+                })
+                .unzip();
+            // NOTE: a `transparent` enum is encoded as the value of its variant, without a selector
+            let selector_len =
+                if matches!(helper_attr, Some(HelperAttr::Transparent)) { 0usize } else { 1 };
+            (
+                quote! { usize::MAX #(.min(#min_by_variant))*.saturating_add(#selector_len) },
+                quote! { 0usize #(.max(#max_by_variant))*.saturating_add(#selector_len) },
+            )
+        }
+        Data::Union(..) => unreachable!("data was already validated to exclude union types"),
+    }
+}
+
 fn derive_merkleization_impl(
     data: &Data,
     name: &Ident,
@@ -325,12 +623,12 @@ fn derive_merkleization_impl(
             let field_count = fields.iter().len();
             let impl_by_field = fields.iter().enumerate().map(|(i, f)| match &f.ident {
                 Some(field_name) => quote_spanned! { f.span() =>
-                    let chunk = self.#field_name.hash_tree_root()?;
+                    let chunk = self.#field_name.hash_tree_root_with::<__H>()?;
                     let range = #i*#BYTES_PER_CHUNK..(#i+1)*#BYTES_PER_CHUNK;
                     chunks[range].copy_from_slice(chunk.as_ref());
                 },
                 None => quote_spanned! { f.span() =>
-                    let chunk = self.0.hash_tree_root()?;
+                    let chunk = self.0.hash_tree_root_with::<__H>()?;
                     let range = #i*#BYTES_PER_CHUNK..(#i+1)*#BYTES_PER_CHUNK;
                     chunks[range].copy_from_slice(chunk.as_ref());
                 },
@@ -341,38 +639,40 @@ fn derive_merkleization_impl(
                 Ok(chunks)
             };
             let hash_tree_root_impl = quote! {
-                let chunks = self.assemble_chunks()?;
-                ssz_rs::__internal::merkleize(&chunks, None)
+                let chunks = self.assemble_chunks::<__H>()?;
+                ssz_rs::__internal::merkleize_with::<__H>(&chunks, None)
             };
             (hash_tree_root_impl, chunks_impl)
         }
         Data::Enum(ref data) => {
-            let implementations = data.variants.iter().enumerate().map(|(i, variant)| {
+            let selectors = variant_selectors(data);
+            let implementations = data.variants.iter().zip(selectors).map(|(variant, selector)| {
                 let variant_name = &variant.ident;
+                let selector = selector as usize;
                 match &variant.fields {
                     Fields::Unnamed(..) => {
                         // NOTE: validated to only be `transparent` operation at this point...
                         let hash_tree_by_variant = if helper_attr.is_some() {
                             quote_spanned! { variant.span() =>
-                               Self::#variant_name(value) => value.hash_tree_root(),
+                               Self::#variant_name(value) => value.hash_tree_root_with::<__H>(),
                             }
                         } else {
                             quote_spanned! { variant.span() =>
                                Self::#variant_name(value) => {
-                                   let selector = #i;
-                                   let chunks = value.hash_tree_root()?;
-                                   Ok(ssz_rs::__internal::mix_in_selector(chunks, selector))
+                                   let selector = #selector;
+                                   let chunks = value.hash_tree_root_with::<__H>()?;
+                                   Ok(ssz_rs::__internal::mix_in_selector_with::<__H>(chunks, selector))
                                }
                             }
                         };
                         let chunks_by_variant = quote! {
-                           Self::#variant_name(value) => Ok(value.hash_tree_root()?.to_vec()),
+                           Self::#variant_name(value) => Ok(value.hash_tree_root_with::<__H>()?.to_vec()),
                         };
                         (hash_tree_by_variant, chunks_by_variant)
                     }
                     Fields::Unit => (
                         quote_spanned! { variant.span() =>
-                            Self::None => Ok(ssz_rs::__internal::mix_in_selector(
+                            Self::None => Ok(ssz_rs::__internal::mix_in_selector_with::<__H>(
                                 Node::default(),
                                 0,
                             )),
@@ -404,13 +704,13 @@ fn derive_merkleization_impl(
     let (impl_generics, ty_generics, _) = generics.split_for_impl();
     quote! {
         impl #impl_generics #name #ty_generics {
-            fn assemble_chunks(&self) -> Result<Vec<u8>, ssz_rs::MerkleizationError> {
+            fn assemble_chunks<__H: ssz_rs::Hasher>(&self) -> Result<Vec<u8>, ssz_rs::MerkleizationError> {
                 #chunks_impl
             }
         }
 
         impl #impl_generics ssz_rs::HashTreeRoot for #name #ty_generics {
-            fn hash_tree_root(&self) -> Result<ssz_rs::Node, ssz_rs::MerkleizationError> {
+            fn hash_tree_root_with<__H: ssz_rs::Hasher>(&self) -> Result<ssz_rs::Node, ssz_rs::MerkleizationError> {
                 #hash_tree_root_impl
             }
         }
@@ -442,7 +742,7 @@ fn derive_generalized_indexable_impl(
                 let fields = &fields.named;
                 let impl_by_field = fields.iter().enumerate().map(|(i, field)| {
                     let field_name = field.ident.as_ref().expect("only named fields");
-                    let selector = format!("{field_name}");
+                    let selector = field_name.unraw().to_string();
                     let field_ty = &field.ty;
                     quote! {
                         #selector => {
@@ -479,17 +779,17 @@ fn derive_generalized_indexable_impl(
             Fields::Unit => unreachable!("validated to exclude this type"),
         },
         Data::Enum(ref data) => {
-            let variant_count = data.variants.len();
-
-            let impl_by_variant = data.variants.iter().enumerate().map(|(i, variant)| {
+            let selectors = variant_selectors(data);
+            let impl_by_variant = data.variants.iter().zip(selectors).map(|(variant, selector)| {
                 let variant_name = &variant.ident;
+                let selector = selector as usize;
                 match &variant.fields {
                     Fields::Unnamed(ref fields) => {
                         let field =
                             fields.unnamed.first().expect("validated to only have one field");
                         let ty = &field.ty;
                         quote! {
-                            #i => {
+                            #selector => {
                                 <#ty as ssz_rs::GeneralizedIndexable>::compute_generalized_index(child, rest)
                             }
                         }
@@ -497,7 +797,7 @@ fn derive_generalized_indexable_impl(
                     Fields::Unit => {
                         // NOTE: this has already been validated to conform to:
                         // first variant, and is `None` identifier
-                        if i != 0 || !is_valid_none_identifier(variant_name) {
+                        if selector != 0 || !is_valid_none_identifier(variant_name) {
                             panic!("internal validation inconsistency; check proc derive macro");
                         }
                         quote! {
@@ -518,13 +818,10 @@ fn derive_generalized_indexable_impl(
                 if let Some((next, rest)) = path.split_first() {
                     match next {
                         PathElement::Index(i) => {
-                            if *i >= #variant_count {
-                                return Err(MerkleizationError::InvalidPathElement(next.clone()))
-                            }
                             let child = parent * 2;
                             match *i {
                                 #(#impl_by_variant)*
-                                _ => unreachable!("validated in covered range"),
+                                _ => Err(MerkleizationError::InvalidPathElement(next.clone())),
                             }
                         }
                         PathElement::Selector => {
@@ -606,7 +903,7 @@ fn derive_prove_impl(data: &Data, name: &Ident, generics: &Generics) -> TokenStr
                     }
                 });
                 let chunks_impl = quote! {
-                    self.assemble_chunks()
+                    self.assemble_chunks::<__H>()
                 };
 
                 let prove_element_impl = quote! {
@@ -625,7 +922,7 @@ fn derive_prove_impl(data: &Data, name: &Ident, generics: &Generics) -> TokenStr
             Fields::Unnamed(..) => {
                 // NOTE: new type pattern, proxy to wrapped type...
                 let chunks_impl = quote! {
-                    self.0.chunks()
+                    self.0.chunks::<__H>()
                 };
 
                 let prove_element_impl = quote! {
@@ -636,6 +933,18 @@ fn derive_prove_impl(data: &Data, name: &Ident, generics: &Generics) -> TokenStr
                     fn decoration(&self) -> Option<usize> {
                         self.0.decoration()
                     }
+
+                    fn decoration_root<__H: ssz_rs::Hasher>(&self) -> Result<Option<ssz_rs::Node>, ssz_rs::MerkleizationError> {
+                        self.0.decoration_root::<__H>()
+                    }
+
+                    fn value_chunk_count(&self) -> usize {
+                        self.0.value_chunk_count()
+                    }
+
+                    fn is_progressive(&self) -> bool {
+                        self.0.is_progressive()
+                    }
                 };
                 (chunks_impl, prove_element_impl, Some(decoration_impl))
             }
@@ -644,22 +953,24 @@ fn derive_prove_impl(data: &Data, name: &Ident, generics: &Generics) -> TokenStr
         Data::Enum(ref data) => {
             let variant_count = data.variants.len();
 
-            let implementations = data.variants.iter().enumerate().map(|(i, variant)| {
+            let selectors = variant_selectors(data);
+            let implementations = data.variants.iter().zip(selectors).map(|(variant, selector)| {
                 let variant_name = &variant.ident;
+                let selector = selector as usize;
                 match &variant.fields {
                     Fields::Unnamed(..) => {
                         let prove_element_impl = quote! {
                             Self::#variant_name(value) => prover.compute_proof(value),
                         };
                         let decoration_impl = quote! {
-                            Self::#variant_name(_) => Some(#i),
+                            Self::#variant_name(_) => Some(#selector),
                         };
                         (prove_element_impl, decoration_impl)
                     }
                     Fields::Unit => {
                         // NOTE: this has already been validated to conform to:
                         // first variant, and is `None` identifier
-                        if i != 0 || !is_valid_none_identifier(variant_name) {
+                        if selector != 0 || !is_valid_none_identifier(variant_name) {
                             panic!("internal validation inconsistency; check proc derive macro");
                         }
                         (
@@ -670,7 +981,7 @@ fn derive_prove_impl(data: &Data, name: &Ident, generics: &Generics) -> TokenStr
                                 }
                             },
                             quote! {
-                                Self::None => Some(#i),
+                                Self::None => Some(0),
                             },
                         )
                     }
@@ -690,7 +1001,7 @@ fn derive_prove_impl(data: &Data, name: &Ident, generics: &Generics) -> TokenStr
                 }
             };
             let chunks_impl = quote! {
-                self.assemble_chunks()
+                self.assemble_chunks::<__H>()
             };
             let decoration_impl = quote! {
                 fn decoration(&self) -> Option<usize> {
@@ -706,14 +1017,14 @@ fn derive_prove_impl(data: &Data, name: &Ident, generics: &Generics) -> TokenStr
 
     quote! {
         impl #impl_generics ssz_rs::Prove for #name #ty_generics {
-            fn chunks(&self) -> Result<Vec<u8>, ssz_rs::MerkleizationError> {
+            fn chunks<__H: ssz_rs::Hasher>(&self) -> Result<Vec<u8>, ssz_rs::MerkleizationError> {
                 #chunks_impl
             }
 
-            fn prove_element(
+            fn prove_element<__H: ssz_rs::Hasher>(
                 &self,
                 index: usize,
-                prover: &mut ssz_rs::proofs::Prover,
+                prover: &mut ssz_rs::proofs::Prover<__H>,
             ) -> Result<(), ssz_rs::MerkleizationError> {
                 #prove_element_impl
             }
@@ -723,6 +1034,120 @@ fn derive_prove_impl(data: &Data, name: &Ident, generics: &Generics) -> TokenStr
     }
 }
 
+fn derive_view_impl(
+    data: &Data,
+    name: &Ident,
+    vis: &Visibility,
+    generics: &Generics,
+) -> TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+
+    let fields = match data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            Fields::Unnamed(ref fields) => {
+                // SAFETY: index is safe because Punctuated always has a first element; qed
+                let field_type = &fields.unnamed[0].ty;
+                return quote! {
+                    impl #impl_generics ssz_rs::Viewable for #name #ty_generics {
+                        type View<'a> = <#field_type as ssz_rs::Viewable>::View<'a>;
+
+                        fn view(encoding: &[u8]) -> Result<Self::View<'_>, ssz_rs::DeserializeError> {
+                            <#field_type as ssz_rs::Viewable>::view(encoding)
+                        }
+                    }
+                }
+            }
+            _ => unimplemented!(
+                "this type of struct is currently not supported by this derive macro"
+            ),
+        },
+        // NOTE: unions are decoded eagerly and so are their own view
+        Data::Enum(..) => {
+            return quote! {
+                impl #impl_generics ssz_rs::Viewable for #name #ty_generics {
+                    type View<'a> = Self;
+
+                    fn view(encoding: &[u8]) -> Result<Self::View<'_>, ssz_rs::DeserializeError> {
+                        <Self as ssz_rs::Deserialize>::deserialize(encoding)
+                    }
+                }
+            }
+        }
+        Data::Union(..) => unreachable!("data was already validated to exclude union types"),
+    };
+
+    let view_name = format_ident!("{}View", name);
+    let view_doc = format!("A view over the SSZ encoding of a [`{name}`].");
+    let mut view_generics = generics.clone();
+    view_generics.params.insert(0, parse_quote!('a));
+    let (view_impl_generics, view_ty_generics, _) = view_generics.split_for_impl();
+
+    let parse_by_field = fields.iter().map(|f| {
+        let field_type = &f.ty;
+        let field_name = f.ident.as_ref().expect("fields are named").unraw().to_string();
+        quote_spanned! { f.span() =>
+            deserializer.parse::<#field_type>(encoding).map_err(|err| err.at(#field_name))?;
+        }
+    });
+
+    let accessor_by_field = fields.iter().enumerate().map(|(i, f)| {
+        let field_type = &f.ty;
+        let field_vis = &f.vis;
+        match &f.ident {
+            Some(field_name) => {
+                let doc = format!("Return a view of the `{field_name}` field.");
+                quote_spanned! { f.span() =>
+                    #[doc = #doc]
+                    #[allow(dead_code)]
+                    #field_vis fn #field_name(&self) -> Result<<#field_type as ssz_rs::Viewable>::View<'a>, ssz_rs::DeserializeError> {
+                        <#field_type as ssz_rs::Viewable>::view(&self.encoding[self.spans[2*#i]..self.spans[2*#i+1]])
+                    }
+                }
+            }
+            None => panic!("should have already returned an impl"),
+        }
+    });
+
+    quote! {
+        #[doc = #view_doc]
+        #[derive(Clone, Debug)]
+        #vis struct #view_name #view_impl_generics {
+            encoding: &'a [u8],
+            spans: Vec<usize>,
+            _value: ::core::marker::PhantomData<fn() -> #name #ty_generics>,
+        }
+
+        impl #view_impl_generics #view_name #view_ty_generics {
+            #(#accessor_by_field)*
+
+            /// Return the underlying encoding.
+            #vis fn as_bytes(&self) -> &'a [u8] {
+                self.encoding
+            }
+
+            /// Decode the entire value.
+            #vis fn deserialize(&self) -> Result<#name #ty_generics, ssz_rs::DeserializeError> {
+                <#name #ty_generics as ssz_rs::Deserialize>::deserialize(self.encoding)
+            }
+        }
+
+        impl #impl_generics ssz_rs::Viewable for #name #ty_generics {
+            type View<'a> = #view_name #view_ty_generics;
+
+            fn view(encoding: &[u8]) -> Result<Self::View<'_>, ssz_rs::DeserializeError> {
+                let mut deserializer = ssz_rs::__internal::ContainerDeserializer::default();
+
+                #(#parse_by_field)*
+
+                let spans = deserializer.finalize(encoding)?;
+
+                Ok(#view_name { encoding, spans, _value: ::core::marker::PhantomData })
+            }
+        }
+    }
+}
+
 fn is_valid_none_identifier(ident: &Ident) -> bool {
     *ident == format_ident!("None")
 }
@@ -759,6 +1184,11 @@ fn validate_derive_input(data: &Data, helper_attrs: &[HelperAttr]) {
     if let Some(attr) = helper_attrs.first() {
         match attr {
             HelperAttr::Transparent => is_transparent = true,
+            HelperAttr::StableContainer { .. } | HelperAttr::Profile { .. } => {
+                if !matches!(data, Data::Struct(data) if matches!(data.fields, Fields::Named(..))) {
+                    panic!("`stable_container` and `profile` options are only compatible with structs with named fields")
+                }
+            }
         }
     }
     if is_transparent && !matches!(data, Data::Enum(..)) {
@@ -786,13 +1216,30 @@ fn validate_derive_input(data: &Data, helper_attrs: &[HelperAttr]) {
                 panic!("SSZ unions cannot have more than 127 variants; this enum has more");
             }
 
+            let selectors =
+                data.variants.iter().filter_map(parse_selector_attr).collect::<Vec<_>>();
+            let is_compatible = !selectors.is_empty();
+            if is_compatible {
+                if selectors.len() != data.variants.len() {
+                    panic!("either every variant or no variant of an enum must have a `selector`")
+                }
+                if is_transparent {
+                    panic!("`transparent` option is not compatible with variant selectors")
+                }
+                let mut seen = HashSet::new();
+                for selector in selectors {
+                    if !(1..=127).contains(&selector) {
+                        panic!("variant selectors must be in the range 1..=127")
+                    }
+                    if !seen.insert(selector) {
+                        panic!("variant selectors must be unique; found {selector} more than once")
+                    }
+                }
+            }
+
             let mut none_forbidden = false;
             let mut already_has_none = false;
             for (i, variant) in data.variants.iter().enumerate() {
-                let mut variant_ssz_attrs = filter_ssz_attrs(variant.attrs.iter());
-                if variant_ssz_attrs.next().is_some() {
-                    panic!("macro attribute `{SSZ_HELPER_ATTRIBUTE}` is only allowed at struct or enum level")
-                }
                 validate_no_attrs(variant.fields.iter());
                 match &variant.fields {
                     Fields::Unnamed(inner) => {
@@ -808,6 +1255,9 @@ fn validate_derive_input(data: &Data, helper_attrs: &[HelperAttr]) {
                         if is_transparent {
                             panic!("`transparent` option is only compatible with unnamed variants")
                         }
+                        if is_compatible {
+                            panic!("enums with variant selectors can only have unnamed variants")
+                        }
                         if none_forbidden {
                             panic!(
                                 "found unit variant that conflicts with previous unnamed variants"
@@ -849,6 +1299,8 @@ fn derive_serializable_impl(
     let deserialize_impl = derive_deserialize_impl(data, helper_attr);
     let is_variable_size_impl = derive_variable_size_impl(data);
     let size_hint_impl = derive_size_hint_impl(data);
+    let (min_encoded_len_impl, max_encoded_len_impl) =
+        derive_encoded_len_bounds_impl(data, helper_attr);
 
     let (impl_generics, ty_generics, _) = generics.split_for_impl();
     quote! {
@@ -868,21 +1320,237 @@ fn derive_serializable_impl(
             fn size_hint() -> usize {
                 #size_hint_impl
             }
+
+            fn min_encoded_len() -> usize {
+                #min_encoded_len_impl
+            }
+
+            fn max_encoded_len() -> usize {
+                #max_encoded_len_impl
+            }
+        }
+    }
+}
+
+fn derive_simple_serialize_impl(
+    data: &Data,
+    name: &Ident,
+    generics: &Generics,
+) -> proc_macro2::TokenStream {
+    let hash_tree_root_from_bytes_impl = match data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => {
+                let fields = &fields.named;
+                let field_count = fields.iter().len();
+                let parse_by_field = fields.iter().map(|f| {
+                    let field_type = &f.ty;
+                    let field_name =
+                        f.ident.as_ref().expect("fields are named").unraw().to_string();
+                    quote_spanned! { f.span() =>
+                        deserializer.parse::<#field_type>(encoding).map_err(|err| err.at(#field_name))?;
+                    }
+                });
+                let chunk_by_field = fields.iter().enumerate().map(|(i, f)| {
+                    let field_type = &f.ty;
+                    quote_spanned! { f.span() =>
+                        let chunk = <#field_type as ssz_rs::SimpleSerialize>::hash_tree_root_from_bytes(&encoding[spans[2*#i]..spans[2*#i+1]])?;
+                        let range = #i*#BYTES_PER_CHUNK..(#i+1)*#BYTES_PER_CHUNK;
+                        chunks[range].copy_from_slice(chunk.as_ref());
+                    }
+                });
+                quote! {
+                    let mut deserializer = ssz_rs::__internal::ContainerDeserializer::default();
+
+                    #(#parse_by_field)*
+
+                    let spans = deserializer.finalize(encoding)?;
+
+                    let mut chunks = vec![0u8; #field_count * #BYTES_PER_CHUNK];
+                    #(#chunk_by_field)*
+                    Ok(ssz_rs::__internal::merkleize(&chunks, None)?)
+                }
+            }
+            Fields::Unnamed(ref fields) => {
+                // SAFETY: index is safe because Punctuated always has a first element; qed
+                let field_type = &fields.unnamed[0].ty;
+                quote! {
+                    <#field_type as ssz_rs::SimpleSerialize>::hash_tree_root_from_bytes(encoding)
+                }
+            }
+            _ => unimplemented!(
+                "this type of struct is currently not supported by this derive macro"
+            ),
+        },
+        Data::Enum(ref data) => {
+            let selectors = variant_selectors(data);
+            let root_by_variant = data.variants.iter().zip(selectors).map(|(variant, selector)| {
+                let mixed_in_selector = selector as usize;
+                match &variant.fields {
+                    Fields::Unnamed(inner) => {
+                        // SAFETY: index is safe because Punctuated always has a first
+                        // element; qed
+                        let variant_type = &inner.unnamed[0];
+                        quote_spanned! { variant.span() =>
+                            #selector => {
+                                // SAFETY: index is safe because encoding isn't empty; qed
+                                let root = <#variant_type as ssz_rs::SimpleSerialize>::hash_tree_root_from_bytes(&encoding[1..])?;
+                                Ok(ssz_rs::__internal::mix_in_selector(root, #mixed_in_selector))
+                            }
+                        }
+                    }
+                    Fields::Unit => {
+                        quote_spanned! { variant.span() =>
+                            0 => {
+                                if encoding.len() != 1 {
+                                    return Err(ssz_rs::DeserializeError::AdditionalInput {
+                                        provided: encoding.len(),
+                                        expected: 1,
+                                    }.into())
+                                }
+                                Ok(ssz_rs::__internal::mix_in_selector(ssz_rs::Node::default(), 0))
+                            },
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            });
+            quote! {
+                if encoding.is_empty() {
+                    return Err(ssz_rs::DeserializeError::ExpectedFurtherInput {
+                        provided: 0,
+                        expected: 1,
+                    }.into());
+                }
+
+                // SAFETY: index is safe because encoding isn't empty; qed
+                match encoding[0] {
+                    #(#root_by_variant)*
+                    b => Err(ssz_rs::DeserializeError::InvalidByte(b).into()),
+                }
+            }
+        }
+        Data::Union(..) => unreachable!("data was already validated to exclude union types"),
+    };
+
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ssz_rs::SimpleSerialize for #name #ty_generics {
+            fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<ssz_rs::Node, ssz_rs::SimpleSerializeError> {
+                #hash_tree_root_from_bytes_impl
+            }
         }
     }
 }
 
-fn derive_simple_serialize_impl(name: &Ident, generics: &Generics) -> proc_macro2::TokenStream {
+fn derive_type_info_impl(data: &Data, name: &Ident, generics: &Generics) -> TokenStream {
+    let type_info_impl = match data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => {
+                let type_info_by_field = fields.named.iter().map(|f| {
+                    let field_name = f.ident.as_ref().expect("only named fields");
+                    let field_name = field_name.unraw().to_string();
+                    let field_type = &f.ty;
+                    quote_spanned! { f.span() =>
+                        (#field_name.to_string(), <#field_type as ssz_rs::SszTypeInfo>::type_info()),
+                    }
+                });
+                quote! {
+                    ssz_rs::SszType::Container(vec![#(#type_info_by_field)*])
+                }
+            }
+            Fields::Unnamed(ref fields) => {
+                // SAFETY: index is safe because Punctuated always has a first element; qed
+                let field_type = &fields.unnamed[0].ty;
+                quote! {
+                    <#field_type as ssz_rs::SszTypeInfo>::type_info()
+                }
+            }
+            _ => unimplemented!(
+                "this type of struct is currently not supported by this derive macro"
+            ),
+        },
+        Data::Enum(ref data) => {
+            let selectors = variant_selectors(data);
+            let type_info_by_variant =
+                data.variants.iter().zip(selectors).map(|(variant, selector)| {
+                    match &variant.fields {
+                        Fields::Unnamed(inner) => {
+                            // SAFETY: index is safe because Punctuated always has a first
+                            // element; qed
+                            let variant_type = &inner.unnamed[0].ty;
+                            quote_spanned! { variant.span() =>
+                                (#selector, Some(<#variant_type as ssz_rs::SszTypeInfo>::type_info())),
+                            }
+                        }
+                        Fields::Unit => quote_spanned! { variant.span() =>
+                            (0, None),
+                        },
+                        _ => unreachable!(),
+                    }
+                });
+            quote! {
+                ssz_rs::SszType::Union(vec![#(#type_info_by_variant)*])
+            }
+        }
+        Data::Union(..) => unreachable!("data was already validated to exclude union types"),
+    };
+
     let (impl_generics, ty_generics, _) = generics.split_for_impl();
 
     quote! {
-        impl #impl_generics ssz_rs::SimpleSerialize for #name #ty_generics {}
+        impl #impl_generics ssz_rs::SszTypeInfo for #name #ty_generics {
+            fn type_info() -> ssz_rs::SszType {
+                #type_info_impl
+            }
+        }
+    }
+}
+
+// Parse the selector of a variant of a "compatible" union from its `ssz(selector = N)` attribute,
+// if any.
+fn parse_selector_attr(variant: &Variant) -> Option<u8> {
+    let mut selectors = filter_ssz_attrs(variant.attrs.iter()).map(|attr| {
+        match attr.parse_meta().expect("can parse attribute") {
+            Meta::List(args) => match args.nested.iter().collect::<Vec<_>>().as_slice() {
+                [NestedMeta::Meta(Meta::NameValue(arg))] if arg.path.is_ident("selector") => {
+                    match &arg.lit {
+                        Lit::Int(selector) => {
+                            selector.base10_parse().expect("`selector` is a `u8`")
+                        }
+                        _ => panic!("`selector` must be an integer"),
+                    }
+                }
+                _ => panic!("only the `selector = N` attribute is supported on variants"),
+            },
+            _ => panic!("only list-like attributes are supported"),
+        }
+    });
+    let selector = selectors.next();
+    if selectors.next().is_some() {
+        panic!("a variant can only have one `selector`")
     }
+    selector
 }
 
-#[derive(Debug, Clone, Copy)]
+// Return the selector of each variant of the enum `data`: the explicit selectors of a
+// "compatible" union, or the position of each variant for a union following the legacy spec.
+fn variant_selectors(data: &DataEnum) -> Vec<u8> {
+    data.variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| {
+            // NOTE: this is "safe" as the number of legal variants fits into `u8`
+            parse_selector_attr(variant).unwrap_or(i as u8)
+        })
+        .collect()
+}
+
+#[derive(Clone)]
 enum HelperAttr {
     Transparent,
+    StableContainer { max_fields: usize },
+    Profile { base: syn::Path },
 }
 
 fn parse_helper_attr(ident: &Ident) -> HelperAttr {
@@ -892,6 +1560,57 @@ fn parse_helper_attr(ident: &Ident) -> HelperAttr {
     }
 }
 
+fn parse_helper_attr_with_args(ident: &Ident, args: &[NestedMeta]) -> HelperAttr {
+    match (ident.to_string().as_str(), args) {
+        ("stable_container", [NestedMeta::Meta(Meta::NameValue(arg))])
+            if arg.path.is_ident("max_fields") =>
+        {
+            match &arg.lit {
+                Lit::Int(max_fields) => HelperAttr::StableContainer {
+                    max_fields: max_fields.base10_parse().expect("`max_fields` is a `usize`"),
+                },
+                _ => panic!("`max_fields` must be an integer"),
+            }
+        }
+        ("stable_container", _) => {
+            panic!("`stable_container` requires the argument `max_fields = N`")
+        }
+        ("profile", [NestedMeta::Meta(Meta::Path(base))]) => {
+            HelperAttr::Profile { base: base.clone() }
+        }
+        ("profile", _) => panic!("`profile` requires the path to its stable container"),
+        (ident, _) => panic!("unsupported helper attribute:{ident}"),
+    }
+}
+
+fn is_serde_arg(arg: &NestedMeta) -> bool {
+    matches!(arg, NestedMeta::Meta(Meta::NameValue(arg)) if arg.path.is_ident("serde"))
+}
+
+// Return `true` if the attribute `ssz(serde = "spec")` requests the representation of the
+// consensus specs with `serde`.
+fn extract_spec_serde_attr(input: &DeriveInput) -> bool {
+    let mut modes = filter_ssz_attrs(input.attrs.iter()).flat_map(|attr| {
+        let meta = attr.parse_meta().unwrap();
+        let args = match meta {
+            Meta::List(args) => args.nested.into_iter().collect::<Vec<_>>(),
+            _ => vec![],
+        };
+        args.into_iter().filter(is_serde_arg).map(|arg| match arg {
+            NestedMeta::Meta(Meta::NameValue(arg)) => match &arg.lit {
+                Lit::Str(mode) if mode.value() == "spec" => (),
+                _ => panic!("the only supported `serde` mode is `\"spec\"`"),
+            },
+            _ => unreachable!("filtered to `serde` arguments"),
+        })
+    });
+    let is_spec = modes.next().is_some();
+    if modes.next().is_some() {
+        panic!("only one `serde` mode is allowed")
+    }
+    is_spec
+}
+
 fn extract_helper_attrs(input: &DeriveInput) -> Vec<HelperAttr> {
     filter_ssz_attrs(input.attrs.iter())
         .flat_map(|attr| {
@@ -900,20 +1619,24 @@ fn extract_helper_attrs(input: &DeriveInput) -> Vec<HelperAttr> {
                 Meta::List(args) => args
                     .nested
                     .iter()
+                    .filter(|arg| !is_serde_arg(arg))
                     .map(|arg| match arg {
-                        NestedMeta::Meta(meta) => {
-                            if let Meta::Path(path) = meta {
-                                assert!(path.leading_colon.is_none());
-                                assert_eq!(path.segments.len(), 1);
-                                let path = &path.segments[0];
-                                match path.arguments {
-                                    PathArguments::None => parse_helper_attr(&path.ident),
-                                    _ => panic!("no arguments are supported to attribute symbols"),
-                                }
-                            } else {
-                                panic!("unsupported argument to helper attribute");
+                        NestedMeta::Meta(Meta::Path(path)) => {
+                            assert!(path.leading_colon.is_none());
+                            assert_eq!(path.segments.len(), 1);
+                            let path = &path.segments[0];
+                            match path.arguments {
+                                PathArguments::None => parse_helper_attr(&path.ident),
+                                _ => panic!("no arguments are supported to attribute symbols"),
                             }
                         }
+                        NestedMeta::Meta(Meta::List(list)) => {
+                            let ident =
+                                list.path.get_ident().expect("helper attribute is an identifier");
+                            let args = list.nested.iter().cloned().collect::<Vec<_>>();
+                            parse_helper_attr_with_args(ident, &args)
+                        }
+                        NestedMeta::Meta(..) => panic!("unsupported argument to helper attribute"),
                         _ => panic!("literals unsupported in attributes"),
                     })
                     .collect::<Vec<_>>(),
@@ -939,7 +1662,17 @@ pub fn derive_serializable(input: proc_macro::TokenStream) -> proc_macro::TokenS
     let name = &input.ident;
     let generics = &input.generics;
 
-    let expansion = derive_serializable_impl(data, name, generics, helper_attr);
+    let expansion = match StableLayout::new(data, name, generics, helper_attr) {
+        Some(layout) => layout.serializable_impl(),
+        None => derive_serializable_impl(data, name, generics, helper_attr),
+    };
+    let spec_serde_impl = extract_spec_serde_attr(&input)
+        .then(|| derive_spec_serde_impl(data, name, generics, helper_attr));
+    let expansion = quote! {
+        #expansion
+
+        #spec_serde_impl
+    };
     proc_macro::TokenStream::from(expansion)
 }
 
@@ -957,51 +1690,142 @@ pub fn derive_hash_tree_root(input: proc_macro::TokenStream) -> proc_macro::Toke
     let name = &input.ident;
     let generics = &input.generics;
 
-    let expansion = derive_merkleization_impl(data, name, generics, helper_attr);
+    let expansion = match StableLayout::new(data, name, generics, helper_attr) {
+        Some(layout) => layout.hash_tree_root_impl(),
+        None => derive_merkleization_impl(data, name, generics, helper_attr),
+    };
     proc_macro::TokenStream::from(expansion)
 }
 
 /// Derive an implementation of the `GeneralizedIndexable` trait to support computation of
 /// generalized indices.
-#[proc_macro_derive(GeneralizedIndexable)]
+#[proc_macro_derive(GeneralizedIndexable, attributes(ssz))]
 pub fn derive_generalized_indexable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
     let data = &input.data;
-    validate_derive_input(data, &[]);
+    let helper_attrs = extract_helper_attrs(&input);
+    validate_derive_input(data, &helper_attrs);
     let name = &input.ident;
     let generics = &input.generics;
 
-    let expansion = derive_generalized_indexable_impl(data, name, generics);
+    let expansion = match StableLayout::new(data, name, generics, helper_attrs.first()) {
+        Some(layout) => layout.generalized_indexable_impl(),
+        None => derive_generalized_indexable_impl(data, name, generics),
+    };
     proc_macro::TokenStream::from(expansion)
 }
 
 /// Derive an implementation of the `Prove` trait to support Merkle proofs.
-#[proc_macro_derive(Prove)]
+#[proc_macro_derive(Prove, attributes(ssz))]
 pub fn derive_prove(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
     let data = &input.data;
-    validate_derive_input(data, &[]);
+    let helper_attrs = extract_helper_attrs(&input);
+    validate_derive_input(data, &helper_attrs);
+    let name = &input.ident;
+    let generics = &input.generics;
+
+    let expansion = match StableLayout::new(data, name, generics, helper_attrs.first()) {
+        Some(layout) => layout.prove_impl(),
+        None => derive_prove_impl(data, name, generics),
+    };
+    proc_macro::TokenStream::from(expansion)
+}
+
+/// Derive an implementation of the `Viewable` trait to support reading values directly from their
+/// encoding.
+///
+/// For a struct `Foo`, this generates a `FooView<'a>` type with an accessor for each field.
+#[proc_macro_derive(Viewable, attributes(ssz))]
+pub fn derive_viewable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let data = &input.data;
+    let helper_attrs = extract_helper_attrs(&input);
+    validate_derive_input(data, &helper_attrs);
+    if matches!(
+        helper_attrs.first(),
+        Some(HelperAttr::StableContainer { .. } | HelperAttr::Profile { .. })
+    ) {
+        panic!("`Viewable` is not supported for stable containers or profiles")
+    }
+
+    let name = &input.ident;
+    let generics = &input.generics;
+
+    let expansion = derive_view_impl(data, name, &input.vis, generics);
+    proc_macro::TokenStream::from(expansion)
+}
+
+/// Derive an implementation of the `SszTypeInfo` trait to describe the SSZ type of the attached
+/// item at runtime.
+#[proc_macro_derive(SszTypeInfo, attributes(ssz))]
+pub fn derive_type_info(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let data = &input.data;
+    let helper_attrs = extract_helper_attrs(&input);
+    validate_derive_input(data, &helper_attrs);
+    if matches!(helper_attrs.first(), Some(HelperAttr::Transparent)) {
+        panic!("`transparent` option is not supported when deriving `SszTypeInfo`")
+    }
+
     let name = &input.ident;
     let generics = &input.generics;
 
-    let expansion = derive_prove_impl(data, name, generics);
+    let expansion = match StableLayout::new(data, name, generics, helper_attrs.first()) {
+        Some(layout) => layout.type_info_impl(),
+        None => derive_type_info_impl(data, name, generics),
+    };
     proc_macro::TokenStream::from(expansion)
 }
 
 /// Derive `SimpleSerialize` for the attached item, including the relevant additional traits
 /// required by the trait bound. Most common macro used from this crate.
-#[proc_macro_derive(SimpleSerialize)]
+#[proc_macro_derive(SimpleSerialize, attributes(ssz))]
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
     let data = &input.data;
-    validate_derive_input(data, &[]);
+    let helper_attrs = extract_helper_attrs(&input);
+    validate_derive_input(data, &helper_attrs);
+    if matches!(helper_attrs.first(), Some(HelperAttr::Transparent)) {
+        panic!("`transparent` option is not supported when deriving `SimpleSerialize`")
+    }
 
     let name = &input.ident;
     let generics = &input.generics;
 
+    let spec_serde_impl = extract_spec_serde_attr(&input)
+        .then(|| derive_spec_serde_impl(data, name, generics, helper_attrs.first()));
+
+    if let Some(layout) = StableLayout::new(data, name, generics, helper_attrs.first()) {
+        let serializable_impl = layout.serializable_impl();
+        let hash_tree_root_impl = layout.hash_tree_root_impl();
+        let generalized_indexable_impl = layout.generalized_indexable_impl();
+        let prove_impl = layout.prove_impl();
+        let simple_serialize_impl = layout.simple_serialize_impl();
+        let type_info_impl = layout.type_info_impl();
+        let expansion = quote! {
+            #serializable_impl
+
+            #hash_tree_root_impl
+
+            #generalized_indexable_impl
+
+            #prove_impl
+
+            #simple_serialize_impl
+
+            #type_info_impl
+
+            #spec_serde_impl
+        };
+        return proc_macro::TokenStream::from(expansion)
+    }
+
     let serializable_impl = derive_serializable_impl(data, name, generics, None);
 
     let merkleization_impl = derive_merkleization_impl(data, name, generics, None);
@@ -1010,7 +1834,9 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
     let prove_impl = derive_prove_impl(data, name, generics);
 
-    let simple_serialize_impl = derive_simple_serialize_impl(name, generics);
+    let simple_serialize_impl = derive_simple_serialize_impl(data, name, generics);
+
+    let type_info_impl = derive_type_info_impl(data, name, generics);
 
     let expansion = quote! {
         #serializable_impl
@@ -1022,6 +1848,10 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         #prove_impl
 
         #simple_serialize_impl
+
+        #type_info_impl
+
+        #spec_serde_impl
     };
 
     proc_macro::TokenStream::from(expansion)
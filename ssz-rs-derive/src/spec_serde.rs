@@ -0,0 +1,292 @@
+//! Derivation of the representation of the consensus specs with `serde`, requested with the
+//! attribute `ssz(serde = "spec")`.
+//!
+//! Containers are objects with a field for each field of the struct, where absent optional fields
+//! of a `StableContainer` or `Profile` are `null`. Unions are objects with a `selector` and a
+//! `value`. Every field or variant is (de)serialized with `ssz_rs::spec_serde::Spec`.
+use crate::{stable_container::option_inner_type, variant_selectors, HelperAttr};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    ext::IdentExt, parse_quote, punctuated::Punctuated, spanned::Spanned, token::Comma, Data,
+    DataEnum, Field, Fields, Generics, Ident,
+};
+
+/// Implement `SpecSerialize`, `SpecDeserialize`, `serde::Serialize` and `serde::Deserialize` for
+/// the item `name` with the given `data`.
+pub(crate) fn derive_spec_serde_impl(
+    data: &Data,
+    name: &Ident,
+    generics: &Generics,
+    helper_attr: Option<&HelperAttr>,
+) -> TokenStream {
+    let is_stable = matches!(
+        helper_attr,
+        Some(HelperAttr::StableContainer { .. } | HelperAttr::Profile { .. })
+    );
+    let spec_impl = match data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => derive_container(&fields.named, name, generics, is_stable),
+            Fields::Unnamed(..) => derive_newtype(name, generics),
+            Fields::Unit => unreachable!("data was already validated to exclude unit structs"),
+        },
+        Data::Enum(data) => {
+            if matches!(helper_attr, Some(HelperAttr::Transparent)) {
+                panic!("`serde = \"spec\"` is not supported for `transparent` enums")
+            }
+            derive_union(data, name, generics)
+        }
+        Data::Union(..) => unreachable!("data was already validated to exclude union types"),
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let de_generics = with_de_lifetime(generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+
+    quote! {
+        const _: () = {
+            use ssz_rs::__internal::serde;
+
+            #spec_impl
+
+            impl #impl_generics serde::Serialize for #name #ty_generics #where_clause {
+                fn serialize<__S: serde::Serializer>(&self, serializer: __S) -> Result<__S::Ok, __S::Error> {
+                    ssz_rs::spec_serde::SpecSerialize::spec_serialize(self, serializer)
+                }
+            }
+
+            impl #de_impl_generics serde::Deserialize<'de> for #name #ty_generics #where_clause {
+                fn deserialize<__D: serde::Deserializer<'de>>(deserializer: __D) -> Result<Self, __D::Error> {
+                    ssz_rs::spec_serde::SpecDeserialize::spec_deserialize(deserializer)
+                }
+            }
+        };
+    }
+}
+
+fn with_de_lifetime(generics: &Generics) -> Generics {
+    let mut generics = generics.clone();
+    generics.params.insert(0, parse_quote!('de));
+    generics
+}
+
+fn spec_impls(
+    name: &Ident,
+    generics: &Generics,
+    serialize_body: TokenStream,
+    deserialize_body: TokenStream,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ssz_rs::spec_serde::SpecSerialize for #name #ty_generics #where_clause {
+            fn spec_serialize<__S: serde::Serializer>(&self, serializer: __S) -> Result<__S::Ok, __S::Error> {
+                #serialize_body
+            }
+        }
+
+        impl #impl_generics ssz_rs::spec_serde::SpecDeserialize for #name #ty_generics #where_clause {
+            fn spec_deserialize<'de, __D: serde::Deserializer<'de>>(deserializer: __D) -> Result<Self, __D::Error> {
+                #deserialize_body
+            }
+        }
+    }
+}
+
+fn derive_newtype(name: &Ident, generics: &Generics) -> TokenStream {
+    spec_impls(
+        name,
+        generics,
+        quote! {
+            ssz_rs::spec_serde::SpecSerialize::spec_serialize(&self.0, serializer)
+        },
+        quote! {
+            ssz_rs::spec_serde::SpecDeserialize::spec_deserialize(deserializer).map(Self)
+        },
+    )
+}
+
+fn derive_container(
+    fields: &Punctuated<Field, Comma>,
+    name: &Ident,
+    generics: &Generics,
+    is_stable: bool,
+) -> TokenStream {
+    let name_str = name.unraw().to_string();
+    let field_count = fields.len();
+    let field_names = fields
+        .iter()
+        .map(|field| field.ident.as_ref().expect("only named fields").unraw().to_string())
+        .collect::<Vec<_>>();
+
+    let serialize_by_field = fields.iter().zip(&field_names).map(|(field, field_name)| {
+        let ident = field.ident.as_ref().expect("only named fields");
+        let is_optional = is_stable && option_inner_type(&field.ty).is_some();
+        let value = if is_optional {
+            quote! { &self.#ident.as_ref().map(ssz_rs::spec_serde::Spec) }
+        } else {
+            quote! { &ssz_rs::spec_serde::Spec(&self.#ident) }
+        };
+        quote_spanned! { field.span() =>
+            state.serialize_field(#field_name, #value)?;
+        }
+    });
+    let serialize_body = quote! {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct(#name_str, #field_count)?;
+        #(#serialize_by_field)*
+        state.end()
+    };
+
+    // NOTE: each field is collected into a slot, and an optional field into an `Option` slot
+    let slots = fields.iter().map(|field| {
+        let ident = field.ident.as_ref().expect("only named fields");
+        format_ident!("__{}", ident.unraw())
+    });
+    let slots = slots.collect::<Vec<_>>();
+    let slot_definitions = fields.iter().zip(&slots).map(|(field, slot)| {
+        let field_type = &field.ty;
+        quote_spanned! { field.span() =>
+            let mut #slot: Option<#field_type> = None;
+        }
+    });
+    let deserialize_by_field =
+        fields.iter().zip(&slots).zip(&field_names).map(|((field, slot), field_name)| {
+            let field_type = &field.ty;
+            let value = match option_inner_type(field_type) {
+                Some(inner_type) if is_stable => quote! {
+                    map.next_value::<Option<ssz_rs::spec_serde::Spec<#inner_type>>>()?
+                        .map(|value| value.0)
+                },
+                _ => quote! {
+                    map.next_value::<ssz_rs::spec_serde::Spec<#field_type>>()?.0
+                },
+            };
+            quote_spanned! { field.span() =>
+                #field_name => {
+                    if #slot.is_some() {
+                        return Err(serde::de::Error::duplicate_field(#field_name))
+                    }
+                    #slot = Some(#value);
+                }
+            }
+        });
+    let initialization_by_field =
+        fields.iter().zip(&slots).zip(&field_names).map(|((field, slot), field_name)| {
+            let ident = field.ident.as_ref().expect("only named fields");
+            if is_stable && option_inner_type(&field.ty).is_some() {
+                quote_spanned! { field.span() =>
+                    #ident: #slot.flatten(),
+                }
+            } else {
+                quote_spanned! { field.span() =>
+                    #ident: #slot.ok_or_else(|| serde::de::Error::missing_field(#field_name))?,
+                }
+            }
+        });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let de_generics = with_de_lifetime(generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let deserialize_body = quote! {
+        deserializer.deserialize_struct(#name_str, FIELDS, __Visitor(core::marker::PhantomData))
+    };
+    let spec_impl = spec_impls(name, generics, serialize_body, deserialize_body);
+
+    quote! {
+        #spec_impl
+
+        const FIELDS: &[&str] = &[#(#field_names),*];
+
+        struct __Visitor #impl_generics (core::marker::PhantomData<fn() -> #name #ty_generics>) #where_clause;
+
+        impl #de_impl_generics serde::de::Visitor<'de> for __Visitor #ty_generics #where_clause {
+            type Value = #name #ty_generics;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str(concat!("struct ", #name_str))
+            }
+
+            fn visit_map<__A: serde::de::MapAccess<'de>>(self, mut map: __A) -> Result<Self::Value, __A::Error> {
+                #(#slot_definitions)*
+                while let Some(key) = ssz_rs::__internal::next_field(&mut map)? {
+                    match key.as_str() {
+                        #(#deserialize_by_field)*
+                        key => return Err(serde::de::Error::unknown_field(key, FIELDS)),
+                    }
+                }
+                Ok(#name {
+                    #(#initialization_by_field)*
+                })
+            }
+        }
+    }
+}
+
+fn derive_union(data: &DataEnum, name: &Ident, generics: &Generics) -> TokenStream {
+    let selectors = variant_selectors(data);
+    let serialize_by_variant = data.variants.iter().zip(&selectors).map(|(variant, selector)| {
+        let variant_name = &variant.ident;
+        match &variant.fields {
+            Fields::Unnamed(..) => quote_spanned! { variant.span() =>
+                Self::#variant_name(value) => ssz_rs::spec_serde::serialize_union(
+                    serializer,
+                    #selector,
+                    &ssz_rs::spec_serde::Spec(value),
+                ),
+            },
+            Fields::Unit => quote_spanned! { variant.span() =>
+                Self::None => ssz_rs::spec_serde::serialize_union(serializer, 0, &()),
+            },
+            _ => unreachable!("data was already validated to exclude named variants"),
+        }
+    });
+    let serialize_body = quote! {
+        match self {
+            #(#serialize_by_variant)*
+        }
+    };
+    let deserialize_body = quote! {
+        ssz_rs::spec_serde::deserialize_union(deserializer)
+    };
+    let spec_impl = spec_impls(name, generics, serialize_body, deserialize_body);
+
+    let deserialize_by_variant = data.variants.iter().zip(&selectors).map(|(variant, selector)| {
+        let variant_name = &variant.ident;
+        match &variant.fields {
+            Fields::Unnamed(inner) => {
+                // SAFETY: index is safe because Punctuated always has a first element; qed
+                let variant_type = &inner.unnamed[0].ty;
+                quote_spanned! { variant.span() =>
+                    #selector => {
+                        let value = <ssz_rs::spec_serde::Spec<#variant_type> as serde::Deserialize>::deserialize(deserializer)?;
+                        Ok(Self::#variant_name(value.0))
+                    }
+                }
+            }
+            Fields::Unit => quote_spanned! { variant.span() =>
+                0 => {
+                    <() as serde::Deserialize>::deserialize(deserializer)?;
+                    Ok(Self::None)
+                }
+            },
+            _ => unreachable!("data was already validated to exclude named variants"),
+        }
+    });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        #spec_impl
+
+        impl #impl_generics ssz_rs::spec_serde::SpecUnion for #name #ty_generics #where_clause {
+            fn spec_deserialize_variant<'de, __D: serde::Deserializer<'de>>(
+                selector: u8,
+                deserializer: __D,
+            ) -> Result<Self, __D::Error> {
+                match selector {
+                    #(#deserialize_by_variant)*
+                    selector => Err(serde::de::Error::custom(format_args!("invalid selector {selector}"))),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,623 @@
+//! Derivation of the `StableContainer` and `Profile` types of EIP-7495.
+//!
+//! Both are encoded as a container of their present fields, prefixed with a bitvector of which
+//! optional fields are present, and merkleized like the `StableContainer` at the root of their
+//! definition: every field is placed at its index in the `StableContainer` and the bitvector of
+//! its active fields is mixed into the root.
+use crate::HelperAttr;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    ext::IdentExt, spanned::Spanned, Data, Fields, GenericArgument, Generics, Ident, Path,
+    PathArguments, Type,
+};
+
+enum Kind<'a> {
+    StableContainer { max_fields: usize },
+    Profile { base: &'a Path },
+}
+
+// A field of a `StableContainer` or `Profile`.
+struct StableField<'a> {
+    name: &'a Ident,
+    // The type of the field, or of the value in the field if it is optional.
+    ty: &'a Type,
+    // The index of the field in the bitvector of optional fields, if it is optional.
+    bit: Option<usize>,
+    // The index of the field in the `StableContainer`.
+    index: TokenStream,
+    span: proc_macro2::Span,
+}
+
+impl StableField<'_> {
+    // Expand to `body` if the field is present.
+    fn when_present(&self, body: TokenStream) -> TokenStream {
+        let name = self.name;
+        match self.bit {
+            Some(_) => quote_spanned! { self.span =>
+                if self.#name.is_some() {
+                    #body
+                }
+            },
+            None => body,
+        }
+    }
+
+    // Expand to `body` with `value` bound to the value of the field if the field is present.
+    fn with_value(&self, body: TokenStream) -> TokenStream {
+        let name = self.name;
+        match self.bit {
+            Some(_) => quote_spanned! { self.span =>
+                if let Some(value) = &self.#name {
+                    #body
+                }
+            },
+            None => quote_spanned! { self.span =>
+                {
+                    let value = &self.#name;
+                    #body
+                }
+            },
+        }
+    }
+
+    // Expand to `body` if the field is marked as active in the `active_fields` of an encoding.
+    fn when_active(&self, body: TokenStream) -> TokenStream {
+        match self.bit {
+            Some(bit) => quote_spanned! { self.span =>
+                if active_fields[#bit] {
+                    #body
+                }
+            },
+            None => body,
+        }
+    }
+}
+
+// Return the type wrapped in `Option` if `ty` is an `Option`.
+pub(crate) fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else { return None };
+    if path.qself.is_some() {
+        return None
+    }
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else { return None };
+    match args.args.first() {
+        Some(GenericArgument::Type(ty)) if args.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
+
+// Return the name of the constant holding the index of the field `name` in a `StableContainer`.
+fn index_constant(name: &Ident) -> Ident {
+    format_ident!("__ssz_rs_index_{}", name.unraw())
+}
+
+/// The layout of a Rust struct deriving a `StableContainer` or `Profile`.
+pub(crate) struct StableLayout<'a> {
+    name: &'a Ident,
+    generics: &'a Generics,
+    kind: Kind<'a>,
+    fields: Vec<StableField<'a>>,
+    // The length of the bitvector of optional fields prefixing the encoding, if any.
+    prefix_bits: Option<usize>,
+}
+
+impl<'a> StableLayout<'a> {
+    /// Return the layout of `data` if `helper_attr` requests a `StableContainer` or `Profile`.
+    ///
+    /// Panics if `data` does not conform to the requested type, which aborts the macro derivation.
+    pub(crate) fn new(
+        data: &'a Data,
+        name: &'a Ident,
+        generics: &'a Generics,
+        helper_attr: Option<&'a HelperAttr>,
+    ) -> Option<Self> {
+        let kind = match helper_attr? {
+            HelperAttr::StableContainer { max_fields } => {
+                Kind::StableContainer { max_fields: *max_fields }
+            }
+            HelperAttr::Profile { base } => Kind::Profile { base },
+            HelperAttr::Transparent => return None,
+        };
+        let fields = match data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(fields) => &fields.named,
+                _ => panic!("stable containers and profiles must be structs with named fields"),
+            },
+            _ => panic!("stable containers and profiles must be structs with named fields"),
+        };
+
+        let mut optional_count = 0;
+        let fields = fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let name = field.ident.as_ref().expect("only named fields");
+                let inner_type = option_inner_type(&field.ty);
+                let index = match kind {
+                    Kind::StableContainer { .. } => quote!(#i),
+                    Kind::Profile { base } => {
+                        let index = index_constant(name);
+                        quote!(<#base>::#index)
+                    }
+                };
+                let bit = inner_type.map(|_| {
+                    optional_count += 1;
+                    match kind {
+                        Kind::StableContainer { .. } => i,
+                        Kind::Profile { .. } => optional_count - 1,
+                    }
+                });
+                if matches!(kind, Kind::StableContainer { .. }) && bit.is_none() {
+                    panic!("every field of a stable container must be an `Option`")
+                }
+                StableField {
+                    name,
+                    ty: inner_type.unwrap_or(&field.ty),
+                    bit,
+                    index,
+                    span: field.span(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let prefix_bits = match kind {
+            Kind::StableContainer { max_fields } => {
+                if max_fields == 0 {
+                    panic!("stable containers must have capacity for at least one field")
+                }
+                if fields.len() > max_fields {
+                    panic!("stable container has more than `max_fields` fields")
+                }
+                Some(max_fields)
+            }
+            Kind::Profile { .. } => (optional_count > 0).then_some(optional_count),
+        };
+
+        Some(Self { name, generics, kind, fields, prefix_bits })
+    }
+
+    fn max_fields(&self) -> TokenStream {
+        match self.kind {
+            Kind::StableContainer { max_fields } => quote!(#max_fields),
+            Kind::Profile { base } => quote!(<#base as ssz_rs::StableContainer>::MAX_FIELDS),
+        }
+    }
+
+    fn prefix_len(&self) -> usize {
+        self.prefix_bits.map(|bits| (bits + 7) / 8).unwrap_or_default()
+    }
+
+    // Expand to the `active_fields` bitvector of optional fields present in `self`.
+    fn active_fields(&self) -> TokenStream {
+        let bits = self.prefix_bits.expect("has optional fields");
+        let set_by_field = self.fields.iter().filter_map(|field| {
+            let bit = field.bit?;
+            Some(field.when_present(quote! {
+                active_fields.set(#bit, true);
+            }))
+        });
+        quote! {
+            let mut active_fields = ssz_rs::Bitvector::<#bits>::default();
+            #(#set_by_field)*
+        }
+    }
+
+    // Expand to the roots of the fields present in `self` by their index, as `fields`.
+    fn field_roots(&self) -> TokenStream {
+        let root_by_field = self.fields.iter().map(|field| {
+            let index = &field.index;
+            field.with_value(quote! {
+                fields.push((#index, ssz_rs::HashTreeRoot::hash_tree_root_with::<__H>(value)?));
+            })
+        });
+        quote! {
+            let mut fields = Vec::new();
+            #(#root_by_field)*
+        }
+    }
+
+    pub(crate) fn serializable_impl(&self) -> TokenStream {
+        let (impl_generics, ty_generics, _) = self.generics.split_for_impl();
+        let name = self.name;
+        let prefix_len = self.prefix_len();
+
+        let stream_prefix = match self.prefix_bits {
+            Some(_) => {
+                let active_fields = self.active_fields();
+                quote! {{
+                    #active_fields
+                    ssz_rs::Serialize::serialize_into(&active_fields, sink)?
+                }}
+            }
+            None => quote!(0),
+        };
+        let fixed_len_by_field = self.fields.iter().map(|field| {
+            let ty = field.ty;
+            field.when_present(quote! {
+                fixed_len += ssz_rs::__internal::fixed_part_len::<#ty>();
+            })
+        });
+        let field_count = self.fields.len();
+        let part_len_by_field = self.fields.iter().enumerate().map(|(i, field)| {
+            let ty = field.ty;
+            field.with_value(quote! {
+                encoded_lens[#i] = ssz_rs::Serialize::encoded_len(value);
+                encoded_len += ssz_rs::__internal::part_len::<#ty>(encoded_lens[#i]);
+            })
+        });
+        let fixed_part_by_field = self.fields.iter().enumerate().map(|(i, field)| {
+            field.with_value(quote! {
+                serializer.write_fixed_with_len(value, encoded_lens[#i])?;
+            })
+        });
+        let variable_part_by_field = self.fields.iter().map(|field| {
+            field.with_value(quote! {
+                serializer.write_variable(value)?;
+            })
+        });
+        let encoded_len_by_field = self.fields.iter().map(|field| {
+            field.with_value(quote! {
+                encoded_len += ssz_rs::__internal::element_encoded_len(value);
+            })
+        });
+
+        let deserialize_prefix = self.prefix_bits.map(|bits| {
+            let field_count = self.fields.len();
+            let validate_unknown_fields = (field_count < bits).then(|| {
+                quote! {
+                    for i in #field_count..#bits {
+                        if active_fields[i] {
+                            return Err(ssz_rs::DeserializeError::InvalidByte(prefix[i / 8]))
+                        }
+                    }
+                }
+            });
+            quote! {
+                if encoding.len() < #prefix_len {
+                    return Err(ssz_rs::DeserializeError::ExpectedFurtherInput {
+                        provided: encoding.len(),
+                        expected: #prefix_len,
+                    })
+                }
+                let (prefix, encoding) = encoding.split_at(#prefix_len);
+                let active_fields =
+                    <ssz_rs::Bitvector<#bits> as ssz_rs::Deserialize>::deserialize(prefix)?;
+                #validate_unknown_fields
+            }
+        });
+        let parse_by_field = self.fields.iter().map(|field| {
+            let ty = field.ty;
+            let field_name = field.name.unraw().to_string();
+            field.when_active(quote! {
+                deserializer.parse::<#ty>(encoding).map_err(|err| err.at(#field_name))?;
+            })
+        });
+        let initialization_by_field = self.fields.iter().map(|field| {
+            let name = field.name;
+            let ty = field.ty;
+            let field_name = name.unraw().to_string();
+            let value = quote! {{
+                let span = spans.next().expect("parsed each present field");
+                <#ty as ssz_rs::Deserialize>::deserialize(&encoding[span[0]..span[1]])
+                    .map_err(|err| err.at(#field_name))?
+            }};
+            match field.bit {
+                Some(bit) => quote_spanned! { field.span =>
+                    #name: if active_fields[#bit] { Some(#value) } else { None },
+                },
+                None => quote_spanned! { field.span =>
+                    #name: #value,
+                },
+            }
+        });
+
+        let is_variable_size_impl = match self.prefix_bits {
+            Some(_) => quote!(true),
+            None => {
+                let impl_by_field = self.fields.iter().map(|field| {
+                    let ty = field.ty;
+                    quote!(<#ty as ssz_rs::Serializable>::is_variable_size())
+                });
+                quote!(#(#impl_by_field)||*)
+            }
+        };
+        let size_hint_by_field = self.fields.iter().map(|field| {
+            let ty = field.ty;
+            quote!(<#ty as ssz_rs::Serializable>::size_hint())
+        });
+        // NOTE: an encoding holds at least every required field, and at most every field
+        let min_by_field = self.fields.iter().filter(|field| field.bit.is_none()).map(|field| {
+            let ty = field.ty;
+            quote!(ssz_rs::__internal::min_part_len::<#ty>())
+        });
+        let max_by_field = self.fields.iter().map(|field| {
+            let ty = field.ty;
+            quote!(ssz_rs::__internal::max_part_len::<#ty>())
+        });
+
+        quote! {
+            impl #impl_generics ssz_rs::Serialize for #name #ty_generics {
+                fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, ssz_rs::SerializeError> {
+                    ssz_rs::__internal::serialize_reserved(self, buffer)
+                }
+
+                fn serialize_into<__S: ssz_rs::Sink + ?Sized>(&self, sink: &mut __S) -> Result<usize, ssz_rs::SerializeError> {
+                    let mut fixed_len = 0;
+                    #(#fixed_len_by_field)*
+
+                    // NOTE: the length of each field is only computed once
+                    #[allow(unused_mut)]
+                    let mut encoded_lens = [0usize; #field_count];
+                    // NOTE: offsets are relative to the end of the prefix of present fields
+                    let mut encoded_len = 0;
+                    #(#part_len_by_field)*
+
+                    let prefix_len = #stream_prefix;
+                    let mut serializer = ssz_rs::__internal::StreamSerializer::new(sink, fixed_len, encoded_len)?;
+
+                    #(#fixed_part_by_field)*
+                    #(#variable_part_by_field)*
+
+                    Ok(prefix_len + serializer.finish()?)
+                }
+
+                fn encoded_len(&self) -> usize {
+                    let mut encoded_len = #prefix_len;
+                    #(#encoded_len_by_field)*
+                    encoded_len
+                }
+            }
+
+            impl #impl_generics ssz_rs::Deserialize for #name #ty_generics {
+                fn deserialize(encoding: &[u8]) -> Result<Self, ssz_rs::DeserializeError> {
+                    #deserialize_prefix
+
+                    let mut deserializer = ssz_rs::__internal::ContainerDeserializer::default();
+                    #(#parse_by_field)*
+                    let spans = deserializer.finalize(encoding)?;
+                    let mut spans = spans.chunks_exact(2);
+
+                    Ok(Self {
+                        #(#initialization_by_field)*
+                    })
+                }
+            }
+
+            impl #impl_generics ssz_rs::Serializable for #name #ty_generics {
+                fn is_variable_size() -> bool {
+                    #is_variable_size_impl
+                }
+
+                fn size_hint() -> usize {
+                    if Self::is_variable_size() {
+                        0
+                    } else {
+                        #(#size_hint_by_field)+*
+                    }
+                }
+
+                fn min_encoded_len() -> usize {
+                    #prefix_len #(.saturating_add(#min_by_field))*
+                }
+
+                fn max_encoded_len() -> usize {
+                    #prefix_len #(.saturating_add(#max_by_field))*
+                }
+            }
+        }
+    }
+
+    pub(crate) fn hash_tree_root_impl(&self) -> TokenStream {
+        let (impl_generics, ty_generics, _) = self.generics.split_for_impl();
+        let name = self.name;
+        let field_roots = self.field_roots();
+        let max_fields = self.max_fields();
+
+        quote! {
+            impl #impl_generics ssz_rs::HashTreeRoot for #name #ty_generics {
+                fn hash_tree_root_with<__H: ssz_rs::Hasher>(&self) -> Result<ssz_rs::Node, ssz_rs::MerkleizationError> {
+                    #field_roots
+                    ssz_rs::__internal::merkleize_active_fields::<__H>(&fields, #max_fields)
+                }
+            }
+        }
+    }
+
+    pub(crate) fn generalized_indexable_impl(&self) -> TokenStream {
+        let (impl_generics, ty_generics, _) = self.generics.split_for_impl();
+        let name = self.name;
+        let max_fields = self.max_fields();
+
+        let impl_by_field = self.fields.iter().map(|field| {
+            let selector = field.name.unraw().to_string();
+            let ty = field.ty;
+            let index = &field.index;
+            quote_spanned! { field.span =>
+                #selector => {
+                    let chunk_position = #index;
+                    let child = parent * 2 * ssz_rs::__internal::get_power_of_two_ceil(Self::chunk_count()) + chunk_position;
+                    <#ty as ssz_rs::GeneralizedIndexable>::compute_generalized_index(child, rest)
+                }
+            }
+        });
+
+        let layout_impl = match self.kind {
+            Kind::StableContainer { max_fields } => {
+                let index_by_field = self.fields.iter().enumerate().map(|(i, field)| {
+                    let index = index_constant(field.name);
+                    quote! {
+                        pub const #index: usize = #i;
+                    }
+                });
+                quote! {
+                    impl #impl_generics ssz_rs::StableContainer for #name #ty_generics {
+                        const MAX_FIELDS: usize = #max_fields;
+                    }
+
+                    #[doc(hidden)]
+                    #[allow(non_upper_case_globals)]
+                    impl #impl_generics #name #ty_generics {
+                        #(#index_by_field)*
+                    }
+                }
+            }
+            Kind::Profile { base } => {
+                let order_by_field = self.fields.windows(2).map(|pair| {
+                    let (previous, next) = (&pair[0].index, &pair[1].index);
+                    quote_spanned! { pair[1].span =>
+                        #[allow(clippy::assertions_on_constants)]
+                        const _: () = assert!(
+                            #previous < #next,
+                            "the fields of a profile must follow the order of the fields of its base"
+                        );
+                    }
+                });
+                quote! {
+                    impl #impl_generics ssz_rs::Profile for #name #ty_generics {
+                        type Base = #base;
+                    }
+
+                    #(#order_by_field)*
+                }
+            }
+        };
+
+        quote! {
+            #layout_impl
+
+            impl #impl_generics ssz_rs::GeneralizedIndexable for #name #ty_generics {
+                fn chunk_count() -> usize {
+                    #max_fields
+                }
+
+                fn compute_generalized_index(
+                    parent: ssz_rs::GeneralizedIndex,
+                    path: ssz_rs::Path,
+                ) -> Result<ssz_rs::GeneralizedIndex, ssz_rs::MerkleizationError> {
+                    if let Some((next, rest)) = path.split_first() {
+                        match next {
+                            ssz_rs::PathElement::Field(field) => match field.as_str() {
+                                #(#impl_by_field)*
+                                _ => Err(ssz_rs::MerkleizationError::InvalidPathElement(next.clone())),
+                            },
+                            elem => Err(ssz_rs::MerkleizationError::InvalidPathElement(elem.clone())),
+                        }
+                    } else {
+                        Ok(parent)
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn prove_impl(&self) -> TokenStream {
+        let (impl_generics, ty_generics, _) = self.generics.split_for_impl();
+        let name = self.name;
+        let field_roots = self.field_roots();
+        let max_fields = self.max_fields();
+
+        let prove_by_field = self.fields.iter().map(|field| {
+            let index = &field.index;
+            let prove_impl = field.with_value(quote! {
+                return prover.compute_proof(value)
+            });
+            quote! {
+                if index == #index {
+                    #prove_impl
+                }
+            }
+        });
+        let index_by_field = self.fields.iter().map(|field| {
+            let index = &field.index;
+            field.when_present(quote! {
+                indices.push(#index);
+            })
+        });
+
+        quote! {
+            impl #impl_generics ssz_rs::Prove for #name #ty_generics {
+                fn chunks<__H: ssz_rs::Hasher>(&self) -> Result<Vec<u8>, ssz_rs::MerkleizationError> {
+                    #field_roots
+                    Ok(ssz_rs::__internal::active_field_chunks(&fields, #max_fields))
+                }
+
+                fn prove_element<__H: ssz_rs::Hasher>(
+                    &self,
+                    index: usize,
+                    prover: &mut ssz_rs::proofs::Prover<__H>,
+                ) -> Result<(), ssz_rs::MerkleizationError> {
+                    #(#prove_by_field)*
+                    Err(ssz_rs::MerkleizationError::InvalidInnerIndex)
+                }
+
+                fn decoration_root<__H: ssz_rs::Hasher>(&self) -> Result<Option<ssz_rs::Node>, ssz_rs::MerkleizationError> {
+                    let mut indices = Vec::new();
+                    #(#index_by_field)*
+                    let root = ssz_rs::__internal::active_fields_root::<__H>(indices, #max_fields)?;
+                    Ok(Some(root))
+                }
+            }
+        }
+    }
+
+    pub(crate) fn simple_serialize_impl(&self) -> TokenStream {
+        let (impl_generics, ty_generics, _) = self.generics.split_for_impl();
+        let name = self.name;
+        quote! {
+            impl #impl_generics ssz_rs::SimpleSerialize for #name #ty_generics {}
+        }
+    }
+
+    pub(crate) fn type_info_impl(&self) -> TokenStream {
+        let (impl_generics, ty_generics, _) = self.generics.split_for_impl();
+        let name = self.name;
+        let max_fields = self.max_fields();
+
+        let type_info_impl = match self.kind {
+            Kind::StableContainer { .. } => {
+                let type_info_by_field = self.fields.iter().map(|field| {
+                    let field_name = field.name.unraw().to_string();
+                    let ty = field.ty;
+                    quote_spanned! { field.span =>
+                        (#field_name.to_string(), <#ty as ssz_rs::SszTypeInfo>::type_info()),
+                    }
+                });
+                quote! {
+                    ssz_rs::SszType::StableContainer(#max_fields, vec![#(#type_info_by_field)*])
+                }
+            }
+            Kind::Profile { .. } => {
+                let type_info_by_field = self.fields.iter().map(|field| {
+                    let field_name = field.name.unraw().to_string();
+                    let ty = field.ty;
+                    let index = &field.index;
+                    let optional = field.bit.is_some();
+                    quote_spanned! { field.span =>
+                        ssz_rs::ProfileField {
+                            name: #field_name.to_string(),
+                            ty: <#ty as ssz_rs::SszTypeInfo>::type_info(),
+                            index: #index,
+                            optional: #optional,
+                        },
+                    }
+                });
+                quote! {
+                    ssz_rs::SszType::Profile(#max_fields, vec![#(#type_info_by_field)*])
+                }
+            }
+        };
+
+        quote! {
+            impl #impl_generics ssz_rs::SszTypeInfo for #name #ty_generics {
+                fn type_info() -> ssz_rs::SszType {
+                    #type_info_impl
+                }
+            }
+        }
+    }
+}
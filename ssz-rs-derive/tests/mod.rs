@@ -2,7 +2,7 @@ use ssz_rs::{prelude::*, proofs::ProofAndWitness};
 use ssz_rs_derive::SimpleSerialize;
 use std::fmt;
 
-#[derive(Debug, Clone, SimpleSerialize, PartialEq, Eq)]
+#[derive(Debug, Clone, SimpleSerialize, Viewable, PartialEq, Eq)]
 struct Foo {
     a: u8,
     b: u32,
@@ -37,12 +37,16 @@ fn prove_for_bar(object: &Bar, path: Path) -> Result<ProofAndWitness, Merkleizat
 #[derive(Debug, PartialEq, Eq, SimpleSerialize)]
 struct Wrapper(Foo);
 
-#[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+#[derive(Debug, PartialEq, Eq, SimpleSerialize, Viewable)]
 struct WrappedList(List<u8, 23>);
 
 fn can_serde<T: Serializable + Eq + fmt::Debug>(data: &T) {
     let mut buf = vec![];
     let _ = data.serialize(&mut buf).unwrap();
+    assert_eq!(data.encoded_len(), buf.len());
+    let mut streamed = vec![];
+    let _ = data.serialize_into(&mut streamed).unwrap();
+    assert_eq!(streamed, buf);
     let recovered = T::deserialize(&buf).unwrap();
     assert_eq!(data, &recovered);
 }
@@ -208,3 +212,361 @@ fn test_transparent_helper() {
         assert_eq!((proof, witness), inner_proofs[i]);
     }
 }
+
+#[derive(Debug, PartialEq, Eq, SimpleSerialize, Viewable)]
+struct Baz<const N: usize> {
+    a: u16,
+    b: List<Foo, N>,
+    c: Option<Vector<u8, 3>>,
+    d: WrappedList,
+}
+
+#[test]
+fn test_derived_view() {
+    let element = Foo {
+        a: 23,
+        b: 445,
+        c: List::<usize, 45>::try_from(vec![9, 8, 7, 6, 5, 4]).unwrap(),
+        d: U256::from(234234),
+    };
+    let value = Baz::<4> {
+        a: 12,
+        b: List::try_from(vec![element.clone(), element]).unwrap(),
+        c: Some(Vector::try_from(vec![1u8, 2, 3]).unwrap()),
+        d: WrappedList(List::try_from(vec![5u8, 6]).unwrap()),
+    };
+    let encoding = serialize(&value).unwrap();
+
+    let view = Baz::<4>::view(&encoding).unwrap();
+    assert_eq!(view.a().unwrap(), 12);
+    let list = view.b().unwrap();
+    assert_eq!(list.len(), 2);
+    let element = list.get(1).unwrap().unwrap();
+    assert_eq!(element.b().unwrap(), 445);
+    assert_eq!(element.c().unwrap().get(2).unwrap().unwrap(), 7);
+    assert_eq!(element.d().unwrap(), U256::from(234234));
+    let inner = view.c().unwrap().unwrap();
+    assert_eq!(inner.get(0).unwrap().unwrap(), 1);
+    assert_eq!(view.d().unwrap().len(), 2);
+    assert_eq!(view.deserialize().unwrap(), value);
+
+    let result = Baz::<4>::view(&encoding[..5]).map_err(DeserializeError::into_inner);
+    assert!(matches!(result, Err(DeserializeError::ExpectedFurtherInput { .. })));
+}
+
+#[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+enum Qux {
+    None,
+    A(u8),
+    B(Wrapper),
+}
+
+#[test]
+fn test_derived_hash_tree_root_from_bytes() {
+    let element = Foo {
+        a: 23,
+        b: 445,
+        c: List::<usize, 45>::try_from(vec![9, 8, 7, 6, 5, 4]).unwrap(),
+        d: U256::from(234234),
+    };
+    let value = Baz::<4> {
+        a: 12,
+        b: List::try_from(vec![element.clone(), element.clone()]).unwrap(),
+        c: Some(Vector::try_from(vec![1u8, 2, 3]).unwrap()),
+        d: WrappedList(List::try_from(vec![5u8, 6]).unwrap()),
+    };
+    let encoding = serialize(&value).unwrap();
+    let root = hash_tree_root_from_bytes::<Baz<4>>(&encoding).unwrap();
+    assert_eq!(root, value.hash_tree_root().unwrap());
+
+    let result = hash_tree_root_from_bytes::<Baz<4>>(&encoding[..5]);
+    assert!(result.is_err());
+
+    for value in [Qux::None, Qux::A(7), Qux::B(Wrapper(element))] {
+        let encoding = serialize(&value).unwrap();
+        let root = hash_tree_root_from_bytes::<Qux>(&encoding).unwrap();
+        assert_eq!(root, value.hash_tree_root().unwrap());
+    }
+
+    assert!(hash_tree_root_from_bytes::<Qux>(&[]).is_err());
+    assert!(hash_tree_root_from_bytes::<Qux>(&[0, 1]).is_err());
+    assert!(hash_tree_root_from_bytes::<Qux>(&[3]).is_err());
+}
+
+#[test]
+fn test_derived_merkleization_with_hasher() {
+    use ssz_rs::hasher::Keccak256;
+
+    let element = Foo {
+        a: 23,
+        b: 445,
+        c: List::<usize, 45>::try_from(vec![9, 8, 7, 6, 5, 4]).unwrap(),
+        d: U256::from(234234),
+    };
+    let root = element.hash_tree_root_with::<Keccak256>().unwrap();
+    assert_ne!(root, element.hash_tree_root().unwrap());
+
+    let path = &["c".into(), 2.into()];
+    let (proof, witness) = element.prove_with::<Keccak256>(path).unwrap();
+    assert_eq!(witness, root);
+    assert!(proof.verify_with::<Keccak256>(witness).is_ok());
+
+    let wrapped = Wrapper(element);
+    assert_eq!(wrapped.hash_tree_root_with::<Keccak256>().unwrap(), root);
+
+    let value = Qux::B(wrapped);
+    let (proof, witness) = value.prove_with::<Keccak256>(&[2.into(), "a".into()]).unwrap();
+    assert_eq!(witness, value.hash_tree_root_with::<Keccak256>().unwrap());
+    assert!(proof.verify_with::<Keccak256>(witness).is_ok());
+}
+
+#[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+struct WrappedProgressiveList(ProgressiveList<u16>);
+
+#[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+struct Log {
+    topics: ProgressiveList<U256>,
+    data: WrappedProgressiveList,
+}
+
+#[test]
+fn test_derived_progressive_list() {
+    let value = Log {
+        topics: (0..7).map(U256::from).collect(),
+        data: WrappedProgressiveList((0..50).collect()),
+    };
+    can_serde(&value);
+
+    let paths: [&[PathElement]; 4] = [
+        &["topics".into(), 6.into()],
+        &["topics".into(), PathElement::Length],
+        &["data".into(), 49.into()],
+        &["data".into(), PathElement::Length],
+    ];
+    for path in paths {
+        let (proof, witness) = value.prove(path).unwrap();
+        assert_eq!(witness, value.hash_tree_root().unwrap());
+        assert_eq!(proof.index, Log::generalized_index(path).unwrap());
+        assert!(proof.verify(witness).is_ok());
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, SimpleSerialize, Viewable)]
+enum Payload {
+    #[ssz(selector = 1)]
+    A(u8),
+    #[ssz(selector = 5)]
+    B(Foo),
+    #[ssz(selector = 127)]
+    C(ProgressiveList<u16>),
+}
+
+#[test]
+fn test_derived_compatible_union() {
+    let element = Foo {
+        a: 23,
+        b: 445,
+        c: List::<usize, 45>::try_from(vec![9, 8, 7, 6, 5, 4]).unwrap(),
+        d: U256::from(234234),
+    };
+
+    // selectors that match the position of the variant are merkleized like a legacy union
+    let value = Payload::A(7);
+    let legacy = Qux::A(7);
+    assert_eq!(serialize(&value).unwrap(), serialize(&legacy).unwrap());
+    assert_eq!(value.hash_tree_root().unwrap(), legacy.hash_tree_root().unwrap());
+
+    let value = Payload::B(element.clone());
+    let encoding = serialize(&value).unwrap();
+    assert_eq!(encoding[0], 5);
+    assert_eq!(encoding[1..], serialize(&element).unwrap());
+    can_serde(&value);
+    assert_eq!(Payload::view(&encoding).unwrap(), value);
+    let root = hash_tree_root_from_bytes::<Payload>(&encoding).unwrap();
+    assert_eq!(root, value.hash_tree_root().unwrap());
+
+    let value = Payload::C((0..20).collect());
+    can_serde(&value);
+
+    // selectors of no variant are invalid
+    assert!(Payload::deserialize(&[0, 1]).is_err());
+    assert!(Payload::deserialize(&[2, 1]).is_err());
+    assert!(hash_tree_root_from_bytes::<Payload>(&[2, 1]).is_err());
+
+    let value = Payload::B(element);
+    let path = &[PathElement::Selector];
+    let (proof, witness) = value.prove(path).unwrap();
+    assert_eq!(proof.index, 3);
+    assert_eq!(proof.leaf, 5usize.hash_tree_root().unwrap());
+    assert!(proof.verify(witness).is_ok());
+
+    let path = &[5.into(), "c".into(), 2.into()];
+    let expected = Foo::compute_generalized_index(2, &path[1..]).unwrap();
+    assert_eq!(Payload::generalized_index(path).unwrap(), expected);
+    let (proof, witness) = value.prove(path).unwrap();
+    assert_eq!(witness, value.hash_tree_root().unwrap());
+    assert!(proof.verify(witness).is_ok());
+
+    assert!(Payload::generalized_index(&[2.into()]).is_err());
+}
+
+#[test]
+fn test_derived_encoded_len_bounds() {
+    // NOTE: `c` holds an offset and at most 45 elements
+    assert_eq!((Foo::min_encoded_len(), Foo::max_encoded_len()), (41, 41 + 45 * 8));
+    assert_eq!((Wrapper::min_encoded_len(), Wrapper::max_encoded_len()), (41, 401));
+    assert_eq!((WrappedList::min_encoded_len(), WrappedList::max_encoded_len()), (0, 23));
+    assert_eq!((Bar::min_encoded_len(), Bar::max_encoded_len()), (1, 401));
+    assert_eq!((Qux::min_encoded_len(), Qux::max_encoded_len()), (1, 402));
+    assert_eq!((Payload::min_encoded_len(), Payload::max_encoded_len()), (1, usize::MAX));
+
+    let value = Foo { a: 1, b: 2, c: List::try_from(vec![3; 45]).unwrap(), d: U256::from(4) };
+    assert_eq!(serialize(&value).unwrap().len(), Foo::max_encoded_len());
+    assert_eq!(Foo::type_info().max_encoded_len(), Foo::max_encoded_len());
+
+    // NOTE: derived types reserve their exact encoded length before serializing in place
+    let value = Payload::B(value);
+    let mut buffer = vec![];
+    value.serialize(&mut buffer).unwrap();
+    assert_eq!((buffer.len(), buffer.capacity()), (value.encoded_len(), value.encoded_len()));
+}
+
+#[test]
+fn test_derived_type_info() {
+    let foo_type = SszType::Container(vec![
+        ("a".into(), SszType::Uint(8)),
+        ("b".into(), SszType::Uint(32)),
+        ("c".into(), SszType::List(Box::new(SszType::Uint(64)), 45)),
+        ("d".into(), SszType::Uint(256)),
+    ]);
+    assert_eq!(Foo::type_info(), foo_type);
+    assert_eq!(Wrapper::type_info(), foo_type);
+    assert_eq!(
+        Payload::type_info(),
+        SszType::Union(vec![
+            (1, Some(SszType::Uint(8))),
+            (5, Some(foo_type.clone())),
+            (127, Some(SszType::ProgressiveList(Box::new(SszType::Uint(16))))),
+        ])
+    );
+
+    let ty = Baz::<4>::type_info();
+    let expected = SszType::Container(vec![
+        ("a".into(), SszType::Uint(16)),
+        ("b".into(), SszType::List(Box::new(foo_type), 4)),
+        (
+            "c".into(),
+            SszType::Union(vec![
+                (0, None),
+                (1, Some(SszType::Vector(Box::new(SszType::Uint(8)), 3))),
+            ]),
+        ),
+        ("d".into(), SszType::List(Box::new(SszType::Uint(8)), 23)),
+    ]);
+    assert_eq!(ty, expected);
+
+    let path = &["b".into(), 3.into(), "c".into(), 44.into()];
+    assert_eq!(ty.generalized_index(path).unwrap(), Baz::<4>::generalized_index(path).unwrap());
+}
+
+#[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+#[ssz(serde = "spec")]
+struct Checkpoint {
+    epoch: u64,
+    root: Node,
+}
+
+#[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+#[ssz(serde = "spec")]
+enum Content {
+    None,
+    Checkpoint(Checkpoint),
+    Bytes(List<u8, 4>),
+}
+
+#[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+#[ssz(serde = "spec")]
+struct Epoch(u64);
+
+#[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+#[ssz(serde = "spec")]
+struct Attestation<const N: usize> {
+    aggregation_bits: Bitlist<N>,
+    r#type: u8,
+    target: Checkpoint,
+    epochs: List<Epoch, N>,
+    signature: Vector<u8, 8>,
+    payload: Content,
+    extra: Option<u16>,
+}
+
+#[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+#[ssz(stable_container(max_fields = 4), serde = "spec")]
+struct Shape {
+    side: Option<u16>,
+    color: Option<u8>,
+    radius: Option<u16>,
+}
+
+#[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+#[ssz(serde = "spec", profile(Shape))]
+struct Circle {
+    color: Option<u8>,
+    radius: u16,
+}
+
+#[test]
+fn test_derived_spec_serde() {
+    let value = Attestation::<4> {
+        aggregation_bits: Bitlist::try_from([true, false, true].as_ref()).unwrap(),
+        r#type: 2,
+        target: Checkpoint { epoch: 12, root: Node::repeat_byte(0xab) },
+        epochs: List::try_from(vec![Epoch(1), Epoch(u64::MAX)]).unwrap(),
+        signature: Vector::try_from(vec![1, 2, 3, 4, 5, 6, 7, 8]).unwrap(),
+        payload: Content::Bytes(List::try_from(vec![0xff]).unwrap()),
+        extra: None,
+    };
+    let json = serde_json::to_value(&value).unwrap();
+    let expected = serde_json::json!({
+        "aggregation_bits": "0x0d",
+        "type": "2",
+        "target": { "epoch": "12", "root": format!("0x{}", "ab".repeat(32)) },
+        "epochs": ["1", "18446744073709551615"],
+        "signature": "0x0102030405060708",
+        "payload": { "selector": 2, "value": "0xff" },
+        "extra": { "selector": 0, "value": null },
+    });
+    assert_eq!(json, expected);
+    assert_eq!(serde_json::from_value::<Attestation<4>>(json).unwrap(), value);
+
+    let payload = Content::Checkpoint(Checkpoint::default());
+    let json = serde_json::to_value(&payload).unwrap();
+    assert_eq!(json["selector"], 1);
+    assert_eq!(serde_json::from_value::<Content>(json).unwrap(), payload);
+    let json = serde_json::json!({ "selector": 0 });
+    assert_eq!(serde_json::from_value::<Content>(json).unwrap(), Content::None);
+
+    let shape = Shape { side: None, color: Some(1), radius: Some(300) };
+    let json = serde_json::to_value(&shape).unwrap();
+    assert_eq!(json, serde_json::json!({ "side": null, "color": "1", "radius": "300" }));
+    assert_eq!(serde_json::from_value::<Shape>(json).unwrap(), shape);
+    let json = serde_json::json!({ "color": "1", "radius": "300" });
+    assert_eq!(serde_json::from_value::<Shape>(json).unwrap(), shape);
+
+    let circle = Circle { color: None, radius: 7 };
+    let json = serde_json::to_value(&circle).unwrap();
+    assert_eq!(json, serde_json::json!({ "color": null, "radius": "7" }));
+    assert_eq!(serde_json::from_value::<Circle>(json).unwrap(), circle);
+
+    let invalid = [
+        serde_json::json!({ "epoch": "1" }),
+        serde_json::json!({ "epoch": "1", "root": Node::ZERO, "slot": "2" }),
+        serde_json::json!({ "epoch": "-1", "root": Node::ZERO }),
+    ];
+    for json in invalid {
+        assert!(serde_json::from_value::<Checkpoint>(json.clone()).is_err(), "{json}");
+    }
+    assert!(serde_json::from_value::<Circle>(serde_json::json!({ "color": "1" })).is_err());
+    let json = serde_json::json!({ "selector": 3, "value": "0xff" });
+    assert!(serde_json::from_value::<Content>(json).is_err());
+}
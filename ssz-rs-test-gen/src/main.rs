@@ -4,6 +4,7 @@
 
 use convert_case::{Case, Casing};
 use num_bigint::BigUint;
+use ssz_rs::Schema;
 use std::{collections::BTreeMap, env, ffi::OsStr, fmt, fs, fs::DirEntry, path::PathBuf};
 
 const DRY_RUN: bool = false;
@@ -19,51 +20,39 @@ use test_utils::{
 };
 "#;
 
-const CONTAINERS_DEFN_FMT: &str = r#"
-#[derive(PartialEq, Eq, Debug, Default, SimpleSerialize)]
-struct SingleFieldTestStruct {
-    a: u8,
-}
-
-#[derive(PartialEq, Eq, Debug, Default, SimpleSerialize)]
-struct SmallTestStruct {
-    a: u16,
-    b: u16,
-}
-
-#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
-struct FixedTestStruct {
-    a: u8,
-    b: u64,
-    c: u32,
-}
-
-#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
-struct VarTestStruct {
-    a: u16,
-    b: List<u16, 1024>,
-    c: u8,
-}
-
-#[derive(PartialEq, Eq, Debug, Default, SimpleSerialize)]
-struct ComplexTestStruct {
-    a: u16,
-    b: List<u16, 128>,
-    c: u8,
-    d: List<u8, 256>,
-    e: VarTestStruct,
-    f: Vector<FixedTestStruct, 4>,
-    g: Vector<VarTestStruct, 2>,
-}
-
-#[derive(PartialEq, Eq, Debug, Default, SimpleSerialize)]
-struct BitsStruct {
-    a: Bitlist<5>,
-    b: Bitvector<2>,
-    c: Bitvector<1>,
-    d: Bitlist<6>,
-    e: Bitvector<8>,
-}
+const CONTAINERS_SCHEMA: &str = r#"
+class SingleFieldTestStruct(Container):
+    a: byte
+
+class SmallTestStruct(Container):
+    a: uint16
+    b: uint16
+
+class FixedTestStruct(Container):
+    a: uint8
+    b: uint64
+    c: uint32
+
+class VarTestStruct(Container):
+    a: uint16
+    b: List[uint16, 1024]
+    c: uint8
+
+class ComplexTestStruct(Container):
+    a: uint16
+    b: List[uint16, 128]
+    c: uint8
+    d: List[byte, 256]
+    e: VarTestStruct
+    f: Vector[FixedTestStruct, 4]
+    g: Vector[VarTestStruct, 2]
+
+class BitsStruct(Container):
+    a: Bitlist[5]
+    b: Bitvector[2]
+    c: Bitvector[1]
+    d: Bitlist[6]
+    e: Bitvector[8]
 "#;
 
 #[derive(Clone, Copy, Debug)]
@@ -382,7 +371,8 @@ impl Generator {
     fn new(ssz_type: SszType) -> Self {
         let mut components = vec![SRC_PREAMBLE.to_string()];
         if matches!(ssz_type, SszType::Container) {
-            components.push(CONTAINERS_DEFN_FMT.to_string());
+            let schema = Schema::parse(CONTAINERS_SCHEMA).expect("is valid schema");
+            components.push(ssz_rs_codegen::generate(&schema));
         }
         Self { ssz_type, components, test_cases: Default::default() }
     }
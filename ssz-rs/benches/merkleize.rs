@@ -0,0 +1,101 @@
+//! Compare the batched hashing of `Sha256` against hashing each pair of nodes with the `Digest`
+//! API.
+//!
+//! Run with `cargo bench --bench merkleize`. Without `--bench` (e.g. under `cargo test
+//! --all-targets`), each case runs once to check that the results match.
+use sha2::Digest;
+use ssz_rs::{
+    hasher::{Sha256, ZeroHashes},
+    prelude::*,
+};
+use std::{hint::black_box, sync::OnceLock, time::Instant};
+
+/// `Sha256` as hashed before `Hasher::hash_pairs`, i.e. one pair at a time with the `Digest` API.
+#[derive(Default)]
+struct DigestSha256(sha2::Sha256);
+
+impl Hasher for DigestSha256 {
+    fn hash_nodes(&mut self, left: &[u8], right: &[u8], out: &mut [u8]) {
+        self.0.update(left);
+        self.0.update(right);
+        out.copy_from_slice(&self.0.finalize_reset());
+    }
+
+    fn zero_hashes() -> &'static ZeroHashes {
+        static ZERO_HASHES: OnceLock<ZeroHashes> = OnceLock::new();
+        ZERO_HASHES.get_or_init(ZeroHashes::new::<Self>)
+    }
+}
+
+fn bench<T: PartialEq + std::fmt::Debug>(name: &str, iterations: u32, f: impl Fn() -> T) -> T {
+    let result = f();
+    let start = Instant::now();
+    for _ in 1..iterations {
+        black_box(f());
+    }
+    if iterations > 1 {
+        println!("{name:<40} {:>12.2?}/iter", start.elapsed() / (iterations - 1));
+    }
+    result
+}
+
+fn compare<T: PartialEq + std::fmt::Debug>(
+    name: &str,
+    iterations: u32,
+    baseline: impl Fn() -> T,
+    batched: impl Fn() -> T,
+) {
+    let expected = bench(&format!("{name} (digest)"), iterations, baseline);
+    let result = bench(&format!("{name} (batched)"), iterations, batched);
+    assert_eq!(result, expected);
+}
+
+fn main() {
+    let iterations = if std::env::args().any(|arg| arg == "--bench") { 20 } else { 1 };
+
+    let nodes = (0..1usize << 22).map(|i| (i * 7 + i / 251) as u8).collect::<Vec<_>>();
+    compare(
+        "hash_pairs of 2^16 pairs",
+        iterations * 10,
+        || {
+            let mut out = vec![0u8; nodes.len() / 2];
+            DigestSha256::default().hash_pairs(&nodes, &mut out);
+            out
+        },
+        || {
+            let mut out = vec![0u8; nodes.len() / 2];
+            Sha256::default().hash_pairs(&nodes, &mut out);
+            out
+        },
+    );
+
+    let list = List::<u64, { 1 << 24 }>::try_from((0..1u64 << 20).collect::<Vec<_>>()).unwrap();
+    compare(
+        "hash_tree_root of 2^18 chunks",
+        iterations,
+        || list.hash_tree_root_with::<DigestSha256>().unwrap(),
+        || list.hash_tree_root_with::<Sha256>().unwrap(),
+    );
+
+    let vector = Vector::<u64, 4096>::try_from((0..4096u64).collect::<Vec<_>>()).unwrap();
+    compare(
+        "hash_tree_root of 2^10 chunks",
+        iterations * 100,
+        || vector.hash_tree_root_with::<DigestSha256>().unwrap(),
+        || vector.hash_tree_root_with::<Sha256>().unwrap(),
+    );
+    compare(
+        "prove in 2^10 chunks",
+        iterations * 100,
+        || vector.prove_with::<DigestSha256>(&[1234.into()]).unwrap(),
+        || vector.prove_with::<Sha256>(&[1234.into()]).unwrap(),
+    );
+
+    let (proof, witness) = list.prove(&[123456.into()]).unwrap();
+    compare(
+        "verify proof in 2^22 chunks",
+        iterations * 10000,
+        || proof.verify_with::<DigestSha256>(witness).is_ok(),
+        || proof.verify_with::<Sha256>(witness).is_ok(),
+    );
+}
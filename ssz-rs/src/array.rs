@@ -1,14 +1,21 @@
+#[cfg(feature = "rayon")]
+use crate::merkleization::{elements_to_chunks_in_parallel, ParallelHashTreeRoot};
 use crate::{
     de::{deserialize_homogeneous_composite, Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
     error::{InstanceError, TypeError},
     lib::*,
     merkleization::{
-        elements_to_chunks, get_power_of_two_ceil, merkleize, pack,
+        elements_to_chunks, get_power_of_two_ceil, merkleize_with, pack,
         proofs::{Prove, Prover},
-        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, MerkleizationError, Node, Path,
-        PathElement,
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, PathElement,
     },
-    ser::{Serialize, SerializeError, Serializer},
+    ser::{
+        homogeneous_encoded_len, max_part_len, min_part_len, serialize_homogeneous_into,
+        serialize_reserved, Serialize, SerializeError, Sink,
+    },
+    view::Viewable,
     Serializable, SimpleSerialize,
 };
 
@@ -23,6 +30,14 @@ where
     fn size_hint() -> usize {
         T::size_hint() * N
     }
+
+    fn min_encoded_len() -> usize {
+        N.saturating_mul(min_part_len::<T>())
+    }
+
+    fn max_encoded_len() -> usize {
+        N.saturating_mul(max_part_len::<T>())
+    }
 }
 
 impl<T, const N: usize> Serialize for [T; N]
@@ -33,11 +48,18 @@ where
         if N == 0 {
             return Err(TypeError::InvalidBound(N).into())
         }
-        let mut serializer = Serializer::default();
-        for element in self {
-            serializer.with_element(element)?;
+        serialize_reserved(self, buffer)
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        if N == 0 {
+            return Err(TypeError::InvalidBound(N).into())
         }
-        serializer.serialize(buffer)
+        serialize_homogeneous_into(self.iter(), sink)
+    }
+
+    fn encoded_len(&self) -> usize {
+        homogeneous_encoded_len(self.iter())
     }
 }
 
@@ -76,9 +98,9 @@ impl<T, const N: usize> HashTreeRoot for [T; N]
 where
     T: SimpleSerialize,
 {
-    fn hash_tree_root(&self) -> Result<Node, MerkleizationError> {
-        let chunks = self.chunks()?;
-        merkleize(&chunks, None)
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        let chunks = self.chunks::<H>()?;
+        merkleize_with::<H>(&chunks, None)
     }
 
     fn is_composite_type() -> bool {
@@ -86,6 +108,20 @@ where
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<T, const N: usize> ParallelHashTreeRoot for [T; N]
+where
+    T: SimpleSerialize + Sync,
+{
+    fn par_hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        if !T::is_composite_type() {
+            return self.hash_tree_root_with::<H>()
+        }
+        let chunks = elements_to_chunks_in_parallel::<H, _>(self)?;
+        merkleize_with::<H>(&chunks, None)
+    }
+}
+
 impl<T, const N: usize> GeneralizedIndexable for [T; N]
 where
     T: SimpleSerialize,
@@ -121,16 +157,19 @@ impl<T, const N: usize> Prove for [T; N]
 where
     T: SimpleSerialize,
 {
-    fn chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
         if T::is_composite_type() {
-            let count = self.len();
-            elements_to_chunks(self.iter().enumerate(), count)
+            elements_to_chunks::<H, _>(self)
         } else {
             pack(self)
         }
     }
 
-    fn prove_element(&self, index: usize, prover: &mut Prover) -> Result<(), MerkleizationError> {
+    fn prove_element<H: Hasher>(
+        &self,
+        index: usize,
+        prover: &mut Prover<H>,
+    ) -> Result<(), MerkleizationError> {
         if index >= N {
             Err(MerkleizationError::InvalidInnerIndex)
         } else {
@@ -140,8 +179,28 @@ where
     }
 }
 
+impl<T, const N: usize> Viewable for [T; N]
+where
+    T: Serializable,
+{
+    type View<'a> = Self;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        Self::deserialize(encoding)
+    }
+}
+
 impl<T, const N: usize> SimpleSerialize for [T; N] where T: SimpleSerialize {}
 
+impl<T, const N: usize> SszTypeInfo for [T; N]
+where
+    T: SszTypeInfo,
+{
+    fn type_info() -> SszType {
+        SszType::Vector(Box::new(T::type_info()), N)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
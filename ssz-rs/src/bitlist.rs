@@ -1,13 +1,15 @@
 use crate::{
     de::{Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
     error::{Error, InstanceError},
     lib::*,
     merkleization::{
-        get_power_of_two_ceil, merkleize, mix_in_length, pack_bytes, proofs::Prove,
-        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, MerkleizationError, Node, Path,
-        PathElement, BITS_PER_CHUNK,
+        get_power_of_two_ceil, merkleize_with, mix_in_length, pack_bytes, proofs::Prove,
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, PathElement, BITS_PER_CHUNK,
     },
-    ser::{Serialize, SerializeError},
+    ser::{Serialize, SerializeError, Sink},
+    view::Viewable,
     Serializable, SimpleSerialize,
 };
 #[cfg(feature = "serde")]
@@ -83,6 +85,7 @@ impl<const N: usize> Bitlist<N> {
         if self.len() > N {
             return Err(InstanceError::Bounded { bound: N, provided: self.len() }.into())
         }
+        buffer.reserve(byte_length(self.len()));
         let start_len = buffer.len();
         buffer.extend_from_slice(self.as_raw_slice());
 
@@ -127,12 +130,43 @@ impl<const N: usize> Serializable for Bitlist<N> {
     fn size_hint() -> usize {
         0
     }
+
+    fn min_encoded_len() -> usize {
+        1
+    }
+
+    fn max_encoded_len() -> usize {
+        byte_length(N)
+    }
 }
 
 impl<const N: usize> Serialize for Bitlist<N> {
     fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
         self.serialize_with_length(buffer, true)
     }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        if self.len() > N {
+            return Err(InstanceError::Bounded { bound: N, provided: self.len() }.into())
+        }
+        // NOTE: the bit marking the end of the bitlist is set in the last byte of the encoding
+        let bytes = self.as_raw_slice();
+        let marker_index = self.len() % BITS_PER_BYTE;
+        let last_byte = if marker_index == 0 {
+            sink.write_bytes(bytes)?;
+            1u8
+        } else {
+            let (last, rest) = bytes.split_last().expect("bitlist cannot be empty");
+            sink.write_bytes(rest)?;
+            last | 1u8 << marker_index
+        };
+        sink.write_bytes(&[last_byte])?;
+        Ok(byte_length(self.len()))
+    }
+
+    fn encoded_len(&self) -> usize {
+        byte_length(self.len())
+    }
 }
 
 impl<const N: usize> Deserialize for Bitlist<N> {
@@ -179,10 +213,10 @@ impl<const N: usize> Deserialize for Bitlist<N> {
 }
 
 impl<const N: usize> HashTreeRoot for Bitlist<N> {
-    fn hash_tree_root(&self) -> Result<Node, MerkleizationError> {
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
         let chunks = self.pack_bits()?;
-        let data_root = merkleize(&chunks, Some(Self::chunk_count()))?;
-        Ok(mix_in_length(data_root, self.len()))
+        let data_root = merkleize_with::<H>(&chunks, Some(Self::chunk_count()))?;
+        Ok(mix_in_length::<H>(data_root, self.len()))
     }
 }
 
@@ -218,7 +252,7 @@ impl<const N: usize> GeneralizedIndexable for Bitlist<N> {
 }
 
 impl<const N: usize> Prove for Bitlist<N> {
-    fn chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
         self.pack_bits()
     }
 
@@ -227,8 +261,23 @@ impl<const N: usize> Prove for Bitlist<N> {
     }
 }
 
+// NOTE: bits are cheap to decode so a `Bitlist` is its own view
+impl<const N: usize> Viewable for Bitlist<N> {
+    type View<'a> = Self;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        Self::deserialize(encoding)
+    }
+}
+
 impl<const N: usize> SimpleSerialize for Bitlist<N> {}
 
+impl<const N: usize> SszTypeInfo for Bitlist<N> {
+    fn type_info() -> SszType {
+        SszType::Bitlist(N)
+    }
+}
+
 impl<const N: usize> TryFrom<&[u8]> for Bitlist<N> {
     type Error = Error;
 
@@ -370,6 +419,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn serde_roundtrip() {
         let input = Bitlist::<COUNT>::try_from(
             [
@@ -387,6 +437,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn serde_bitlist() {
         let input = Bitlist::<COUNT>::try_from(
             [true, true, true, true, true, true, true, true, false, true, true, true].as_ref(),
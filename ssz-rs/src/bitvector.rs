@@ -1,13 +1,15 @@
 use crate::{
     de::{Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
     error::{Error, InstanceError, TypeError},
     lib::*,
     merkleization::{
-        get_power_of_two_ceil, merkleize, pack_bytes, proofs::Prove, GeneralizedIndex,
-        GeneralizedIndexable, HashTreeRoot, MerkleizationError, Node, Path, PathElement,
+        get_power_of_two_ceil, merkleize_with, pack_bytes, proofs::Prove, GeneralizedIndex,
+        GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node, Path, PathElement,
         BITS_PER_CHUNK,
     },
-    ser::{Serialize, SerializeError},
+    ser::{Serialize, SerializeError, Sink},
+    view::Viewable,
     Serializable, SimpleSerialize,
 };
 #[cfg(feature = "serde")]
@@ -129,6 +131,20 @@ impl<const N: usize> Serialize for Bitvector<N> {
         }
         Ok(bytes_to_write)
     }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        if N == 0 {
+            return Err(TypeError::InvalidBound(N).into())
+        }
+        for byte in self.0.chunks(BITS_PER_BYTE) {
+            sink.write_bytes(&[byte.load()])?;
+        }
+        Ok(Self::size_hint())
+    }
+
+    fn encoded_len(&self) -> usize {
+        Self::size_hint()
+    }
 }
 
 impl<const N: usize> Deserialize for Bitvector<N> {
@@ -168,9 +184,9 @@ impl<const N: usize> Deserialize for Bitvector<N> {
 }
 
 impl<const N: usize> HashTreeRoot for Bitvector<N> {
-    fn hash_tree_root(&self) -> Result<Node, MerkleizationError> {
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
         let chunks = self.pack_bits()?;
-        merkleize(&chunks, Some(Self::chunk_count()))
+        merkleize_with::<H>(&chunks, Some(Self::chunk_count()))
     }
 }
 
@@ -205,13 +221,28 @@ impl<const N: usize> GeneralizedIndexable for Bitvector<N> {
 }
 
 impl<const N: usize> Prove for Bitvector<N> {
-    fn chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
         self.pack_bits()
     }
 }
 
+// NOTE: bits are cheap to decode so a `Bitvector` is its own view
+impl<const N: usize> Viewable for Bitvector<N> {
+    type View<'a> = Self;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        Self::deserialize(encoding)
+    }
+}
+
 impl<const N: usize> SimpleSerialize for Bitvector<N> {}
 
+impl<const N: usize> SszTypeInfo for Bitvector<N> {
+    fn type_info() -> SszType {
+        SszType::Bitvector(N)
+    }
+}
+
 impl<const N: usize> TryFrom<&[u8]> for Bitvector<N> {
     type Error = Error;
 
@@ -323,6 +354,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn serde_roundtrip() {
         let input = Bitvector::<COUNT>::try_from(
             [false, false, false, true, true, false, false, false, false, false, false, false]
@@ -336,6 +368,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn serde_bitvector() {
         let input = Bitvector::<COUNT>::try_from(
             [false, false, false, true, true, false, false, false, false, true, false, false]
@@ -1,11 +1,13 @@
 use crate::{
     de::{Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
     lib::*,
     merkleization::{
-        proofs::Prove, GeneralizedIndexable, HashTreeRoot, MerkleizationError, Node,
+        proofs::Prove, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
         BYTES_PER_CHUNK,
     },
-    ser::{Serialize, SerializeError},
+    ser::{Serialize, SerializeError, Sink},
+    view::Viewable,
     Serializable, SimpleSerialize,
 };
 
@@ -25,6 +27,15 @@ impl Serialize for bool {
         buffer.push(value);
         Ok(1)
     }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        sink.write_bytes(&[u8::from(*self)])?;
+        Ok(1)
+    }
+
+    fn encoded_len(&self) -> usize {
+        1
+    }
 }
 
 impl Deserialize for bool {
@@ -43,7 +54,7 @@ impl Deserialize for bool {
 }
 
 impl HashTreeRoot for bool {
-    fn hash_tree_root(&self) -> Result<Node, MerkleizationError> {
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
         let mut node = Node::default();
         if *self {
             node[0] = 1;
@@ -63,7 +74,7 @@ impl GeneralizedIndexable for bool {
 }
 
 impl Prove for bool {
-    fn chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
         let mut vec = vec![0u8; BYTES_PER_CHUNK];
         if *self {
             vec[0] = 1;
@@ -72,8 +83,22 @@ impl Prove for bool {
     }
 }
 
+impl Viewable for bool {
+    type View<'a> = Self;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        Self::deserialize(encoding)
+    }
+}
+
 impl SimpleSerialize for bool {}
 
+impl SszTypeInfo for bool {
+    fn type_info() -> SszType {
+        SszType::Boolean
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
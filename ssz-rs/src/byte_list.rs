@@ -0,0 +1,381 @@
+#[cfg(feature = "std")]
+use crate::de::Reader;
+use crate::{
+    de::{Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
+    error::{Error, InstanceError},
+    lib::*,
+    list::List,
+    merkleization::{
+        hasher::Sha256, merkleize, merkleize_with, mix_in_length, pack_bytes, proofs::Prove,
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, BYTES_PER_CHUNK,
+    },
+    ser::{Serialize, SerializeError, Sink},
+    view::Viewable,
+    Serializable, SimpleSerialize,
+};
+use alloy_primitives::Bytes;
+
+/// A variable number of bytes, up to `N`, stored contiguously.
+///
+/// A `ByteList<N>` has the same encoding and merkleization as a `List<u8, N>`, but is encoded,
+/// decoded and chunked as a whole rather than byte-by-byte. With the `serde` feature, it is
+/// represented as a `0x`-prefixed hex string.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Default)]
+pub struct ByteList<const N: usize>(Vec<u8>);
+
+impl<const N: usize> fmt::Debug for ByteList<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "ByteList<{N}>(len={})0x", self.len())?;
+        for byte in &self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> ByteList<N> {
+    /// Append `byte` to the end of the list, returning an error if the list is full.
+    pub fn push(&mut self, byte: u8) -> Result<(), Error> {
+        self.extend_from_slice(&[byte])
+    }
+
+    /// Append `bytes` to the end of the list, returning an error if they do not fit.
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let len = self.len() + bytes.len();
+        if len > N {
+            return Err(Error::Instance(InstanceError::Bounded { bound: N, provided: len }))
+        }
+        self.0.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Shorten the list to `len` bytes, if it is longer.
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len)
+    }
+
+    /// Return the bytes of this list.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+
+    // Number of chunks for this type, rounded up to a complete number of chunks
+    fn chunk_count() -> usize {
+        (N + BYTES_PER_CHUNK - 1) / BYTES_PER_CHUNK
+    }
+
+    fn pack_bytes(&self) -> Vec<u8> {
+        let mut chunks = self.0.clone();
+        pack_bytes(&mut chunks);
+        chunks
+    }
+}
+
+impl<const N: usize> Deref for ByteList<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for ByteList<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for ByteList<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> Serializable for ByteList<N> {
+    fn is_variable_size() -> bool {
+        true
+    }
+
+    fn size_hint() -> usize {
+        0
+    }
+
+    fn min_encoded_len() -> usize {
+        0
+    }
+
+    fn max_encoded_len() -> usize {
+        N
+    }
+}
+
+impl<const N: usize> Serialize for ByteList<N> {
+    fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+        buffer.extend_from_slice(&self.0);
+        Ok(self.len())
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        sink.write_bytes(&self.0)?;
+        Ok(self.len())
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<const N: usize> Deserialize for ByteList<N> {
+    fn deserialize(encoding: &[u8]) -> Result<Self, DeserializeError> {
+        Ok(Self(Self::view(encoding)?.to_vec()))
+    }
+
+    #[cfg(feature = "std")]
+    fn deserialize_reader<R: std::io::Read>(
+        reader: &mut Reader<R>,
+        len: Option<usize>,
+    ) -> Result<Self, DeserializeError> {
+        if let Some(len) = len {
+            if len > N {
+                return Err(InstanceError::Bounded { bound: N, provided: len }.into())
+            }
+        }
+        let encoding = reader.read_encoding(len)?;
+        Self::deserialize(&encoding)
+    }
+}
+
+impl<const N: usize> HashTreeRoot for ByteList<N> {
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        let data_root = merkleize_with::<H>(&self.pack_bytes(), Some(Self::chunk_count()))?;
+        Ok(mix_in_length::<H>(data_root, self.len()))
+    }
+}
+
+impl<const N: usize> GeneralizedIndexable for ByteList<N> {
+    fn chunk_count() -> usize {
+        Self::chunk_count()
+    }
+
+    fn compute_generalized_index(
+        parent: GeneralizedIndex,
+        path: Path,
+    ) -> Result<GeneralizedIndex, MerkleizationError> {
+        List::<u8, N>::compute_generalized_index(parent, path)
+    }
+}
+
+impl<const N: usize> Prove for ByteList<N> {
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        Ok(self.pack_bytes())
+    }
+
+    fn decoration(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+// NOTE: a `ByteList` is viewed as the slice of its bytes within the encoding
+impl<const N: usize> Viewable for ByteList<N> {
+    type View<'a> = &'a [u8];
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        if encoding.len() > N {
+            return Err(InstanceError::Bounded { bound: N, provided: encoding.len() }.into())
+        }
+        Ok(encoding)
+    }
+}
+
+impl<const N: usize> SimpleSerialize for ByteList<N> {
+    fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<Node, Error> {
+        let mut chunks = Self::view(encoding)?.to_vec();
+        pack_bytes(&mut chunks);
+        let data_root = merkleize(&chunks, Some(Self::chunk_count()))?;
+        Ok(mix_in_length::<Sha256>(data_root, encoding.len()))
+    }
+}
+
+impl<const N: usize> SszTypeInfo for ByteList<N> {
+    fn type_info() -> SszType {
+        SszType::List(Box::new(SszType::Uint(8)), N)
+    }
+}
+
+impl<const N: usize> TryFrom<Vec<u8>> for ByteList<N> {
+    type Error = (Vec<u8>, Error);
+
+    fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
+        if data.len() > N {
+            let len = data.len();
+            Err((data, Error::Instance(InstanceError::Bounded { bound: N, provided: len })))
+        } else {
+            Ok(Self(data))
+        }
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8]> for ByteList<N> {
+    type Error = Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() > N {
+            let len = data.len();
+            Err(Error::Instance(InstanceError::Bounded { bound: N, provided: len }))
+        } else {
+            Ok(Self(data.to_vec()))
+        }
+    }
+}
+
+impl<const N: usize> TryFrom<Bytes> for ByteList<N> {
+    type Error = Error;
+
+    fn try_from(data: Bytes) -> Result<Self, Self::Error> {
+        Self::try_from(data.as_ref())
+    }
+}
+
+impl<const N: usize> From<ByteList<N>> for Vec<u8> {
+    fn from(value: ByteList<N>) -> Self {
+        value.0
+    }
+}
+
+impl<const N: usize> From<ByteList<N>> for Bytes {
+    fn from(value: ByteList<N>) -> Self {
+        Bytes::from(value.0)
+    }
+}
+
+impl<const N: usize> From<List<u8, N>> for ByteList<N> {
+    fn from(value: List<u8, N>) -> Self {
+        Self(value.to_vec())
+    }
+}
+
+impl<const N: usize> From<ByteList<N>> for List<u8, N> {
+    fn from(value: ByteList<N>) -> Self {
+        // SAFETY: a `ByteList<N>` holds at most `N` bytes; qed
+        List::try_from(value.0).map_err(|(_, err)| err).expect("bytes are within bound")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for ByteList<N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        alloy_primitives::serde_hex::serialize(&self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for ByteList<N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let data: Vec<u8> = alloy_primitives::serde_hex::deserialize(deserializer)?;
+        Self::try_from(data).map_err(|(_, err)| serde::de::Error::custom(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{serialize, PathElement};
+
+    const COUNT: usize = 100;
+
+    fn sample(len: usize) -> (ByteList<COUNT>, List<u8, COUNT>) {
+        let bytes = (0..len as u8).map(|i| i.wrapping_mul(7)).collect::<Vec<_>>();
+        let value = ByteList::try_from(bytes.as_slice()).unwrap();
+        let expected = List::try_from(bytes).unwrap();
+        (value, expected)
+    }
+
+    #[test]
+    fn test_matches_list() {
+        for len in [0, 1, 31, 32, 33, 64, 99, 100] {
+            let (value, expected) = sample(len);
+
+            let encoding = serialize(&value).unwrap();
+            assert_eq!(encoding, serialize(&expected).unwrap());
+            assert_eq!(value.encoded_len(), encoding.len());
+            assert_eq!(ByteList::<COUNT>::deserialize(&encoding).unwrap(), value);
+            assert_eq!(ByteList::<COUNT>::from(expected.clone()), value);
+            assert_eq!(List::from(value.clone()), expected);
+
+            let root = value.hash_tree_root().unwrap();
+            assert_eq!(root, expected.hash_tree_root().unwrap());
+            assert_eq!(ByteList::<COUNT>::hash_tree_root_from_bytes(&encoding).unwrap(), root);
+        }
+        assert_eq!(ByteList::<COUNT>::type_info(), List::<u8, COUNT>::type_info());
+
+        let (value, expected) = sample(50);
+        for path in [&[PathElement::Index(40)][..], &[PathElement::Length]] {
+            let index = ByteList::<COUNT>::generalized_index(path).unwrap();
+            assert_eq!(index, List::<u8, COUNT>::generalized_index(path).unwrap());
+            let (proof, witness) = value.prove(path).unwrap();
+            assert_eq!(witness, value.hash_tree_root().unwrap());
+            assert!(proof.verify(witness).is_ok());
+            assert_eq!(proof, expected.prove(path).unwrap().0);
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        let result = ByteList::<COUNT>::deserialize(&[0u8; COUNT + 1]);
+        assert!(matches!(
+            result,
+            Err(DeserializeError::InvalidInstance(InstanceError::Bounded { bound: COUNT, .. }))
+        ));
+        assert!(ByteList::<COUNT>::hash_tree_root_from_bytes(&[0u8; COUNT + 1]).is_err());
+        assert!(ByteList::<COUNT>::try_from(vec![0u8; COUNT + 1]).is_err());
+
+        let mut value = ByteList::<3>::try_from([1u8, 2].as_ref()).unwrap();
+        value.push(3).unwrap();
+        assert!(value.push(4).is_err());
+        assert!(value.extend_from_slice(&[4, 5]).is_err());
+        value.truncate(1);
+        value.extend_from_slice(&[4, 5]).unwrap();
+        assert_eq!(&value[..], &[1, 4, 5]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_deserialize_reader() {
+        let (value, _) = sample(40);
+        let encoding = serialize(&value).unwrap();
+        let mut reader = Reader::new(encoding.as_slice(), encoding.len());
+        assert_eq!(ByteList::<COUNT>::deserialize_reader(&mut reader, None).unwrap(), value);
+
+        let mut reader = Reader::new(encoding.as_slice(), encoding.len());
+        let result = ByteList::<32>::deserialize_reader(&mut reader, Some(encoding.len()));
+        assert!(matches!(result, Err(DeserializeError::InvalidInstance(..))));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let value = ByteList::<4>::try_from(Bytes::from_static(&[1, 2, 3])).unwrap();
+        assert_eq!(Bytes::from(value.clone()), Bytes::from_static(&[1, 2, 3]));
+        assert_eq!(Vec::from(value), vec![1, 2, 3]);
+        assert!(ByteList::<2>::try_from(Bytes::from_static(&[1, 2, 3])).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let value = ByteList::<8>::try_from([0xde, 0xad, 0xbe, 0xef].as_ref()).unwrap();
+        let serialization = serde_json::to_string(&value).unwrap();
+        assert_eq!(serialization, "\"0xdeadbeef\"");
+        let recovered: ByteList<8> = serde_json::from_str(&serialization).unwrap();
+        assert_eq!(recovered, value);
+        assert_eq!(serde_json::to_string(&ByteList::<8>::default()).unwrap(), "\"0x\"");
+        assert!(serde_json::from_str::<ByteList<2>>("\"0xdeadbeef\"").is_err());
+    }
+}
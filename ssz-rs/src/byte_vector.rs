@@ -0,0 +1,321 @@
+use crate::{
+    de::{Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
+    error::{Error, InstanceError, TypeError},
+    lib::*,
+    merkleization::{
+        merkleize, merkleize_with, pack_bytes, proofs::Prove, GeneralizedIndex,
+        GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node, Path,
+        BYTES_PER_CHUNK,
+    },
+    ser::{Serialize, SerializeError, Sink},
+    vector::Vector,
+    view::Viewable,
+    Serializable, SimpleSerialize,
+};
+use alloy_primitives::{Bytes, FixedBytes};
+
+/// A fixed number of bytes, stored contiguously.
+///
+/// A `ByteVector<N>` has the same encoding and merkleization as a `Vector<u8, N>`, but is encoded,
+/// decoded and chunked as a whole rather than byte-by-byte. With the `serde` feature, it is
+/// represented as a `0x`-prefixed hex string.
+///
+/// NOTE: a `ByteVector` of length `0` is illegal.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub struct ByteVector<const N: usize>(FixedBytes<N>);
+
+impl<const N: usize> fmt::Debug for ByteVector<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "ByteVector<{N}>({})", self.0)
+    }
+}
+
+impl<const N: usize> ByteVector<N> {
+    fn chunk_count() -> usize {
+        (N + BYTES_PER_CHUNK - 1) / BYTES_PER_CHUNK
+    }
+
+    fn pack_bytes(&self) -> Vec<u8> {
+        let mut chunks = self.0.to_vec();
+        pack_bytes(&mut chunks);
+        chunks
+    }
+}
+
+impl<const N: usize> Deref for ByteVector<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_slice()
+    }
+}
+
+impl<const N: usize> DerefMut for ByteVector<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0.as_mut_slice()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for ByteVector<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+impl<const N: usize> Serializable for ByteVector<N> {
+    fn is_variable_size() -> bool {
+        false
+    }
+
+    fn size_hint() -> usize {
+        N
+    }
+}
+
+impl<const N: usize> Serialize for ByteVector<N> {
+    fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+        if N == 0 {
+            return Err(TypeError::InvalidBound(N).into())
+        }
+        buffer.extend_from_slice(self.0.as_slice());
+        Ok(N)
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        if N == 0 {
+            return Err(TypeError::InvalidBound(N).into())
+        }
+        sink.write_bytes(self.0.as_slice())?;
+        Ok(N)
+    }
+
+    fn encoded_len(&self) -> usize {
+        N
+    }
+}
+
+impl<const N: usize> Deserialize for ByteVector<N> {
+    fn deserialize(encoding: &[u8]) -> Result<Self, DeserializeError> {
+        if N == 0 {
+            return Err(TypeError::InvalidBound(N).into())
+        }
+        if encoding.len() < N {
+            return Err(DeserializeError::ExpectedFurtherInput {
+                provided: encoding.len(),
+                expected: N,
+            })
+        }
+        if encoding.len() > N {
+            return Err(DeserializeError::AdditionalInput { provided: encoding.len(), expected: N })
+        }
+        Ok(Self(FixedBytes::from_slice(encoding)))
+    }
+}
+
+impl<const N: usize> HashTreeRoot for ByteVector<N> {
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        merkleize_with::<H>(&self.pack_bytes(), Some(Self::chunk_count()))
+    }
+}
+
+impl<const N: usize> GeneralizedIndexable for ByteVector<N> {
+    fn chunk_count() -> usize {
+        Self::chunk_count()
+    }
+
+    fn compute_generalized_index(
+        parent: GeneralizedIndex,
+        path: Path,
+    ) -> Result<GeneralizedIndex, MerkleizationError> {
+        Vector::<u8, N>::compute_generalized_index(parent, path)
+    }
+}
+
+impl<const N: usize> Prove for ByteVector<N> {
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        Ok(self.pack_bytes())
+    }
+}
+
+// NOTE: a `ByteVector` is viewed as the slice of its bytes within the encoding
+impl<const N: usize> Viewable for ByteVector<N> {
+    type View<'a> = &'a [u8];
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        if N == 0 {
+            return Err(TypeError::InvalidBound(N).into())
+        }
+        if encoding.len() != N {
+            return Err(InstanceError::Exact { required: N, provided: encoding.len() }.into())
+        }
+        Ok(encoding)
+    }
+}
+
+impl<const N: usize> SimpleSerialize for ByteVector<N> {
+    fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<Node, Error> {
+        let mut chunks = Self::view(encoding)?.to_vec();
+        pack_bytes(&mut chunks);
+        Ok(merkleize(&chunks, Some(Self::chunk_count()))?)
+    }
+}
+
+impl<const N: usize> SszTypeInfo for ByteVector<N> {
+    fn type_info() -> SszType {
+        SszType::Vector(Box::new(SszType::Uint(8)), N)
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for ByteVector<N> {
+    fn from(value: [u8; N]) -> Self {
+        Self(FixedBytes(value))
+    }
+}
+
+impl<const N: usize> From<ByteVector<N>> for [u8; N] {
+    fn from(value: ByteVector<N>) -> Self {
+        value.0 .0
+    }
+}
+
+impl<const N: usize> From<FixedBytes<N>> for ByteVector<N> {
+    fn from(value: FixedBytes<N>) -> Self {
+        Self(value)
+    }
+}
+
+impl<const N: usize> From<ByteVector<N>> for FixedBytes<N> {
+    fn from(value: ByteVector<N>) -> Self {
+        value.0
+    }
+}
+
+impl<const N: usize> From<ByteVector<N>> for Bytes {
+    fn from(value: ByteVector<N>) -> Self {
+        Bytes::copy_from_slice(value.0.as_slice())
+    }
+}
+
+impl<const N: usize> From<Vector<u8, N>> for ByteVector<N> {
+    fn from(value: Vector<u8, N>) -> Self {
+        Self(FixedBytes::from_slice(&value))
+    }
+}
+
+impl<const N: usize> TryFrom<ByteVector<N>> for Vector<u8, N> {
+    type Error = Error;
+
+    fn try_from(value: ByteVector<N>) -> Result<Self, Self::Error> {
+        Vector::try_from(value.0.to_vec()).map_err(|(_, err)| err)
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8]> for ByteVector<N> {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if value.len() != N {
+            let len = value.len();
+            Err(Error::Instance(InstanceError::Exact { required: N, provided: len }))
+        } else {
+            Ok(Self(FixedBytes::from_slice(value)))
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for ByteVector<N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        alloy_primitives::serde_hex::serialize(self.0.as_slice(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for ByteVector<N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let data: Bytes = alloy_primitives::serde_hex::deserialize(deserializer)?;
+        Self::try_from(data.as_ref()).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{serialize, PathElement};
+
+    const COUNT: usize = 37;
+
+    fn sample() -> (ByteVector<COUNT>, Vector<u8, COUNT>) {
+        let bytes = (0..COUNT as u8).map(|i| i.wrapping_mul(7)).collect::<Vec<_>>();
+        let value = ByteVector::try_from(bytes.as_slice()).unwrap();
+        let expected = Vector::try_from(bytes).unwrap();
+        (value, expected)
+    }
+
+    #[test]
+    fn test_matches_vector() {
+        let (value, expected) = sample();
+
+        let encoding = serialize(&value).unwrap();
+        assert_eq!(encoding, serialize(&expected).unwrap());
+        assert_eq!(value.encoded_len(), encoding.len());
+        assert_eq!(ByteVector::<COUNT>::deserialize(&encoding).unwrap(), value);
+        assert_eq!(ByteVector::<COUNT>::from(expected.clone()), value);
+
+        let root = value.hash_tree_root().unwrap();
+        assert_eq!(root, expected.hash_tree_root().unwrap());
+        assert_eq!(ByteVector::<COUNT>::hash_tree_root_from_bytes(&encoding).unwrap(), root);
+        assert_eq!(ByteVector::<COUNT>::type_info(), Vector::<u8, COUNT>::type_info());
+
+        let path = &[PathElement::Index(33)];
+        let index = ByteVector::<COUNT>::generalized_index(path).unwrap();
+        assert_eq!(index, Vector::<u8, COUNT>::generalized_index(path).unwrap());
+        let (proof, witness) = value.prove(path).unwrap();
+        assert_eq!(witness, root);
+        assert!(proof.verify(witness).is_ok());
+        assert_eq!(proof, expected.prove(path).unwrap().0);
+    }
+
+    #[test]
+    fn test_invalid_lengths() {
+        let result = ByteVector::<COUNT>::deserialize(&[0u8; COUNT - 1]);
+        assert!(matches!(result, Err(DeserializeError::ExpectedFurtherInput { .. })));
+        let result = ByteVector::<COUNT>::deserialize(&[0u8; COUNT + 1]);
+        assert!(matches!(result, Err(DeserializeError::AdditionalInput { .. })));
+        let result = ByteVector::<COUNT>::try_from([0u8; 3].as_ref());
+        assert!(matches!(
+            result,
+            Err(Error::Instance(InstanceError::Exact { required: COUNT, provided: 3 }))
+        ));
+        assert!(serialize(&ByteVector::<0>::default()).is_err());
+        assert!(ByteVector::<0>::deserialize(&[]).is_err());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let value = ByteVector::from([1u8, 2, 3, 4]);
+        assert_eq!(&value[..], &[1, 2, 3, 4]);
+        let fixed: FixedBytes<4> = value.into();
+        assert_eq!(ByteVector::from(fixed), value);
+        assert_eq!(Bytes::from(value), Bytes::from_static(&[1, 2, 3, 4]));
+        assert_eq!(<[u8; 4]>::from(value), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let value = ByteVector::from([0xde, 0xad, 0xbe, 0xef]);
+        let serialization = serde_json::to_string(&value).unwrap();
+        assert_eq!(serialization, "\"0xdeadbeef\"");
+        let recovered: ByteVector<4> = serde_json::from_str(&serialization).unwrap();
+        assert_eq!(recovered, value);
+        assert!(serde_json::from_str::<ByteVector<4>>("\"0xdead\"").is_err());
+    }
+}
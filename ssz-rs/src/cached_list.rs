@@ -0,0 +1,452 @@
+use crate::{
+    de::{Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
+    error::Error,
+    lib::*,
+    list::List,
+    merkleization::{
+        hasher::{is_sha256, Sha256},
+        mix_in_length,
+        proofs::{Prove, Prover},
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, SharedElementsCache,
+    },
+    ser::{Serialize, SerializeError, Sink},
+    view::{ListView, Viewable},
+    Serializable, SimpleSerialize,
+};
+
+#[cfg(feature = "std")]
+use crate::de::Reader;
+
+/// A `List` which caches its Merkle tree so that computing the hash tree root after modifying
+/// some elements only re-hashes the branches above those elements.
+///
+/// Modifications made through `IndexMut`, `get_mut`, `push` and `pop` are tracked per element.
+/// Any other mutable access to the underlying `List` (via `DerefMut`) may change any element, so
+/// every element is re-hashed on the next root computation; only the branches whose chunks
+/// actually changed are re-hashed above them.
+///
+/// Computing the hash tree root also stores any pending changes in the cache, including through a
+/// shared reference, e.g. when computing the root of a container holding this list. The cache is
+/// locked while doing so.
+#[derive(Clone)]
+pub struct CachedList<T: Serializable, const N: usize> {
+    data: List<T, N>,
+    cache: SharedElementsCache,
+}
+
+impl<T, const N: usize> CachedList<T, N>
+where
+    T: SimpleSerialize,
+{
+    /// Compute the hash tree root of this list, updating the cache to reflect any changes.
+    ///
+    /// Unlike `HashTreeRoot::hash_tree_root`, this does not need to lock the cache.
+    pub fn recompute_hash_tree_root(&mut self) -> Result<Node, MerkleizationError> {
+        let data_root = self.cache.get_mut().update(&self.data)?;
+        Ok(mix_in_length::<Sha256>(data_root, self.data.len()))
+    }
+}
+
+impl<T, const N: usize> CachedList<T, N>
+where
+    T: Serializable,
+{
+    /// Consume this list, returning the underlying `List`.
+    pub fn into_inner(self) -> List<T, N> {
+        self.data
+    }
+
+    /// Return a mutable reference to the element at `index`, if it exists.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.cache.get_mut().mark(index);
+        self.data.get_mut(index)
+    }
+
+    /// Append `value` to the end of the list.
+    pub fn push(&mut self, value: T) {
+        self.cache.get_mut().mark(self.data.len());
+        self.data.push(value);
+    }
+
+    /// Remove the last element of the list, returning it if it exists.
+    pub fn pop(&mut self) -> Option<T> {
+        // NOTE: the change in length is tracked by the cache
+        self.data.pop()
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for CachedList<T, N>
+where
+    T: Serializable + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        fmt::Debug::fmt(&self.data, f)
+    }
+}
+
+impl<T, const N: usize> Default for CachedList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn default() -> Self {
+        List::default().into()
+    }
+}
+
+impl<T, const N: usize> PartialEq for CachedList<T, N>
+where
+    T: Serializable + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<T, const N: usize> Eq for CachedList<T, N> where T: Serializable + Eq {}
+
+impl<T, const N: usize> From<List<T, N>> for CachedList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn from(data: List<T, N>) -> Self {
+        Self { data, cache: SharedElementsCache::new::<T>(N) }
+    }
+}
+
+impl<T, const N: usize> TryFrom<Vec<T>> for CachedList<T, N>
+where
+    T: SimpleSerialize,
+{
+    type Error = (Vec<T>, Error);
+
+    fn try_from(data: Vec<T>) -> Result<Self, Self::Error> {
+        List::try_from(data).map(Into::into)
+    }
+}
+
+impl<T, const N: usize> Deref for CachedList<T, N>
+where
+    T: Serializable,
+{
+    type Target = List<T, N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<T, const N: usize> DerefMut for CachedList<T, N>
+where
+    T: Serializable,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.cache.get_mut().mark_all();
+        &mut self.data
+    }
+}
+
+impl<T, const N: usize> Index<usize> for CachedList<T, N>
+where
+    T: Serializable,
+{
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for CachedList<T, N>
+where
+    T: Serializable,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.cache.get_mut().mark(index);
+        &mut self.data[index]
+    }
+}
+
+impl<T, const N: usize> Serializable for CachedList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn is_variable_size() -> bool {
+        List::<T, N>::is_variable_size()
+    }
+
+    fn size_hint() -> usize {
+        List::<T, N>::size_hint()
+    }
+
+    fn min_encoded_len() -> usize {
+        List::<T, N>::min_encoded_len()
+    }
+
+    fn max_encoded_len() -> usize {
+        List::<T, N>::max_encoded_len()
+    }
+}
+
+impl<T, const N: usize> Serialize for CachedList<T, N>
+where
+    T: Serializable,
+{
+    fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+        self.data.serialize(buffer)
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        self.data.serialize_into(sink)
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.data.encoded_len()
+    }
+}
+
+impl<T, const N: usize> Deserialize for CachedList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn deserialize(encoding: &[u8]) -> Result<Self, DeserializeError> {
+        List::deserialize(encoding).map(Into::into)
+    }
+
+    #[cfg(feature = "std")]
+    fn deserialize_reader<R: std::io::Read>(
+        reader: &mut Reader<R>,
+        len: Option<usize>,
+    ) -> Result<Self, DeserializeError> {
+        List::deserialize_reader(reader, len).map(Into::into)
+    }
+}
+
+impl<T, const N: usize> HashTreeRoot for CachedList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        // NOTE: the cache only holds `Sha256` Merkle trees
+        if !is_sha256::<H>() {
+            return self.data.hash_tree_root_with::<H>()
+        }
+        let data_root = self.cache.update(&self.data)?;
+        Ok(mix_in_length::<Sha256>(data_root, self.data.len()))
+    }
+}
+
+impl<T, const N: usize> GeneralizedIndexable for CachedList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn chunk_count() -> usize {
+        <List<T, N> as GeneralizedIndexable>::chunk_count()
+    }
+
+    fn compute_generalized_index(
+        parent: GeneralizedIndex,
+        path: Path,
+    ) -> Result<GeneralizedIndex, MerkleizationError> {
+        List::<T, N>::compute_generalized_index(parent, path)
+    }
+}
+
+impl<T, const N: usize> Prove for CachedList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        self.data.chunks::<H>()
+    }
+
+    fn prove_element<H: Hasher>(
+        &self,
+        index: usize,
+        prover: &mut Prover<H>,
+    ) -> Result<(), MerkleizationError> {
+        self.data.prove_element(index, prover)
+    }
+
+    fn decoration(&self) -> Option<usize> {
+        self.data.decoration()
+    }
+}
+
+impl<T, const N: usize> Viewable for CachedList<T, N>
+where
+    T: SimpleSerialize,
+{
+    type View<'a> = ListView<'a, T, N>;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        ListView::new(encoding)
+    }
+}
+
+impl<T, const N: usize> SimpleSerialize for CachedList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<Node, Error> {
+        List::<T, N>::hash_tree_root_from_bytes(encoding)
+    }
+}
+
+impl<T, const N: usize> SszTypeInfo for CachedList<T, N>
+where
+    T: Serializable + SszTypeInfo,
+{
+    fn type_info() -> SszType {
+        SszType::List(Box::new(T::type_info()), N)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const N: usize> serde::Serialize for CachedList<T, N>
+where
+    T: Serializable + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&self.data, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const N: usize> serde::Deserialize<'de> for CachedList<T, N>
+where
+    T: SimpleSerialize + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <List<T, N> as serde::Deserialize>::deserialize(deserializer).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, proofs::tests::compute_and_verify_proof_for_path, U256};
+
+    fn assert_roots_match<T: SimpleSerialize, const N: usize>(value: &mut CachedList<T, N>) {
+        let expected = value.data.hash_tree_root().unwrap();
+        assert_eq!(value.hash_tree_root().unwrap(), expected);
+        assert_eq!(value.recompute_hash_tree_root().unwrap(), expected);
+        assert_eq!(value.hash_tree_root().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_cached_list_of_basic_type() {
+        let mut value = CachedList::<u16, 100>::default();
+        assert_roots_match(&mut value);
+
+        for i in 0..70 {
+            value.push(i);
+            if i % 7 == 0 {
+                assert_roots_match(&mut value);
+            }
+        }
+        assert_roots_match(&mut value);
+
+        value[3] = 2222;
+        assert_roots_match(&mut value);
+        *value.get_mut(69).unwrap() = 3333;
+        value[16] = 4444;
+        value[17] = 5555;
+        assert_roots_match(&mut value);
+
+        for _ in 0..20 {
+            value.pop();
+        }
+        assert_roots_match(&mut value);
+        value.pop();
+        value[0] = 1;
+        assert_roots_match(&mut value);
+
+        value.retain(|x| x % 3 == 0);
+        assert_roots_match(&mut value);
+        value.clear();
+        assert_roots_match(&mut value);
+    }
+
+    #[test]
+    fn test_cached_list_of_composite_type() {
+        let data = (0..33).map(|i| vec![U256::from(i)].try_into().unwrap()).collect::<Vec<_>>();
+        let mut value = CachedList::<List<U256, 2>, 40>::try_from(data).unwrap();
+        assert_roots_match(&mut value);
+
+        value[4].push(U256::from(4444));
+        assert_roots_match(&mut value);
+        value[32].clear();
+        value.push(Default::default());
+        value.push(List::try_from(vec![U256::from(1), U256::from(2)]).unwrap());
+        assert_roots_match(&mut value);
+
+        value.truncate(16);
+        assert_roots_match(&mut value);
+        value.pop();
+        assert_roots_match(&mut value);
+    }
+
+    #[derive(Default, SimpleSerialize)]
+    struct State {
+        slot: u64,
+        balances: CachedList<u64, 1024>,
+    }
+
+    #[derive(Default, SimpleSerialize)]
+    struct UncachedState {
+        slot: u64,
+        balances: List<u64, 1024>,
+    }
+
+    #[test]
+    fn test_cached_list_in_container() {
+        let mut state = State::default();
+        for i in 0..600 {
+            state.balances.push(i);
+        }
+        let root = state.hash_tree_root().unwrap();
+        assert!(state.balances.cache.is_clean(600));
+
+        state.balances[321] = 0;
+        assert!(!state.balances.cache.is_clean(600));
+        let expected = UncachedState { slot: state.slot, balances: state.balances.data.clone() };
+        let expected = expected.hash_tree_root().unwrap();
+        assert_ne!(expected, root);
+        assert_eq!(state.hash_tree_root().unwrap(), expected);
+        assert!(state.balances.cache.is_clean(600));
+        assert_eq!(state.hash_tree_root().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_cached_list_exceeds_limit() {
+        let mut value = CachedList::<u64, 4>::default();
+        for i in 0..5 {
+            value.push(i);
+        }
+        assert!(matches!(value.hash_tree_root(), Err(MerkleizationError::InputExceedsLimit(1))));
+        assert!(value.data.hash_tree_root().is_err());
+        assert!(value.recompute_hash_tree_root().is_err());
+        value.pop();
+        assert_roots_match(&mut value);
+    }
+
+    #[test]
+    fn test_cached_list_roundtrip_and_prove() {
+        let value = CachedList::<u32, 40>::try_from(vec![1u32, 2, 3, 4, 5]).unwrap();
+        let encoding = crate::serialize(&value).unwrap();
+        let mut recovered = CachedList::<u32, 40>::deserialize(&encoding).unwrap();
+        assert_eq!(value, recovered);
+        assert_roots_match(&mut recovered);
+
+        let path = &[3.into()];
+        compute_and_verify_proof_for_path(&recovered, path);
+    }
+}
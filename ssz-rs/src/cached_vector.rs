@@ -0,0 +1,372 @@
+use crate::{
+    de::{Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
+    error::Error,
+    lib::*,
+    merkleization::{
+        hasher::is_sha256,
+        proofs::{Prove, Prover},
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, SharedElementsCache,
+    },
+    ser::{Serialize, SerializeError, Sink},
+    vector::Vector,
+    view::{VectorView, Viewable},
+    Serializable, SimpleSerialize,
+};
+
+#[cfg(feature = "std")]
+use crate::de::Reader;
+
+/// A `Vector` which caches its Merkle tree so that computing the hash tree root after modifying
+/// some elements only re-hashes the branches above those elements.
+///
+/// Modifications made through `IndexMut` and `get_mut` are tracked per element. Any other mutable
+/// access to the underlying `Vector` (via `DerefMut`) may change any element, so every element is
+/// re-hashed on the next root computation; only the branches whose chunks actually changed are
+/// re-hashed above them.
+///
+/// Computing the hash tree root also stores any pending changes in the cache, including through a
+/// shared reference, e.g. when computing the root of a container holding this vector. The cache is
+/// locked while doing so.
+#[derive(Clone)]
+pub struct CachedVector<T: Serializable, const N: usize> {
+    data: Vector<T, N>,
+    cache: SharedElementsCache,
+}
+
+impl<T, const N: usize> CachedVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    /// Compute the hash tree root of this vector, updating the cache to reflect any changes.
+    ///
+    /// Unlike `HashTreeRoot::hash_tree_root`, this does not need to lock the cache.
+    pub fn recompute_hash_tree_root(&mut self) -> Result<Node, MerkleizationError> {
+        self.cache.get_mut().update(&self.data)
+    }
+}
+
+impl<T, const N: usize> CachedVector<T, N>
+where
+    T: Serializable,
+{
+    /// Consume this vector, returning the underlying `Vector`.
+    pub fn into_inner(self) -> Vector<T, N> {
+        self.data
+    }
+
+    /// Return a mutable reference to the element at `index`, if it exists.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.cache.get_mut().mark(index);
+        self.data.get_mut(index)
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for CachedVector<T, N>
+where
+    T: Serializable + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        fmt::Debug::fmt(&self.data, f)
+    }
+}
+
+impl<T, const N: usize> Default for CachedVector<T, N>
+where
+    T: SimpleSerialize + Default,
+{
+    fn default() -> Self {
+        Vector::default().into()
+    }
+}
+
+impl<T, const N: usize> PartialEq for CachedVector<T, N>
+where
+    T: Serializable + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<T, const N: usize> Eq for CachedVector<T, N> where T: Serializable + Eq {}
+
+impl<T, const N: usize> From<Vector<T, N>> for CachedVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn from(data: Vector<T, N>) -> Self {
+        Self { data, cache: SharedElementsCache::new::<T>(N) }
+    }
+}
+
+impl<T, const N: usize> TryFrom<Vec<T>> for CachedVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    type Error = (Vec<T>, Error);
+
+    fn try_from(data: Vec<T>) -> Result<Self, Self::Error> {
+        Vector::try_from(data).map(Into::into)
+    }
+}
+
+impl<T, const N: usize> Deref for CachedVector<T, N>
+where
+    T: Serializable,
+{
+    type Target = Vector<T, N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<T, const N: usize> DerefMut for CachedVector<T, N>
+where
+    T: Serializable,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.cache.get_mut().mark_all();
+        &mut self.data
+    }
+}
+
+impl<T, const N: usize> Index<usize> for CachedVector<T, N>
+where
+    T: Serializable,
+{
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for CachedVector<T, N>
+where
+    T: Serializable,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.cache.get_mut().mark(index);
+        &mut self.data[index]
+    }
+}
+
+impl<T, const N: usize> Serializable for CachedVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn is_variable_size() -> bool {
+        Vector::<T, N>::is_variable_size()
+    }
+
+    fn size_hint() -> usize {
+        Vector::<T, N>::size_hint()
+    }
+
+    fn min_encoded_len() -> usize {
+        Vector::<T, N>::min_encoded_len()
+    }
+
+    fn max_encoded_len() -> usize {
+        Vector::<T, N>::max_encoded_len()
+    }
+}
+
+impl<T, const N: usize> Serialize for CachedVector<T, N>
+where
+    T: Serializable,
+{
+    fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+        self.data.serialize(buffer)
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        self.data.serialize_into(sink)
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.data.encoded_len()
+    }
+}
+
+impl<T, const N: usize> Deserialize for CachedVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn deserialize(encoding: &[u8]) -> Result<Self, DeserializeError> {
+        Vector::deserialize(encoding).map(Into::into)
+    }
+
+    #[cfg(feature = "std")]
+    fn deserialize_reader<R: std::io::Read>(
+        reader: &mut Reader<R>,
+        len: Option<usize>,
+    ) -> Result<Self, DeserializeError> {
+        Vector::deserialize_reader(reader, len).map(Into::into)
+    }
+}
+
+impl<T, const N: usize> HashTreeRoot for CachedVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        // NOTE: the cache only holds `Sha256` Merkle trees
+        if !is_sha256::<H>() {
+            return self.data.hash_tree_root_with::<H>()
+        }
+        self.cache.update(&self.data)
+    }
+}
+
+impl<T, const N: usize> GeneralizedIndexable for CachedVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn chunk_count() -> usize {
+        <Vector<T, N> as GeneralizedIndexable>::chunk_count()
+    }
+
+    fn compute_generalized_index(
+        parent: GeneralizedIndex,
+        path: Path,
+    ) -> Result<GeneralizedIndex, MerkleizationError> {
+        Vector::<T, N>::compute_generalized_index(parent, path)
+    }
+}
+
+impl<T, const N: usize> Prove for CachedVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        self.data.chunks::<H>()
+    }
+
+    fn prove_element<H: Hasher>(
+        &self,
+        index: usize,
+        prover: &mut Prover<H>,
+    ) -> Result<(), MerkleizationError> {
+        self.data.prove_element(index, prover)
+    }
+
+    fn decoration(&self) -> Option<usize> {
+        self.data.decoration()
+    }
+}
+
+impl<T, const N: usize> Viewable for CachedVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    type View<'a> = VectorView<'a, T, N>;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        VectorView::new(encoding)
+    }
+}
+
+impl<T, const N: usize> SimpleSerialize for CachedVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<Node, Error> {
+        Vector::<T, N>::hash_tree_root_from_bytes(encoding)
+    }
+}
+
+impl<T, const N: usize> SszTypeInfo for CachedVector<T, N>
+where
+    T: Serializable + SszTypeInfo,
+{
+    fn type_info() -> SszType {
+        SszType::Vector(Box::new(T::type_info()), N)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const N: usize> serde::Serialize for CachedVector<T, N>
+where
+    T: Serializable + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&self.data, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const N: usize> serde::Deserialize<'de> for CachedVector<T, N>
+where
+    T: SimpleSerialize + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <Vector<T, N> as serde::Deserialize>::deserialize(deserializer).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{proofs::tests::compute_and_verify_proof_for_path, List};
+
+    fn assert_roots_match<T: SimpleSerialize, const N: usize>(value: &mut CachedVector<T, N>) {
+        let expected = value.data.hash_tree_root().unwrap();
+        assert_eq!(value.hash_tree_root().unwrap(), expected);
+        assert_eq!(value.recompute_hash_tree_root().unwrap(), expected);
+        assert_eq!(value.hash_tree_root().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_cached_vector_of_basic_type() {
+        let mut value = CachedVector::<u64, 37>::default();
+        assert_roots_match(&mut value);
+
+        value[0] = 1;
+        assert_roots_match(&mut value);
+        value[36] = 36;
+        *value.get_mut(17).unwrap() = 17;
+        assert_roots_match(&mut value);
+
+        value.iter_mut().enumerate().for_each(|(i, x)| *x = i as u64 * 3);
+        assert_roots_match(&mut value);
+        value[20] = 60;
+        assert_roots_match(&mut value);
+    }
+
+    #[test]
+    fn test_cached_vector_of_composite_type() {
+        let mut value = CachedVector::<List<bool, 8>, 5>::default();
+        assert_roots_match(&mut value);
+
+        value[2].push(true);
+        assert_roots_match(&mut value);
+        value[4].push(false);
+        value[0].push(true);
+        assert_roots_match(&mut value);
+
+        value.swap(0, 2);
+        assert_roots_match(&mut value);
+
+        let path = &[2.into(), 0.into()];
+        compute_and_verify_proof_for_path(&value, path);
+    }
+
+    #[test]
+    fn test_cached_vector_roundtrip() {
+        let value = CachedVector::<u8, 3>::try_from(vec![1u8, 2, 3]).unwrap();
+        let encoding = crate::serialize(&value).unwrap();
+        let mut recovered = CachedVector::<u8, 3>::deserialize(&encoding).unwrap();
+        assert_eq!(value, recovered);
+        assert_roots_match(&mut recovered);
+    }
+}
@@ -180,15 +180,15 @@ mod tests {
     #[test]
     fn decode_variable_container_with_no_input() {
         let data = vec![];
-        let result = AnotherVarTestStruct::deserialize(&data);
-        assert!(matches!(result, Err(DeserializeError::ExpectedFurtherInput { .. })));
+        let err = AnotherVarTestStruct::deserialize(&data).unwrap_err();
+        assert!(matches!(err.inner(), DeserializeError::ExpectedFurtherInput { .. }));
     }
 
     #[test]
     fn decode_fixed_container_with_no_input() {
         let data = vec![];
-        let result = BasicContainer::deserialize(&data);
-        assert!(matches!(result, Err(DeserializeError::ExpectedFurtherInput { .. })));
+        let err = BasicContainer::deserialize(&data).unwrap_err();
+        assert!(matches!(err.inner(), DeserializeError::ExpectedFurtherInput { .. }));
     }
 
     #[test]
@@ -223,6 +223,45 @@ mod tests {
         assert_eq!(value, recovered);
     }
 
+    #[test]
+    fn serialize_container_into_sink() {
+        #[derive(Default, Debug, PartialEq, Eq, Serializable)]
+        struct Nested {
+            a: SerializableStruct,
+            b: Option<List<u8, 8>>,
+            c: List<AnotherVarTestStruct, 4>,
+            d: Bitlist<16>,
+        }
+
+        let value = Nested {
+            a: SerializableStruct {
+                a: List::try_from(vec![1u16, 2, 3]).unwrap(),
+                b: 2u16,
+                c: 16u8,
+                d: BasicContainer { a: 5u32, d: true },
+            },
+            b: Some(List::try_from(vec![9u8, 8]).unwrap()),
+            c: List::try_from(vec![
+                AnotherVarTestStruct { a: List::try_from(vec![7u16]).unwrap(), b: 1, c: 2 },
+                AnotherVarTestStruct::default(),
+            ])
+            .unwrap(),
+            d: Bitlist::try_from(&[true, false, true][..]).unwrap(),
+        };
+
+        let mut expected = vec![];
+        let expected_len = value.serialize(&mut expected).expect("can serialize");
+        assert_eq!(value.encoded_len(), expected_len);
+
+        let mut buffer = vec![];
+        let len = value.serialize_into(&mut buffer).expect("can serialize");
+        assert_eq!(len, expected_len);
+        assert_eq!(buffer, expected);
+
+        let recovered = Nested::deserialize(&buffer).expect("can decode");
+        assert_eq!(value, recovered);
+    }
+
     #[test]
     fn test_prove_container() {
         type V = Vector<u8, 25>;
@@ -1,6 +1,7 @@
 use crate::{
     error::{InstanceError, TypeError},
     lib::*,
+    merkleization::PathElement,
     ser::BYTES_PER_LENGTH_OFFSET,
     Serializable,
 };
@@ -27,6 +28,50 @@ pub enum DeserializeError {
     /// No corresponding variant of the requested enum was present. (refer to `transparent`
     /// attribute of `ssz-rs-derive` macro)
     NoMatchingVariant,
+    /// The value at `path`, relative to the value being deserialized, could not be deserialized.
+    AtPath { path: Vec<PathElement>, error: Box<DeserializeError> },
+    /// The reader could not provide the encoding.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+}
+
+impl DeserializeError {
+    /// Prepend `element` to the path of `self`, e.g. when `self` occurred deserializing the field
+    /// or element named by `element`.
+    pub fn at(self, element: impl Into<PathElement>) -> Self {
+        match self {
+            Self::AtPath { mut path, error } => {
+                path.insert(0, element.into());
+                Self::AtPath { path, error }
+            }
+            error => Self::AtPath { path: vec![element.into()], error: Box::new(error) },
+        }
+    }
+
+    /// Return the path of the value where `self` occurred, relative to the value being
+    /// deserialized, e.g. `[Field("body"), Field("attestations"), Index(3)]`.
+    pub fn path(&self) -> &[PathElement] {
+        match self {
+            Self::AtPath { path, .. } => path,
+            _ => &[],
+        }
+    }
+
+    /// Return `self` without its path, e.g. to match on the kind of error.
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::AtPath { error, .. } => error,
+            error => error,
+        }
+    }
+
+    /// Return `self` without its path.
+    pub fn into_inner(self) -> Self {
+        match self {
+            Self::AtPath { error, .. } => *error,
+            error => error,
+        }
+    }
 }
 
 impl From<InstanceError> for DeserializeError {
@@ -56,6 +101,27 @@ impl Display for DeserializeError {
             DeserializeError::OffsetNotIncreasing { start, end } => write!(f, "invalid offset points to byte {end} before byte {start}"),
             DeserializeError::MissingOffset => write!(f, "an offset was missing when deserializing a variable-sized type"),
             DeserializeError::NoMatchingVariant => write!(f, "no corresponding variant of the requested enum was present"),
+            DeserializeError::AtPath { path, error } => {
+                write!(f, "at ")?;
+                for (i, element) in path.iter().enumerate() {
+                    let name = match element {
+                        PathElement::Index(index) => {
+                            write!(f, "[{index}]")?;
+                            continue
+                        }
+                        PathElement::Field(name) => name.as_str(),
+                        PathElement::Length => "__len__",
+                        PathElement::Selector => "__selector__",
+                    };
+                    if i > 0 {
+                        write!(f, ".")?;
+                    }
+                    write!(f, "{name}")?;
+                }
+                write!(f, ": {error}")
+            }
+            #[cfg(feature = "std")]
+            DeserializeError::Io(err) => write!(f, "could not read from reader: {err}"),
         }
     }
 }
@@ -69,6 +135,134 @@ pub trait Deserialize {
     fn deserialize(encoding: &[u8]) -> Result<Self, DeserializeError>
     where
         Self: Sized;
+
+    /// Deserialize this value from the next `len` bytes of `reader`, or from the rest of `reader`
+    /// if `len` is `None`.
+    ///
+    /// The default implementation reads the entire encoding into a buffer; types holding other
+    /// values should override it to read each part as it is needed.
+    #[cfg(feature = "std")]
+    fn deserialize_reader<R: std::io::Read>(
+        reader: &mut Reader<R>,
+        len: Option<usize>,
+    ) -> Result<Self, DeserializeError>
+    where
+        Self: Sized,
+    {
+        let encoding = reader.read_encoding(len)?;
+        Self::deserialize(&encoding)
+    }
+}
+
+/// A `std::io::Read` from which at most `limit` bytes are read when deserializing.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct Reader<R> {
+    inner: R,
+    position: usize,
+    limit: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Reader<R> {
+    /// Prepare to read an encoding of at most `limit` bytes from `inner`.
+    pub fn new(inner: R, limit: usize) -> Self {
+        Self { inner, position: 0, limit }
+    }
+
+    /// Return the number of bytes read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Consume this reader, returning the underlying `std::io::Read`.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    // Reads into `buffer` until it is full or the end of the input is reached,
+    // returning the number of bytes read.
+    fn read_up_to(&mut self, buffer: &mut [u8]) -> Result<usize, DeserializeError> {
+        let available = self.limit - self.position;
+        let target = buffer.len().min(available.saturating_add(1));
+        let mut filled = 0;
+        while filled < target {
+            match self.inner.read(&mut buffer[filled..target]) {
+                Ok(0) => break,
+                Ok(count) => filled += count,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(DeserializeError::Io(err)),
+            }
+        }
+        if filled > available {
+            return Err(DeserializeError::AdditionalInput {
+                provided: self.position + filled,
+                expected: self.limit,
+            })
+        }
+        self.position += filled;
+        Ok(filled)
+    }
+
+    /// Fill `buffer` from the input.
+    pub fn read_exact(&mut self, buffer: &mut [u8]) -> Result<(), DeserializeError> {
+        let filled = self.read_up_to(buffer)?;
+        if filled < buffer.len() {
+            return Err(DeserializeError::ExpectedFurtherInput {
+                provided: filled,
+                expected: buffer.len(),
+            })
+        }
+        Ok(())
+    }
+
+    // Ensures the next `len` bytes are within the limit, so that buffers of that size can be
+    // allocated before reading into them.
+    fn check_len(&self, len: Option<usize>) -> Result<(), DeserializeError> {
+        if let Some(len) = len {
+            let available = self.limit - self.position;
+            if len > available {
+                return Err(DeserializeError::AdditionalInput {
+                    provided: self.position + len,
+                    expected: self.limit,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the next `len` bytes of the input, or the rest of the input if `len` is `None`.
+    pub fn read_encoding(&mut self, len: Option<usize>) -> Result<Vec<u8>, DeserializeError> {
+        self.check_len(len)?;
+        match len {
+            Some(len) => {
+                let mut buffer = vec![0u8; len];
+                self.read_exact(&mut buffer)?;
+                Ok(buffer)
+            }
+            None => {
+                let mut buffer = vec![];
+                let mut chunk = [0u8; 4096];
+                loop {
+                    let filled = self.read_up_to(&mut chunk)?;
+                    if filled == 0 {
+                        return Ok(buffer)
+                    }
+                    buffer.extend_from_slice(&chunk[..filled]);
+                }
+            }
+        }
+    }
+
+    /// Ensure there is no more input to read.
+    pub fn expect_end(&mut self) -> Result<(), DeserializeError> {
+        let expected = self.position;
+        let mut buffer = [0u8; 1];
+        if self.read_up_to(&mut buffer)? != 0 {
+            return Err(DeserializeError::AdditionalInput { provided: expected + 1, expected })
+        }
+        Ok(())
+    }
 }
 
 fn deserialize_fixed_homogeneous_composite<T>(encoding: &[u8]) -> Result<Vec<T>, DeserializeError>
@@ -79,8 +273,8 @@ where
     debug_assert_eq!(encoding.len() % T::size_hint(), 0);
 
     let mut elements = vec![];
-    for chunk in encoding.chunks_exact(T::size_hint()) {
-        let element = T::deserialize(chunk)?;
+    for (i, chunk) in encoding.chunks_exact(T::size_hint()).enumerate() {
+        let element = T::deserialize(chunk).map_err(|err| err.at(i))?;
         elements.push(element);
     }
     Ok(elements)
@@ -92,6 +286,15 @@ fn deserialize_variable_homogeneous_composite<T>(
 where
     T: Deserialize,
 {
+    deserialize_variable_homogeneous_composite_with(encoding, T::deserialize)
+}
+
+// Deserializes the variable-size elements of a homogeneous collection from `encoding`, decoding
+// each element with `deserialize_element`.
+pub(crate) fn deserialize_variable_homogeneous_composite_with<T>(
+    encoding: &[u8],
+    mut deserialize_element: impl FnMut(&[u8]) -> Result<T, DeserializeError>,
+) -> Result<Vec<T>, DeserializeError> {
     if encoding.is_empty() {
         return Ok(vec![])
     }
@@ -122,7 +325,7 @@ where
 
     let element_count = offsets_len / BYTES_PER_LENGTH_OFFSET;
     let mut result = Vec::with_capacity(element_count);
-    for span in offsets.windows(2) {
+    for (i, span) in offsets.windows(2).enumerate() {
         // SAFETY: index is safe because span is a pair; qed
         let start = span[0];
         let end = span[1];
@@ -131,7 +334,7 @@ where
         }
 
         // SAFETY: index is safe because start <= end; qed
-        let element = T::deserialize(&encoding[start..end])?;
+        let element = deserialize_element(&encoding[start..end]).map_err(|err| err.at(i))?;
         result.push(element);
     }
     Ok(result)
@@ -148,6 +351,190 @@ where
     }
 }
 
+#[cfg(feature = "std")]
+fn deserialize_fixed_homogeneous_composite_from_reader<T, R>(
+    reader: &mut Reader<R>,
+    len: Option<usize>,
+    bound: usize,
+) -> Result<Vec<T>, DeserializeError>
+where
+    T: Serializable,
+    R: std::io::Read,
+{
+    let size = T::size_hint();
+    let mut buffer = vec![0u8; size];
+    let mut elements = vec![];
+    match len {
+        Some(len) => {
+            let remainder = len % size;
+            if remainder != 0 {
+                return Err(DeserializeError::AdditionalInput {
+                    provided: len,
+                    // SAFETY: checked subtraction is unnecessary, as len > remainder; qed
+                    expected: len - remainder,
+                })
+            }
+            let count = len / size;
+            if count > bound {
+                return Err(InstanceError::Bounded { bound, provided: count }.into())
+            }
+            reader.check_len(Some(len))?;
+            elements.reserve_exact(count);
+            for i in 0..count {
+                reader.read_exact(&mut buffer)?;
+                elements.push(T::deserialize(&buffer).map_err(|err| err.at(i))?);
+            }
+        }
+        None => loop {
+            let filled = reader.read_up_to(&mut buffer)?;
+            if filled == 0 {
+                break
+            }
+            if filled < size {
+                let provided = elements.len() * size + filled;
+                return Err(DeserializeError::AdditionalInput {
+                    provided,
+                    expected: provided - filled,
+                })
+            }
+            if elements.len() == bound {
+                return Err(InstanceError::Bounded { bound, provided: bound + 1 }.into())
+            }
+            let element = T::deserialize(&buffer).map_err(|err| err.at(elements.len()))?;
+            elements.push(element);
+        },
+    }
+    Ok(elements)
+}
+
+#[cfg(feature = "std")]
+fn deserialize_variable_homogeneous_composite_from_reader<T, R>(
+    reader: &mut Reader<R>,
+    len: Option<usize>,
+    bound: usize,
+) -> Result<Vec<T>, DeserializeError>
+where
+    T: Deserialize,
+    R: std::io::Read,
+{
+    reader.check_len(len)?;
+    let mut offset = [0u8; BYTES_PER_LENGTH_OFFSET];
+    match len {
+        Some(0) => return Ok(vec![]),
+        Some(len) if len < BYTES_PER_LENGTH_OFFSET => {
+            return Err(DeserializeError::ExpectedFurtherInput {
+                provided: len,
+                expected: BYTES_PER_LENGTH_OFFSET,
+            })
+        }
+        Some(_) => reader.read_exact(&mut offset)?,
+        None => {
+            let filled = reader.read_up_to(&mut offset)?;
+            if filled == 0 {
+                return Ok(vec![])
+            }
+            if filled < BYTES_PER_LENGTH_OFFSET {
+                return Err(DeserializeError::ExpectedFurtherInput {
+                    provided: filled,
+                    expected: BYTES_PER_LENGTH_OFFSET,
+                })
+            }
+        }
+    }
+
+    let offsets_len = u32::from_le_bytes(offset) as usize;
+    if let Some(len) = len {
+        if len < offsets_len {
+            return Err(DeserializeError::ExpectedFurtherInput {
+                provided: len,
+                expected: offsets_len,
+            })
+        }
+    }
+    if offsets_len % BYTES_PER_LENGTH_OFFSET != 0 {
+        return Err(DeserializeError::InvalidOffsetsLength(offsets_len))
+    }
+    let element_count = offsets_len / BYTES_PER_LENGTH_OFFSET;
+    if element_count == 0 {
+        // NOTE: any remaining input is ignored, matching `deserialize_homogeneous_composite`
+        let _ = reader.read_encoding(len.map(|len| len - BYTES_PER_LENGTH_OFFSET))?;
+        return Ok(vec![])
+    }
+    if element_count > bound {
+        return Err(InstanceError::Bounded { bound, provided: element_count }.into())
+    }
+    reader.check_len(Some(offsets_len - BYTES_PER_LENGTH_OFFSET))?;
+
+    let mut offsets = Vec::with_capacity(element_count);
+    offsets.push(offsets_len);
+    for _ in 1..element_count {
+        reader.read_exact(&mut offset)?;
+        offsets.push(u32::from_le_bytes(offset) as usize);
+    }
+
+    let mut result = Vec::with_capacity(element_count);
+    for (i, &start) in offsets.iter().enumerate() {
+        let element_len = match offsets.get(i + 1).copied().or(len) {
+            Some(end) => {
+                if start > end {
+                    return Err(DeserializeError::OffsetNotIncreasing { start, end })
+                }
+                Some(end - start)
+            }
+            None => None,
+        };
+        result.push(T::deserialize_reader(reader, element_len).map_err(|err| err.at(i))?);
+    }
+    Ok(result)
+}
+
+// Deserializes the elements of a homogeneous collection of at most `bound` elements from the
+// next `len` bytes of `reader`, or the rest of `reader` if `len` is `None`.
+#[cfg(feature = "std")]
+pub(crate) fn deserialize_homogeneous_composite_from_reader<T, R>(
+    reader: &mut Reader<R>,
+    len: Option<usize>,
+    bound: usize,
+) -> Result<Vec<T>, DeserializeError>
+where
+    T: Serializable,
+    R: std::io::Read,
+{
+    if T::is_variable_size() {
+        deserialize_variable_homogeneous_composite_from_reader(reader, len, bound)
+    } else {
+        deserialize_fixed_homogeneous_composite_from_reader(reader, len, bound)
+    }
+}
+
+// Reads the selector of a union from the next `len` bytes of `reader`, or the rest of `reader`
+// if `len` is `None`, returning the selector and the length of the remaining value.
+#[cfg(feature = "std")]
+pub fn read_selector<R: std::io::Read>(
+    reader: &mut Reader<R>,
+    len: Option<usize>,
+) -> Result<(u8, Option<usize>), DeserializeError> {
+    let mut selector = [0u8; 1];
+    if len == Some(0) || reader.read_up_to(&mut selector)? == 0 {
+        return Err(DeserializeError::ExpectedFurtherInput { provided: 0, expected: 1 })
+    }
+    Ok((selector[0], len.map(|len| len - 1)))
+}
+
+// Ensures no value follows the selector of the `None` variant of a union, given the remaining
+// length returned from `read_selector`.
+#[cfg(feature = "std")]
+pub fn read_none_value<R: std::io::Read>(
+    reader: &mut Reader<R>,
+    len: Option<usize>,
+) -> Result<(), DeserializeError> {
+    match len {
+        Some(0) => Ok(()),
+        Some(len) => Err(DeserializeError::AdditionalInput { provided: len + 1, expected: 1 }),
+        None => reader.expect_end(),
+    }
+}
+
 #[derive(Debug)]
 enum Segment {
     Fixed(usize, usize),
@@ -171,8 +558,19 @@ pub struct ContainerDeserializer {
 impl ContainerDeserializer {
     // NOTE: segments must be parsed in order following the order of the fields of the container.
     pub fn parse<T: Serializable>(&mut self, encoding: &[u8]) -> Result<(), DeserializeError> {
+        self.parse_segment(encoding, T::is_variable_size(), T::size_hint())
+    }
+
+    // Parse the segment of a field of a variable or fixed size, where `size_hint` is the size of
+    // a field of a fixed size.
+    pub(crate) fn parse_segment(
+        &mut self,
+        encoding: &[u8],
+        is_variable_size: bool,
+        size_hint: usize,
+    ) -> Result<(), DeserializeError> {
         let start = self.total_bytes_read;
-        if T::is_variable_size() {
+        if is_variable_size {
             let end = start + BYTES_PER_LENGTH_OFFSET;
 
             let target =
@@ -192,14 +590,14 @@ impl ContainerDeserializer {
 
                 if *previous_offset > encoding.len() {
                     return Err(DeserializeError::ExpectedFurtherInput {
-                        provided: encoding.len() - previous_offset,
+                        provided: encoding.len().saturating_sub(*previous_offset),
                         expected: next_offset - previous_offset,
                     })
                 }
 
                 if next_offset > encoding.len() {
                     return Err(DeserializeError::ExpectedFurtherInput {
-                        provided: encoding.len() - next_offset,
+                        provided: encoding.len().saturating_sub(next_offset),
                         expected: next_offset - previous_offset,
                     })
                 }
@@ -209,7 +607,7 @@ impl ContainerDeserializer {
             self.offsets.push(next_offset);
             self.segments.push(Segment::Offset);
         } else {
-            let encoded_length = T::size_hint();
+            let encoded_length = size_hint;
             let end = self.total_bytes_read + encoded_length;
             if encoding.len() < self.total_bytes_read {
                 return Err(DeserializeError::ExpectedFurtherInput {
@@ -286,3 +684,281 @@ impl ContainerDeserializer {
         Ok(spans)
     }
 }
+
+// `StreamDeserializer` is the counterpart of `ContainerDeserializer` reading from a `Reader`.
+// Intended use:
+// - call `parse` for each field of the container, in order, to validate the fixed part
+// - call `read` for each field of the container, in order, to decode each field
+// - call `finish` to ensure all of the input was read
+// NOTE: mainly intended for private use in the proc derive macro.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct StreamDeserializer<'a, R> {
+    reader: &'a mut Reader<R>,
+    len: Option<usize>,
+    fixed: Vec<u8>,
+    segments: Vec<Segment>,
+    offsets: Vec<usize>,
+    cursor: usize,
+    next_segment: usize,
+    next_offset: usize,
+}
+
+#[cfg(feature = "std")]
+impl<'a, R: std::io::Read> StreamDeserializer<'a, R> {
+    // Reads the fixed part of `fixed_len` bytes of a container encoded in the next `len` bytes of
+    // `reader`, or the rest of `reader` if `len` is `None`.
+    pub fn new(
+        reader: &'a mut Reader<R>,
+        fixed_len: usize,
+        len: Option<usize>,
+    ) -> Result<Self, DeserializeError> {
+        if let Some(len) = len {
+            if len < fixed_len {
+                return Err(DeserializeError::ExpectedFurtherInput {
+                    provided: len,
+                    expected: fixed_len,
+                })
+            }
+            reader.check_len(Some(len))?;
+        }
+        let fixed = reader.read_encoding(Some(fixed_len))?;
+        Ok(Self {
+            reader,
+            len,
+            fixed,
+            segments: vec![],
+            offsets: vec![],
+            cursor: 0,
+            next_segment: 0,
+            next_offset: 0,
+        })
+    }
+
+    // NOTE: segments must be parsed in order following the order of the fields of the container.
+    pub fn parse<T: Serializable>(&mut self) -> Result<(), DeserializeError> {
+        let start = self.cursor;
+        if T::is_variable_size() {
+            let end = start + BYTES_PER_LENGTH_OFFSET;
+            // SAFETY: index is safe as the fixed part contains this offset; qed
+            let next_offset = u32::deserialize(&self.fixed[start..end])? as usize;
+
+            match self.offsets.last() {
+                Some(&previous_offset) => {
+                    if next_offset < previous_offset {
+                        return Err(DeserializeError::OffsetNotIncreasing {
+                            start: previous_offset,
+                            end: next_offset,
+                        })
+                    }
+                }
+                None => {
+                    // the variable parts must immediately follow the fixed part
+                    let fixed_len = self.fixed.len();
+                    if next_offset < fixed_len {
+                        return Err(DeserializeError::OffsetNotIncreasing {
+                            start: fixed_len,
+                            end: next_offset,
+                        })
+                    }
+                    if next_offset > fixed_len {
+                        return Err(DeserializeError::AdditionalInput {
+                            provided: next_offset,
+                            expected: fixed_len,
+                        })
+                    }
+                }
+            }
+            if let Some(len) = self.len {
+                if next_offset > len {
+                    return Err(DeserializeError::ExpectedFurtherInput {
+                        provided: len,
+                        expected: next_offset,
+                    })
+                }
+            }
+
+            self.cursor = end;
+            self.offsets.push(next_offset);
+            self.segments.push(Segment::Offset);
+        } else {
+            let end = start + T::size_hint();
+            self.cursor = end;
+            self.segments.push(Segment::Fixed(start, end));
+        }
+        Ok(())
+    }
+
+    // NOTE: fields must be read in order following the order of the fields of the container.
+    pub fn read<T: Serializable>(&mut self) -> Result<T, DeserializeError> {
+        let segment =
+            self.segments.get(self.next_segment).ok_or(DeserializeError::MissingOffset)?;
+        self.next_segment += 1;
+        match *segment {
+            Segment::Fixed(start, end) => T::deserialize(&self.fixed[start..end]),
+            Segment::Offset => {
+                let start = self.offsets[self.next_offset];
+                self.next_offset += 1;
+                let end = self.offsets.get(self.next_offset).copied().or(self.len);
+                // NOTE: offsets were validated to be increasing when parsing
+                T::deserialize_reader(self.reader, end.map(|end| end - start))
+            }
+        }
+    }
+
+    // Ensures the input was entirely consumed.
+    pub fn finish(self) -> Result<(), DeserializeError> {
+        if !self.offsets.is_empty() {
+            // NOTE: the last variable part extends to the end of the input
+            return Ok(())
+        }
+        match self.len {
+            Some(len) if len > self.fixed.len() => {
+                Err(DeserializeError::AdditionalInput { provided: len, expected: self.fixed.len() })
+            }
+            Some(_) => Ok(()),
+            None => self.reader.expect_end(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::{deserialize_reader, prelude::*};
+
+    #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+    struct Foo {
+        a: u32,
+        b: List<List<u16, 8>, 4>,
+        c: Option<Vector<u8, 2>>,
+        d: List<u64, 16>,
+    }
+
+    fn value() -> Foo {
+        Foo {
+            a: 7,
+            b: List::try_from(vec![
+                List::try_from(vec![1u16, 2]).unwrap(),
+                List::default(),
+                List::try_from(vec![3u16]).unwrap(),
+            ])
+            .unwrap(),
+            c: Some(Vector::try_from(vec![4u8, 5]).unwrap()),
+            d: List::try_from(vec![6u64, 7, 8]).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_reader() {
+        let value = value();
+        let encoding = serialize(&value).unwrap();
+
+        let recovered = deserialize_reader::<Foo, _>(&encoding[..], encoding.len()).unwrap();
+        assert_eq!(recovered, value);
+
+        let err = deserialize_reader::<Foo, _>(&encoding[..], encoding.len() - 1).unwrap_err();
+        assert!(matches!(err.inner(), DeserializeError::AdditionalInput { .. }));
+        assert_eq!(err.path(), &["d".into()]);
+
+        let err =
+            deserialize_reader::<Foo, _>(&encoding[..encoding.len() - 1], usize::MAX).unwrap_err();
+        assert!(matches!(err.inner(), DeserializeError::AdditionalInput { .. }));
+
+        let result = deserialize_reader::<Foo, _>(&encoding[..9], usize::MAX);
+        assert!(matches!(result, Err(DeserializeError::ExpectedFurtherInput { .. })));
+
+        let mut invalid = encoding;
+        invalid.push(0);
+        let err = deserialize_reader::<Foo, _>(&invalid[..], invalid.len()).unwrap_err();
+        assert!(matches!(err.inner(), DeserializeError::AdditionalInput { .. }));
+        assert_eq!(err.path(), &["d".into()]);
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+    struct Attestation {
+        aggregation_bits: Bitlist<8>,
+        slot: u64,
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+    struct Body {
+        attestations: List<Attestation, 4>,
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+    struct Block {
+        slot: u64,
+        body: Body,
+    }
+
+    #[test]
+    fn test_error_paths() {
+        let attestation =
+            Attestation { aggregation_bits: Bitlist::try_from(&[true][..]).unwrap(), slot: 1 };
+        let block = Block {
+            slot: 2,
+            body: Body {
+                attestations: List::try_from(vec![
+                    Attestation::default(),
+                    Attestation::default(),
+                    Attestation::default(),
+                    attestation,
+                ])
+                .unwrap(),
+            },
+        };
+        let mut encoding = serialize(&block).unwrap();
+        // NOTE: the encoding ends with the bits of the last attestation
+        *encoding.last_mut().unwrap() = 0;
+
+        let expected_path: Vec<PathElement> =
+            vec!["body".into(), "attestations".into(), 3.into(), "aggregation_bits".into()];
+        let err = Block::deserialize(&encoding).unwrap_err();
+        assert_eq!(err.path(), expected_path);
+        assert!(matches!(err.inner(), DeserializeError::InvalidByte(0)));
+        assert_eq!(
+            err.to_string(),
+            "at body.attestations[3].aggregation_bits: invalid byte 0 when decoding data of the expected type"
+        );
+
+        let err = deserialize_reader::<Block, _>(&encoding[..], encoding.len()).unwrap_err();
+        assert_eq!(err.path(), expected_path);
+        let err = Block::type_info().deserialize(&encoding).unwrap_err();
+        assert_eq!(err.path(), expected_path);
+        assert!(matches!(err.into_inner(), DeserializeError::InvalidByte(0)));
+
+        let err = Option::<bool>::deserialize(&[1, 2]).unwrap_err();
+        assert_eq!(err.path(), &[PathElement::Index(1)]);
+        let err = bool::deserialize(&[2]).unwrap_err();
+        assert!(err.path().is_empty());
+        assert!(matches!(err.inner(), DeserializeError::InvalidByte(2)));
+    }
+
+    #[test]
+    fn test_deserialize_reader_leaves_trailing_input() {
+        let value = value();
+        let mut encoding = serialize(&value).unwrap();
+        let len = encoding.len();
+        encoding.extend_from_slice(&[1, 2, 3]);
+
+        let mut reader = Reader::new(&encoding[..], len);
+        let recovered = Foo::deserialize_reader(&mut reader, Some(len)).unwrap();
+        assert_eq!(recovered, value);
+        assert_eq!(reader.position(), len);
+        assert_eq!(reader.into_inner(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_deserialize_reader_io_error() {
+        struct FailingReader;
+
+        impl std::io::Read for FailingReader {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::new(std::io::ErrorKind::Other, "failed"))
+            }
+        }
+
+        let result = deserialize_reader::<Foo, _>(FailingReader, 1024);
+        assert!(matches!(result, Err(DeserializeError::Io(..))));
+    }
+}
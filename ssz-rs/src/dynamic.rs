@@ -0,0 +1,1701 @@
+//! Support for SSZ types only known at runtime, e.g. for tools decoding values of types defined
+//! elsewhere.
+use crate::{
+    de::{
+        deserialize_variable_homogeneous_composite_with, ContainerDeserializer, DeserializeError,
+    },
+    error::{InstanceError, TypeError},
+    lib::*,
+    merkleization::{
+        generalized_index::default_generalized_index,
+        get_power_of_two_ceil, merkleize_progressive_with, merkleize_with, mix_in_aux,
+        multiproofs::MultiProofAndWitness,
+        pack_bytes, progressive_chunk_generalized_index,
+        proofs::{ProofAndWitness, Prove, Prover},
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, PathElement, BITS_PER_CHUNK, BYTES_PER_CHUNK,
+    },
+    ser::{check_encoded_len, Serialize, SerializeError, Sink, BYTES_PER_LENGTH_OFFSET},
+    stable_container::{active_field_chunks, active_fields_root},
+    U256,
+};
+
+const BITS_PER_BYTE: usize = crate::BITS_PER_BYTE as usize;
+
+/// The type of an SSZ value, known at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SszType {
+    /// An unsigned integer with the given number of bits, one of 8, 16, 32, 64, 128 or 256.
+    Uint(usize),
+    Boolean,
+    /// A collection of exactly the given number of elements of the given type.
+    Vector(Box<SszType>, usize),
+    /// A collection of at most the given number of elements of the given type.
+    List(Box<SszType>, usize),
+    /// A collection of any number of elements of the given type, merkleized progressively
+    /// following EIP-7916.
+    ProgressiveList(Box<SszType>),
+    /// A collection of exactly the given number of bits.
+    Bitvector(usize),
+    /// A collection of at most the given number of bits.
+    Bitlist(usize),
+    /// A collection of the given named fields, in order.
+    Container(Vec<(String, SszType)>),
+    /// A container with capacity for the given number of fields, holding any of the given named
+    /// fields, following EIP-7495.
+    StableContainer(usize, Vec<(String, SszType)>),
+    /// A subset of the fields of a `StableContainer` with capacity for the given number of
+    /// fields, following EIP-7495.
+    Profile(usize, Vec<ProfileField>),
+    /// A value of the type of one of the given variants, by selector. A variant without a type
+    /// is the `None` variant of the union.
+    Union(Vec<(u8, Option<SszType>)>),
+}
+
+/// A type whose `SszType` is known, e.g. to enumerate the fields of a container at runtime.
+///
+/// Implemented for the types provided by this crate and derived with `SimpleSerialize`.
+pub trait SszTypeInfo {
+    /// Return the `SszType` of `Self`.
+    fn type_info() -> SszType;
+}
+
+/// A field of an `SszType::Profile`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileField {
+    pub name: String,
+    pub ty: SszType,
+    /// The index of the field in the `StableContainer` of the profile.
+    pub index: usize,
+    pub optional: bool,
+}
+
+impl SszType {
+    /// Check that `self` is a legal SSZ type, e.g. that it is not an empty vector.
+    pub fn validate(&self) -> Result<(), TypeError> {
+        match self {
+            Self::Uint(bits) => {
+                if !matches!(bits, 8 | 16 | 32 | 64 | 128 | 256) {
+                    return Err(TypeError::InvalidElementSize(*bits))
+                }
+                Ok(())
+            }
+            Self::Boolean | Self::Bitlist(..) => Ok(()),
+            Self::Vector(element_type, len) => {
+                if *len == 0 {
+                    return Err(TypeError::InvalidBound(*len))
+                }
+                element_type.validate()
+            }
+            Self::List(element_type, ..) | Self::ProgressiveList(element_type) => {
+                element_type.validate()
+            }
+            Self::Bitvector(len) => {
+                if *len == 0 {
+                    return Err(TypeError::InvalidBound(*len))
+                }
+                Ok(())
+            }
+            Self::Container(fields) => {
+                if fields.is_empty() {
+                    return Err(TypeError::InvalidBound(0))
+                }
+                fields.iter().try_for_each(|(_, field_type)| field_type.validate())
+            }
+            Self::StableContainer(max_fields, fields) => {
+                if *max_fields == 0 || fields.len() > *max_fields {
+                    return Err(TypeError::InvalidBound(*max_fields))
+                }
+                fields.iter().try_for_each(|(_, field_type)| field_type.validate())
+            }
+            Self::Profile(max_fields, fields) => {
+                if *max_fields == 0 {
+                    return Err(TypeError::InvalidBound(*max_fields))
+                }
+                let mut next_index = 0;
+                for field in fields {
+                    // NOTE: fields follow the order of the fields of the `StableContainer`
+                    if field.index < next_index || field.index >= *max_fields {
+                        return Err(TypeError::InvalidBound(field.index))
+                    }
+                    next_index = field.index + 1;
+                    field.ty.validate()?;
+                }
+                Ok(())
+            }
+            Self::Union(variants) => {
+                if variants.is_empty() {
+                    return Err(TypeError::InvalidBound(0))
+                }
+                let mut selectors = HashSet::new();
+                for (selector, variant_type) in variants {
+                    if *selector > 127 || !selectors.insert(*selector) {
+                        return Err(TypeError::InvalidBound(*selector as usize))
+                    }
+                    match variant_type {
+                        Some(variant_type) => variant_type.validate()?,
+                        // NOTE: only the `None` variant may lack a type
+                        None if *selector != 0 => {
+                            return Err(TypeError::InvalidBound(*selector as usize))
+                        }
+                        None => {}
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Indicate whether values of `self` are encoded with a variable size.
+    pub fn is_variable_size(&self) -> bool {
+        match self {
+            Self::Uint(..) | Self::Boolean | Self::Bitvector(..) => false,
+            Self::Vector(element_type, ..) => element_type.is_variable_size(),
+            Self::List(..) |
+            Self::ProgressiveList(..) |
+            Self::Bitlist(..) |
+            Self::StableContainer(..) |
+            Self::Union(..) => true,
+            Self::Container(fields) => {
+                fields.iter().any(|(_, field_type)| field_type.is_variable_size())
+            }
+            Self::Profile(_, fields) => {
+                fields.iter().any(|field| field.optional || field.ty.is_variable_size())
+            }
+        }
+    }
+
+    /// Return the number of bytes in the encoding of values of `self`, or 0 if they are encoded
+    /// with a variable size.
+    pub fn size_hint(&self) -> usize {
+        if self.is_variable_size() {
+            return 0
+        }
+        match self {
+            Self::Uint(bits) => bits / BITS_PER_BYTE,
+            Self::Boolean => 1,
+            Self::Vector(element_type, len) => element_type.size_hint() * len,
+            Self::Bitvector(len) => (len + BITS_PER_BYTE - 1) / BITS_PER_BYTE,
+            Self::Container(fields) => {
+                fields.iter().map(|(_, field_type)| field_type.size_hint()).sum()
+            }
+            Self::Profile(_, fields) => fields.iter().map(|field| field.ty.size_hint()).sum(),
+            _ => unreachable!("type is of a fixed size"),
+        }
+    }
+
+    /// Return the minimum number of bytes in the encoding of a value of `self`.
+    pub fn min_encoded_len(&self) -> usize {
+        if !self.is_variable_size() {
+            return self.size_hint()
+        }
+        match self {
+            Self::Vector(element_type, len) => len.saturating_mul(element_type.min_part_len()),
+            Self::List(..) | Self::ProgressiveList(..) => 0,
+            Self::Bitlist(..) => 1,
+            Self::Container(fields) => sum_lens(fields.iter().map(|(_, ty)| ty.min_part_len())),
+            Self::StableContainer(max_fields, ..) => bytes_for_bits(*max_fields),
+            Self::Profile(_, fields) => {
+                let required = fields.iter().filter(|field| !field.optional);
+                bytes_for_bits(optional_field_count(fields))
+                    .saturating_add(sum_lens(required.map(|field| field.ty.min_part_len())))
+            }
+            Self::Union(variants) => {
+                let value_lens = variants.iter().map(|(_, variant_type)| {
+                    variant_type.as_ref().map(SszType::min_encoded_len).unwrap_or(0)
+                });
+                value_lens.min().unwrap_or(0).saturating_add(1)
+            }
+            _ => unreachable!("type is of a variable size"),
+        }
+    }
+
+    /// Return the maximum number of bytes in the encoding of a value of `self`, saturating at
+    /// `usize::MAX`, e.g. for a `ProgressiveList`.
+    pub fn max_encoded_len(&self) -> usize {
+        if !self.is_variable_size() {
+            return self.size_hint()
+        }
+        match self {
+            Self::Vector(element_type, len) | Self::List(element_type, len) => {
+                len.saturating_mul(element_type.max_part_len())
+            }
+            Self::ProgressiveList(..) => usize::MAX,
+            Self::Bitlist(limit) => limit / BITS_PER_BYTE + 1,
+            Self::Container(fields) => sum_lens(fields.iter().map(|(_, ty)| ty.max_part_len())),
+            Self::StableContainer(max_fields, fields) => bytes_for_bits(*max_fields)
+                .saturating_add(sum_lens(fields.iter().map(|(_, ty)| ty.max_part_len()))),
+            Self::Profile(_, fields) => bytes_for_bits(optional_field_count(fields))
+                .saturating_add(sum_lens(fields.iter().map(|field| field.ty.max_part_len()))),
+            Self::Union(variants) => {
+                let value_lens = variants.iter().map(|(_, variant_type)| {
+                    variant_type.as_ref().map(SszType::max_encoded_len).unwrap_or(0)
+                });
+                value_lens.max().unwrap_or(0).saturating_add(1)
+            }
+            _ => unreachable!("type is of a variable size"),
+        }
+    }
+
+    // Minimum number of bytes a value of this type occupies in the encoding of a container or
+    // collection, including its offset if it is of a variable size.
+    fn min_part_len(&self) -> usize {
+        if self.is_variable_size() {
+            self.min_encoded_len().saturating_add(BYTES_PER_LENGTH_OFFSET)
+        } else {
+            self.size_hint()
+        }
+    }
+
+    // Maximum number of bytes a value of this type occupies in the encoding of a container or
+    // collection, including its offset if it is of a variable size.
+    fn max_part_len(&self) -> usize {
+        if self.is_variable_size() {
+            self.max_encoded_len().saturating_add(BYTES_PER_LENGTH_OFFSET)
+        } else {
+            self.size_hint()
+        }
+    }
+
+    /// Indicate whether `self` is a "basic" type, rather than a "composite" type.
+    pub fn is_basic(&self) -> bool {
+        matches!(self, Self::Uint(..) | Self::Boolean)
+    }
+
+    // Number of bytes an element of this type occupies in the chunks of a collection.
+    fn item_length(&self) -> usize {
+        if self.is_basic() {
+            self.size_hint()
+        } else {
+            BYTES_PER_CHUNK
+        }
+    }
+
+    /// Return the chunk count when merkleizing values of `self`.
+    ///
+    /// The chunk count of a `ProgressiveList` depends on its value, so is given as 0.
+    pub fn chunk_count(&self) -> usize {
+        match self {
+            Self::Uint(..) | Self::Boolean | Self::Union(..) => 1,
+            Self::Vector(element_type, len) | Self::List(element_type, len) => {
+                collection_chunk_count(element_type, *len)
+            }
+            Self::ProgressiveList(..) => 0,
+            Self::Bitvector(len) | Self::Bitlist(len) => {
+                (len + BITS_PER_CHUNK - 1) / BITS_PER_CHUNK
+            }
+            Self::Container(fields) => fields.len(),
+            Self::StableContainer(max_fields, ..) | Self::Profile(max_fields, ..) => *max_fields,
+        }
+    }
+
+    /// Return the default value of `self`.
+    pub fn default_value(&self) -> DynamicValue {
+        match self {
+            Self::Uint(bits) => DynamicValue::Uint { bits: *bits, value: U256::ZERO },
+            Self::Boolean => DynamicValue::Boolean(false),
+            Self::Vector(element_type, len) => DynamicValue::Vector {
+                element_type: element_type.as_ref().clone(),
+                elements: vec![element_type.default_value(); *len],
+            },
+            Self::List(element_type, limit) => DynamicValue::List {
+                element_type: element_type.as_ref().clone(),
+                limit: *limit,
+                elements: vec![],
+            },
+            Self::ProgressiveList(element_type) => DynamicValue::ProgressiveList {
+                element_type: element_type.as_ref().clone(),
+                elements: vec![],
+            },
+            Self::Bitvector(len) => DynamicValue::Bitvector(vec![false; *len]),
+            Self::Bitlist(limit) => DynamicValue::Bitlist { limit: *limit, bits: vec![] },
+            Self::Container(fields) => DynamicValue::Container(
+                fields
+                    .iter()
+                    .map(|(name, field_type)| (name.clone(), field_type.default_value()))
+                    .collect(),
+            ),
+            Self::StableContainer(max_fields, fields) => DynamicValue::StableContainer {
+                max_fields: *max_fields,
+                fields: fields.clone(),
+                values: vec![None; fields.len()],
+            },
+            Self::Profile(max_fields, fields) => DynamicValue::Profile {
+                max_fields: *max_fields,
+                fields: fields.clone(),
+                values: fields
+                    .iter()
+                    .map(|field| (!field.optional).then(|| field.ty.default_value()))
+                    .collect(),
+            },
+            Self::Union(variants) => {
+                let (selector, variant_type) = variants.first().expect("union has a variant");
+                DynamicValue::Union {
+                    variants: variants.clone(),
+                    selector: *selector,
+                    value: variant_type.as_ref().map(|ty| Box::new(ty.default_value())),
+                }
+            }
+        }
+    }
+
+    /// Deserialize a value of `self` from its `encoding`.
+    pub fn deserialize(&self, encoding: &[u8]) -> Result<DynamicValue, DeserializeError> {
+        self.validate()?;
+        self.decode(encoding)
+    }
+
+    // Deserialize a value of `self` from its `encoding`, assuming `self` is valid.
+    pub(crate) fn decode(&self, encoding: &[u8]) -> Result<DynamicValue, DeserializeError> {
+        match self {
+            Self::Uint(bits) => {
+                expect_len(encoding, bits / BITS_PER_BYTE)?;
+                Ok(DynamicValue::Uint { bits: *bits, value: U256::from_le_slice(encoding) })
+            }
+            Self::Boolean => {
+                expect_len(encoding, 1)?;
+                match encoding[0] {
+                    0 => Ok(DynamicValue::Boolean(false)),
+                    1 => Ok(DynamicValue::Boolean(true)),
+                    b => Err(DeserializeError::InvalidByte(b)),
+                }
+            }
+            Self::Vector(element_type, len) => {
+                let elements = if element_type.is_variable_size() {
+                    let elements = decode_variable_elements(element_type, encoding)?;
+                    if elements.len() != *len {
+                        return Err(InstanceError::Exact {
+                            required: *len,
+                            provided: elements.len(),
+                        }
+                        .into())
+                    }
+                    elements
+                } else {
+                    expect_len(encoding, self.size_hint())?;
+                    decode_fixed_elements(element_type, encoding)?
+                };
+                Ok(DynamicValue::Vector { element_type: element_type.as_ref().clone(), elements })
+            }
+            Self::List(element_type, limit) => {
+                let elements = decode_elements(element_type, encoding)?;
+                if elements.len() > *limit {
+                    return Err(
+                        InstanceError::Bounded { bound: *limit, provided: elements.len() }.into()
+                    )
+                }
+                Ok(DynamicValue::List {
+                    element_type: element_type.as_ref().clone(),
+                    limit: *limit,
+                    elements,
+                })
+            }
+            Self::ProgressiveList(element_type) => Ok(DynamicValue::ProgressiveList {
+                element_type: element_type.as_ref().clone(),
+                elements: decode_elements(element_type, encoding)?,
+            }),
+            Self::Bitvector(len) => {
+                expect_len(encoding, self.size_hint())?;
+                Ok(DynamicValue::Bitvector(decode_bitvector(encoding, *len)?))
+            }
+            Self::Bitlist(limit) => {
+                let Some((last_byte, _)) = encoding.split_last() else {
+                    return Err(DeserializeError::ExpectedFurtherInput { provided: 0, expected: 1 })
+                };
+                let max_len = limit / BITS_PER_BYTE + 1;
+                if encoding.len() > max_len {
+                    return Err(DeserializeError::AdditionalInput {
+                        provided: encoding.len(),
+                        expected: max_len,
+                    })
+                }
+                if *last_byte == 0 {
+                    return Err(DeserializeError::InvalidByte(*last_byte))
+                }
+                // NOTE: the highest set bit marks the end of the bitlist
+                let len = (encoding.len() - 1) * BITS_PER_BYTE + last_byte.ilog2() as usize;
+                if len > *limit {
+                    return Err(InstanceError::Bounded { bound: *limit, provided: len }.into())
+                }
+                Ok(DynamicValue::Bitlist { limit: *limit, bits: unpack_bits(encoding, len) })
+            }
+            Self::Container(fields) => {
+                let field_types = fields
+                    .iter()
+                    .map(|(name, field_type)| (name.as_str(), Some(field_type)))
+                    .collect::<Vec<_>>();
+                let values = decode_fields(&field_types, encoding)?;
+                let fields = fields
+                    .iter()
+                    .zip(values)
+                    .map(|((name, _), value)| (name.clone(), value.expect("field is present")))
+                    .collect();
+                Ok(DynamicValue::Container(fields))
+            }
+            Self::StableContainer(max_fields, fields) => {
+                let (active_fields, fields_encoding) = split_active_fields(encoding, *max_fields)?;
+                if let Some(i) = active_fields.iter().skip(fields.len()).position(|bit| *bit) {
+                    let i = fields.len() + i;
+                    return Err(DeserializeError::InvalidByte(encoding[i / BITS_PER_BYTE]))
+                }
+                let field_types = fields
+                    .iter()
+                    .zip(active_fields)
+                    .map(|((name, field_type), active)| {
+                        (name.as_str(), active.then_some(field_type))
+                    })
+                    .collect::<Vec<_>>();
+                Ok(DynamicValue::StableContainer {
+                    max_fields: *max_fields,
+                    fields: fields.clone(),
+                    values: decode_fields(&field_types, fields_encoding)?,
+                })
+            }
+            Self::Profile(max_fields, fields) => {
+                let optional_count = fields.iter().filter(|field| field.optional).count();
+                let (active_fields, encoding) = if optional_count > 0 {
+                    split_active_fields(encoding, optional_count)?
+                } else {
+                    (vec![], encoding)
+                };
+                let mut active_fields = active_fields.into_iter();
+                let field_types = fields
+                    .iter()
+                    .map(|field| {
+                        let active = !field.optional ||
+                            active_fields.next().expect("one bit per optional field");
+                        (field.name.as_str(), active.then_some(&field.ty))
+                    })
+                    .collect::<Vec<_>>();
+                Ok(DynamicValue::Profile {
+                    max_fields: *max_fields,
+                    fields: fields.clone(),
+                    values: decode_fields(&field_types, encoding)?,
+                })
+            }
+            Self::Union(variants) => {
+                let Some((selector, encoding)) = encoding.split_first() else {
+                    return Err(DeserializeError::ExpectedFurtherInput { provided: 0, expected: 1 })
+                };
+                let (_, variant_type) = variants
+                    .iter()
+                    .find(|(s, _)| s == selector)
+                    .ok_or(DeserializeError::InvalidByte(*selector))?;
+                let value = match variant_type {
+                    Some(variant_type) => Some(Box::new(
+                        variant_type.decode(encoding).map_err(|err| err.at(*selector as usize))?,
+                    )),
+                    None => {
+                        if !encoding.is_empty() {
+                            return Err(DeserializeError::AdditionalInput {
+                                provided: encoding.len() + 1,
+                                expected: 1,
+                            })
+                        }
+                        None
+                    }
+                };
+                Ok(DynamicValue::Union { variants: variants.clone(), selector: *selector, value })
+            }
+        }
+    }
+
+    /// Compute the generalized index of `path` through values of `self`.
+    pub fn generalized_index(&self, path: Path) -> Result<GeneralizedIndex, MerkleizationError> {
+        self.compute_generalized_index(default_generalized_index(), path)
+    }
+
+    /// Compute the generalized index starting from `parent` and following `path` through values
+    /// of `self`.
+    pub fn compute_generalized_index(
+        &self,
+        parent: GeneralizedIndex,
+        path: Path,
+    ) -> Result<GeneralizedIndex, MerkleizationError> {
+        let Some((next, rest)) = path.split_first() else { return Ok(parent) };
+        match (self, next) {
+            (Self::Uint(..) | Self::Boolean, _) => {
+                Err(MerkleizationError::InvalidPath(path.to_vec()))
+            }
+            (Self::Vector(element_type, len), PathElement::Index(i)) if i < len => {
+                let chunk_position = i * element_type.item_length() / BYTES_PER_CHUNK;
+                let child = parent * get_power_of_two_ceil(self.chunk_count()) + chunk_position;
+                element_type.compute_generalized_index(child, rest)
+            }
+            (Self::List(element_type, limit), PathElement::Index(i)) if i < limit => {
+                let chunk_position = i * element_type.item_length() / BYTES_PER_CHUNK;
+                let child = parent * 2 * get_power_of_two_ceil(self.chunk_count()) + chunk_position;
+                element_type.compute_generalized_index(child, rest)
+            }
+            (Self::ProgressiveList(element_type), PathElement::Index(i)) => {
+                let chunk_position = i * element_type.item_length() / BYTES_PER_CHUNK;
+                let chunk_index = progressive_chunk_generalized_index(chunk_position);
+                let depth = chunk_index.ilog2();
+                // SAFETY: checked subtraction is unnecessary, as `chunk_index >= 1 << depth`; qed
+                let child = ((parent * 2) << depth) + chunk_index - (1 << depth);
+                element_type.compute_generalized_index(child, rest)
+            }
+            (Self::Bitvector(len), PathElement::Index(i)) if i < len => {
+                let chunk_position = i / BITS_PER_CHUNK;
+                let child = parent * get_power_of_two_ceil(self.chunk_count()) + chunk_position;
+                Self::Boolean.compute_generalized_index(child, rest)
+            }
+            (Self::Bitlist(limit), PathElement::Index(i)) if i < limit => {
+                let chunk_position = i / BITS_PER_CHUNK;
+                let child = parent * 2 * get_power_of_two_ceil(self.chunk_count()) + chunk_position;
+                Self::Boolean.compute_generalized_index(child, rest)
+            }
+            (Self::List(..) | Self::ProgressiveList(..), PathElement::Length) |
+            (Self::Union(..), PathElement::Selector) => {
+                if rest.is_empty() {
+                    Ok(parent * 2 + 1)
+                } else {
+                    Err(MerkleizationError::InvalidPath(rest.to_vec()))
+                }
+            }
+            (Self::Container(fields), PathElement::Field(name)) => {
+                let (i, (_, field_type)) = fields
+                    .iter()
+                    .enumerate()
+                    .find(|(_, (field_name, _))| field_name == name)
+                    .ok_or_else(|| MerkleizationError::InvalidPathElement(next.clone()))?;
+                let child = parent * get_power_of_two_ceil(fields.len()) + i;
+                field_type.compute_generalized_index(child, rest)
+            }
+            (Self::StableContainer(max_fields, fields), PathElement::Field(name)) => {
+                let (i, (_, field_type)) = fields
+                    .iter()
+                    .enumerate()
+                    .find(|(_, (field_name, _))| field_name == name)
+                    .ok_or_else(|| MerkleizationError::InvalidPathElement(next.clone()))?;
+                let child = parent * 2 * get_power_of_two_ceil(*max_fields) + i;
+                field_type.compute_generalized_index(child, rest)
+            }
+            (Self::Profile(max_fields, fields), PathElement::Field(name)) => {
+                let field = fields
+                    .iter()
+                    .find(|field| &field.name == name)
+                    .ok_or_else(|| MerkleizationError::InvalidPathElement(next.clone()))?;
+                let child = parent * 2 * get_power_of_two_ceil(*max_fields) + field.index;
+                field.ty.compute_generalized_index(child, rest)
+            }
+            (Self::Union(variants), PathElement::Index(i)) => {
+                let (_, variant_type) = variants
+                    .iter()
+                    .find(|(selector, _)| *selector as usize == *i)
+                    .ok_or_else(|| MerkleizationError::InvalidPathElement(next.clone()))?;
+                let child = parent * 2;
+                match variant_type {
+                    Some(variant_type) => variant_type.compute_generalized_index(child, rest),
+                    None if rest.is_empty() => Ok(child),
+                    None => Err(MerkleizationError::InvalidPath(rest.to_vec())),
+                }
+            }
+            _ => Err(MerkleizationError::InvalidPathElement(next.clone())),
+        }
+    }
+}
+
+/// Formats `self` like the type expressions of a `Schema`, e.g. `List[uint16, 1024]`, writing
+/// the fields of containers as `Container`.
+impl Display for SszType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uint(bits) => write!(f, "uint{bits}"),
+            Self::Boolean => write!(f, "boolean"),
+            Self::Vector(element_type, len) => write!(f, "Vector[{element_type}, {len}]"),
+            Self::List(element_type, limit) => write!(f, "List[{element_type}, {limit}]"),
+            Self::ProgressiveList(element_type) => write!(f, "ProgressiveList[{element_type}]"),
+            Self::Bitvector(len) => write!(f, "Bitvector[{len}]"),
+            Self::Bitlist(limit) => write!(f, "Bitlist[{limit}]"),
+            Self::Container(..) => write!(f, "Container"),
+            Self::StableContainer(max_fields, ..) => write!(f, "StableContainer[{max_fields}]"),
+            Self::Profile(max_fields, ..) => write!(f, "Profile[StableContainer[{max_fields}]]"),
+            Self::Union(variants) => {
+                write!(f, "Union[")?;
+                for (i, (_, variant_type)) in variants.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match variant_type {
+                        Some(variant_type) => write!(f, "{variant_type}")?,
+                        None => write!(f, "None")?,
+                    }
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+// Number of chunks of a collection of `len` elements of `element_type`.
+fn collection_chunk_count(element_type: &SszType, len: usize) -> usize {
+    (len * element_type.item_length() + BYTES_PER_CHUNK - 1) / BYTES_PER_CHUNK
+}
+
+fn bytes_for_bits(bits: usize) -> usize {
+    (bits + BITS_PER_BYTE - 1) / BITS_PER_BYTE
+}
+
+fn optional_field_count(fields: &[ProfileField]) -> usize {
+    fields.iter().filter(|field| field.optional).count()
+}
+
+fn sum_lens(lens: impl Iterator<Item = usize>) -> usize {
+    lens.fold(0, usize::saturating_add)
+}
+
+fn expect_len(encoding: &[u8], expected: usize) -> Result<(), DeserializeError> {
+    if encoding.len() < expected {
+        return Err(DeserializeError::ExpectedFurtherInput { provided: encoding.len(), expected })
+    }
+    if encoding.len() > expected {
+        return Err(DeserializeError::AdditionalInput { provided: encoding.len(), expected })
+    }
+    Ok(())
+}
+
+// Deserialize the elements of a list of any length from their `encoding`.
+fn decode_elements(
+    element_type: &SszType,
+    encoding: &[u8],
+) -> Result<Vec<DynamicValue>, DeserializeError> {
+    if element_type.is_variable_size() {
+        return decode_variable_elements(element_type, encoding)
+    }
+    let remainder = encoding.len() % element_type.size_hint();
+    if remainder != 0 {
+        return Err(DeserializeError::AdditionalInput {
+            provided: encoding.len(),
+            // SAFETY: checked subtraction is unnecessary, as encoding.len() > remainder; qed
+            expected: encoding.len() - remainder,
+        })
+    }
+    decode_fixed_elements(element_type, encoding)
+}
+
+fn decode_fixed_elements(
+    element_type: &SszType,
+    encoding: &[u8],
+) -> Result<Vec<DynamicValue>, DeserializeError> {
+    encoding
+        .chunks_exact(element_type.size_hint())
+        .enumerate()
+        .map(|(i, chunk)| element_type.decode(chunk).map_err(|err| err.at(i)))
+        .collect()
+}
+
+fn decode_variable_elements(
+    element_type: &SszType,
+    encoding: &[u8],
+) -> Result<Vec<DynamicValue>, DeserializeError> {
+    deserialize_variable_homogeneous_composite_with(encoding, |encoding| {
+        element_type.decode(encoding)
+    })
+}
+
+// Deserialize the fields of a container from their `encoding`, given the name of each field and
+// its type if it is present in the encoding.
+fn decode_fields(
+    field_types: &[(&str, Option<&SszType>)],
+    encoding: &[u8],
+) -> Result<Vec<Option<DynamicValue>>, DeserializeError> {
+    let mut deserializer = ContainerDeserializer::default();
+    for (name, field_type) in field_types {
+        let Some(field_type) = field_type else { continue };
+        deserializer
+            .parse_segment(encoding, field_type.is_variable_size(), field_type.size_hint())
+            .map_err(|err| err.at(*name))?;
+    }
+    let spans = deserializer.finalize(encoding)?;
+    let mut spans = spans.chunks_exact(2);
+    field_types
+        .iter()
+        .map(|(name, field_type)| {
+            field_type
+                .map(|field_type| {
+                    let span = spans.next().expect("parsed each present field");
+                    field_type.decode(&encoding[span[0]..span[1]]).map_err(|err| err.at(*name))
+                })
+                .transpose()
+        })
+        .collect()
+}
+
+fn decode_bitvector(encoding: &[u8], len: usize) -> Result<Vec<bool>, DeserializeError> {
+    let remainder_count = len % BITS_PER_BYTE;
+    if remainder_count != 0 {
+        // SAFETY: index is safe because the encoding of a bitvector is not empty; qed
+        let last_byte = encoding[encoding.len() - 1];
+        if last_byte >> remainder_count != 0 {
+            return Err(DeserializeError::InvalidByte(last_byte))
+        }
+    }
+    Ok(unpack_bits(encoding, len))
+}
+
+// Split the bitvector of `len` bits marking the active fields of a `StableContainer` or `Profile`
+// from the `encoding` of its fields.
+pub(crate) fn split_active_fields(
+    encoding: &[u8],
+    len: usize,
+) -> Result<(Vec<bool>, &[u8]), DeserializeError> {
+    let prefix_len = (len + BITS_PER_BYTE - 1) / BITS_PER_BYTE;
+    if encoding.len() < prefix_len {
+        return Err(DeserializeError::ExpectedFurtherInput {
+            provided: encoding.len(),
+            expected: prefix_len,
+        })
+    }
+    let (prefix, encoding) = encoding.split_at(prefix_len);
+    Ok((decode_bitvector(prefix, len)?, encoding))
+}
+
+// Return the first `len` bits packed into `bytes`, least significant bit first.
+fn unpack_bits(bytes: &[u8], len: usize) -> Vec<bool> {
+    (0..len).map(|i| bytes[i / BITS_PER_BYTE] & (1 << (i % BITS_PER_BYTE)) != 0).collect()
+}
+
+// Append `bits` to `buffer` packed into bytes, least significant bit first.
+fn pack_bits(bits: &[bool], buffer: &mut Vec<u8>) {
+    buffer.extend(
+        bits.chunks(BITS_PER_BYTE).map(|byte| {
+            byte.iter().enumerate().fold(0u8, |acc, (i, bit)| acc | ((*bit as u8) << i))
+        }),
+    );
+}
+
+/// A value of an SSZ type only known at runtime.
+///
+/// Values are serialized and merkleized following the same rules as the equivalent static types,
+/// e.g. a `DynamicValue::List` of `DynamicValue::Uint`s of 16 bits is encoded and merkleized
+/// like a `List<u16, N>`. The type of a value is given by `ty`, and values of some `SszType` can
+/// be deserialized with `SszType::deserialize`.
+///
+/// Generalized indices of a `DynamicValue` are computed from its type, e.g.
+/// `value.ty().generalized_index(path)`, rather than with `GeneralizedIndexable`, which only
+/// supports types known at compile time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynamicValue {
+    Uint {
+        bits: usize,
+        value: U256,
+    },
+    Boolean(bool),
+    Vector {
+        element_type: SszType,
+        elements: Vec<DynamicValue>,
+    },
+    List {
+        element_type: SszType,
+        limit: usize,
+        elements: Vec<DynamicValue>,
+    },
+    ProgressiveList {
+        element_type: SszType,
+        elements: Vec<DynamicValue>,
+    },
+    Bitvector(Vec<bool>),
+    Bitlist {
+        limit: usize,
+        bits: Vec<bool>,
+    },
+    Container(Vec<(String, DynamicValue)>),
+    /// A `StableContainer` with the value of each of its `fields`, if present.
+    StableContainer {
+        max_fields: usize,
+        fields: Vec<(String, SszType)>,
+        values: Vec<Option<DynamicValue>>,
+    },
+    /// A `Profile` with the value of each of its `fields`, if present.
+    Profile {
+        max_fields: usize,
+        fields: Vec<ProfileField>,
+        values: Vec<Option<DynamicValue>>,
+    },
+    Union {
+        variants: Vec<(u8, Option<SszType>)>,
+        selector: u8,
+        value: Option<Box<DynamicValue>>,
+    },
+}
+
+impl DynamicValue {
+    /// Return the type of `self`.
+    pub fn ty(&self) -> SszType {
+        match self {
+            Self::Uint { bits, .. } => SszType::Uint(*bits),
+            Self::Boolean(..) => SszType::Boolean,
+            Self::Vector { element_type, elements } => {
+                SszType::Vector(Box::new(element_type.clone()), elements.len())
+            }
+            Self::List { element_type, limit, .. } => {
+                SszType::List(Box::new(element_type.clone()), *limit)
+            }
+            Self::ProgressiveList { element_type, .. } => {
+                SszType::ProgressiveList(Box::new(element_type.clone()))
+            }
+            Self::Bitvector(bits) => SszType::Bitvector(bits.len()),
+            Self::Bitlist { limit, .. } => SszType::Bitlist(*limit),
+            Self::Container(fields) => SszType::Container(
+                fields.iter().map(|(name, value)| (name.clone(), value.ty())).collect(),
+            ),
+            Self::StableContainer { max_fields, fields, .. } => {
+                SszType::StableContainer(*max_fields, fields.clone())
+            }
+            Self::Profile { max_fields, fields, .. } => {
+                SszType::Profile(*max_fields, fields.clone())
+            }
+            Self::Union { variants, .. } => SszType::Union(variants.clone()),
+        }
+    }
+
+    /// Return the value of the field `name` if `self` is a container with such a field present.
+    pub fn field(&self, name: &str) -> Option<&DynamicValue> {
+        match self {
+            Self::Container(fields) => {
+                fields.iter().find(|(field_name, _)| field_name == name).map(|(_, value)| value)
+            }
+            Self::StableContainer { fields, values, .. } => {
+                let i = fields.iter().position(|(field_name, _)| field_name == name)?;
+                values.get(i)?.as_ref()
+            }
+            Self::Profile { fields, values, .. } => {
+                let i = fields.iter().position(|field| field.name == name)?;
+                values.get(i)?.as_ref()
+            }
+            _ => None,
+        }
+    }
+
+    fn is_variable_size(&self) -> bool {
+        match self {
+            Self::Uint { .. } | Self::Boolean(..) | Self::Bitvector(..) => false,
+            Self::Vector { element_type, .. } => element_type.is_variable_size(),
+            Self::List { .. } |
+            Self::ProgressiveList { .. } |
+            Self::Bitlist { .. } |
+            Self::StableContainer { .. } |
+            Self::Union { .. } => true,
+            Self::Container(fields) => fields.iter().any(|(_, value)| value.is_variable_size()),
+            Self::Profile { fields, .. } => {
+                fields.iter().any(|field| field.optional || field.ty.is_variable_size())
+            }
+        }
+    }
+
+    // Number of bytes `self` occupies in an encoding containing it, including any offset.
+    fn element_encoded_len(&self) -> usize {
+        if self.is_variable_size() {
+            BYTES_PER_LENGTH_OFFSET + self.encoded_len()
+        } else {
+            self.encoded_len()
+        }
+    }
+
+    // Return the capacity of the `StableContainer` of `self` and the index in it of each field
+    // present in `self`, with its value, if `self` is a `StableContainer` or a `Profile`.
+    fn active_fields(&self) -> Option<(usize, Vec<(usize, &DynamicValue)>)> {
+        match self {
+            Self::StableContainer { max_fields, values, .. } => {
+                let fields = values
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, value)| Some((i, value.as_ref()?)))
+                    .collect();
+                Some((*max_fields, fields))
+            }
+            Self::Profile { max_fields, fields, values } => {
+                let fields = fields
+                    .iter()
+                    .zip(values)
+                    .filter_map(|(field, value)| Some((field.index, value.as_ref()?)))
+                    .collect();
+                Some((*max_fields, fields))
+            }
+            _ => None,
+        }
+    }
+
+    // Return the bitvector of optional fields present in `self`, which prefixes the encoding of
+    // a `StableContainer` or a `Profile` with optional fields.
+    fn optional_fields(&self) -> Result<Option<Vec<bool>>, SerializeError> {
+        match self {
+            Self::StableContainer { max_fields, fields, values } => {
+                if values.len() != fields.len() {
+                    return Err(InstanceError::Exact {
+                        required: fields.len(),
+                        provided: values.len(),
+                    }
+                    .into())
+                }
+                let mut bits = vec![false; *max_fields];
+                for (bit, value) in bits.iter_mut().zip(values) {
+                    *bit = value.is_some();
+                }
+                Ok(Some(bits))
+            }
+            Self::Profile { fields, values, .. } => {
+                if values.len() != fields.len() {
+                    return Err(InstanceError::Exact {
+                        required: fields.len(),
+                        provided: values.len(),
+                    }
+                    .into())
+                }
+                let required_count = fields.iter().filter(|field| !field.optional).count();
+                let provided = fields
+                    .iter()
+                    .zip(values)
+                    .filter(|(field, value)| !field.optional && value.is_some())
+                    .count();
+                if provided != required_count {
+                    return Err(InstanceError::Exact { required: required_count, provided }.into())
+                }
+                let bits = fields
+                    .iter()
+                    .zip(values)
+                    .filter(|(field, _)| field.optional)
+                    .map(|(_, value)| value.is_some())
+                    .collect::<Vec<_>>();
+                Ok((!bits.is_empty()).then_some(bits))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // NOTE: each offset is written once the position of its element is known, so that every
+    // element is written in place without computing its length first
+    fn serialize_composite<'a>(
+        elements: impl Iterator<Item = &'a DynamicValue> + Clone,
+        buffer: &mut Vec<u8>,
+    ) -> Result<usize, SerializeError> {
+        let start = buffer.len();
+        let mut offset_positions = vec![];
+        for element in elements.clone() {
+            if element.is_variable_size() {
+                offset_positions.push(buffer.len());
+                buffer.extend_from_slice(&[0u8; BYTES_PER_LENGTH_OFFSET]);
+            } else {
+                element.write(buffer)?;
+            }
+        }
+        let variable_elements = elements.filter(|element| element.is_variable_size());
+        for (element, position) in variable_elements.zip(offset_positions) {
+            let offset = buffer.len() - start;
+            check_encoded_len(offset)?;
+            // SAFETY: `offset` fits in `u32` as checked above
+            buffer[position..position + BYTES_PER_LENGTH_OFFSET]
+                .copy_from_slice(&(offset as u32).to_le_bytes());
+            element.write(buffer)?;
+        }
+        let len = buffer.len() - start;
+        check_encoded_len(len)?;
+        Ok(len)
+    }
+
+    // Check that `self` (but not any value nested in it) can be serialized.
+    fn check_serializable(&self) -> Result<(), SerializeError> {
+        match self {
+            Self::Uint { bits, value } => {
+                SszType::Uint(*bits).validate()?;
+                if value.bit_len() > *bits {
+                    return Err(
+                        InstanceError::Bounded { bound: *bits, provided: value.bit_len() }.into()
+                    )
+                }
+            }
+            Self::Vector { elements, .. } if elements.is_empty() => {
+                return Err(TypeError::InvalidBound(0).into())
+            }
+            Self::List { limit, elements, .. } if elements.len() > *limit => {
+                return Err(InstanceError::Bounded { bound: *limit, provided: elements.len() }.into())
+            }
+            Self::Bitvector(bits) if bits.is_empty() => {
+                return Err(TypeError::InvalidBound(0).into())
+            }
+            Self::Bitlist { limit, bits } if bits.len() > *limit => {
+                return Err(InstanceError::Bounded { bound: *limit, provided: bits.len() }.into())
+            }
+            Self::Container(fields) if fields.is_empty() => {
+                return Err(TypeError::InvalidBound(0).into())
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Append the encoding of `self` to `buffer`, without reserving its length first.
+    fn write(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+        self.check_serializable()?;
+        match self {
+            Self::Uint { bits, value } => {
+                let byte_len = bits / BITS_PER_BYTE;
+                buffer.extend_from_slice(&value.as_le_slice()[..byte_len]);
+                Ok(byte_len)
+            }
+            Self::Boolean(value) => {
+                buffer.push(*value as u8);
+                Ok(1)
+            }
+            Self::Vector { elements, .. } |
+            Self::List { elements, .. } |
+            Self::ProgressiveList { elements, .. } => {
+                Self::serialize_composite(elements.iter(), buffer)
+            }
+            Self::Bitvector(bits) => {
+                let start = buffer.len();
+                pack_bits(bits, buffer);
+                Ok(buffer.len() - start)
+            }
+            Self::Bitlist { bits, .. } => {
+                let start = buffer.len();
+                pack_bits(bits, buffer);
+                // NOTE: mark the end of the bitlist with the next bit
+                if bits.len() % BITS_PER_BYTE == 0 {
+                    buffer.push(1);
+                } else {
+                    let last_byte = buffer.last_mut().expect("bits is not empty");
+                    *last_byte |= 1 << (bits.len() % BITS_PER_BYTE);
+                }
+                Ok(buffer.len() - start)
+            }
+            Self::Container(fields) => {
+                Self::serialize_composite(fields.iter().map(|(_, value)| value), buffer)
+            }
+            Self::StableContainer { values, .. } | Self::Profile { values, .. } => {
+                let start = buffer.len();
+                if let Some(bits) = self.optional_fields()? {
+                    pack_bits(&bits, buffer);
+                }
+                let prefix_len = buffer.len() - start;
+                Ok(prefix_len + Self::serialize_composite(values.iter().flatten(), buffer)?)
+            }
+            Self::Union { selector, value, .. } => {
+                buffer.push(*selector);
+                let value_len = match value {
+                    Some(value) => value.write(buffer)?,
+                    None => 0,
+                };
+                Ok(1 + value_len)
+            }
+        }
+    }
+
+    // Write the encoding of the composite value holding `elements` to `sink`, computing the length
+    // of each element once to write its offset.
+    fn serialize_composite_into<'a, S: Sink + ?Sized>(
+        elements: impl Iterator<Item = &'a DynamicValue> + Clone,
+        sink: &mut S,
+    ) -> Result<usize, SerializeError> {
+        let lens = elements.clone().map(Serialize::encoded_len).collect::<Vec<_>>();
+        let (mut fixed_len, mut variable_len) = (0, 0);
+        for (element, &len) in elements.clone().zip(&lens) {
+            if element.is_variable_size() {
+                fixed_len += BYTES_PER_LENGTH_OFFSET;
+                variable_len += len;
+            } else {
+                fixed_len += len;
+            }
+        }
+        let total_len = fixed_len + variable_len;
+        check_encoded_len(total_len)?;
+
+        let mut next_offset = fixed_len;
+        for (element, len) in elements.clone().zip(&lens) {
+            if element.is_variable_size() {
+                // SAFETY: `next_offset` fits in `u32` if the total length check holds
+                sink.write_bytes(&(next_offset as u32).to_le_bytes())?;
+                next_offset += len;
+            } else {
+                element.serialize_into(sink)?;
+            }
+        }
+        for element in elements.filter(|element| element.is_variable_size()) {
+            element.serialize_into(sink)?;
+        }
+        Ok(total_len)
+    }
+}
+
+impl Serialize for DynamicValue {
+    fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+        buffer.reserve(self.encoded_len());
+        self.write(buffer)
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        self.check_serializable()?;
+        match self {
+            Self::Vector { elements, .. } |
+            Self::List { elements, .. } |
+            Self::ProgressiveList { elements, .. } => {
+                Self::serialize_composite_into(elements.iter(), sink)
+            }
+            Self::Container(fields) => {
+                Self::serialize_composite_into(fields.iter().map(|(_, value)| value), sink)
+            }
+            Self::StableContainer { values, .. } | Self::Profile { values, .. } => {
+                let mut prefix = vec![];
+                if let Some(bits) = self.optional_fields()? {
+                    pack_bits(&bits, &mut prefix);
+                }
+                sink.write_bytes(&prefix)?;
+                Ok(prefix.len() + Self::serialize_composite_into(values.iter().flatten(), sink)?)
+            }
+            Self::Union { selector, value, .. } => {
+                sink.write_bytes(&[*selector])?;
+                let value_len = match value {
+                    Some(value) => value.serialize_into(sink)?,
+                    None => 0,
+                };
+                Ok(1 + value_len)
+            }
+            // NOTE: the encoding of a basic value or of bits is written at once
+            _ => {
+                let mut buffer = vec![];
+                self.write(&mut buffer)?;
+                sink.write_bytes(&buffer)?;
+                Ok(buffer.len())
+            }
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        match self {
+            Self::Uint { bits, .. } => bits / BITS_PER_BYTE,
+            Self::Boolean(..) => 1,
+            Self::Vector { elements, .. } |
+            Self::List { elements, .. } |
+            Self::ProgressiveList { elements, .. } => {
+                elements.iter().map(Self::element_encoded_len).sum()
+            }
+            Self::Bitvector(bits) => (bits.len() + BITS_PER_BYTE - 1) / BITS_PER_BYTE,
+            Self::Bitlist { bits, .. } => bits.len() / BITS_PER_BYTE + 1,
+            Self::Container(fields) => {
+                fields.iter().map(|(_, value)| value.element_encoded_len()).sum()
+            }
+            Self::StableContainer { max_fields, values, .. } => {
+                let prefix_len = (max_fields + BITS_PER_BYTE - 1) / BITS_PER_BYTE;
+                prefix_len + values.iter().flatten().map(Self::element_encoded_len).sum::<usize>()
+            }
+            Self::Profile { fields, values, .. } => {
+                let optional_count = fields.iter().filter(|field| field.optional).count();
+                let prefix_len = (optional_count + BITS_PER_BYTE - 1) / BITS_PER_BYTE;
+                prefix_len + values.iter().flatten().map(Self::element_encoded_len).sum::<usize>()
+            }
+            Self::Union { value, .. } => 1 + value.as_ref().map_or(0, |value| value.encoded_len()),
+        }
+    }
+}
+
+impl HashTreeRoot for DynamicValue {
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        let chunks = self.chunks::<H>()?;
+        let root = if self.is_progressive() {
+            merkleize_progressive_with::<H>(&chunks)?
+        } else {
+            merkleize_with::<H>(&chunks, Some(self.value_chunk_count()))?
+        };
+        match self.decoration_root::<H>()? {
+            Some(decoration) => Ok(mix_in_aux::<H>(root, decoration)),
+            None => Ok(root),
+        }
+    }
+
+    fn is_composite_type() -> bool {
+        // NOTE: the type of a `DynamicValue` is only known from the value
+        true
+    }
+}
+
+// NOTE: generalized indices of a `DynamicValue` are computed from its type with
+// `SszType::generalized_index`
+impl GeneralizedIndexable for DynamicValue {}
+
+impl Prove for DynamicValue {
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        let mut chunks = vec![];
+        match self {
+            Self::Uint { .. } | Self::Boolean(..) => {
+                self.serialize(&mut chunks)?;
+            }
+            Self::Vector { element_type, elements } |
+            Self::List { element_type, elements, .. } |
+            Self::ProgressiveList { element_type, elements }
+                if element_type.is_basic() =>
+            {
+                for element in elements {
+                    element.serialize(&mut chunks)?;
+                }
+            }
+            Self::Vector { elements, .. } |
+            Self::List { elements, .. } |
+            Self::ProgressiveList { elements, .. } => {
+                for element in elements {
+                    chunks.extend_from_slice(element.hash_tree_root_with::<H>()?.as_slice());
+                }
+            }
+            Self::Bitvector(bits) | Self::Bitlist { bits, .. } => pack_bits(bits, &mut chunks),
+            Self::Container(fields) => {
+                for (_, value) in fields {
+                    chunks.extend_from_slice(value.hash_tree_root_with::<H>()?.as_slice());
+                }
+            }
+            Self::StableContainer { .. } | Self::Profile { .. } => {
+                let (max_fields, fields) = self.active_fields().expect("is a stable container");
+                let roots = fields
+                    .into_iter()
+                    .map(|(index, value)| Ok((index, value.hash_tree_root_with::<H>()?)))
+                    .collect::<Result<Vec<_>, MerkleizationError>>()?;
+                return Ok(active_field_chunks(&roots, max_fields))
+            }
+            Self::Union { value, .. } => {
+                let root = match value {
+                    Some(value) => value.hash_tree_root_with::<H>()?,
+                    None => Node::default(),
+                };
+                chunks.extend_from_slice(root.as_slice());
+            }
+        }
+        pack_bytes(&mut chunks);
+        Ok(chunks)
+    }
+
+    fn prove_element<H: Hasher>(
+        &self,
+        index: usize,
+        prover: &mut Prover<H>,
+    ) -> Result<(), MerkleizationError> {
+        let element = match self {
+            Self::Vector { elements, .. } |
+            Self::List { elements, .. } |
+            Self::ProgressiveList { elements, .. } => elements.get(index),
+            Self::Container(fields) => fields.get(index).map(|(_, value)| value),
+            Self::StableContainer { .. } | Self::Profile { .. } => {
+                let (_, fields) = self.active_fields().expect("is a stable container");
+                fields.into_iter().find(|(i, _)| *i == index).map(|(_, value)| value)
+            }
+            Self::Union { value: Some(value), .. } if index == 0 => Some(value.as_ref()),
+            Self::Union { value: None, .. } if index == 0 => {
+                let leaf = 0usize;
+                return prover.compute_proof(&leaf)
+            }
+            _ => return Err(MerkleizationError::NoInnerElement),
+        };
+        let element = element.ok_or(MerkleizationError::InvalidInnerIndex)?;
+        prover.compute_proof(element)
+    }
+
+    fn decoration(&self) -> Option<usize> {
+        match self {
+            Self::List { elements, .. } | Self::ProgressiveList { elements, .. } => {
+                Some(elements.len())
+            }
+            Self::Bitlist { bits, .. } => Some(bits.len()),
+            Self::Union { selector, .. } => Some(*selector as usize),
+            _ => None,
+        }
+    }
+
+    fn decoration_root<H: Hasher>(&self) -> Result<Option<Node>, MerkleizationError> {
+        if let Some((max_fields, fields)) = self.active_fields() {
+            let indices = fields.into_iter().map(|(index, _)| index);
+            return Ok(Some(active_fields_root::<H>(indices, max_fields)?))
+        }
+        self.decoration().map(|decoration| decoration.hash_tree_root_with::<H>()).transpose()
+    }
+
+    fn value_chunk_count(&self) -> usize {
+        match self {
+            Self::Uint { .. } | Self::Boolean(..) | Self::Union { .. } => 1,
+            Self::Vector { element_type, elements } |
+            Self::ProgressiveList { element_type, elements } => {
+                collection_chunk_count(element_type, elements.len())
+            }
+            Self::List { element_type, limit, .. } => collection_chunk_count(element_type, *limit),
+            Self::Bitvector(bits) => (bits.len() + BITS_PER_CHUNK - 1) / BITS_PER_CHUNK,
+            Self::Bitlist { limit, .. } => (limit + BITS_PER_CHUNK - 1) / BITS_PER_CHUNK,
+            Self::Container(fields) => fields.len(),
+            Self::StableContainer { max_fields, .. } | Self::Profile { max_fields, .. } => {
+                *max_fields
+            }
+        }
+    }
+
+    fn is_progressive(&self) -> bool {
+        matches!(self, Self::ProgressiveList { .. })
+    }
+
+    fn prove_with<H: Hasher>(&self, path: Path) -> Result<ProofAndWitness, MerkleizationError> {
+        let index = self.ty().generalized_index(path)?;
+        let mut prover = Prover::<H>::new(index);
+        prover.compute_proof(self)?;
+        Ok(prover.into())
+    }
+
+    fn prove_multi_with<H: Hasher>(
+        &self,
+        paths: &[Path],
+    ) -> Result<MultiProofAndWitness, MerkleizationError> {
+        let ty = self.ty();
+        let indices =
+            paths.iter().map(|path| ty.generalized_index(path)).collect::<Result<Vec<_>, _>>()?;
+        Prover::<H>::compute_multiproof(self, indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+    struct FixedTestStruct {
+        a: u8,
+        b: u64,
+        c: u32,
+    }
+
+    #[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+    struct VarTestStruct {
+        a: u16,
+        b: List<u16, 1024>,
+        c: u8,
+    }
+
+    #[derive(PartialEq, Eq, Debug, Default, SimpleSerialize)]
+    struct ComplexTestStruct {
+        a: u16,
+        b: List<u16, 128>,
+        c: u8,
+        d: List<u8, 256>,
+        e: VarTestStruct,
+        f: Vector<FixedTestStruct, 4>,
+        g: Vector<VarTestStruct, 2>,
+    }
+
+    #[derive(PartialEq, Eq, Debug, SimpleSerialize)]
+    enum Payload {
+        None,
+        A(u16),
+        B(List<VarTestStruct, 3>),
+    }
+
+    #[derive(PartialEq, Eq, Debug, SimpleSerialize)]
+    struct BitsAndUnions {
+        a: Bitlist<5>,
+        b: Bitvector<2>,
+        c: Bitlist<600>,
+        d: Bitvector<300>,
+        e: Payload,
+        f: List<Payload, 4>,
+        g: U256,
+        h: bool,
+    }
+
+    fn fixed_test_struct_type() -> SszType {
+        SszType::Container(vec![
+            ("a".into(), SszType::Uint(8)),
+            ("b".into(), SszType::Uint(64)),
+            ("c".into(), SszType::Uint(32)),
+        ])
+    }
+
+    fn var_test_struct_type() -> SszType {
+        SszType::Container(vec![
+            ("a".into(), SszType::Uint(16)),
+            ("b".into(), SszType::List(Box::new(SszType::Uint(16)), 1024)),
+            ("c".into(), SszType::Uint(8)),
+        ])
+    }
+
+    fn complex_test_struct_type() -> SszType {
+        SszType::Container(vec![
+            ("a".into(), SszType::Uint(16)),
+            ("b".into(), SszType::List(Box::new(SszType::Uint(16)), 128)),
+            ("c".into(), SszType::Uint(8)),
+            ("d".into(), SszType::List(Box::new(SszType::Uint(8)), 256)),
+            ("e".into(), var_test_struct_type()),
+            ("f".into(), SszType::Vector(Box::new(fixed_test_struct_type()), 4)),
+            ("g".into(), SszType::Vector(Box::new(var_test_struct_type()), 2)),
+        ])
+    }
+
+    fn bits_and_unions_type() -> SszType {
+        let payload = SszType::Union(vec![
+            (0, None),
+            (1, Some(SszType::Uint(16))),
+            (2, Some(SszType::List(Box::new(var_test_struct_type()), 3))),
+        ]);
+        SszType::Container(vec![
+            ("a".into(), SszType::Bitlist(5)),
+            ("b".into(), SszType::Bitvector(2)),
+            ("c".into(), SszType::Bitlist(600)),
+            ("d".into(), SszType::Bitvector(300)),
+            ("e".into(), payload.clone()),
+            ("f".into(), SszType::List(Box::new(payload), 4)),
+            ("g".into(), SszType::Uint(256)),
+            ("h".into(), SszType::Boolean),
+        ])
+    }
+
+    fn var_test_struct(a: u16, b: &[u16], c: u8) -> VarTestStruct {
+        VarTestStruct { a, b: b.try_into().unwrap(), c }
+    }
+
+    fn encode(value: &DynamicValue) -> Result<Vec<u8>, SerializeError> {
+        let mut buffer = vec![];
+        value.serialize(&mut buffer)?;
+        let mut streamed = vec![];
+        value.serialize_into(&mut streamed)?;
+        assert_eq!(streamed, buffer);
+        Ok(buffer)
+    }
+
+    // Check `ty` decodes, encodes, merkleizes and proves `value` like its static type does.
+    fn check_against_static<T: SimpleSerialize>(value: &T, ty: &SszType, paths: &[Path]) {
+        let encoding = serialize(value).unwrap();
+        let dynamic_value = ty.deserialize(&encoding).unwrap();
+        assert_eq!(&dynamic_value.ty(), ty);
+        assert_eq!(encode(&dynamic_value).unwrap(), encoding);
+        assert_eq!(dynamic_value.encoded_len(), encoding.len());
+        assert_eq!(dynamic_value.hash_tree_root().unwrap(), value.hash_tree_root().unwrap());
+
+        for path in paths {
+            assert_eq!(ty.generalized_index(path).unwrap(), T::generalized_index(path).unwrap());
+            assert_eq!(dynamic_value.prove(path).unwrap(), value.prove(path).unwrap());
+        }
+        let (multiproof, witness) = dynamic_value.prove_multi(paths).unwrap();
+        assert_eq!((multiproof, witness), value.prove_multi(paths).unwrap());
+    }
+
+    #[test]
+    fn test_complex_test_struct() {
+        let value = ComplexTestStruct {
+            a: 51972,
+            b: List::try_from(vec![48645u16]).unwrap(),
+            c: 46,
+            d: List::try_from(vec![105u8, 93, 147, 119, 12]).unwrap(),
+            e: var_test_struct(45, &[1, 2, 3], 9),
+            f: Vector::try_from(vec![
+                FixedTestStruct { a: 70, b: 905948488145107787, c: 2675781419 },
+                FixedTestStruct { a: 3, b: 12, c: 4 },
+                FixedTestStruct::default(),
+                FixedTestStruct { a: 255, b: u64::MAX, c: u32::MAX },
+            ])
+            .unwrap(),
+            g: Vector::try_from(vec![var_test_struct(7, &[], 8), var_test_struct(1, &[4; 40], 2)])
+                .unwrap(),
+        };
+        let ty = complex_test_struct_type();
+        let paths: [Path; 6] = [
+            &["a".into()],
+            &["b".into(), 0.into()],
+            &["d".into(), PathElement::Length],
+            &["e".into(), "b".into(), 2.into()],
+            &["f".into(), 3.into(), "b".into()],
+            &["g".into(), 1.into(), "b".into(), 39.into()],
+        ];
+        check_against_static(&value, &ty, &paths);
+        check_against_static(&ComplexTestStruct::default(), &ty, &paths);
+
+        let encoding = serialize(&ComplexTestStruct::default()).unwrap();
+        assert_eq!(encode(&ty.default_value()).unwrap(), encoding);
+    }
+
+    #[test]
+    fn test_bits_and_unions() {
+        let value = BitsAndUnions {
+            a: Bitlist::try_from([true, false, true].as_ref()).unwrap(),
+            b: Bitvector::try_from([false, true].as_ref()).unwrap(),
+            c: Bitlist::try_from(&[true; 513][..]).unwrap(),
+            d: Bitvector::try_from(&[true; 300][..]).unwrap(),
+            e: Payload::B(List::try_from(vec![var_test_struct(1, &[2, 3], 4)]).unwrap()),
+            f: List::try_from(vec![Payload::None, Payload::A(23), Payload::B(Default::default())])
+                .unwrap(),
+            g: U256::from(1) << 200,
+            h: true,
+        };
+        let ty = bits_and_unions_type();
+        let paths: [Path; 7] = [
+            &["a".into(), 2.into()],
+            &["c".into(), 512.into()],
+            &["d".into(), 299.into()],
+            &["e".into(), PathElement::Selector],
+            &["e".into(), 2.into(), 0.into(), "b".into(), 1.into()],
+            &["f".into(), 1.into(), 1.into()],
+            &["f".into(), 0.into(), 0.into()],
+        ];
+        check_against_static(&value, &ty, &paths);
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+    #[ssz(stable_container(max_fields = 8))]
+    struct Shape {
+        side: Option<u16>,
+        color: Option<u8>,
+        radius: Option<List<u16, 4>>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+    #[ssz(profile(Shape))]
+    struct Disc {
+        color: Option<u8>,
+        radius: List<u16, 4>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+    struct Drawing {
+        shapes: ProgressiveList<Shape>,
+        disc: Disc,
+        label: Option<[u8; 3]>,
+        digest: Node,
+        sizes: ProgressiveList<u32>,
+    }
+
+    #[test]
+    fn test_type_info() {
+        assert_eq!(ComplexTestStruct::type_info(), complex_test_struct_type());
+        assert_eq!(BitsAndUnions::type_info(), bits_and_unions_type());
+        assert_eq!(usize::type_info(), SszType::Uint(64));
+        assert_eq!(
+            Disc::type_info(),
+            SszType::Profile(
+                8,
+                vec![
+                    ProfileField {
+                        name: "color".into(),
+                        ty: SszType::Uint(8),
+                        index: 1,
+                        optional: true,
+                    },
+                    ProfileField {
+                        name: "radius".into(),
+                        ty: SszType::List(Box::new(SszType::Uint(16)), 4),
+                        index: 2,
+                        optional: false,
+                    },
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_stable_containers_and_progressive_lists() {
+        let shapes = (0..20u16)
+            .map(|i| Shape {
+                side: (i % 2 == 0).then_some(i),
+                color: (i % 3 == 0).then_some(i as u8),
+                radius: (i % 5 == 0).then(|| List::try_from(vec![i; (i % 4) as usize]).unwrap()),
+            })
+            .collect::<Vec<_>>();
+        let value = Drawing {
+            shapes: shapes.into(),
+            disc: Disc { color: None, radius: List::try_from(vec![3, 4]).unwrap() },
+            label: Some([1, 2, 3]),
+            digest: Node::repeat_byte(0xab),
+            sizes: (0..100).collect(),
+        };
+        let paths: [Path; 6] = [
+            &["shapes".into(), 15.into(), "radius".into(), 2.into()],
+            &["shapes".into(), 3.into(), "color".into()],
+            &["shapes".into(), PathElement::Length],
+            &["disc".into(), "radius".into(), 1.into()],
+            &["label".into(), 1.into(), 2.into()],
+            &["sizes".into(), 99.into()],
+        ];
+        check_against_static(&value, &Drawing::type_info(), &paths);
+
+        let ty = Shape::type_info();
+        check_against_static(&Shape::default(), &ty, &[&["side".into()]]);
+        let encoding = serialize(&Shape::default()).unwrap();
+        assert_eq!(encode(&ty.default_value()).unwrap(), encoding);
+
+        // NOTE: a field beyond the fields of the stable container is active
+        assert!(ty.deserialize(&[0b1000]).is_err());
+    }
+
+    #[test]
+    fn test_encoded_len_bounds() {
+        let ty = fixed_test_struct_type();
+        assert_eq!((ty.min_encoded_len(), ty.max_encoded_len()), (13, 13));
+        let ty = var_test_struct_type();
+        assert_eq!((ty.min_encoded_len(), ty.max_encoded_len()), (7, 7 + 1024 * 2));
+        let ty = SszType::Vector(Box::new(var_test_struct_type()), 2);
+        assert_eq!((ty.min_encoded_len(), ty.max_encoded_len()), (22, 2 * (4 + 2055)));
+        let ty = SszType::Bitlist(600);
+        assert_eq!((ty.min_encoded_len(), ty.max_encoded_len()), (1, 76));
+
+        let payload = SszType::Union(vec![
+            (0, None),
+            (1, Some(SszType::Uint(16))),
+            (2, Some(SszType::List(Box::new(var_test_struct_type()), 3))),
+        ]);
+        assert_eq!((payload.min_encoded_len(), payload.max_encoded_len()), (1, 1 + 3 * 2059));
+
+        let ty = Shape::type_info();
+        assert_eq!((ty.min_encoded_len(), ty.max_encoded_len()), (1, 1 + 2 + 1 + 4 + 8));
+        assert_eq!(serialize(&Shape::default()).unwrap().len(), ty.min_encoded_len());
+        let ty = Disc::type_info();
+        assert_eq!((ty.min_encoded_len(), ty.max_encoded_len()), (5, 1 + 1 + 4 + 8));
+        let disc = Disc { color: Some(1), radius: List::try_from(vec![1, 2, 3, 4]).unwrap() };
+        assert_eq!(serialize(&disc).unwrap().len(), ty.max_encoded_len());
+
+        let ty = Drawing::type_info();
+        assert_eq!(ty.max_encoded_len(), usize::MAX);
+
+        fn check_bounds<T: Serializable + SszTypeInfo>() {
+            let ty = T::type_info();
+            assert_eq!(T::min_encoded_len(), ty.min_encoded_len());
+            assert_eq!(T::max_encoded_len(), ty.max_encoded_len());
+        }
+        check_bounds::<FixedTestStruct>();
+        check_bounds::<VarTestStruct>();
+        check_bounds::<ComplexTestStruct>();
+        check_bounds::<Payload>();
+        check_bounds::<BitsAndUnions>();
+        check_bounds::<Shape>();
+        check_bounds::<Disc>();
+        check_bounds::<Drawing>();
+        check_bounds::<Option<Bitlist<9>>>();
+        check_bounds::<[ByteList<7>; 3]>();
+        check_bounds::<List<ByteVector<5>, 3>>();
+        let ty = SszType::List(Box::new(SszType::Uint(64)), usize::MAX);
+        assert_eq!((ty.min_encoded_len(), ty.max_encoded_len()), (0, usize::MAX));
+    }
+
+    #[test]
+    fn test_invalid_types_and_encodings() {
+        assert!(SszType::Uint(7).deserialize(&[0]).is_err());
+        assert!(SszType::Vector(Box::new(SszType::Boolean), 0).deserialize(&[]).is_err());
+        assert!(SszType::Container(vec![]).deserialize(&[]).is_err());
+        let union = SszType::Union(vec![(0, None), (1, None)]);
+        assert!(union.validate().is_err());
+        let profile = SszType::Profile(
+            4,
+            vec![
+                ProfileField { name: "a".into(), ty: SszType::Boolean, index: 2, optional: false },
+                ProfileField { name: "b".into(), ty: SszType::Boolean, index: 1, optional: false },
+            ],
+        );
+        assert!(profile.validate().is_err());
+
+        assert!(SszType::Boolean.deserialize(&[2]).is_err());
+        assert!(SszType::Uint(16).deserialize(&[1, 2, 3]).is_err());
+        assert!(SszType::Bitvector(3).deserialize(&[0b1000]).is_err());
+        assert!(SszType::Bitlist(3).deserialize(&[0b10000]).is_err());
+        assert!(SszType::Bitlist(3).deserialize(&[0]).is_err());
+        let list = SszType::List(Box::new(SszType::Uint(16)), 2);
+        assert!(list.deserialize(&[1, 0, 2, 0, 3, 0]).is_err());
+        assert!(list.deserialize(&[1, 0, 2]).is_err());
+
+        let ty = complex_test_struct_type();
+        let encoding = serialize(&ComplexTestStruct::default()).unwrap();
+        assert!(ty.deserialize(&encoding[..encoding.len() - 1]).is_err());
+        assert!(ty.generalized_index(&["z".into()]).is_err());
+
+        let value = DynamicValue::Uint { bits: 8, value: U256::from(256) };
+        assert!(encode(&value).is_err());
+    }
+}
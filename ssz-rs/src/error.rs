@@ -53,6 +53,8 @@ impl std::error::Error for Error {}
 pub enum TypeError {
     /// A type is invalid for the given bounds.
     InvalidBound(usize),
+    /// A type is invalid for the given size of its elements.
+    InvalidElementSize(usize),
 }
 
 impl Display for TypeError {
@@ -61,6 +63,9 @@ impl Display for TypeError {
             Self::InvalidBound(size) => {
                 write!(f, "the type for this value is invalid with bound {size}")
             }
+            Self::InvalidElementSize(size) => {
+                write!(f, "the type for this value is invalid with elements of size {size}")
+            }
         }
     }
 }
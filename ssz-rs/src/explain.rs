@@ -0,0 +1,410 @@
+//! Annotated layouts of SSZ encodings, showing what each range of bytes of an encoding decodes to
+//! and where decoding an invalid encoding fails.
+use crate::{
+    de::{ContainerDeserializer, DeserializeError},
+    dynamic::{split_active_fields, SszType},
+    lib::*,
+    ser::BYTES_PER_LENGTH_OFFSET,
+};
+
+// Number of bytes of a region displayed before eliding the rest.
+const MAX_DISPLAYED_BYTES: usize = 16;
+
+// A position in an encoding where decoding failed, with the error.
+type Failure = (usize, DeserializeError);
+
+/// What a `Region` of an encoding holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionKind {
+    /// A value of the given type.
+    Value(SszType),
+    /// The offset of a variable-size field or element, with the position in the encoding it
+    /// points to.
+    Offset(usize),
+    /// The selector of a union.
+    Selector(u8),
+    /// The bitvector of the given number of active fields of a `StableContainer` or `Profile`.
+    ActiveFields(usize),
+}
+
+/// A range of bytes of an encoding, e.g. a field of a container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// The position of the region in the encoding.
+    pub range: Range<usize>,
+    /// The name of the field, or the index of the element, the region belongs to.
+    pub label: String,
+    pub kind: RegionKind,
+    /// The regions making up a composite value, in the order of the encoding.
+    pub children: Vec<Region>,
+}
+
+impl Region {
+    fn new(range: Range<usize>, label: impl Into<String>, kind: RegionKind) -> Self {
+        Self { range, label: label.into(), kind, children: vec![] }
+    }
+}
+
+/// The layout of an encoding of some `SszType`, returned from `SszType::explain`.
+///
+/// Displays as an annotated hex dump of each region, followed by any error decoding the encoding.
+#[derive(Debug)]
+pub struct Explanation<'a> {
+    encoding: &'a [u8],
+    /// The region of the whole encoding.
+    pub region: Region,
+    /// The position in the encoding where decoding failed and the error, if the encoding is
+    /// invalid.
+    pub error: Option<(usize, DeserializeError)>,
+}
+
+impl SszType {
+    /// Explain how `encoding` decodes to a value of `self`, e.g. to find which field of an
+    /// invalid encoding fails to decode.
+    ///
+    /// Regions of the encoding are found like `deserialize` does, so the explanation ends where
+    /// deserializing fails. The types of a `SimpleSerialize` type can be explained with
+    /// `T::type_info().explain(encoding)`.
+    pub fn explain<'a>(&self, encoding: &'a [u8]) -> Explanation<'a> {
+        let mut region = Region::new(0..encoding.len(), "value", RegionKind::Value(self.clone()));
+        let result = match self.validate() {
+            Ok(()) => explain_value(self, encoding, 0, &mut region.children),
+            Err(err) => Err((0, err.into())),
+        };
+        Explanation { encoding, region, error: result.err() }
+    }
+}
+
+// Add the regions of the `encoding` of a value of `ty` found at `position` to `children`.
+fn explain_value(
+    ty: &SszType,
+    encoding: &[u8],
+    position: usize,
+    children: &mut Vec<Region>,
+) -> Result<(), Failure> {
+    explain_parts(ty, encoding, position, children)?;
+    // NOTE: any remaining error is in the value itself, e.g. a list over its limit
+    ty.decode(encoding).map(|_| ()).map_err(|err| (position, err))
+}
+
+// Add the region of a value of `ty` at `position` with its own regions to `children`.
+fn explain_child(
+    label: &str,
+    ty: &SszType,
+    encoding: &[u8],
+    position: usize,
+    children: &mut Vec<Region>,
+) -> Result<(), Failure> {
+    let range = position..position + encoding.len();
+    let mut region = Region::new(range, label, RegionKind::Value(ty.clone()));
+    let result = explain_value(ty, encoding, position, &mut region.children);
+    children.push(region);
+    result
+}
+
+fn explain_parts(
+    ty: &SszType,
+    encoding: &[u8],
+    position: usize,
+    children: &mut Vec<Region>,
+) -> Result<(), Failure> {
+    match ty {
+        SszType::Vector(element_type, ..) |
+        SszType::List(element_type, ..) |
+        SszType::ProgressiveList(element_type)
+            if !element_type.is_basic() =>
+        {
+            explain_elements(element_type, encoding, position, children)
+        }
+        SszType::Container(fields) => {
+            let fields =
+                fields.iter().map(|(name, field_type)| (name.clone(), field_type)).collect();
+            explain_fields(fields, encoding, position, children)
+        }
+        SszType::StableContainer(max_fields, fields) => {
+            let (active_fields, encoding, position) =
+                explain_active_fields(encoding, *max_fields, position, children)?;
+            let fields = fields
+                .iter()
+                .zip(active_fields)
+                .filter(|(_, active)| *active)
+                .map(|((name, field_type), _)| (name.clone(), field_type))
+                .collect();
+            explain_fields(fields, encoding, position, children)
+        }
+        SszType::Profile(_, fields) => {
+            let optional_count = fields.iter().filter(|field| field.optional).count();
+            let (active_fields, encoding, position) = if optional_count > 0 {
+                explain_active_fields(encoding, optional_count, position, children)?
+            } else {
+                (vec![], encoding, position)
+            };
+            let mut active_fields = active_fields.into_iter();
+            let fields = fields
+                .iter()
+                .filter(|field| !field.optional || active_fields.next().unwrap_or_default())
+                .map(|field| (field.name.clone(), &field.ty))
+                .collect();
+            explain_fields(fields, encoding, position, children)
+        }
+        SszType::Union(variants) => {
+            let Some((selector, encoding)) = encoding.split_first() else { return Ok(()) };
+            let region =
+                Region::new(position..position + 1, "selector", RegionKind::Selector(*selector));
+            children.push(region);
+            match variants.iter().find(|(s, _)| s == selector) {
+                Some((_, Some(variant_type))) => {
+                    explain_child("value", variant_type, encoding, position + 1, children)
+                }
+                _ => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+// Add the region of the bitvector of `len` active fields at the start of the `encoding` of a
+// `StableContainer` or `Profile` to `children`, returning the active fields with the encoding of
+// the fields and its position.
+fn explain_active_fields<'a>(
+    encoding: &'a [u8],
+    len: usize,
+    position: usize,
+    children: &mut Vec<Region>,
+) -> Result<(Vec<bool>, &'a [u8], usize), Failure> {
+    let (active_fields, fields_encoding) =
+        split_active_fields(encoding, len).map_err(|err| (position, err))?;
+    let fields_position = position + encoding.len() - fields_encoding.len();
+    children.push(Region::new(
+        position..fields_position,
+        "active_fields",
+        RegionKind::ActiveFields(len),
+    ));
+    Ok((active_fields, fields_encoding, fields_position))
+}
+
+// Add the regions of the elements of a collection of composite `element_type` to `children`.
+fn explain_elements(
+    element_type: &SszType,
+    encoding: &[u8],
+    position: usize,
+    children: &mut Vec<Region>,
+) -> Result<(), Failure> {
+    let count = if element_type.is_variable_size() {
+        // NOTE: the first offset marks the end of the offsets, so gives the number of elements
+        let first_offset = encoding.get(..BYTES_PER_LENGTH_OFFSET).map(read_offset).unwrap_or(0);
+        if first_offset > encoding.len() || first_offset % BYTES_PER_LENGTH_OFFSET != 0 {
+            // NOTE: leave the error to decoding the collection itself
+            return Ok(())
+        }
+        first_offset / BYTES_PER_LENGTH_OFFSET
+    } else {
+        encoding.len() / element_type.size_hint()
+    };
+    let elements = (0..count).map(|i| (format!("[{i}]"), element_type)).collect();
+    explain_fields(elements, encoding, position, children)
+}
+
+// Add the regions of the given fields of a container to `children`, following the spans computed
+// by `ContainerDeserializer`: first the fixed part of each field, i.e. either its value or the
+// offset of its value, then the values of the variable-size fields.
+fn explain_fields(
+    fields: Vec<(String, &SszType)>,
+    encoding: &[u8],
+    position: usize,
+    children: &mut Vec<Region>,
+) -> Result<(), Failure> {
+    let mut deserializer = ContainerDeserializer::default();
+    let mut cursor = 0;
+    for (name, field_type) in &fields {
+        let is_variable_size = field_type.is_variable_size();
+        let end = cursor +
+            if is_variable_size { BYTES_PER_LENGTH_OFFSET } else { field_type.size_hint() };
+        if let (true, Some(offset)) = (is_variable_size, encoding.get(cursor..end)) {
+            let range = position + cursor..position + end;
+            let target = position + read_offset(offset);
+            children.push(Region::new(range, name.as_str(), RegionKind::Offset(target)));
+        }
+        deserializer
+            .parse_segment(encoding, is_variable_size, field_type.size_hint())
+            .map_err(|err| (position + cursor, err))?;
+        if !is_variable_size {
+            explain_child(name, field_type, &encoding[cursor..end], position + cursor, children)?;
+        }
+        cursor = end;
+    }
+    let spans = deserializer.finalize(encoding).map_err(|err| (position + cursor, err))?;
+    for ((name, field_type), span) in fields.iter().zip(spans.chunks_exact(2)) {
+        if field_type.is_variable_size() {
+            let (start, end) = (span[0], span[1]);
+            explain_child(name, field_type, &encoding[start..end], position + start, children)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_offset(bytes: &[u8]) -> usize {
+    u32::from_le_bytes(bytes.try_into().expect("offset has 4 bytes")) as usize
+}
+
+impl Explanation<'_> {
+    fn write_region(&self, f: &mut Formatter<'_>, region: &Region, depth: usize) -> fmt::Result {
+        let Range { start, end } = region.range;
+        write!(f, "{start:>6}..{end:<6} {:indent$}{}: ", "", region.label, indent = 2 * depth)?;
+        match &region.kind {
+            RegionKind::Value(ty) => write!(f, "{ty}")?,
+            RegionKind::Offset(target) => write!(f, "offset -> {target}")?,
+            RegionKind::Selector(selector) => write!(f, "{selector}")?,
+            RegionKind::ActiveFields(len) => write!(f, "Bitvector[{len}]")?,
+        }
+        if region.children.is_empty() && start < end {
+            let bytes = &self.encoding[start..end];
+            write!(f, " |")?;
+            for byte in bytes.iter().take(MAX_DISPLAYED_BYTES) {
+                write!(f, " {byte:02x}")?;
+            }
+            if bytes.len() > MAX_DISPLAYED_BYTES {
+                write!(f, " ... ({} bytes)", bytes.len())?;
+            }
+        }
+        writeln!(f)?;
+        region.children.iter().try_for_each(|child| self.write_region(f, child, depth + 1))
+    }
+}
+
+impl Display for Explanation<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_region(f, &self.region, 0)?;
+        if let Some((position, err)) = &self.error {
+            writeln!(f, "error at byte {position}: {err}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(PartialEq, Eq, Debug, Default, SimpleSerialize)]
+    struct Entry {
+        flag: bool,
+        data: List<u8, 4>,
+    }
+
+    #[derive(PartialEq, Eq, Debug, Default, SimpleSerialize)]
+    struct Block {
+        slot: u16,
+        entries: List<Entry, 4>,
+        extra: List<u8, 8>,
+    }
+
+    fn block() -> Block {
+        Block {
+            slot: 7,
+            entries: List::try_from(vec![
+                Entry { flag: true, data: List::try_from(vec![1, 2]).unwrap() },
+                Entry::default(),
+            ])
+            .unwrap(),
+            extra: List::try_from(vec![0xff]).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let encoding = serialize(&block()).unwrap();
+        let explanation = Block::type_info().explain(&encoding);
+        assert!(explanation.error.is_none());
+        let expected = "     0..31     value: Container
+     0..2        slot: uint16 | 07 00
+     2..6        entries: offset -> 10 | 0a 00 00 00
+     6..10       extra: offset -> 30 | 1e 00 00 00
+    10..30       entries: List[Container, 4]
+    10..14         [0]: offset -> 18 | 08 00 00 00
+    14..18         [1]: offset -> 25 | 0f 00 00 00
+    18..25         [0]: Container
+    18..19           flag: boolean | 01
+    19..23           data: offset -> 23 | 05 00 00 00
+    23..25           data: List[uint8, 4] | 01 02
+    25..30         [1]: Container
+    25..26           flag: boolean | 00
+    26..30           data: offset -> 30 | 05 00 00 00
+    30..30           data: List[uint8, 4]
+    30..31       extra: List[uint8, 8] | ff
+";
+        assert_eq!(explanation.to_string(), expected);
+
+        let union = SszType::Union(vec![(0, None), (1, Some(SszType::Uint(16)))]);
+        let explanation = union.explain(&[1, 2, 3]);
+        assert!(explanation.error.is_none());
+        let kinds = explanation.region.children.iter().map(|region| &region.kind);
+        let expected = [RegionKind::Selector(1), RegionKind::Value(SszType::Uint(16))];
+        assert!(kinds.eq(expected.iter()));
+
+        let ty = SszType::StableContainer(
+            4,
+            vec![
+                ("a".into(), SszType::Uint(8)),
+                ("b".into(), SszType::List(Box::new(SszType::Uint(8)), 2)),
+            ],
+        );
+        let explanation = ty.explain(&[0b11, 7, 5, 0, 0, 0, 9]);
+        assert!(explanation.error.is_none());
+        let expected = "     0..7      value: StableContainer[4]
+     0..1        active_fields: Bitvector[4] | 03
+     1..2        a: uint8 | 07
+     2..6        b: offset -> 6 | 05 00 00 00
+     6..7        b: List[uint8, 2] | 09
+";
+        assert_eq!(explanation.to_string(), expected);
+    }
+
+    #[test]
+    fn test_explain_invalid_encodings() {
+        let encoding = serialize(&block()).unwrap();
+
+        let mut invalid = encoding.clone();
+        invalid[6] = 9;
+        let explanation = Block::type_info().explain(&invalid);
+        let (position, err) = explanation.error.as_ref().unwrap();
+        assert_eq!(*position, 6);
+        assert!(matches!(err, DeserializeError::OffsetNotIncreasing { start: 10, end: 9 }));
+        let last = explanation.region.children.last().unwrap();
+        assert_eq!((last.range.clone(), &last.kind), (6..10, &RegionKind::Offset(9)));
+        assert!(explanation
+            .to_string()
+            .ends_with("error at byte 6: invalid offset points to byte 9 before byte 10\n"));
+
+        let mut invalid = encoding.clone();
+        invalid[25] = 2;
+        let explanation = Block::type_info().explain(&invalid);
+        let (position, err) = explanation.error.as_ref().unwrap();
+        assert_eq!(*position, 25);
+        assert!(matches!(err, DeserializeError::InvalidByte(2)));
+        assert!(Block::deserialize(&invalid).is_err());
+
+        let mut invalid = encoding.clone();
+        invalid[6] = 40;
+        let explanation = Block::type_info().explain(&invalid);
+        assert!(matches!(
+            explanation.error,
+            Some((6, DeserializeError::ExpectedFurtherInput { .. }))
+        ));
+        assert!(Block::deserialize(&invalid).is_err());
+
+        let explanation = Block::type_info().explain(&encoding[..8]);
+        let (position, err) = explanation.error.as_ref().unwrap();
+        assert_eq!(*position, 6);
+        assert!(matches!(err, DeserializeError::ExpectedFurtherInput { .. }));
+
+        let explanation = SszType::Uint(16).explain(&[1, 2, 3]);
+        let (position, err) = explanation.error.as_ref().unwrap();
+        assert_eq!(*position, 0);
+        assert!(matches!(err, DeserializeError::AdditionalInput { provided: 3, expected: 2 }));
+
+        let explanation = SszType::Uint(7).explain(&[1]);
+        assert!(matches!(explanation.error, Some((0, DeserializeError::InvalidType(..)))));
+    }
+}
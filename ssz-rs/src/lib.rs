@@ -81,8 +81,25 @@
 //! assert!(proof.verify(witness).is_ok());
 //! ```
 //!
+//! Read a single field from an encoding without decoding the rest of it:
+//!
+//! ```
+//! # use ssz_rs::prelude::*;
+//! #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize, Viewable)]
+//! struct Block {
+//!   slot: u64,
+//!   transactions: List<List<u8, 1024>, 16>,
+//! }
+//!
+//! let block = Block { slot: 42, ..Default::default() };
+//! let encoding = serialize(&block).unwrap();
+//! let view = Block::view(&encoding).unwrap();
+//! assert_eq!(view.slot().unwrap(), 42);
+//! assert!(view.transactions().unwrap().is_empty());
+//! ```
+//!
 //! [ssz]: https://github.com/ethereum/consensus-specs/blob/dev/ssz/simple-serialize.md
-#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
 #[cfg(not(feature = "std"))]
 extern crate alloc;
@@ -91,17 +108,33 @@ mod array;
 mod bitlist;
 mod bitvector;
 mod boolean;
+mod byte_list;
+mod byte_vector;
+mod cached_list;
+mod cached_vector;
 mod container;
 mod de;
+mod dynamic;
 mod error;
+mod explain;
 mod list;
 mod merkleization;
+mod persistent_list;
+mod persistent_vector;
+mod progressive_list;
+mod schema;
 mod ser;
 #[cfg(feature = "serde")]
 mod serde;
+#[cfg(feature = "snappy")]
+pub mod snappy;
+#[cfg(feature = "serde")]
+pub mod spec_serde;
+mod stable_container;
 mod uint;
 mod union;
 mod vector;
+mod view;
 
 mod lib {
     mod core {
@@ -111,30 +144,36 @@ mod lib {
         pub use std::*;
     }
 
-    pub use self::core::{any, cmp, fmt};
+    pub use self::core::{any, cmp, fmt, mem, slice};
 
     pub use self::{
         cmp::Ordering,
         core::{
             fmt::{Debug, Display, Formatter},
-            ops::{Deref, DerefMut, Index, IndexMut},
+            ops::{Deref, DerefMut, Index, IndexMut, Range},
             slice::SliceIndex,
+            str::FromStr,
         },
     };
 
     #[cfg(not(feature = "std"))]
-    pub use alloc::{format, string::String, string::ToString, vec, vec::Vec};
+    pub use alloc::{boxed::Box, format, string::String, string::ToString, vec, vec::Vec};
 
     #[cfg(feature = "std")]
     pub use std::vec::Vec;
 
+    #[cfg(not(feature = "std"))]
+    pub use alloc::sync::Arc;
+
+    #[cfg(feature = "std")]
+    pub use std::sync::Arc;
+
     #[cfg(not(feature = "std"))]
     pub use alloc::collections::{BTreeMap as HashMap, BTreeSet as HashSet};
 
     #[cfg(feature = "std")]
     pub use std::collections::{HashMap, HashSet};
 
-    #[cfg(feature = "serde")]
     pub use self::core::marker::PhantomData;
 }
 
@@ -149,32 +188,84 @@ pub trait Serializable: Serialize + Deserialize {
     // expected number of bytes for the serialization of this type
     // or 0 if unknown ahead of time
     fn size_hint() -> usize;
+
+    /// Return the minimum number of bytes in the encoding of a value of this type.
+    ///
+    /// The default implementation is only exact for types of a fixed size.
+    fn min_encoded_len() -> usize {
+        Self::size_hint()
+    }
+
+    /// Return the maximum number of bytes in the encoding of a value of this type, saturating at
+    /// `usize::MAX`, e.g. for a `ProgressiveList`.
+    ///
+    /// The default implementation is only exact for types of a fixed size.
+    fn max_encoded_len() -> usize {
+        if Self::is_variable_size() {
+            usize::MAX
+        } else {
+            Self::size_hint()
+        }
+    }
 }
 
 /// `SimpleSerialize` is a trait for types conforming to the SSZ spec.
 /// These types can be encoded and decoded while also supporting the
 /// merkelization scheme of SSZ.
-pub trait SimpleSerialize: Serializable + HashTreeRoot + GeneralizedIndexable + Prove {}
+pub trait SimpleSerialize: Serializable + HashTreeRoot + GeneralizedIndexable + Prove {
+    /// Compute the hash tree root of the value encoded in `encoding`.
+    ///
+    /// The default implementation deserializes the value; implementing types should override it to
+    /// merkleize the encoding directly.
+    fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<Node, SimpleSerializeError>
+    where
+        Self: Sized,
+    {
+        let value = Self::deserialize(encoding)?;
+        Ok(value.hash_tree_root()?)
+    }
+}
 
 mod exports {
     pub use crate::{
         bitlist::Bitlist,
         bitvector::Bitvector,
+        byte_list::ByteList,
+        byte_vector::ByteVector,
+        cached_list::CachedList,
+        cached_vector::CachedVector,
         de::{Deserialize, DeserializeError},
+        dynamic::{DynamicValue, ProfileField, SszType, SszTypeInfo},
         error::{Error as SimpleSerializeError, InstanceError, TypeError},
+        explain::{Explanation, Region, RegionKind},
         list::List,
         merkleization::{
             generalized_index::default_generalized_index,
+            hasher::{self, Hasher},
             multiproofs,
             proofs::{self, is_valid_merkle_branch, Prove},
             GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, MerkleizationError, Node, Path,
             PathElement,
         },
-        ser::{Serialize, SerializeError},
+        persistent_list::PersistentList,
+        persistent_vector::PersistentVector,
+        progressive_list::ProgressiveList,
+        schema::{Schema, SchemaError},
+        ser::{Serialize, SerializeError, Sink},
+        stable_container::{Profile, StableContainer},
         uint::U256,
         vector::Vector,
+        view::{ListView, VectorView, Viewable},
     };
 
+    #[cfg(feature = "std")]
+    pub use crate::de::Reader;
+    #[cfg(feature = "rayon")]
+    pub use crate::merkleization::ParallelHashTreeRoot;
+
+    #[cfg(feature = "serde")]
+    pub use crate::spec_serde::{Spec, SpecDeserialize, SpecSerialize};
+
     /// `serialize` is a convenience function for taking a value that
     /// implements `SimpleSerialize` and attempts to encode it to
     /// a `Vec<u8>` according to the SSZ spec.
@@ -182,8 +273,10 @@ mod exports {
     where
         T: crate::Serializable,
     {
-        let mut result = crate::lib::Vec::new();
-        value.serialize(&mut result)?;
+        // NOTE: writes to the buffer allocated for the exact encoded length, as `serialize` would
+        // compute the length again to reserve it
+        let mut result = crate::lib::Vec::with_capacity(value.encoded_len());
+        value.serialize_into(&mut result)?;
         Ok(result)
     }
 
@@ -196,6 +289,29 @@ mod exports {
     {
         T::deserialize(encoding)
     }
+
+    /// `hash_tree_root_from_bytes` is a convenience function for computing
+    /// the hash tree root of some value that implements `SimpleSerialize`
+    /// directly from its encoding in a `&[u8]`, without deserializing the value.
+    pub fn hash_tree_root_from_bytes<T>(encoding: &[u8]) -> Result<Node, SimpleSerializeError>
+    where
+        T: crate::SimpleSerialize,
+    {
+        T::hash_tree_root_from_bytes(encoding)
+    }
+
+    /// `deserialize_reader` is a convenience function for deserializing a value
+    /// from the entire contents of `reader`, failing if the encoding is longer
+    /// than `max_len` bytes.
+    #[cfg(feature = "std")]
+    pub fn deserialize_reader<T, R>(reader: R, max_len: usize) -> Result<T, DeserializeError>
+    where
+        T: crate::Serializable,
+        R: std::io::Read,
+    {
+        let mut reader = crate::de::Reader::new(reader, max_len);
+        T::deserialize_reader(&mut reader, None)
+    }
 }
 
 pub use crate::exports::*;
@@ -210,7 +326,8 @@ pub mod prelude {
     #[doc(hidden)]
     pub use crate as ssz_rs;
     pub use ssz_rs_derive::{
-        GeneralizedIndexable, HashTreeRoot, Prove, Serializable, SimpleSerialize,
+        GeneralizedIndexable, HashTreeRoot, Prove, Serializable, SimpleSerialize, SszTypeInfo,
+        Viewable,
     };
 }
 
@@ -218,9 +335,44 @@ pub mod prelude {
 /// `internal` contains functionality that is exposed purely for the derive proc macro crate
 pub mod __internal {
     // exported for derive macro to avoid code duplication...
+    #[cfg(feature = "std")]
+    pub use crate::de::{read_none_value, read_selector, StreamDeserializer};
+    #[cfg(feature = "serde")]
+    pub use crate::spec_serde::next_field;
     pub use crate::{
         de::ContainerDeserializer,
-        merkleization::{generalized_index::get_power_of_two_ceil, merkleize, mix_in_selector},
-        ser::Serializer,
+        merkleization::{
+            generalized_index::get_power_of_two_ceil, merkleize, merkleize_with, mix_in_selector,
+            mix_in_selector_with,
+        },
+        ser::{
+            element_encoded_len, fixed_part_len, max_part_len, min_part_len, part_len,
+            serialize_reserved, StreamSerializer,
+        },
+        stable_container::{active_field_chunks, active_fields_root, merkleize_active_fields},
     };
+    #[cfg(feature = "serde")]
+    pub use serde;
+    #[cfg(feature = "std")]
+    pub use std::io::Read;
+
+    // Expands to the given items only if the `std` feature of this crate is enabled.
+    #[cfg(feature = "std")]
+    #[macro_export]
+    #[doc(hidden)]
+    macro_rules! __std_only {
+        ($($tt:tt)*) => {
+            $($tt)*
+        };
+    }
+
+    // Expands to the given items only if the `std` feature of this crate is enabled.
+    #[cfg(not(feature = "std"))]
+    #[macro_export]
+    #[doc(hidden)]
+    macro_rules! __std_only {
+        ($($tt:tt)*) => {};
+    }
+
+    pub use crate::__std_only as std_only;
 }
@@ -1,17 +1,29 @@
 use crate::{
     de::{deserialize_homogeneous_composite, Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
     error::{Error, InstanceError},
     lib::*,
     merkleization::{
-        elements_to_chunks, get_power_of_two_ceil, merkleize, mix_in_length, pack,
+        elements_to_chunks, get_power_of_two_ceil,
+        hasher::Sha256,
+        merkleize, merkleize_with, mix_in_length, pack,
         proofs::{Prove, Prover},
-        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, MerkleizationError, Node, Path,
-        PathElement, BYTES_PER_CHUNK,
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, PathElement, BYTES_PER_CHUNK,
     },
-    ser::{Serialize, SerializeError, Serializer},
+    ser::{
+        homogeneous_encoded_len, max_part_len, serialize_homogeneous_into, serialize_reserved,
+        Serialize, SerializeError, Sink,
+    },
+    view::ListView,
     Serializable, SimpleSerialize,
 };
 
+#[cfg(feature = "std")]
+use crate::de::{deserialize_homogeneous_composite_from_reader, Reader};
+#[cfg(feature = "rayon")]
+use crate::merkleization::{elements_to_chunks_in_parallel, ParallelHashTreeRoot};
+
 /// A homogenous collection of a variable number of values.
 #[derive(PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
@@ -145,6 +157,14 @@ where
     fn size_hint() -> usize {
         0
     }
+
+    fn min_encoded_len() -> usize {
+        0
+    }
+
+    fn max_encoded_len() -> usize {
+        N.saturating_mul(max_part_len::<T>())
+    }
 }
 
 impl<T, const N: usize> Serialize for List<T, N>
@@ -155,11 +175,18 @@ where
         if self.len() > N {
             return Err(InstanceError::Bounded { bound: N, provided: self.len() }.into())
         }
-        let mut serializer = Serializer::default();
-        for element in &self.data {
-            serializer.with_element(element)?;
+        serialize_reserved(self, buffer)
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        if self.len() > N {
+            return Err(InstanceError::Bounded { bound: N, provided: self.len() }.into())
         }
-        serializer.serialize(buffer)
+        serialize_homogeneous_into(self.data.iter(), sink)
+    }
+
+    fn encoded_len(&self) -> usize {
+        homogeneous_encoded_len(self.data.iter())
     }
 }
 
@@ -190,16 +217,28 @@ where
         })?;
         Ok(result)
     }
+
+    #[cfg(feature = "std")]
+    fn deserialize_reader<R: std::io::Read>(
+        reader: &mut Reader<R>,
+        len: Option<usize>,
+    ) -> Result<Self, DeserializeError> {
+        let result = deserialize_homogeneous_composite_from_reader(reader, len, N)?;
+        let result = result.try_into().map_err(|(_, err)| match err {
+            Error::Instance(err) => DeserializeError::InvalidInstance(err),
+            _ => unreachable!("no other error variant allowed here"),
+        })?;
+        Ok(result)
+    }
 }
 
 impl<T, const N: usize> List<T, N>
 where
     T: SimpleSerialize,
 {
-    fn assemble_chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
+    fn assemble_chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
         if T::is_composite_type() {
-            let count = self.len();
-            elements_to_chunks(self.data.iter().enumerate(), count)
+            elements_to_chunks::<H, _>(&self.data)
         } else {
             pack(self)
         }
@@ -215,14 +254,18 @@ where
         (N * T::size_hint() + BYTES_PER_CHUNK - 1) / BYTES_PER_CHUNK
     }
 
-    fn compute_hash_tree_root(&self) -> Result<Node, MerkleizationError> {
-        let chunks = self.assemble_chunks()?;
+    fn compute_hash_tree_root<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        let chunks = self.assemble_chunks::<H>()?;
+        self.root_of_chunks::<H>(&chunks)
+    }
+
+    fn root_of_chunks<H: Hasher>(&self, chunks: &[u8]) -> Result<Node, MerkleizationError> {
         let data_root = if T::is_composite_type() {
-            merkleize(&chunks, Some(N))?
+            merkleize_with::<H>(chunks, Some(N))?
         } else {
-            merkleize(&chunks, Some(Self::chunk_count()))?
+            merkleize_with::<H>(chunks, Some(Self::chunk_count()))?
         };
-        Ok(mix_in_length(data_root, self.len()))
+        Ok(mix_in_length::<H>(data_root, self.len()))
     }
 }
 
@@ -230,8 +273,22 @@ impl<T, const N: usize> HashTreeRoot for List<T, N>
 where
     T: SimpleSerialize,
 {
-    fn hash_tree_root(&self) -> Result<Node, MerkleizationError> {
-        self.compute_hash_tree_root()
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        self.compute_hash_tree_root::<H>()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T, const N: usize> ParallelHashTreeRoot for List<T, N>
+where
+    T: SimpleSerialize + Sync,
+{
+    fn par_hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        if !T::is_composite_type() {
+            return self.compute_hash_tree_root::<H>()
+        }
+        let chunks = elements_to_chunks_in_parallel::<H, _>(&self.data)?;
+        self.root_of_chunks::<H>(&chunks)
     }
 }
 
@@ -279,11 +336,15 @@ impl<T, const N: usize> Prove for List<T, N>
 where
     T: SimpleSerialize,
 {
-    fn chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
-        self.assemble_chunks()
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        self.assemble_chunks::<H>()
     }
 
-    fn prove_element(&self, index: usize, prover: &mut Prover) -> Result<(), MerkleizationError> {
+    fn prove_element<H: Hasher>(
+        &self,
+        index: usize,
+        prover: &mut Prover<H>,
+    ) -> Result<(), MerkleizationError> {
         if index >= N {
             Err(MerkleizationError::InvalidInnerIndex)
         } else {
@@ -297,7 +358,30 @@ where
     }
 }
 
-impl<T, const N: usize> SimpleSerialize for List<T, N> where T: SimpleSerialize {}
+impl<T, const N: usize> SimpleSerialize for List<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<Node, Error> {
+        let view = ListView::<T, N>::new(encoding)?;
+        let chunks = view.chunks()?;
+        let data_root = if T::is_composite_type() {
+            merkleize(&chunks, Some(N))?
+        } else {
+            merkleize(&chunks, Some(Self::chunk_count()))?
+        };
+        Ok(mix_in_length::<Sha256>(data_root, view.len()))
+    }
+}
+
+impl<T, const N: usize> SszTypeInfo for List<T, N>
+where
+    T: Serializable + SszTypeInfo,
+{
+    fn type_info() -> SszType {
+        SszType::List(Box::new(T::type_info()), N)
+    }
+}
 
 #[cfg(feature = "serde")]
 struct ListVisitor<T: Serializable>(PhantomData<Vec<T>>);
@@ -422,6 +506,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn test_serde() {
         type L = List<u8, 4>;
         let data = vec![1u8, 22];
@@ -432,6 +517,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     #[should_panic]
     fn test_illegal_serde() {
         type L = List<u8, 4>;
@@ -516,4 +602,26 @@ mod tests {
         other[0] = 244;
         assert_eq!(input.cmp(&other), Ordering::Less);
     }
+
+    #[test]
+    fn test_hash_tree_root_from_bytes() {
+        type L = List<List<bool, 7>, 5>;
+        let inner = List::<bool, 7>::try_from(vec![true, false, true]).unwrap();
+        let value = L::try_from(vec![inner.clone(), List::default(), inner]).unwrap();
+        let encoding = serialize(&value).unwrap();
+        let root = L::hash_tree_root_from_bytes(&encoding).unwrap();
+        assert_eq!(root, value.hash_tree_root().unwrap());
+
+        let value = List::<u16, COUNT>::try_from(vec![1u16, 2, 3, 4, 5]).unwrap();
+        let encoding = serialize(&value).unwrap();
+        let root = List::<u16, COUNT>::hash_tree_root_from_bytes(&encoding).unwrap();
+        assert_eq!(root, value.hash_tree_root().unwrap());
+
+        // odd number of bytes for a list of `u16`
+        let result = List::<u16, COUNT>::hash_tree_root_from_bytes(&encoding[..3]);
+        assert!(result.is_err());
+        // bools must be encoded as 0 or 1
+        let result = List::<bool, COUNT>::hash_tree_root_from_bytes(&[1, 2]);
+        assert!(result.is_err());
+    }
 }
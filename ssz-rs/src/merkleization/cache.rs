@@ -0,0 +1,376 @@
+//! Support for caching Merkle trees across modifications of the underlying data.
+use crate::{
+    lib::*,
+    merkleization::{hash_nodes, zero_hash, MerkleizationError as Error, Node, BYTES_PER_CHUNK},
+    SimpleSerialize,
+};
+use sha2::{Digest, Sha256};
+
+// Nodes assigned in one layer of a `MerkleCache`, sorted by their index in that layer.
+type LayerUpdates = Vec<(usize, Node)>;
+
+/// A Merkle tree that keeps every node so that a change to some of its leaves only requires
+/// re-hashing the branches above those leaves.
+///
+/// Only the nodes covering the current leaves are stored. Any node past the end of a layer is the
+/// root of a "zero" subtree, following the same virtual padding used by `merkleize`.
+#[derive(Debug, Clone)]
+pub(crate) struct MerkleCache {
+    // `layers[0]` holds the leaves and `layers[depth]` holds the root, if any leaves are present
+    layers: Vec<Vec<Node>>,
+}
+
+impl MerkleCache {
+    // Invariant: `depth < MAX_MERKLE_TREE_DEPTH`
+    pub(crate) fn new(depth: usize) -> Self {
+        Self { layers: vec![vec![]; depth + 1] }
+    }
+
+    fn depth(&self) -> usize {
+        // SAFETY: checked subtraction is unnecessary, as `layers` is never empty; qed
+        self.layers.len() - 1
+    }
+
+    fn leaf_count(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    fn leaf(&self, index: usize) -> Option<&Node> {
+        self.layers[0].get(index)
+    }
+
+    pub(crate) fn root(&self) -> Node {
+        match self.layers[self.depth()].first() {
+            Some(root) => *root,
+            None => Node::from_slice(zero_hash::<Sha256>(self.depth())),
+        }
+    }
+
+    fn node<'a>(&'a self, depth: usize, index: usize, updates: &'a [(usize, Node)]) -> &'a Node {
+        match updates.binary_search_by_key(&index, |(i, _)| *i) {
+            Ok(position) => &updates[position].1,
+            Err(_) => &self.layers[depth][index],
+        }
+    }
+
+    // Compute every node that changes once the tree holds `leaf_count` leaves and the given
+    // `leaves` have been assigned.
+    // Invariant: `leaves` is sorted by index and every index is less than `leaf_count`
+    // Invariant: `leaves` covers every index from the current leaf count up to `leaf_count`
+    fn compute_updates(&self, leaf_count: usize, leaves: LayerUpdates) -> Vec<LayerUpdates> {
+        debug_assert!(leaves.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        debug_assert!(leaves.last().map(|(i, _)| *i < leaf_count).unwrap_or(true));
+
+        let mut touched = leaves.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+        if leaf_count != self.leaf_count() && leaf_count != 0 {
+            // NOTE: the branch over the last leaf may now gain (or lose) virtual padding
+            // SAFETY: checked subtraction is unnecessary, as leaf_count != 0; qed
+            let last = leaf_count - 1;
+            if let Err(position) = touched.binary_search(&last) {
+                touched.insert(position, last);
+            }
+        }
+
+        let mut hasher = Sha256::new();
+        let mut updates = Vec::with_capacity(self.layers.len());
+        updates.push(leaves);
+        let mut layer_len = leaf_count;
+        for depth in 0..self.depth() {
+            for index in touched.iter_mut() {
+                *index /= 2;
+            }
+            touched.dedup();
+
+            let current = &updates[depth];
+            let mut next = Vec::with_capacity(touched.len());
+            for &parent in &touched {
+                let left = self.node(depth, 2 * parent, current);
+                let right_index = 2 * parent + 1;
+                let mut node = Node::default();
+                if right_index < layer_len {
+                    let right = self.node(depth, right_index, current);
+                    hash_nodes(&mut hasher, left, right, node.as_mut_slice());
+                } else {
+                    hash_nodes(&mut hasher, left, zero_hash::<Sha256>(depth), node.as_mut_slice());
+                }
+                next.push((parent, node));
+            }
+            updates.push(next);
+            layer_len = (layer_len + 1) / 2;
+        }
+        updates
+    }
+
+    // Apply the changes described by `leaf_count` and `leaves` (see `compute_updates`), returning
+    // the new root.
+    fn update(&mut self, leaf_count: usize, leaves: LayerUpdates) -> Node {
+        let updates = self.compute_updates(leaf_count, leaves);
+        let mut layer_len = leaf_count;
+        for (layer, updates) in self.layers.iter_mut().zip(updates) {
+            layer.resize(layer_len, Node::default());
+            for (index, node) in updates {
+                layer[index] = node;
+            }
+            layer_len = (layer_len + 1) / 2;
+        }
+        self.root()
+    }
+}
+
+/// Tracks the elements of a homogeneous collection that changed since its `MerkleCache` was last
+/// brought up to date.
+#[derive(Debug, Clone)]
+pub(crate) struct ElementsCache {
+    tree: MerkleCache,
+    // maximum number of chunks in the collection's Merkle tree
+    limit: usize,
+    // number of elements reflected in `tree`
+    len: usize,
+    dirty: HashSet<usize>,
+    // set when any element may have changed, e.g. after exposing mutable access to all of them
+    all_dirty: bool,
+}
+
+impl ElementsCache {
+    /// Construct an (empty) cache for a collection holding up to `bound` elements of type `T`.
+    pub(crate) fn new<T: SimpleSerialize>(bound: usize) -> Self {
+        let limit = chunk_count::<T>(bound);
+        let depth = limit.next_power_of_two().trailing_zeros() as usize;
+        Self {
+            tree: MerkleCache::new(depth),
+            limit,
+            len: 0,
+            dirty: HashSet::new(),
+            all_dirty: true,
+        }
+    }
+
+    /// Record that the element at `index` may have changed.
+    pub(crate) fn mark(&mut self, index: usize) {
+        if !self.all_dirty {
+            self.dirty.insert(index);
+        }
+    }
+
+    /// Record that any element may have changed.
+    pub(crate) fn mark_all(&mut self) {
+        self.all_dirty = true;
+        self.dirty.clear();
+    }
+
+    fn is_clean(&self, len: usize) -> bool {
+        !self.all_dirty && self.dirty.is_empty() && self.len == len
+    }
+
+    // Compute the new chunk count of `elements` and each chunk that may differ from `tree`.
+    fn changed_chunks<T: SimpleSerialize>(
+        &self,
+        elements: &[T],
+    ) -> Result<(usize, LayerUpdates), Error> {
+        let count = chunk_count::<T>(elements.len());
+        if count > self.limit {
+            return Err(Error::InputExceedsLimit(self.limit))
+        }
+
+        let mut indices = if self.all_dirty {
+            (0..count).collect::<Vec<_>>()
+        } else {
+            let mut indices = vec![];
+            for &index in self.dirty.iter().filter(|&&index| index < elements.len()) {
+                indices.extend(chunk_range::<T>(index));
+            }
+            if elements.len() != self.len {
+                // NOTE: elements were added or removed at the end of the collection
+                let first_change = chunk_index_of_element::<T>(self.len.min(elements.len()));
+                indices.extend(first_change..count);
+            }
+            indices.sort_unstable();
+            indices.dedup();
+            indices
+        };
+        indices.retain(|&index| index < count);
+
+        let mut leaves = Vec::with_capacity(indices.len());
+        for index in indices {
+            let chunk = compute_chunk(elements, index)?;
+            if self.tree.leaf(index) != Some(&chunk) {
+                leaves.push((index, chunk));
+            }
+        }
+        Ok((count, leaves))
+    }
+
+    /// Compute the root of the Merkle tree of `elements`, updating the cache to reflect them.
+    pub(crate) fn update<T: SimpleSerialize>(&mut self, elements: &[T]) -> Result<Node, Error> {
+        if self.is_clean(elements.len()) {
+            return Ok(self.tree.root())
+        }
+        let (count, leaves) = self.changed_chunks(elements)?;
+        let root = self.tree.update(count, leaves);
+        self.len = elements.len();
+        self.dirty.clear();
+        self.all_dirty = false;
+        Ok(root)
+    }
+
+    // Forget every node of the tree, e.g. if an update may have been interrupted.
+    #[cfg(feature = "std")]
+    fn reset(&mut self) {
+        self.tree = MerkleCache::new(self.tree.depth());
+        self.len = 0;
+        self.mark_all();
+    }
+}
+
+#[cfg(feature = "std")]
+type Lock<T> = std::sync::Mutex<T>;
+
+#[cfg(not(feature = "std"))]
+type Lock<T> = core::cell::RefCell<T>;
+
+/// An `ElementsCache` that is also brought up to date when computing the root through a shared
+/// reference, e.g. when the collection is the field of a container.
+pub(crate) struct SharedElementsCache(Lock<ElementsCache>);
+
+impl SharedElementsCache {
+    /// Construct an (empty) cache for a collection holding up to `bound` elements of type `T`.
+    pub(crate) fn new<T: SimpleSerialize>(bound: usize) -> Self {
+        Self(Lock::new(ElementsCache::new::<T>(bound)))
+    }
+
+    /// Return the cache, without locking it as `self` is borrowed mutably.
+    pub(crate) fn get_mut(&mut self) -> &mut ElementsCache {
+        #[cfg(feature = "std")]
+        return self.0.get_mut().unwrap_or_else(|err| reset_poisoned(err.into_inner()));
+        #[cfg(not(feature = "std"))]
+        return self.0.get_mut();
+    }
+
+    #[cfg(feature = "std")]
+    fn lock(&self) -> std::sync::MutexGuard<'_, ElementsCache> {
+        self.0.lock().unwrap_or_else(|err| reset_poisoned(err.into_inner()))
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn lock(&self) -> core::cell::RefMut<'_, ElementsCache> {
+        self.0.borrow_mut()
+    }
+
+    /// Compute the root of the Merkle tree of `elements`, updating the cache to reflect them.
+    pub(crate) fn update<T: SimpleSerialize>(&self, elements: &[T]) -> Result<Node, Error> {
+        self.lock().update(elements)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_clean(&self, len: usize) -> bool {
+        self.lock().is_clean(len)
+    }
+}
+
+// A panic while updating the cache may have left it inconsistent, so start over.
+#[cfg(feature = "std")]
+fn reset_poisoned<C: DerefMut<Target = ElementsCache>>(mut cache: C) -> C {
+    cache.reset();
+    cache
+}
+
+impl Clone for SharedElementsCache {
+    fn clone(&self) -> Self {
+        Self(Lock::new(ElementsCache::clone(&self.lock())))
+    }
+}
+
+// Number of chunks required to merkleize `len` elements of type `T`.
+fn chunk_count<T: SimpleSerialize>(len: usize) -> usize {
+    if T::is_composite_type() {
+        len
+    } else {
+        (len * T::size_hint() + BYTES_PER_CHUNK - 1) / BYTES_PER_CHUNK
+    }
+}
+
+// Index of the chunk containing the start of the element at `index`.
+fn chunk_index_of_element<T: SimpleSerialize>(index: usize) -> usize {
+    if T::is_composite_type() {
+        index
+    } else {
+        index * T::size_hint() / BYTES_PER_CHUNK
+    }
+}
+
+// Indices of the chunks the element at `index` contributes to.
+fn chunk_range<T: SimpleSerialize>(index: usize) -> Range<usize> {
+    chunk_index_of_element::<T>(index)..chunk_count::<T>(index + 1)
+}
+
+// Compute the chunk at `index` of the Merkle tree of `elements`.
+fn compute_chunk<T: SimpleSerialize>(elements: &[T], index: usize) -> Result<Node, Error> {
+    if T::is_composite_type() {
+        return elements[index].hash_tree_root()
+    }
+
+    let size = T::size_hint();
+    let start = index * BYTES_PER_CHUNK;
+    let first = start / size;
+    let last = (start + BYTES_PER_CHUNK + size - 1) / size;
+    let mut buffer = Vec::with_capacity((last - first) * size);
+    for element in &elements[first..last.min(elements.len())] {
+        element.serialize(&mut buffer)?;
+    }
+    // SAFETY: checked subtraction is unnecessary, as first * size <= start; qed
+    let offset = start - first * size;
+    let end = buffer.len().min(offset + BYTES_PER_CHUNK);
+    let mut chunk = Node::default();
+    chunk[..end - offset].copy_from_slice(&buffer[offset..end]);
+    Ok(chunk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkleization::merkleize;
+
+    fn chunks_from(seeds: &[u8]) -> Vec<u8> {
+        seeds.iter().flat_map(|&seed| [seed; BYTES_PER_CHUNK]).collect()
+    }
+
+    fn leaves_from(seeds: &[u8]) -> LayerUpdates {
+        seeds.iter().enumerate().map(|(i, &seed)| (i, Node::repeat_byte(seed))).collect()
+    }
+
+    #[test]
+    fn test_merkle_cache_matches_merkleize() {
+        for limit in [1usize, 2, 5, 8, 33] {
+            let depth = limit.next_power_of_two().trailing_zeros() as usize;
+            let mut tree = MerkleCache::new(depth);
+            assert_eq!(tree.root(), merkleize(&[], Some(limit)).unwrap());
+
+            let mut seeds = (1..=limit as u8).collect::<Vec<_>>();
+            let root = tree.update(seeds.len(), leaves_from(&seeds));
+            assert_eq!(root, merkleize(&chunks_from(&seeds), Some(limit)).unwrap());
+
+            // shrink, then change a leaf
+            for len in (0..seeds.len()).rev() {
+                seeds.truncate(len);
+                let root = tree.update(len, vec![]);
+                assert_eq!(root, merkleize(&chunks_from(&seeds), Some(limit)).unwrap());
+
+                if let Some(first) = seeds.first_mut() {
+                    *first = 0xff;
+                    let leaves = vec![(0, Node::repeat_byte(0xff))];
+                    let root = tree.update(len, leaves);
+                    assert_eq!(root, merkleize(&chunks_from(&seeds), Some(limit)).unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_compute_chunk_of_unaligned_elements() {
+        let elements = (0..40u8).map(|i| [i, i, i]).collect::<Vec<_>>();
+        let chunks = crate::merkleization::pack(&elements).unwrap();
+        for (index, chunk) in chunks.chunks(BYTES_PER_CHUNK).enumerate() {
+            assert_eq!(compute_chunk(&elements, index).unwrap().as_slice(), chunk);
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Support for the hash functions used to compute Merkle trees.
+use crate::{lib::*, merkleization::BYTES_PER_CHUNK};
+pub use alloy_primitives::utils::Keccak256;
+use sha2::Digest;
+pub use sha2::Sha256;
+
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+mod x86;
+
+pub(crate) const MAX_MERKLE_TREE_DEPTH: usize = 64;
+
+/// A hash function used to compute the Merkle trees of SSZ types.
+///
+/// `Sha256` is the hash function specified by SSZ and is used by default. Other hash functions can
+/// be supplied to methods like `HashTreeRoot::hash_tree_root_with` and `Prove::prove_with`.
+///
+/// Implementations must also provide the table of "zero hashes" for the hash function, i.e. the
+/// roots of perfect binary trees with all "zero" leaves. This table can be computed with
+/// `ZeroHashes::new` (e.g. once, behind a `std::sync::OnceLock`) or generated ahead of time
+/// and loaded with `ZeroHashes::from_bytes`.
+pub trait Hasher: Default + 'static {
+    /// Write the digest of the concatenation of `left` and `right` into `out`.
+    fn hash_nodes(&mut self, left: &[u8], right: &[u8], out: &mut [u8]);
+
+    /// Hash each consecutive pair of nodes in `nodes`, writing the digest of each pair into the
+    /// corresponding node of `out`.
+    ///
+    /// Implementations should override this to hash many pairs at once where the hash function
+    /// supports it.
+    ///
+    /// Invariant: `nodes.len() == 2 * out.len()`
+    /// Invariant: `out.len() % BYTES_PER_CHUNK == 0`
+    fn hash_pairs(&mut self, nodes: &[u8], out: &mut [u8]) {
+        debug_assert_eq!(nodes.len(), 2 * out.len());
+        let pairs = nodes.chunks_exact(2 * BYTES_PER_CHUNK);
+        for (pair, out) in pairs.zip(out.chunks_exact_mut(BYTES_PER_CHUNK)) {
+            let (left, right) = pair.split_at(BYTES_PER_CHUNK);
+            self.hash_nodes(left, right, out);
+        }
+    }
+
+    /// Return the "zero hashes" for this hash function.
+    fn zero_hashes() -> &'static ZeroHashes;
+}
+
+/// The roots of perfect binary trees with all "zero" leaves for some `Hasher`, indexed by the
+/// height of the tree.
+#[derive(Clone)]
+pub struct ZeroHashes([u8; MAX_MERKLE_TREE_DEPTH * BYTES_PER_CHUNK]);
+
+impl ZeroHashes {
+    /// Compute the "zero hashes" with the hash function `H`.
+    pub fn new<H: Hasher>() -> Self {
+        let mut hasher = H::default();
+        let mut buffer = [0u8; MAX_MERKLE_TREE_DEPTH * BYTES_PER_CHUNK];
+        for i in 0..MAX_MERKLE_TREE_DEPTH - 1 {
+            let focus = &mut buffer[i * BYTES_PER_CHUNK..(i + 2) * BYTES_PER_CHUNK];
+            let (source, target) = focus.split_at_mut(BYTES_PER_CHUNK);
+            hasher.hash_nodes(source, source, target);
+        }
+        Self(buffer)
+    }
+
+    /// Construct the "zero hashes" from a table computed ahead of time, where each consecutive
+    /// chunk of `BYTES_PER_CHUNK` bytes is the root of the next highest tree.
+    pub const fn from_bytes(bytes: [u8; MAX_MERKLE_TREE_DEPTH * BYTES_PER_CHUNK]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Index<usize> for ZeroHashes {
+    type Output = [u8];
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index * BYTES_PER_CHUNK..(index + 1) * BYTES_PER_CHUNK]
+    }
+}
+
+impl Debug for ZeroHashes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.chunks(BYTES_PER_CHUNK)).finish()
+    }
+}
+
+// Return `true` if `H` is `Sha256`, e.g. so that Merkle trees cached with `Sha256` can be reused.
+pub(crate) fn is_sha256<H: Hasher>() -> bool {
+    any::TypeId::of::<H>() == any::TypeId::of::<Sha256>()
+}
+
+// Grab the precomputed "zero hashes" from the build stage
+include!(concat!(env!("OUT_DIR"), "/context.rs"));
+
+// The initial hash value of SHA-256.
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+// The final block when hashing a message of exactly one block with SHA-256, holding only the
+// padding and the length of the message (512 bits).
+const SHA256_PADDING_BLOCK: [u8; 64] = {
+    let mut block = [0u8; 64];
+    block[0] = 0x80;
+    block[62] = 0x02;
+    block
+};
+
+// Hash the 64 bytes of `block` with SHA-256, writing the digest into `out`.
+//
+// As the length of the message is fixed, this skips the buffering of the `Digest` API and calls the
+// compression function directly, which uses the SHA extensions of the CPU when available.
+fn sha256_block(block: &[u8], out: &mut [u8]) {
+    let mut state = SHA256_IV;
+    sha2::compress256(&mut state, slice::from_ref(block.into()));
+    sha2::compress256(&mut state, slice::from_ref(SHA256_PADDING_BLOCK[..].into()));
+    for (word, out) in state.iter().zip(out.chunks_exact_mut(4)) {
+        out.copy_from_slice(&word.to_be_bytes());
+    }
+}
+
+impl Hasher for Sha256 {
+    fn hash_nodes(&mut self, left: &[u8], right: &[u8], out: &mut [u8]) {
+        if left.len() != BYTES_PER_CHUNK || right.len() != BYTES_PER_CHUNK {
+            self.update(left);
+            self.update(right);
+            out.copy_from_slice(&self.finalize_reset());
+            return
+        }
+        let mut block = [0u8; 2 * BYTES_PER_CHUNK];
+        block[..BYTES_PER_CHUNK].copy_from_slice(left);
+        block[BYTES_PER_CHUNK..].copy_from_slice(right);
+        sha256_block(&block, out);
+    }
+
+    fn hash_pairs(&mut self, nodes: &[u8], out: &mut [u8]) {
+        debug_assert_eq!(nodes.len(), 2 * out.len());
+        #[cfg(all(feature = "std", target_arch = "x86_64"))]
+        let (nodes, out) = x86::hash_pairs(nodes, out);
+        let pairs = nodes.chunks_exact(2 * BYTES_PER_CHUNK);
+        for (pair, out) in pairs.zip(out.chunks_exact_mut(BYTES_PER_CHUNK)) {
+            sha256_block(pair, out);
+        }
+    }
+
+    fn zero_hashes() -> &'static ZeroHashes {
+        &SHA256_ZERO_HASHES
+    }
+}
+
+impl Hasher for Keccak256 {
+    fn hash_nodes(&mut self, left: &[u8], right: &[u8], out: &mut [u8]) {
+        self.update(left);
+        self.update(right);
+        mem::take(self).finalize_into(out);
+    }
+
+    fn zero_hashes() -> &'static ZeroHashes {
+        &KECCAK256_ZERO_HASHES
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use alloy_primitives::keccak256;
+
+    #[test]
+    fn test_zero_hashes_match_build() {
+        let zero_hashes = ZeroHashes::new::<Sha256>();
+        assert_eq!(zero_hashes.0, SHA256_ZERO_HASHES.0);
+        let zero_hashes = ZeroHashes::new::<Keccak256>();
+        assert_eq!(zero_hashes.0, KECCAK256_ZERO_HASHES.0);
+    }
+
+    fn hash_pairs_with_digest<D: Digest>(nodes: &[u8]) -> Vec<u8> {
+        nodes.chunks(2 * BYTES_PER_CHUNK).flat_map(|pair| D::digest(pair)).collect()
+    }
+
+    #[test]
+    fn test_hash_pairs() {
+        let nodes = (0..16 * BYTES_PER_CHUNK).map(|i| (i * 7) as u8).collect::<Vec<_>>();
+        let mut out = vec![0u8; 8 * BYTES_PER_CHUNK];
+        Sha256::default().hash_pairs(&nodes, &mut out);
+        assert_eq!(out, hash_pairs_with_digest::<Sha256>(&nodes));
+
+        let mut node = [0u8; BYTES_PER_CHUNK];
+        Sha256::default().hash_nodes(&nodes[..32], &nodes[32..64], &mut node);
+        assert_eq!(node, out[..BYTES_PER_CHUNK]);
+
+        Keccak256::default().hash_pairs(&nodes, &mut out);
+        let expected = nodes.chunks(2 * BYTES_PER_CHUNK).flat_map(keccak256).collect::<Vec<_>>();
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_hash_tree_root_with_keccak256() {
+        let value = Vector::<u64, 8>::try_from((0..8).collect::<Vec<_>>()).unwrap();
+        let chunks = serialize(&value).unwrap();
+        let root = value.hash_tree_root_with::<Keccak256>().unwrap();
+        assert_eq!(root, keccak256(chunks));
+        assert_ne!(root, value.hash_tree_root().unwrap());
+
+        type Inner = List<u16, 40>;
+        let elements =
+            (0..5u16).map(|i| Inner::try_from(vec![i; i as usize]).unwrap()).collect::<Vec<_>>();
+        let list = List::<Inner, 7>::try_from(elements.clone()).unwrap();
+        let root = list.hash_tree_root_with::<Keccak256>().unwrap();
+        let cached = CachedList::<Inner, 7>::try_from(elements.clone()).unwrap();
+        assert_eq!(cached.hash_tree_root_with::<Keccak256>().unwrap(), root);
+        let persistent = PersistentList::<Inner, 7>::try_from(elements).unwrap();
+        assert_eq!(persistent.hash_tree_root_with::<Keccak256>().unwrap(), root);
+        assert_eq!(persistent.hash_tree_root().unwrap(), list.hash_tree_root().unwrap());
+    }
+
+    #[test]
+    fn test_prove_with_keccak256() {
+        type Inner = List<u16, 40>;
+        let elements =
+            (0..5u16).map(|i| Inner::try_from(vec![i; i as usize]).unwrap()).collect::<Vec<_>>();
+        let list = List::<Inner, 7>::try_from(elements).unwrap();
+        let path = &[3.into(), 2.into()];
+        let (proof, witness) = list.prove_with::<Keccak256>(path).unwrap();
+        assert_eq!(witness, list.hash_tree_root_with::<Keccak256>().unwrap());
+        assert!(proof.verify_with::<Keccak256>(witness).is_ok());
+        assert!(proof.verify(witness).is_err());
+
+        let root = multiproofs::calculate_merkle_root_with::<Keccak256>(
+            proof.leaf,
+            &proof.branch,
+            proof.index,
+        )
+        .unwrap();
+        assert_eq!(root, witness);
+    }
+}
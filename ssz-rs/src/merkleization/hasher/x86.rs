@@ -0,0 +1,350 @@
+//! Hashing of many pairs of nodes at once with SHA-256 on `x86_64`, using the SHA extensions of
+//! the CPU to hash two pairs at once, or AVX2 to hash eight pairs at once in the lanes of its
+//! 256-bit registers.
+//!
+//! As each pair is a single block of 64 bytes, the message schedule of the block holding the
+//! padding is the same for every pair and is computed ahead of time.
+use super::{SHA256_IV, SHA256_PADDING_BLOCK};
+use crate::merkleization::BYTES_PER_CHUNK;
+use std::arch::x86_64::*;
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+// The message schedule of `SHA256_PADDING_BLOCK`, plus the round constants, which is the same for
+// every pair.
+const PADDING_SCHEDULE: [u32; 64] = {
+    let mut schedule = [0u32; 64];
+    let block = SHA256_PADDING_BLOCK;
+    let mut i = 0;
+    while i < 16 {
+        schedule[i] = u32::from_be_bytes([
+            block[4 * i],
+            block[4 * i + 1],
+            block[4 * i + 2],
+            block[4 * i + 3],
+        ]);
+        i += 1;
+    }
+    while i < 64 {
+        let (w2, w15) = (schedule[i - 2], schedule[i - 15]);
+        let sigma1 = w2.rotate_right(17) ^ w2.rotate_right(19) ^ (w2 >> 10);
+        let sigma0 = w15.rotate_right(7) ^ w15.rotate_right(18) ^ (w15 >> 3);
+        schedule[i] = sigma1
+            .wrapping_add(schedule[i - 7])
+            .wrapping_add(sigma0)
+            .wrapping_add(schedule[i - 16]);
+        i += 1;
+    }
+    let mut i = 0;
+    while i < 64 {
+        schedule[i] = schedule[i].wrapping_add(K[i]);
+        i += 1;
+    }
+    schedule
+};
+
+/// Hash each consecutive pair of nodes in `nodes` into `out` like `Hasher::hash_pairs`, as many
+/// pairs at a time as supported by the CPU, and return the pairs left to hash one at a time.
+pub(super) fn hash_pairs<'a, 'b>(nodes: &'a [u8], out: &'b mut [u8]) -> (&'a [u8], &'b mut [u8]) {
+    if is_x86_feature_detected!("sha") && is_x86_feature_detected!("sse4.1") {
+        let (nodes, remaining_nodes, out, remaining_out) = split_lanes(nodes, out, SHA_NI_LANES);
+        // SAFETY: the CPU supports the SHA extensions and SSE4.1.
+        unsafe { hash_pairs_sha_ni(nodes, out) };
+        (remaining_nodes, remaining_out)
+    } else if is_x86_feature_detected!("avx2") {
+        let (nodes, remaining_nodes, out, remaining_out) = split_lanes(nodes, out, AVX2_LANES);
+        // SAFETY: the CPU supports AVX2.
+        unsafe { hash_pairs_avx2(nodes, out) };
+        (remaining_nodes, remaining_out)
+    } else {
+        (nodes, out)
+    }
+}
+
+// Split off the pairs in `nodes` (and their digests in `out`) which can be hashed `lanes` at a
+// time from the remaining pairs.
+#[allow(clippy::type_complexity)]
+fn split_lanes<'a, 'b>(
+    nodes: &'a [u8],
+    out: &'b mut [u8],
+    lanes: usize,
+) -> (&'a [u8], &'a [u8], &'b mut [u8], &'b mut [u8]) {
+    let len = out.len() - out.len() % (lanes * BYTES_PER_CHUNK);
+    let (nodes, remaining_nodes) = nodes.split_at(2 * len);
+    let (out, remaining_out) = out.split_at_mut(len);
+    (nodes, remaining_nodes, out, remaining_out)
+}
+
+const SHA_NI_LANES: usize = 2;
+
+// Invariant: `nodes.len() == 2 * out.len()`
+// Invariant: `out.len() % (SHA_NI_LANES * BYTES_PER_CHUNK) == 0`
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn hash_pairs_sha_ni(nodes: &[u8], out: &mut [u8]) {
+    // Hashing two pairs at once interleaves two independent sequences of rounds, which hides the
+    // latency of each round.
+    let mask = byte_swap_mask();
+    let iv = load_state(&SHA256_IV);
+    let blocks = nodes.chunks_exact(SHA_NI_LANES * 2 * BYTES_PER_CHUNK);
+    for (blocks, out) in blocks.zip(out.chunks_exact_mut(SHA_NI_LANES * BYTES_PER_CHUNK)) {
+        let mut schedules = [[_mm_setzero_si128(); 4]; SHA_NI_LANES];
+        for (schedule, block) in schedules.iter_mut().zip(blocks.chunks_exact(2 * BYTES_PER_CHUNK))
+        {
+            for (words, block) in schedule.iter_mut().zip(block.chunks_exact(16)) {
+                *words = _mm_shuffle_epi8(_mm_loadu_si128(block.as_ptr().cast()), mask);
+            }
+        }
+
+        let mut states = [iv; SHA_NI_LANES];
+        for i in 0..16 {
+            for (state, schedule) in states.iter_mut().zip(schedules.iter_mut()) {
+                if i >= 4 {
+                    schedule[i % 4] = next_words(
+                        schedule[i % 4],
+                        schedule[(i + 1) % 4],
+                        schedule[(i + 2) % 4],
+                        schedule[(i + 3) % 4],
+                    );
+                }
+                let k = _mm_loadu_si128(K[4 * i..].as_ptr().cast());
+                rounds4(state, _mm_add_epi32(schedule[i % 4], k));
+            }
+        }
+        for state in &mut states {
+            state.0 = _mm_add_epi32(state.0, iv.0);
+            state.1 = _mm_add_epi32(state.1, iv.1);
+        }
+
+        let intermediates = states;
+        for i in 0..16 {
+            let k = _mm_loadu_si128(PADDING_SCHEDULE[4 * i..].as_ptr().cast());
+            for state in &mut states {
+                rounds4(state, k);
+            }
+        }
+        for ((state, intermediate), out) in
+            states.iter().zip(intermediates).zip(out.chunks_exact_mut(BYTES_PER_CHUNK))
+        {
+            let abef = _mm_add_epi32(state.0, intermediate.0);
+            let cdgh = _mm_add_epi32(state.1, intermediate.1);
+            let feba = _mm_shuffle_epi32::<0x1B>(abef);
+            let dchg = _mm_shuffle_epi32::<0xB1>(cdgh);
+            let dcba = _mm_blend_epi16::<0xF0>(feba, dchg);
+            let hgef = _mm_alignr_epi8::<8>(dchg, feba);
+            _mm_storeu_si128(out.as_mut_ptr().cast(), _mm_shuffle_epi8(dcba, mask));
+            _mm_storeu_si128(out[16..].as_mut_ptr().cast(), _mm_shuffle_epi8(hgef, mask));
+        }
+    }
+}
+
+// Load `state` into the pair of registers holding the words `abef` and `cdgh`, as expected by the
+// SHA extensions.
+#[inline]
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn load_state(state: &[u32; 8]) -> (__m128i, __m128i) {
+    let dcba = _mm_loadu_si128(state.as_ptr().cast());
+    let hgfe = _mm_loadu_si128(state[4..].as_ptr().cast());
+    let cdab = _mm_shuffle_epi32::<0xB1>(dcba);
+    let efgh = _mm_shuffle_epi32::<0x1B>(hgfe);
+    (_mm_alignr_epi8::<8>(cdab, efgh), _mm_blend_epi16::<0xF0>(efgh, cdab))
+}
+
+// Compute the next four words of the message schedule from the previous sixteen.
+#[inline]
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn next_words(w0: __m128i, w1: __m128i, w2: __m128i, w3: __m128i) -> __m128i {
+    let t = _mm_add_epi32(_mm_sha256msg1_epu32(w0, w1), _mm_alignr_epi8::<4>(w3, w2));
+    _mm_sha256msg2_epu32(t, w3)
+}
+
+// Run four rounds on `state`, where `k` holds the sum of the words of the message schedule and the
+// round constants.
+#[inline]
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn rounds4(state: &mut (__m128i, __m128i), k: __m128i) {
+    let (abef, cdgh) = *state;
+    let cdgh = _mm_sha256rnds2_epu32(cdgh, abef, k);
+    let abef = _mm_sha256rnds2_epu32(abef, cdgh, _mm_shuffle_epi32::<0x0E>(k));
+    *state = (abef, cdgh);
+}
+
+#[inline]
+#[target_feature(enable = "ssse3")]
+unsafe fn byte_swap_mask() -> __m128i {
+    _mm_setr_epi8(3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8, 15, 14, 13, 12)
+}
+
+const AVX2_LANES: usize = 8;
+
+// Invariant: `nodes.len() == 2 * out.len()`
+// Invariant: `out.len() % (AVX2_LANES * BYTES_PER_CHUNK) == 0`
+#[target_feature(enable = "avx2")]
+unsafe fn hash_pairs_avx2(nodes: &[u8], out: &mut [u8]) {
+    let blocks = nodes.chunks_exact(AVX2_LANES * 2 * BYTES_PER_CHUNK);
+    for (blocks, out) in blocks.zip(out.chunks_exact_mut(AVX2_LANES * BYTES_PER_CHUNK)) {
+        // Each block of 64 bytes is loaded as two rows of eight words, which are transposed so
+        // that each register holds the same word of the message for every lane.
+        let mut rows = [_mm256_setzero_si256(); 16];
+        for (lane, block) in blocks.chunks_exact(2 * BYTES_PER_CHUNK).enumerate() {
+            rows[lane] = _mm256_loadu_si256(block.as_ptr().cast());
+            rows[AVX2_LANES + lane] = _mm256_loadu_si256(block[BYTES_PER_CHUNK..].as_ptr().cast());
+        }
+        let mut schedule = [_mm256_setzero_si256(); 16];
+        for half in 0..2 {
+            let words = &mut schedule[half * AVX2_LANES..(half + 1) * AVX2_LANES];
+            words.copy_from_slice(&transpose(&rows[half * AVX2_LANES..(half + 1) * AVX2_LANES]));
+            byte_swap(words);
+        }
+
+        let mut iv = [_mm256_setzero_si256(); 8];
+        for (iv, word) in iv.iter_mut().zip(SHA256_IV) {
+            *iv = _mm256_set1_epi32(word as i32);
+        }
+        let mut state = iv;
+        for i in 0..64 {
+            if i >= 16 {
+                let (w2, w15) = (schedule[(i - 2) % 16], schedule[(i - 15) % 16]);
+                let sigma1 =
+                    xor3(rotr::<17, 15>(w2), rotr::<19, 13>(w2), _mm256_srli_epi32::<10>(w2));
+                let sigma0 =
+                    xor3(rotr::<7, 25>(w15), rotr::<18, 14>(w15), _mm256_srli_epi32::<3>(w15));
+                schedule[i % 16] = add4(sigma1, schedule[(i - 7) % 16], sigma0, schedule[i % 16]);
+            }
+            let k = _mm256_add_epi32(schedule[i % 16], _mm256_set1_epi32(K[i] as i32));
+            round(&mut state, k);
+        }
+        for (word, iv) in state.iter_mut().zip(iv) {
+            *word = _mm256_add_epi32(*word, iv);
+        }
+
+        let intermediate = state;
+        for k in PADDING_SCHEDULE {
+            round(&mut state, _mm256_set1_epi32(k as i32));
+        }
+        for (word, intermediate) in state.iter_mut().zip(intermediate) {
+            *word = _mm256_add_epi32(*word, intermediate);
+        }
+
+        // Transposing the state back yields the digest of each lane.
+        let mut digests = transpose(&state);
+        byte_swap(&mut digests);
+        for (digest, out) in digests.iter().zip(out.chunks_exact_mut(BYTES_PER_CHUNK)) {
+            _mm256_storeu_si256(out.as_mut_ptr().cast(), *digest);
+        }
+    }
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn round(state: &mut [__m256i; 8], k: __m256i) {
+    let [a, b, c, d, e, f, g, h] = *state;
+    let sigma1 = xor3(rotr::<6, 26>(e), rotr::<11, 21>(e), rotr::<25, 7>(e));
+    let choice = _mm256_xor_si256(_mm256_and_si256(e, f), _mm256_andnot_si256(e, g));
+    let t1 = add4(h, sigma1, choice, k);
+    let sigma0 = xor3(rotr::<2, 30>(a), rotr::<13, 19>(a), rotr::<22, 10>(a));
+    let majority =
+        _mm256_xor_si256(_mm256_and_si256(_mm256_xor_si256(a, b), c), _mm256_and_si256(a, b));
+    let t2 = _mm256_add_epi32(sigma0, majority);
+    *state = [_mm256_add_epi32(t1, t2), a, b, c, _mm256_add_epi32(d, t1), e, f, g];
+}
+
+// Rotate each word of `x` right by `N` bits, where `M == 32 - N`.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn rotr<const N: i32, const M: i32>(x: __m256i) -> __m256i {
+    _mm256_or_si256(_mm256_srli_epi32::<N>(x), _mm256_slli_epi32::<M>(x))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn xor3(a: __m256i, b: __m256i, c: __m256i) -> __m256i {
+    _mm256_xor_si256(_mm256_xor_si256(a, b), c)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn add4(a: __m256i, b: __m256i, c: __m256i, d: __m256i) -> __m256i {
+    _mm256_add_epi32(_mm256_add_epi32(a, b), _mm256_add_epi32(c, d))
+}
+
+// Convert each word between big-endian and native byte order.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn byte_swap(rows: &mut [__m256i]) {
+    let mask = _mm256_setr_epi8(
+        3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8, 15, 14, 13, 12, 3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8,
+        15, 14, 13, 12,
+    );
+    for row in rows {
+        *row = _mm256_shuffle_epi8(*row, mask);
+    }
+}
+
+// Transpose the 8x8 matrix of words in `rows`.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn transpose(rows: &[__m256i]) -> [__m256i; 8] {
+    let (r0, r1, r2, r3) = (rows[0], rows[1], rows[2], rows[3]);
+    let (r4, r5, r6, r7) = (rows[4], rows[5], rows[6], rows[7]);
+    let (t0, t1) = (_mm256_unpacklo_epi32(r0, r1), _mm256_unpackhi_epi32(r0, r1));
+    let (t2, t3) = (_mm256_unpacklo_epi32(r2, r3), _mm256_unpackhi_epi32(r2, r3));
+    let (t4, t5) = (_mm256_unpacklo_epi32(r4, r5), _mm256_unpackhi_epi32(r4, r5));
+    let (t6, t7) = (_mm256_unpacklo_epi32(r6, r7), _mm256_unpackhi_epi32(r6, r7));
+    let (u0, u1) = (_mm256_unpacklo_epi64(t0, t2), _mm256_unpackhi_epi64(t0, t2));
+    let (u2, u3) = (_mm256_unpacklo_epi64(t1, t3), _mm256_unpackhi_epi64(t1, t3));
+    let (u4, u5) = (_mm256_unpacklo_epi64(t4, t6), _mm256_unpackhi_epi64(t4, t6));
+    let (u6, u7) = (_mm256_unpacklo_epi64(t5, t7), _mm256_unpackhi_epi64(t5, t7));
+    [
+        _mm256_permute2x128_si256(u0, u4, 0x20),
+        _mm256_permute2x128_si256(u1, u5, 0x20),
+        _mm256_permute2x128_si256(u2, u6, 0x20),
+        _mm256_permute2x128_si256(u3, u7, 0x20),
+        _mm256_permute2x128_si256(u0, u4, 0x31),
+        _mm256_permute2x128_si256(u1, u5, 0x31),
+        _mm256_permute2x128_si256(u2, u6, 0x31),
+        _mm256_permute2x128_si256(u3, u7, 0x31),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    fn check_hash_pairs(lanes: usize, hash_pairs: unsafe fn(&[u8], &mut [u8])) {
+        let count = 4 * lanes;
+        let nodes =
+            (0..count * 2 * BYTES_PER_CHUNK).map(|i| (i * 13 + i / 7) as u8).collect::<Vec<_>>();
+        let mut out = vec![0u8; count * BYTES_PER_CHUNK];
+        // SAFETY: the caller checked that the CPU supports the instructions used by `hash_pairs`.
+        unsafe { hash_pairs(&nodes, &mut out) };
+        let expected =
+            nodes.chunks(2 * BYTES_PER_CHUNK).flat_map(Sha256::digest).collect::<Vec<_>>();
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_hash_pairs() {
+        if is_x86_feature_detected!("sha") && is_x86_feature_detected!("sse4.1") {
+            check_hash_pairs(SHA_NI_LANES, hash_pairs_sha_ni);
+        }
+        if is_x86_feature_detected!("avx2") {
+            check_hash_pairs(AVX2_LANES, hash_pairs_avx2);
+        }
+
+        let nodes = vec![1u8; 11 * 2 * BYTES_PER_CHUNK];
+        let mut out = vec![0u8; 11 * BYTES_PER_CHUNK];
+        let (remaining_nodes, remaining_out) = hash_pairs(&nodes, &mut out);
+        assert_eq!(remaining_nodes.len(), 2 * remaining_out.len());
+        assert!(remaining_out.len() < AVX2_LANES * BYTES_PER_CHUNK);
+    }
+}
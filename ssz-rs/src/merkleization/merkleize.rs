@@ -1,13 +1,15 @@
 //! Support for computing Merkle trees.
 use crate::{
     lib::*,
-    merkleization::{MerkleizationError as Error, Node, BYTES_PER_CHUNK},
+    merkleization::{
+        hasher::{Hasher, Sha256, MAX_MERKLE_TREE_DEPTH},
+        MerkleizationError as Error, Node, BYTES_PER_CHUNK,
+    },
     ser::Serialize,
     GeneralizedIndex,
 };
 #[cfg(feature = "serde")]
 use alloy_primitives::hex::FromHex;
-use sha2::{Digest, Sha256};
 
 // The generalized index for the root of the "decorated" type in any Merkleized type that supports
 // decoration.
@@ -18,7 +20,12 @@ const DECORATION_GENERALIZED_INDEX: GeneralizedIndex = 3;
 /// Types that can provide the root of their corresponding Merkle tree following the SSZ spec.
 pub trait HashTreeRoot {
     /// Compute the "hash tree root" of `Self`.
-    fn hash_tree_root(&self) -> Result<Node, Error>;
+    fn hash_tree_root(&self) -> Result<Node, Error> {
+        self.hash_tree_root_with::<Sha256>()
+    }
+
+    /// Compute the "hash tree root" of `Self` using the hash function `H`.
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, Error>;
 
     /// Indicate the "composite" nature of `Self`.
     fn is_composite_type() -> bool {
@@ -52,38 +59,29 @@ where
     Ok(buffer)
 }
 
-fn hash_nodes(hasher: &mut Sha256, a: impl AsRef<[u8]>, b: impl AsRef<[u8]>, out: &mut [u8]) {
-    hasher.update(a);
-    hasher.update(b);
-    out.copy_from_slice(&hasher.finalize_reset());
-}
-
-const MAX_MERKLE_TREE_DEPTH: usize = 64;
-
-#[derive(Debug)]
-struct Context {
-    zero_hashes: [u8; MAX_MERKLE_TREE_DEPTH * BYTES_PER_CHUNK],
+pub(crate) fn hash_nodes<H: Hasher>(
+    hasher: &mut H,
+    a: impl AsRef<[u8]>,
+    b: impl AsRef<[u8]>,
+    out: &mut [u8],
+) {
+    hasher.hash_nodes(a.as_ref(), b.as_ref(), out);
 }
 
-impl Index<usize> for Context {
-    type Output = [u8];
-
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.zero_hashes[index * BYTES_PER_CHUNK..(index + 1) * BYTES_PER_CHUNK]
-    }
+// Return the root of a perfect binary tree of height `depth` with all "zero" leaves.
+// Invariant: `depth < MAX_MERKLE_TREE_DEPTH`
+pub(crate) fn zero_hash<H: Hasher>(depth: usize) -> &'static [u8] {
+    &H::zero_hashes()[depth]
 }
 
-// Grab the precomputed context from the build stage
-include!(concat!(env!("OUT_DIR"), "/context.rs"));
-
 /// Return the root of the root node of a binary tree formed from `chunks`.
 ///
 /// `chunks` forms the bottom layer of this tree.
 ///
 /// This implementation is memory efficient by relying on pre-computed subtrees of all
-/// "zero" leaves stored in the `CONTEXT`. SSZ specifies that `chunks` is padded to the next power
-/// of two and this can be quite large for some types. "Zero" subtrees are virtualized to avoid the
-/// memory and computation cost of large trees with partially empty leaves.
+/// "zero" leaves stored in the `ZeroHashes` of the `Hasher`. SSZ specifies that `chunks` is padded
+/// to the next power of two and this can be quite large for some types. "Zero" subtrees are
+/// virtualized to avoid the memory and computation cost of large trees with partially empty leaves.
 ///
 /// The implementation approach treats `chunks` as the bottom layer of a perfect binary tree
 /// and for each height performs the hashing required to compute the parent layer in place.
@@ -93,7 +91,10 @@ include!(concat!(env!("OUT_DIR"), "/context.rs"));
 /// Invariant: `leaf_count.next_power_of_two() == leaf_count`
 /// Invariant: `leaf_count != 0`
 /// Invariant: `leaf_count.trailing_zeros() < MAX_MERKLE_TREE_DEPTH`
-fn merkleize_chunks_with_virtual_padding(chunks: &[u8], leaf_count: usize) -> Result<Node, Error> {
+fn merkleize_chunks_with_virtual_padding<H: Hasher>(
+    chunks: &[u8],
+    leaf_count: usize,
+) -> Result<Node, Error> {
     debug_assert!(chunks.len() % BYTES_PER_CHUNK == 0);
     // NOTE: This also asserts that leaf_count != 0
     debug_assert!(leaf_count.next_power_of_two() == leaf_count);
@@ -108,79 +109,22 @@ fn merkleize_chunks_with_virtual_padding(chunks: &[u8], leaf_count: usize) -> Re
         let depth = height - 1;
         // SAFETY: index is safe while depth == leaf_count.trailing_zeros() < MAX_MERKLE_TREE_DEPTH;
         // qed
-        return Ok(CONTEXT[depth as usize].try_into().expect("can produce a single root chunk"))
+        return Ok(zero_hash::<H>(depth as usize)
+            .try_into()
+            .expect("can produce a single root chunk"))
+    }
+
+    #[cfg(feature = "rayon")]
+    if chunk_count >= MIN_PARALLEL_CHUNKS {
+        return Ok(merkleize_chunks_in_parallel::<H>(chunks, leaf_count))
     }
 
     let mut layer = chunks.to_vec();
-    // SAFETY: checked subtraction is unnecessary, as we return early when chunk_count == 0; qed
-    let mut last_index = chunk_count - 1;
-    let mut hasher = Sha256::new();
+    let mut node_count = chunk_count;
+    let mut hasher = H::default();
     // for each layer of the tree, starting from the bottom and walking up to the root:
-    for k in (1..height).rev() {
-        // for each pair of nodes in this layer:
-        for i in (0..2usize.pow(k)).step_by(2) {
-            let parent_index = i / 2;
-            let (parent, left, right) = match i.cmp(&last_index) {
-                Ordering::Less => {
-                    // SAFETY: index is safe because (i+1)*BYTES_PER_CHUNK < layer.len():
-                    // i < last_index == chunk_count - 1 == (layer.len() / BYTES_PER_CHUNK) - 1
-                    // so i+1 < layer.len() / BYTES_PER_CHUNK
-                    // so (i+1)*BYTES_PER_CHUNK < layer.len(); qed
-                    let focus =
-                        &mut layer[parent_index * BYTES_PER_CHUNK..(i + 2) * BYTES_PER_CHUNK];
-                    // SAFETY: checked subtraction is unnecessary:
-                    // focus.len() = (i + 2 - parent_index) * BYTES_PER_CHUNK
-                    // and
-                    // i >= parent_index
-                    // so focus.len() >= 2 * BYTES_PER_CHUNK; qed
-                    let children_index = focus.len() - 2 * BYTES_PER_CHUNK;
-                    let (parent, children) = focus.split_at_mut(children_index);
-                    let (left, right) = children.split_at_mut(BYTES_PER_CHUNK);
-
-                    // NOTE: we do not need mutability on `right` here so drop that capability
-                    (parent, left, &*right)
-                }
-                Ordering::Equal => {
-                    // SAFETY: index is safe because i*BYTES_PER_CHUNK < layer.len():
-                    // i*BYTES_PER_CHUNK < (i+1)*BYTES_PER_CHUNK < layer.len()
-                    // (see previous case); qed
-                    let focus =
-                        &mut layer[parent_index * BYTES_PER_CHUNK..(i + 1) * BYTES_PER_CHUNK];
-                    // SAFETY: checked subtraction is unnecessary:
-                    // focus.len() = (i + 1 - parent_index) * BYTES_PER_CHUNK
-                    // and
-                    // i >= parent_index
-                    // so focus.len() >= BYTES_PER_CHUNK; qed
-                    let children_index = focus.len() - BYTES_PER_CHUNK;
-                    // NOTE: left.len() == BYTES_PER_CHUNK
-                    let (parent, left) = focus.split_at_mut(children_index);
-                    // SAFETY: checked subtraction is unnecessary:
-                    // k <= height - 1
-                    // so depth >= height - (height - 1) - 1
-                    //           = 0; qed
-                    let depth = height - k - 1;
-                    // SAFETY: index is safe because depth < CONTEXT.len():
-                    // depth <= height - 1 == leaf_count.trailing_zeros()
-                    // leaf_count.trailing_zeros() < MAX_MERKLE_TREE_DEPTH == CONTEXT.len(); qed
-                    let right = &CONTEXT[depth as usize];
-                    (parent, left, right)
-                }
-                _ => break,
-            };
-            if i == 0 {
-                // NOTE: nodes share memory here and so we can't use the `hash_nodes` utility
-                // as the disjunct nature is reflect in that functions type signature
-                // so instead we will just replicate here.
-                hasher.update(&left);
-                hasher.update(right);
-                left.copy_from_slice(&hasher.finalize_reset());
-            } else {
-                // SAFETY: index is safe because parent.len() % BYTES_PER_CHUNK == 0 and
-                // parent isn't empty; qed
-                hash_nodes(&mut hasher, left, right, &mut parent[..BYTES_PER_CHUNK]);
-            }
-        }
-        last_index /= 2;
+    for depth in 0..(height - 1) as usize {
+        node_count = hash_layer_in_place(&mut hasher, &mut layer, node_count, depth);
     }
 
     // SAFETY: index is safe because layer.len() >= BYTES_PER_CHUNK:
@@ -190,9 +134,122 @@ fn merkleize_chunks_with_virtual_padding(chunks: &[u8], leaf_count: usize) -> Re
     Ok(layer[..BYTES_PER_CHUNK].try_into().expect("can produce a single root chunk"))
 }
 
+// Hash the first `node_count` nodes of `layer` in pairs, writing the parent layer to the front of
+// `layer` and returning the number of parents. A missing right sibling is the root of a "zero"
+// subtree of height `depth`.
+//
+// Pairs are handed to `Hasher::hash_pairs` in batches. The parents in `start..end` are disjoint
+// from their children in `2 * start..2 * end` when `end <= 2 * start`, so each batch can be twice
+// the size of the previous one and most of the layer is hashed in a few large batches.
+//
+// Invariant: `node_count != 0`
+// Invariant: `node_count * BYTES_PER_CHUNK <= layer.len()`
+// Invariant: `depth < MAX_MERKLE_TREE_DEPTH`
+fn hash_layer_in_place<H: Hasher>(
+    hasher: &mut H,
+    layer: &mut [u8],
+    node_count: usize,
+    depth: usize,
+) -> usize {
+    let pair_count = node_count / 2;
+    if pair_count > 0 {
+        // NOTE: the first parent shares memory with its children, so hash from a copy of them
+        let mut children = [0u8; 2 * BYTES_PER_CHUNK];
+        children.copy_from_slice(&layer[..2 * BYTES_PER_CHUNK]);
+        hasher.hash_pairs(&children, &mut layer[..BYTES_PER_CHUNK]);
+    }
+    let mut start = 1;
+    while start < pair_count {
+        let end = cmp::min(2 * start, pair_count);
+        let (parents, children) = layer.split_at_mut(2 * start * BYTES_PER_CHUNK);
+        hasher.hash_pairs(
+            &children[..2 * (end - start) * BYTES_PER_CHUNK],
+            &mut parents[start * BYTES_PER_CHUNK..end * BYTES_PER_CHUNK],
+        );
+        start = end;
+    }
+    if node_count % 2 == 1 {
+        // SAFETY: checked subtraction is unnecessary, as node_count % 2 == 1; qed
+        let last = node_count - 1;
+        let mut left = [0u8; BYTES_PER_CHUNK];
+        left.copy_from_slice(&layer[last * BYTES_PER_CHUNK..node_count * BYTES_PER_CHUNK]);
+        let parent = &mut layer[pair_count * BYTES_PER_CHUNK..(pair_count + 1) * BYTES_PER_CHUNK];
+        hash_nodes(hasher, left, zero_hash::<H>(depth), parent);
+    }
+    pair_count + node_count % 2
+}
+
+// The number of chunks in a layer of a Merkle tree before its parent layer is hashed across
+// threads.
+#[cfg(feature = "rayon")]
+const MIN_PARALLEL_CHUNKS: usize = 1024;
+
+// The number of pairs of nodes hashed together by each thread when a layer is hashed across
+// threads.
+#[cfg(feature = "rayon")]
+const PARALLEL_BATCH_PAIRS: usize = 128;
+
+// The number of elements in a collection before their roots are computed across threads.
+#[cfg(feature = "rayon")]
+const MIN_PARALLEL_ELEMENTS: usize = 64;
+
+/// Return the root of the root node of a binary tree formed from `chunks`, hashing each
+/// sufficiently large layer of the tree across threads.
+///
+/// Unlike `merkleize_chunks_with_virtual_padding`, each layer is computed into a new buffer so
+/// that the pairs of nodes in the layer can be hashed independently. The resulting root is
+/// identical.
+///
+/// Invariant: `chunks.len() % BYTES_PER_CHUNK == 0`
+/// Invariant: `chunks.len() != 0`
+/// Invariant: `leaf_count.next_power_of_two() == leaf_count`
+/// Invariant: `leaf_count.trailing_zeros() < MAX_MERKLE_TREE_DEPTH`
+#[cfg(feature = "rayon")]
+fn merkleize_chunks_in_parallel<H: Hasher>(chunks: &[u8], leaf_count: usize) -> Node {
+    use rayon::prelude::*;
+
+    let height = leaf_count.trailing_zeros() as usize;
+    let mut layer = chunks.to_vec();
+    for depth in 0..height {
+        let node_count = layer.len() / BYTES_PER_CHUNK;
+        let pair_count = node_count / 2;
+        let mut parents = vec![0u8; (node_count + 1) / 2 * BYTES_PER_CHUNK];
+        let (children, last_child) = layer.split_at(2 * pair_count * BYTES_PER_CHUNK);
+        let (pair_parents, last_parent) = parents.split_at_mut(pair_count * BYTES_PER_CHUNK);
+        let mut hasher = H::default();
+        if node_count >= MIN_PARALLEL_CHUNKS {
+            pair_parents
+                .par_chunks_mut(PARALLEL_BATCH_PAIRS * BYTES_PER_CHUNK)
+                .zip(children.par_chunks(2 * PARALLEL_BATCH_PAIRS * BYTES_PER_CHUNK))
+                .for_each_init(H::default, |hasher, (parents, children)| {
+                    hasher.hash_pairs(children, parents)
+                });
+        } else {
+            hasher.hash_pairs(children, pair_parents);
+        }
+        if !last_child.is_empty() {
+            // NOTE: a missing right sibling is the root of a "zero" subtree at this depth
+            hash_nodes(&mut hasher, last_child, zero_hash::<H>(depth), last_parent);
+        }
+        layer = parents;
+    }
+
+    // SAFETY: index is safe because layer.len() == BYTES_PER_CHUNK, as every layer is half the
+    // size of the layer below it, rounded up, and the bottom layer has at most `leaf_count` nodes;
+    // qed
+    layer[..BYTES_PER_CHUNK].try_into().expect("can produce a single root chunk")
+}
+
 // Return the root of the Merklization of a binary tree formed from `chunks`.
 // Invariant: `chunks.len() % BYTES_PER_CHUNK == 0`
 pub fn merkleize(chunks: &[u8], limit: Option<usize>) -> Result<Node, Error> {
+    merkleize_with::<Sha256>(chunks, limit)
+}
+
+// Return the root of the Merklization of a binary tree formed from `chunks` using the hash function
+// `H`.
+// Invariant: `chunks.len() % BYTES_PER_CHUNK == 0`
+pub fn merkleize_with<H: Hasher>(chunks: &[u8], limit: Option<usize>) -> Result<Node, Error> {
     debug_assert!(chunks.len() % BYTES_PER_CHUNK == 0);
     let chunk_count = chunks.len() / BYTES_PER_CHUNK;
     let mut leaf_count = chunk_count.next_power_of_two();
@@ -202,53 +259,160 @@ pub fn merkleize(chunks: &[u8], limit: Option<usize>) -> Result<Node, Error> {
         }
         leaf_count = limit.next_power_of_two();
     }
-    merkleize_chunks_with_virtual_padding(chunks, leaf_count)
+    merkleize_chunks_with_virtual_padding::<H>(chunks, leaf_count)
+}
+
+// Return the number of chunks in the subtree at `level` of a progressive Merkle tree.
+const fn progressive_subtree_leaf_count(level: u32) -> usize {
+    1 << (2 * level)
+}
+
+// Return the level of the subtree holding the chunk at `position` in a progressive Merkle tree,
+// along with the position of the chunk in that subtree.
+fn progressive_subtree_position(mut position: usize) -> (u32, usize) {
+    let mut level = 0;
+    while position >= progressive_subtree_leaf_count(level) {
+        position -= progressive_subtree_leaf_count(level);
+        level += 1;
+    }
+    (level, position)
 }
 
-fn mix_in_decoration(root: Node, decoration: usize) -> Node {
+/// Return the generalized index of the chunk at `position` in a progressive Merkle tree, relative
+/// to the root of the tree.
+///
+/// The subtree at each `level` of a progressive Merkle tree hangs off the right of the node at
+/// depth `level` along the left edge of the tree and holds the next `4 ** level` chunks.
+pub fn progressive_chunk_generalized_index(position: usize) -> GeneralizedIndex {
+    let (level, position) = progressive_subtree_position(position);
+    let subtree_root = (1 << (level + 1)) + 1;
+    subtree_root * progressive_subtree_leaf_count(level) + position
+}
+
+// Return the root of the progressive Merklization of `chunks`, following EIP-7916.
+// Invariant: `chunks.len() % BYTES_PER_CHUNK == 0`
+pub fn merkleize_progressive(chunks: &[u8]) -> Result<Node, Error> {
+    merkleize_progressive_with::<Sha256>(chunks)
+}
+
+// Return the root of the progressive Merklization of `chunks`, following EIP-7916, using the hash
+// function `H`.
+// Invariant: `chunks.len() % BYTES_PER_CHUNK == 0`
+pub fn merkleize_progressive_with<H: Hasher>(chunks: &[u8]) -> Result<Node, Error> {
+    debug_assert!(chunks.len() % BYTES_PER_CHUNK == 0);
+    let mut subtree_roots = vec![];
+    let mut rest = chunks;
+    let mut level = 0;
+    while !rest.is_empty() {
+        let leaf_count = progressive_subtree_leaf_count(level);
+        let (subtree, next) = rest.split_at(rest.len().min(leaf_count * BYTES_PER_CHUNK));
+        subtree_roots.push(merkleize_chunks_with_virtual_padding::<H>(subtree, leaf_count)?);
+        rest = next;
+        level += 1;
+    }
+
+    // the tree terminates with a "zero" node to the left of the last subtree
+    let mut hasher = H::default();
+    let mut root = Node::default();
+    for subtree_root in subtree_roots.iter().rev() {
+        let left = root;
+        hash_nodes(&mut hasher, left, subtree_root, root.as_mut());
+    }
+    Ok(root)
+}
+
+fn mix_in_decoration<H: Hasher>(root: Node, decoration: usize) -> Node {
     let decoration_data = decoration.hash_tree_root().expect("can merkleize usize");
+    mix_in_aux::<H>(root, decoration_data)
+}
 
-    let mut hasher = Sha256::new();
+// Mix the root `aux` of some auxiliary data into `root`, e.g. the `active_fields` of a
+// `StableContainer`.
+pub(crate) fn mix_in_aux<H: Hasher>(root: Node, aux: Node) -> Node {
+    let mut hasher = H::default();
     let mut output = vec![0u8; BYTES_PER_CHUNK];
-    hash_nodes(&mut hasher, root, decoration_data, &mut output);
+    hash_nodes(&mut hasher, root, aux, &mut output);
     output.as_slice().try_into().expect("can extract root")
 }
 
-pub(crate) fn mix_in_length(root: Node, length: usize) -> Node {
-    mix_in_decoration(root, length)
+pub(crate) fn mix_in_length<H: Hasher>(root: Node, length: usize) -> Node {
+    mix_in_decoration::<H>(root, length)
 }
 
 pub fn mix_in_selector(root: Node, selector: usize) -> Node {
-    mix_in_decoration(root, selector)
+    mix_in_selector_with::<Sha256>(root, selector)
 }
 
-pub(crate) fn elements_to_chunks<'a, T: HashTreeRoot + 'a>(
-    elements: impl Iterator<Item = (usize, &'a T)>,
-    count: usize,
+pub fn mix_in_selector_with<H: Hasher>(root: Node, selector: usize) -> Node {
+    mix_in_decoration::<H>(root, selector)
+}
+
+/// Types whose hash tree root can be computed with the roots of their elements computed across
+/// threads, with the `rayon` feature enabled.
+///
+/// This requires the elements to be `Sync`, so it is provided apart from `HashTreeRoot` to leave
+/// the bounds of the collection types unchanged by the feature. The resulting root is identical to
+/// the one of `HashTreeRoot`, which still hashes large layers of the Merkle tree across threads.
+#[cfg(feature = "rayon")]
+pub trait ParallelHashTreeRoot: HashTreeRoot {
+    /// Compute the "hash tree root" of `Self` across threads.
+    fn par_hash_tree_root(&self) -> Result<Node, Error> {
+        self.par_hash_tree_root_with::<Sha256>()
+    }
+
+    /// Compute the "hash tree root" of `Self` across threads using the hash function `H`.
+    fn par_hash_tree_root_with<H: Hasher>(&self) -> Result<Node, Error>;
+}
+
+pub(crate) fn elements_to_chunks<H: Hasher, T: HashTreeRoot>(
+    elements: &[T],
 ) -> Result<Vec<u8>, Error> {
-    let mut chunks = vec![0u8; count * BYTES_PER_CHUNK];
-    for (i, elem) in elements {
-        let chunk = elem.hash_tree_root()?;
+    let mut chunks = vec![0u8; elements.len() * BYTES_PER_CHUNK];
+    for (i, elem) in elements.iter().enumerate() {
+        let chunk = elem.hash_tree_root_with::<H>()?;
         let range = i * BYTES_PER_CHUNK..(i + 1) * BYTES_PER_CHUNK;
         chunks[range].copy_from_slice(chunk.as_ref());
     }
     Ok(chunks)
 }
 
+// Like `elements_to_chunks`, computing the roots of sufficiently many `elements` across threads.
+#[cfg(feature = "rayon")]
+pub(crate) fn elements_to_chunks_in_parallel<H: Hasher, T: HashTreeRoot + Sync>(
+    elements: &[T],
+) -> Result<Vec<u8>, Error> {
+    use rayon::prelude::*;
+
+    if elements.len() < MIN_PARALLEL_ELEMENTS {
+        return elements_to_chunks::<H, _>(elements)
+    }
+    let mut chunks = vec![0u8; elements.len() * BYTES_PER_CHUNK];
+    chunks.par_chunks_mut(BYTES_PER_CHUNK).zip(elements).try_for_each(|(chunk, elem)| {
+        let root = elem.hash_tree_root_with::<H>()?;
+        chunk.copy_from_slice(root.as_ref());
+        Ok::<_, Error>(())
+    })?;
+    Ok(chunks)
+}
+
 pub struct Tree(Vec<u8>);
 
 impl Tree {
-    pub fn mix_in_decoration(
+    pub fn mix_in_decoration<H: Hasher>(
         &mut self,
-        decoration: usize,
-        hasher: &mut Sha256,
+        decoration_node: Node,
+        hasher: &mut H,
     ) -> Result<(), Error> {
         let target_node = &mut self[DECORATION_GENERALIZED_INDEX];
-        let decoration_node = decoration.hash_tree_root()?;
         target_node.copy_from_slice(decoration_node.as_ref());
-        hasher.update(&self[INNER_ROOT_GENERALIZED_INDEX]);
-        hasher.update(&self[DECORATION_GENERALIZED_INDEX]);
-        self[1].copy_from_slice(&hasher.finalize_reset());
+        let mut root = [0u8; BYTES_PER_CHUNK];
+        hash_nodes(
+            hasher,
+            &self[INNER_ROOT_GENERALIZED_INDEX],
+            &self[DECORATION_GENERALIZED_INDEX],
+            &mut root,
+        );
+        self[1].copy_from_slice(&root);
         Ok(())
     }
 
@@ -286,9 +450,8 @@ impl std::fmt::Debug for Tree {
 // Return the full Merkle tree of the `chunks`.
 // Invariant: `chunks.len() % BYTES_PER_CHUNK == 0`
 // Invariant: `leaf_count.next_power_of_two() == leaf_count`
-// NOTE: naive implementation, can make much more efficient
-pub fn compute_merkle_tree(
-    hasher: &mut Sha256,
+pub fn compute_merkle_tree<H: Hasher>(
+    hasher: &mut H,
     chunks: &[u8],
     leaf_count: usize,
 ) -> Result<Tree, Error> {
@@ -305,26 +468,149 @@ pub fn compute_merkle_tree(
     let mut buffer = vec![0u8; node_count * BYTES_PER_CHUNK];
     buffer[leaf_start..leaf_start + chunks.len()].copy_from_slice(chunks);
 
-    for i in (1..node_count).rev().step_by(2) {
-        // SAFETY: checked subtraction is unnecessary, as i >= 1; qed
-        let parent_index = (i - 1) / 2;
-        let focus = &mut buffer[parent_index * BYTES_PER_CHUNK..(i + 1) * BYTES_PER_CHUNK];
-        // SAFETY: checked subtraction is unnecessary:
-        // focus.len() = (i + 1 - parent_index) * BYTES_PER_CHUNK
-        //             = ((2*i + 2 - i + 1) / 2) * BYTES_PER_CHUNK
-        //             = ((i + 3) / 2) * BYTES_PER_CHUNK
-        // and
-        // i >= 1
-        // so focus.len() >= 2 * BYTES_PER_CHUNK; qed
-        let children_index = focus.len() - 2 * BYTES_PER_CHUNK;
-        // NOTE: children.len() == 2 * BYTES_PER_CHUNK
-        let (parent, children) = focus.split_at_mut(children_index);
-        let (left, right) = children.split_at(BYTES_PER_CHUNK);
-        hash_nodes(hasher, left, right, &mut parent[..BYTES_PER_CHUNK]);
+    // for each layer of the tree, starting from the parents of the leaves and walking up to the
+    // root: the `width` parents of the layer start at index `width - 1` and are followed by their
+    // children
+    let mut width = leaf_count / 2;
+    while width > 0 {
+        // SAFETY: checked subtraction is unnecessary, as width >= 1; qed
+        let focus = &mut buffer[(width - 1) * BYTES_PER_CHUNK..(4 * width - 1) * BYTES_PER_CHUNK];
+        let (parents, children) = focus.split_at_mut(width * BYTES_PER_CHUNK);
+        hasher.hash_pairs(children, parents);
+        width /= 2;
     }
     Ok(Tree(buffer))
 }
 
+/// The Merkle tree of some chunks following the progressive Merklization of EIP-7916.
+pub struct ProgressiveTree {
+    root: Node,
+    decoration: Option<Node>,
+    // the nodes along the left edge of the tree, ending with the "zero" node terminating it
+    spine: Vec<Node>,
+    // the subtree hanging off the right of each node along the left edge of the tree, save the
+    // last one
+    subtrees: Vec<Tree>,
+}
+
+// The location of a node in a `ProgressiveTree`.
+enum ProgressiveNode {
+    Root,
+    Decoration,
+    Spine(usize),
+    // The node at the local generalized index in the subtree at the given level, along with the
+    // depth of the node in the subtree.
+    Subtree { level: u32, index: GeneralizedIndex, depth: u32 },
+}
+
+impl ProgressiveTree {
+    // Locate the node at the generalized `index` in a tree of this shape, whether it exists or not.
+    fn locate(&self, index: GeneralizedIndex) -> Result<ProgressiveNode, Error> {
+        let depth = index.checked_ilog2().ok_or(Error::InvalidGeneralizedIndex)?;
+        let index = if self.decoration.is_some() {
+            match (depth, index >> depth.saturating_sub(1)) {
+                (0, _) => return Ok(ProgressiveNode::Root),
+                (1, 3) => return Ok(ProgressiveNode::Decoration),
+                // NOTE: drop the layer of the decoration to index relative to the data
+                (_, 2) => index - (1 << (depth - 1)),
+                _ => return Err(Error::InvalidGeneralizedIndex),
+            }
+        } else {
+            index
+        };
+
+        let depth = index.ilog2();
+        // SAFETY: checked subtraction is unnecessary, as `index >= 1 << depth`; qed
+        let index = index - (1 << depth);
+        if index == 0 {
+            return Ok(ProgressiveNode::Spine(depth as usize))
+        }
+        // NOTE: the remaining bits take the right branch into the subtree at `level` and then
+        // address the node within that subtree
+        let local_depth = index.ilog2();
+        let level = depth - 1 - local_depth;
+        Ok(ProgressiveNode::Subtree { level, index, depth: local_depth })
+    }
+
+    // Identify the generalized index of the node in `self` that is `index` or its closest ancestor,
+    // returning the depth of the node in `self`, the position of the chunk it corresponds to (if
+    // any) and its generalized index.
+    pub(crate) fn compute_local_merkle_coordinates(
+        &self,
+        index: GeneralizedIndex,
+    ) -> Result<(u32, usize, GeneralizedIndex), Error> {
+        let depth = index.checked_ilog2().ok_or(Error::InvalidGeneralizedIndex)?;
+        match self.locate(index)? {
+            ProgressiveNode::Root | ProgressiveNode::Decoration => Ok((depth, 0, index)),
+            ProgressiveNode::Spine(i) if i < self.spine.len() => Ok((depth, 0, index)),
+            ProgressiveNode::Subtree { level, index: local_index, depth: local_depth }
+                if (level as usize) < self.subtrees.len() =>
+            {
+                let chunk_depth = 2 * level;
+                let excess_depth = local_depth.saturating_sub(chunk_depth);
+                let chunk_index = local_index >> excess_depth;
+                let position = if local_depth >= chunk_depth {
+                    // SAFETY: checked subtraction is unnecessary, as `chunk_index` is in the leaf
+                    // layer of the subtree; qed
+                    let offset = chunk_index - progressive_subtree_leaf_count(level);
+                    (0..level).map(progressive_subtree_leaf_count).sum::<usize>() + offset
+                } else {
+                    0
+                };
+                Ok((depth - excess_depth, position, index >> excess_depth))
+            }
+            _ => Err(Error::InvalidGeneralizedIndex),
+        }
+    }
+}
+
+impl Index<GeneralizedIndex> for ProgressiveTree {
+    type Output = [u8];
+
+    fn index(&self, index: GeneralizedIndex) -> &Self::Output {
+        match self.locate(index).expect("valid generalized index") {
+            ProgressiveNode::Root => self.root.as_slice(),
+            ProgressiveNode::Decoration => {
+                self.decoration.as_ref().expect("tree is decorated").as_slice()
+            }
+            ProgressiveNode::Spine(i) => self.spine[i].as_slice(),
+            ProgressiveNode::Subtree { level, index, .. } => &self.subtrees[level as usize][index],
+        }
+    }
+}
+
+// Return the full progressive Merkle tree of the `chunks`, mixing in the `decoration` if any.
+// Invariant: `chunks.len() % BYTES_PER_CHUNK == 0`
+pub fn compute_progressive_merkle_tree<H: Hasher>(
+    hasher: &mut H,
+    chunks: &[u8],
+    decoration: Option<Node>,
+) -> Result<ProgressiveTree, Error> {
+    debug_assert!(chunks.len() % BYTES_PER_CHUNK == 0);
+    let mut subtrees = vec![];
+    let mut rest = chunks;
+    let mut level = 0;
+    while !rest.is_empty() {
+        let leaf_count = progressive_subtree_leaf_count(level);
+        let (subtree, next) = rest.split_at(rest.len().min(leaf_count * BYTES_PER_CHUNK));
+        subtrees.push(compute_merkle_tree(hasher, subtree, leaf_count)?);
+        rest = next;
+        level += 1;
+    }
+
+    let mut spine = vec![Node::default(); subtrees.len() + 1];
+    for (i, subtree) in subtrees.iter().enumerate().rev() {
+        let left = spine[i + 1];
+        hash_nodes(hasher, left, &subtree[1], spine[i].as_mut());
+    }
+
+    let mut root = spine[0];
+    if let Some(decoration) = decoration {
+        hash_nodes(hasher, spine[0], decoration, root.as_mut());
+    }
+    Ok(ProgressiveTree { root, decoration, spine, subtrees })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -332,7 +618,7 @@ mod tests {
 
     // Return the root of the Merklization of a binary tree formed from `chunks`.
     fn merkleize_chunks(chunks: &[u8], leaf_count: usize) -> Result<Node, Error> {
-        let mut hasher = Sha256::new();
+        let mut hasher = Sha256::default();
         let tree = compute_merkle_tree(&mut hasher, chunks, leaf_count)?;
         let root_index = default_generalized_index();
         Ok(tree[root_index].try_into().expect("can produce a single root chunk"))
@@ -446,7 +732,8 @@ mod tests {
     #[test]
     fn test_merkleize_chunks() {
         let chunks = vec![1u8; 3 * BYTES_PER_CHUNK];
-        let root = merkleize_chunks_with_virtual_padding(&chunks, 4).expect("can merkleize");
+        let root =
+            merkleize_chunks_with_virtual_padding::<Sha256>(&chunks, 4).expect("can merkleize");
         assert_eq!(
             root,
             decode_node_from_hex(
@@ -455,7 +742,8 @@ mod tests {
         );
 
         let chunks = vec![1u8; 5 * BYTES_PER_CHUNK];
-        let root = merkleize_chunks_with_virtual_padding(&chunks, 8).expect("can merkleize");
+        let root =
+            merkleize_chunks_with_virtual_padding::<Sha256>(&chunks, 8).expect("can merkleize");
         assert_eq!(
             root,
             decode_node_from_hex(
@@ -464,7 +752,8 @@ mod tests {
         );
 
         let chunks = vec![1u8; 6 * BYTES_PER_CHUNK];
-        let root = merkleize_chunks_with_virtual_padding(&chunks, 8).expect("can merkleize");
+        let root =
+            merkleize_chunks_with_virtual_padding::<Sha256>(&chunks, 8).expect("can merkleize");
         assert_eq!(
             root,
             decode_node_from_hex(
@@ -476,8 +765,8 @@ mod tests {
     #[test]
     fn test_merkleize_chunks_with_many_virtual_nodes() {
         let chunks = vec![1u8; 5 * BYTES_PER_CHUNK];
-        let root =
-            merkleize_chunks_with_virtual_padding(&chunks, 2usize.pow(10)).expect("can merkleize");
+        let root = merkleize_chunks_with_virtual_padding::<Sha256>(&chunks, 2usize.pow(10))
+            .expect("can merkleize");
         assert_eq!(
             root,
             decode_node_from_hex(
@@ -486,8 +775,8 @@ mod tests {
         );
 
         let chunks = vec![1u8; 70 * BYTES_PER_CHUNK];
-        let root =
-            merkleize_chunks_with_virtual_padding(&chunks, 2usize.pow(63)).expect("can merkleize");
+        let root = merkleize_chunks_with_virtual_padding::<Sha256>(&chunks, 2usize.pow(63))
+            .expect("can merkleize");
         assert_eq!(
             root,
             decode_node_from_hex(
@@ -676,4 +965,39 @@ mod tests {
         );
         assert_eq!(foo_root, expected_root);
     }
+
+    #[test]
+    fn test_merkleize_large_layers() {
+        // NOTE: large enough to hash layers across threads with the `rayon` feature
+        for chunk_count in [1024, 3073] {
+            let chunks = (0..chunk_count * BYTES_PER_CHUNK).map(|i| i as u8).collect::<Vec<_>>();
+            let leaf_count = 2 * chunk_count.next_power_of_two();
+            let expected = merkleize_chunks(&chunks, leaf_count).unwrap();
+            let root =
+                merkleize_chunks_with_virtual_padding::<Sha256>(&chunks, leaf_count).unwrap();
+            assert_eq!(root, expected);
+        }
+    }
+
+    #[test]
+    fn test_hash_tree_root_of_many_elements() {
+        // NOTE: large enough to hash elements across threads with the `rayon` feature
+        type Element = Vector<u8, 33>;
+        let elements =
+            (0..200u8).map(|i| Element::try_from(vec![i; 33]).unwrap()).collect::<Vec<_>>();
+        let list = List::<Element, 1000>::try_from(elements.clone()).unwrap();
+
+        let chunks = elements.iter().flat_map(|e| e.hash_tree_root().unwrap()).collect::<Vec<_>>();
+        let data_root = merkleize(&chunks, Some(1000)).unwrap();
+        let expected = mix_in_length::<Sha256>(data_root, elements.len());
+        assert_eq!(list.hash_tree_root().unwrap(), expected);
+        #[cfg(feature = "rayon")]
+        {
+            assert_eq!(list.par_hash_tree_root().unwrap(), expected);
+            let vector = Vector::<Element, 200>::try_from(elements.clone()).unwrap();
+            assert_eq!(vector.par_hash_tree_root().unwrap(), vector.hash_tree_root().unwrap());
+            let list = ProgressiveList::<Element>::try_from(elements).unwrap();
+            assert_eq!(list.par_hash_tree_root().unwrap(), list.hash_tree_root().unwrap());
+        }
+    }
 }
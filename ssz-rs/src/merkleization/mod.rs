@@ -1,15 +1,21 @@
+mod cache;
 pub mod generalized_index;
+pub mod hasher;
 mod merkleize;
 pub mod multiproofs;
 mod node;
+mod persistent;
 pub mod proofs;
 
 use crate::{lib::*, ser::SerializeError};
+pub(crate) use cache::SharedElementsCache;
 pub use generalized_index::{
     get_power_of_two_ceil, GeneralizedIndex, GeneralizedIndexable, Path, PathElement,
 };
+pub use hasher::Hasher;
 pub use merkleize::*;
 pub use node::*;
+pub(crate) use persistent::PersistentTree;
 
 pub(crate) const BYTES_PER_CHUNK: usize = 32;
 pub(crate) const BITS_PER_CHUNK: usize = BYTES_PER_CHUNK * (crate::BITS_PER_BYTE as usize);
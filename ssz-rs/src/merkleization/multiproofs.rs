@@ -3,10 +3,11 @@ use crate::{
     lib::*,
     merkleization::{
         generalized_index::{get_bit, get_path_length, parent, sibling},
+        hash_nodes,
+        hasher::{Hasher, Sha256},
         GeneralizedIndex, MerkleizationError as Error, Node,
     },
 };
-use sha2::{Digest, Sha256};
 
 fn get_branch_indices(tree_index: GeneralizedIndex) -> Vec<GeneralizedIndex> {
     let mut focus = sibling(tree_index);
@@ -30,7 +31,7 @@ fn get_path_indices(tree_index: GeneralizedIndex) -> Vec<GeneralizedIndex> {
     result
 }
 
-fn get_helper_indices(indices: &[GeneralizedIndex]) -> Vec<GeneralizedIndex> {
+pub(crate) fn get_helper_indices(indices: &[GeneralizedIndex]) -> Vec<GeneralizedIndex> {
     let mut all_helper_indices = HashSet::new();
     let mut all_path_indices = HashSet::new();
 
@@ -49,6 +50,14 @@ pub fn calculate_merkle_root(
     leaf: Node,
     proof: &[Node],
     index: GeneralizedIndex,
+) -> Result<Node, Error> {
+    calculate_merkle_root_with::<Sha256>(leaf, proof, index)
+}
+
+pub fn calculate_merkle_root_with<H: Hasher>(
+    leaf: Node,
+    proof: &[Node],
+    index: GeneralizedIndex,
 ) -> Result<Node, Error> {
     let path_length = get_path_length(index)?;
     if path_length != proof.len() {
@@ -56,16 +65,14 @@ pub fn calculate_merkle_root(
     }
     let mut result = leaf;
 
-    let mut hasher = Sha256::new();
+    let mut hasher = H::default();
     for (i, next) in proof.iter().enumerate() {
+        let current = result;
         if get_bit(index, i) {
-            hasher.update(next);
-            hasher.update(result);
+            hash_nodes(&mut hasher, next, current, result.as_mut());
         } else {
-            hasher.update(result);
-            hasher.update(next);
+            hash_nodes(&mut hasher, current, next, result.as_mut());
         }
-        result.copy_from_slice(&hasher.finalize_reset());
     }
     Ok(result)
 }
@@ -76,7 +83,16 @@ pub fn verify_merkle_proof(
     index: GeneralizedIndex,
     root: Node,
 ) -> Result<(), Error> {
-    if calculate_merkle_root(leaf, proof, index)? == root {
+    verify_merkle_proof_with::<Sha256>(leaf, proof, index, root)
+}
+
+pub fn verify_merkle_proof_with<H: Hasher>(
+    leaf: Node,
+    proof: &[Node],
+    index: GeneralizedIndex,
+    root: Node,
+) -> Result<(), Error> {
+    if calculate_merkle_root_with::<H>(leaf, proof, index)? == root {
         Ok(())
     } else {
         Err(Error::InvalidProof)
@@ -87,6 +103,14 @@ pub fn calculate_multi_merkle_root(
     leaves: &[Node],
     proof: &[Node],
     indices: &[GeneralizedIndex],
+) -> Result<Node, Error> {
+    calculate_multi_merkle_root_with::<Sha256>(leaves, proof, indices)
+}
+
+pub fn calculate_multi_merkle_root_with<H: Hasher>(
+    leaves: &[Node],
+    proof: &[Node],
+    indices: &[GeneralizedIndex],
 ) -> Result<Node, Error> {
     if leaves.len() != indices.len() {
         return Err(Error::InvalidProof)
@@ -107,7 +131,7 @@ pub fn calculate_multi_merkle_root(
     let mut keys = objects.keys().cloned().collect::<Vec<_>>();
     keys.sort_by(|a, b| b.cmp(a));
 
-    let mut hasher = Sha256::new();
+    let mut hasher = H::default();
     let mut pos = 0;
     while pos < keys.len() {
         let key = keys.get(pos).unwrap();
@@ -119,13 +143,11 @@ pub fn calculate_multi_merkle_root(
         if should_compute {
             let right_index = key | 1;
             let left_index = sibling(right_index);
-            let left_input = objects.get(&left_index).expect("contains index");
-            let right_input = objects.get(&right_index).expect("contains index");
-            hasher.update(left_input);
-            hasher.update(right_input);
+            let left_input = *objects.get(&left_index).expect("contains index");
+            let right_input = *objects.get(&right_index).expect("contains index");
 
             let parent = objects.entry(parent_index).or_default();
-            parent.copy_from_slice(&hasher.finalize_reset());
+            hash_nodes(&mut hasher, left_input, right_input, parent.as_mut());
             keys.push(parent_index);
         }
         pos += 1;
@@ -141,9 +163,48 @@ pub fn verify_merkle_multiproof(
     indices: &[GeneralizedIndex],
     root: Node,
 ) -> Result<(), Error> {
-    if calculate_multi_merkle_root(leaves, proof, indices)? == root {
+    verify_merkle_multiproof_with::<Sha256>(leaves, proof, indices, root)
+}
+
+pub fn verify_merkle_multiproof_with<H: Hasher>(
+    leaves: &[Node],
+    proof: &[Node],
+    indices: &[GeneralizedIndex],
+    root: Node,
+) -> Result<(), Error> {
+    if calculate_multi_merkle_root_with::<H>(leaves, proof, indices)? == root {
         Ok(())
     } else {
         Err(Error::InvalidProof)
     }
 }
+
+/// Convenience type for a Merkle multiproof and the root of the Merkle tree, which serves as
+/// "witness" that the proof is valid.
+pub type MultiProofAndWitness = (MultiProof, Node);
+
+/// Contains data necessary to verify each of the `leaves` was included under some witness "root"
+/// node at the generalized position in `indices` with the same offset.
+///
+/// The `branch` holds the helper nodes required to compute the root, ordered by descending
+/// generalized index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiProof {
+    pub leaves: Vec<Node>,
+    pub branch: Vec<Node>,
+    pub indices: Vec<GeneralizedIndex>,
+}
+
+impl MultiProof {
+    /// Verify `self` against the provided `root` witness node.
+    /// This `root` is the hash tree root of the SSZ object that produced the proof.
+    pub fn verify(&self, root: Node) -> Result<(), Error> {
+        self.verify_with::<Sha256>(root)
+    }
+
+    /// Verify `self` against the provided `root` witness node, where the Merkle tree was computed
+    /// with the hash function `H`.
+    pub fn verify_with<H: Hasher>(&self, root: Node) -> Result<(), Error> {
+        verify_merkle_multiproof_with::<H>(&self.leaves, &self.branch, &self.indices, root)
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{lib::*, merkleization::BYTES_PER_CHUNK, prelude::*};
+use crate::{
+    dynamic::{SszType, SszTypeInfo},
+    lib::*,
+    merkleization::BYTES_PER_CHUNK,
+    prelude::*,
+};
 
 /// Represents a node in a Merkle tree as defined by the SSZ spec.
 pub type Node = alloy_primitives::B256;
@@ -8,6 +13,15 @@ impl Serialize for Node {
         buffer.extend_from_slice(self.as_slice());
         Ok(Self::size_hint())
     }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        sink.write_bytes(self.as_slice())?;
+        Ok(Self::size_hint())
+    }
+
+    fn encoded_len(&self) -> usize {
+        Self::size_hint()
+    }
 }
 
 impl Deserialize for Node {
@@ -41,8 +55,8 @@ impl Serializable for Node {
 }
 
 impl HashTreeRoot for Node {
-    fn hash_tree_root(&self) -> Result<Node, MerkleizationError> {
-        let chunks = self.chunks()?;
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        let chunks = self.chunks::<H>()?;
         Ok(Node::try_from(chunks.as_slice()).expect("is right size"))
     }
 
@@ -54,18 +68,33 @@ impl HashTreeRoot for Node {
 impl GeneralizedIndexable for Node {}
 
 impl Prove for Node {
-    fn chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
         Ok(self.to_vec())
     }
 }
 
+impl Viewable for Node {
+    type View<'a> = Self;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        Self::deserialize(encoding)
+    }
+}
+
 impl SimpleSerialize for Node {}
 
+impl SszTypeInfo for Node {
+    fn type_info() -> SszType {
+        SszType::Vector(Box::new(SszType::Uint(8)), BYTES_PER_CHUNK)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
+    #[cfg(feature = "serde")]
     fn test_serde() {
         let mut node = Node::default();
         node[2] = 33;
@@ -0,0 +1,391 @@
+//! A persistent Merkle tree holding the elements of a homogeneous collection.
+use crate::{
+    error::{Error, InstanceError, TypeError},
+    lib::*,
+    merkleization::{
+        hash_nodes,
+        hasher::{is_sha256, Hasher},
+        merkleize_with, pack, zero_hash, MerkleizationError, Node, BYTES_PER_CHUNK,
+    },
+    SimpleSerialize,
+};
+use sha2::{Digest, Sha256};
+
+// A node of the tree, holding its own root.
+// Leaves hold the elements contributing to a single chunk of the collection's Merkle tree.
+#[derive(Debug)]
+enum TreeNode<T> {
+    Leaf { root: Node, elements: Vec<T> },
+    Branch { root: Node, left: Subtree<T>, right: Subtree<T> },
+}
+
+impl<T> TreeNode<T> {
+    fn root(&self) -> &Node {
+        match self {
+            Self::Leaf { root, .. } | Self::Branch { root, .. } => root,
+        }
+    }
+}
+
+// A missing subtree is a "zero" subtree, following the virtual padding used by `merkleize`.
+type Subtree<T> = Option<Arc<TreeNode<T>>>;
+
+fn subtree_root<T>(subtree: &Subtree<T>, height: usize) -> &[u8] {
+    match subtree {
+        Some(node) => node.root().as_slice(),
+        None => zero_hash::<Sha256>(height),
+    }
+}
+
+// Construct the parent of `left` and `right`, two subtrees of height `height`.
+fn branch<T>(
+    hasher: &mut Sha256,
+    left: Subtree<T>,
+    right: Subtree<T>,
+    height: usize,
+) -> Subtree<T> {
+    if left.is_none() && right.is_none() {
+        return None
+    }
+    let mut root = Node::default();
+    hash_nodes(
+        hasher,
+        subtree_root(&left, height),
+        subtree_root(&right, height),
+        root.as_mut_slice(),
+    );
+    Some(Arc::new(TreeNode::Branch { root, left, right }))
+}
+
+fn leaf<T: SimpleSerialize>(elements: Vec<T>) -> Result<Subtree<T>, MerkleizationError> {
+    let root = match elements.first() {
+        None => return Ok(None),
+        Some(element) if T::is_composite_type() => element.hash_tree_root()?,
+        Some(_) => Node::from_slice(&pack(&elements)?),
+    };
+    Ok(Some(Arc::new(TreeNode::Leaf { root, elements })))
+}
+
+// Replace the leaf at `index` in `subtree` of height `height`, re-hashing the path to it.
+// Every other node is shared with the previous version of `subtree`.
+fn replace<T>(
+    hasher: &mut Sha256,
+    subtree: &mut Subtree<T>,
+    height: usize,
+    index: usize,
+    leaf: Subtree<T>,
+) {
+    if height == 0 {
+        *subtree = leaf;
+        return
+    }
+    let (mut left, mut right) = match subtree.as_deref() {
+        Some(TreeNode::Branch { left, right, .. }) => (left.clone(), right.clone()),
+        _ => (None, None),
+    };
+    // SAFETY: checked subtraction is unnecessary, as height > 0; qed
+    let child_height = height - 1;
+    if (index >> child_height) & 1 == 0 {
+        replace(hasher, &mut left, child_height, index, leaf);
+    } else {
+        replace(hasher, &mut right, child_height, index, leaf);
+    }
+    *subtree = branch(hasher, left, right, child_height);
+}
+
+/// A Merkle tree whose nodes are shared between clones, so that a clone only copies the nodes
+/// on the path to any leaf it later modifies.
+///
+/// Each leaf holds the elements of one chunk: a single element for composite types, otherwise as
+/// many elements as are packed into a chunk.
+pub(crate) struct PersistentTree<T> {
+    root: Subtree<T>,
+    // height of the tree, fixed by the maximum number of chunks
+    depth: usize,
+    // number of elements
+    len: usize,
+    // number of elements per leaf
+    per_leaf: usize,
+}
+
+impl<T> Clone for PersistentTree<T> {
+    fn clone(&self) -> Self {
+        Self { root: self.root.clone(), depth: self.depth, len: self.len, per_leaf: self.per_leaf }
+    }
+}
+
+impl<T: Debug> Debug for PersistentTree<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PersistentTree<T> {
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn root(&self) -> Node {
+        Node::from_slice(subtree_root(&self.root, self.depth))
+    }
+
+    fn leaf_at(&self, index: usize) -> Option<&TreeNode<T>> {
+        let mut node = self.root.as_deref()?;
+        for height in (0..self.depth).rev() {
+            node = match node {
+                TreeNode::Branch { left, right, .. } => {
+                    if (index >> height) & 1 == 0 {
+                        left.as_deref()?
+                    } else {
+                        right.as_deref()?
+                    }
+                }
+                TreeNode::Leaf { .. } => return None,
+            };
+        }
+        Some(node)
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None
+        }
+        match self.leaf_at(index / self.per_leaf)? {
+            TreeNode::Leaf { elements, .. } => elements.get(index % self.per_leaf),
+            TreeNode::Branch { .. } => None,
+        }
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_, T> {
+        Iter { stack: self.root.as_deref().into_iter().collect(), elements: &[] }
+    }
+
+    /// Return the chunks of the collection's Merkle tree, i.e. the roots of every leaf.
+    pub(crate) fn chunks(&self) -> Vec<u8> {
+        let mut chunks = Vec::with_capacity(self.len / self.per_leaf * BYTES_PER_CHUNK);
+        let mut stack = self.root.as_deref().into_iter().collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            match node {
+                TreeNode::Leaf { root, .. } => chunks.extend_from_slice(root.as_slice()),
+                TreeNode::Branch { left, right, .. } => {
+                    stack.extend(right.as_deref());
+                    stack.extend(left.as_deref());
+                }
+            }
+        }
+        chunks
+    }
+}
+
+impl<T: SimpleSerialize> PersistentTree<T> {
+    /// Construct an (empty) tree for a collection of up to `bound` elements.
+    ///
+    /// Basic types are only supported if a whole number of elements fits in a chunk.
+    pub(crate) fn new(bound: usize) -> Result<Self, TypeError> {
+        let per_leaf = if T::is_composite_type() {
+            1
+        } else {
+            let size = T::size_hint();
+            if BYTES_PER_CHUNK % size != 0 {
+                return Err(TypeError::InvalidElementSize(size))
+            }
+            BYTES_PER_CHUNK / size
+        };
+        let leaf_count = (bound + per_leaf - 1) / per_leaf;
+        let depth = leaf_count.next_power_of_two().trailing_zeros() as usize;
+        Ok(Self { root: None, depth, len: 0, per_leaf })
+    }
+
+    /// Construct a tree for a collection of up to `bound` elements, holding `elements`.
+    pub(crate) fn from_elements(elements: Vec<T>, bound: usize) -> Result<Self, Error> {
+        let mut tree = Self::new(bound).map_err(Error::Type)?;
+        if elements.len() > bound {
+            return Err(Error::Instance(InstanceError::Bounded { bound, provided: elements.len() }))
+        }
+        tree.len = elements.len();
+
+        let mut layer = Vec::with_capacity((elements.len() + tree.per_leaf - 1) / tree.per_leaf);
+        let mut elements = elements.into_iter().peekable();
+        while elements.peek().is_some() {
+            layer.push(leaf(elements.by_ref().take(tree.per_leaf).collect())?);
+        }
+
+        let mut hasher = Sha256::new();
+        for height in 0..tree.depth {
+            let mut nodes = layer.into_iter();
+            layer = Vec::with_capacity((nodes.len() + 1) / 2);
+            while let Some(left) = nodes.next() {
+                layer.push(branch(&mut hasher, left, nodes.next().flatten(), height));
+            }
+        }
+        tree.root = layer.pop().flatten();
+        Ok(tree)
+    }
+
+    /// Return the chunks of the collection's Merkle tree, computing the roots of any composite
+    /// elements with the hash function `H`.
+    pub(crate) fn chunks_with<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        // NOTE: the tree only holds `Sha256` roots, while packed basic elements are independent
+        // of the hash function
+        if !T::is_composite_type() || is_sha256::<H>() {
+            return Ok(self.chunks())
+        }
+        let mut chunks = Vec::with_capacity(self.len * BYTES_PER_CHUNK);
+        for element in self.iter() {
+            chunks.extend_from_slice(element.hash_tree_root_with::<H>()?.as_slice());
+        }
+        Ok(chunks)
+    }
+
+    /// Return the root of the collection's Merkle tree, computed with the hash function `H`.
+    pub(crate) fn root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        if is_sha256::<H>() {
+            return Ok(self.root())
+        }
+        let chunks = self.chunks_with::<H>()?;
+        merkleize_with::<H>(&chunks, Some(1 << self.depth))
+    }
+}
+
+impl<T: SimpleSerialize + Clone> PersistentTree<T> {
+    // Apply `f` to the elements of the leaf at `index`, then replace the leaf with the result.
+    fn update_leaf<R>(
+        &mut self,
+        index: usize,
+        f: impl FnOnce(&mut Vec<T>) -> R,
+    ) -> Result<R, MerkleizationError> {
+        let mut elements = match self.leaf_at(index) {
+            Some(TreeNode::Leaf { elements, .. }) => elements.clone(),
+            _ => Vec::with_capacity(self.per_leaf),
+        };
+        let result = f(&mut elements);
+        let leaf = leaf(elements)?;
+        replace(&mut Sha256::new(), &mut self.root, self.depth, index, leaf);
+        Ok(result)
+    }
+
+    // Invariant: `index < self.len`
+    pub(crate) fn set(&mut self, index: usize, value: T) -> Result<(), MerkleizationError> {
+        let offset = index % self.per_leaf;
+        self.update_leaf(index / self.per_leaf, |elements| elements[offset] = value)
+    }
+
+    // Invariant: `self.len` is less than the bound of the tree
+    pub(crate) fn push(&mut self, value: T) -> Result<(), MerkleizationError> {
+        self.update_leaf(self.len / self.per_leaf, |elements| elements.push(value))?;
+        self.len += 1;
+        Ok(())
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        let index = self.len.checked_sub(1)?;
+        let value = self
+            .update_leaf(index / self.per_leaf, |elements| elements.pop())
+            // SAFETY: the remaining elements of this leaf were packed before; qed
+            .expect("can merkleize remaining elements");
+        self.len = index;
+        value
+    }
+}
+
+/// An iterator over the elements of a `PersistentTree`.
+pub(crate) struct Iter<'a, T> {
+    stack: Vec<&'a TreeNode<T>>,
+    elements: &'a [T],
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Self { stack: self.stack.clone(), elements: self.elements }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((element, rest)) = self.elements.split_first() {
+                self.elements = rest;
+                return Some(element)
+            }
+            match self.stack.pop()? {
+                TreeNode::Leaf { elements, .. } => self.elements = elements,
+                TreeNode::Branch { left, right, .. } => {
+                    self.stack.extend(right.as_deref());
+                    self.stack.extend(left.as_deref());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{merkleization::merkleize, HashTreeRoot, List};
+
+    fn children<T>(subtree: &Subtree<T>) -> (&Subtree<T>, &Subtree<T>) {
+        match subtree.as_deref() {
+            Some(TreeNode::Branch { left, right, .. }) => (left, right),
+            _ => panic!("expected a branch"),
+        }
+    }
+
+    #[test]
+    fn test_persistent_tree_shares_nodes() {
+        let elements = (0..100u64).collect::<Vec<_>>();
+        let tree = PersistentTree::from_elements(elements, 1024).unwrap();
+
+        let mut other = tree.clone();
+        other.set(1, 1111).unwrap();
+        assert_eq!(tree.get(1), Some(&1));
+        assert_eq!(other.get(1), Some(&1111));
+        assert_ne!(tree.root(), other.root());
+
+        // only the path to the modified (first) leaf is copied
+        let (mut a, mut b) = (&tree.root, &other.root);
+        for _ in 0..tree.depth {
+            assert!(!Arc::ptr_eq(a.as_ref().unwrap(), b.as_ref().unwrap()));
+            let ((a_left, a_right), (b_left, b_right)) = (children(a), children(b));
+            match (a_right, b_right) {
+                (Some(a_right), Some(b_right)) => assert!(Arc::ptr_eq(a_right, b_right)),
+                (None, None) => {}
+                _ => panic!("trees have the same shape"),
+            }
+            (a, b) = (a_left, b_left);
+        }
+    }
+
+    #[test]
+    fn test_persistent_tree_matches_merkleize() {
+        let mut tree = PersistentTree::<u32>::new(100).unwrap();
+        let mut list = List::<u32, 100>::default();
+        for i in 0..100 {
+            assert_eq!(tree.root(), merkleize(&pack(&list).unwrap(), Some(13)).unwrap());
+            tree.push(i).unwrap();
+            list.push(i);
+        }
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), list.to_vec());
+        assert_eq!(tree.chunks(), pack(&list).unwrap());
+        while let Some(value) = tree.pop() {
+            assert_eq!(Some(value), list.pop());
+            assert_eq!(tree.root(), merkleize(&pack(&list).unwrap(), Some(13)).unwrap());
+        }
+        assert_eq!(tree.len(), 0);
+
+        let elements =
+            (0..9).map(|i| List::<u8, 4>::try_from(vec![i]).unwrap()).collect::<Vec<_>>();
+        let tree = PersistentTree::from_elements(elements.clone(), 9).unwrap();
+        let chunks = elements.iter().flat_map(|e| e.hash_tree_root().unwrap()).collect::<Vec<_>>();
+        assert_eq!(tree.root(), merkleize(&chunks, Some(9)).unwrap());
+    }
+
+    #[test]
+    fn test_persistent_tree_of_unaligned_elements() {
+        assert!(matches!(
+            PersistentTree::<[u8; 3]>::new(10),
+            Err(TypeError::InvalidElementSize(3))
+        ));
+    }
+}
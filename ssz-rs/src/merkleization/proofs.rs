@@ -3,11 +3,13 @@ pub use crate::merkleization::generalized_index::log_2;
 use crate::{
     lib::*,
     merkleization::{
-        compute_merkle_tree, GeneralizedIndex, GeneralizedIndexable, MerkleizationError as Error,
-        Node, Path,
+        compute_merkle_tree, compute_progressive_merkle_tree, hash_nodes,
+        hasher::{Hasher, Sha256},
+        multiproofs::{get_helper_indices, MultiProof, MultiProofAndWitness},
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, MerkleizationError as Error, Node,
+        Path, ProgressiveTree, Tree,
     },
 };
-use sha2::{Digest, Sha256};
 
 /// Convenience type for a Merkle proof and the root of the Merkle tree, which serves as
 /// "witness" that the proof is valid.
@@ -43,14 +45,31 @@ pub(crate) fn compute_local_merkle_coordinates(
 }
 
 /// A type that knows how to compute Merkle proofs assuming a target type is `Prove`.
+///
+/// Merkle trees are computed with the hash function `H`.
 #[derive(Debug)]
-pub struct Prover {
-    hasher: Sha256,
+pub struct Prover<H: Hasher = Sha256> {
+    hasher: H,
     proof: Proof,
     witness: Node,
+    // The generalized indices of the nodes to collect for a multiproof, relative to the value
+    // currently being proven, and the nodes collected so far.
+    targets: Option<Vec<GeneralizedIndex>>,
+    nodes: Vec<(GeneralizedIndex, Node)>,
 }
 
-impl Prover {
+impl<H: Hasher> Prover<H> {
+    /// Construct a `Prover` for the node at the generalized `index`.
+    pub fn new(index: GeneralizedIndex) -> Self {
+        Self {
+            hasher: H::default(),
+            proof: Proof { leaf: Default::default(), branch: vec![], index },
+            witness: Default::default(),
+            targets: None,
+            nodes: vec![],
+        }
+    }
+
     fn set_leaf(&mut self, leaf: &[u8]) {
         self.proof.leaf = leaf.try_into().expect("is correct size");
     }
@@ -67,19 +86,15 @@ impl Prover {
 
     /// Derive a Merkle proof relative to `data` given the parameters in `self`.
     pub fn compute_proof<T: Prove + ?Sized>(&mut self, data: &T) -> Result<(), Error> {
-        let chunk_count = T::chunk_count();
-        let mut leaf_count = chunk_count.next_power_of_two();
-        let parent_index = self.proof.index;
-        let decoration = data.decoration();
-        if decoration.is_some() {
-            // double to account for decoration layer
-            leaf_count *= 2;
+        if let Some(targets) = self.targets.take() {
+            return self.collect_nodes(data, targets)
         }
 
+        let parent_index = self.proof.index;
+        let tree = self.compute_tree(data)?;
         let (local_depth, local_index, local_generalized_index) =
-            compute_local_merkle_coordinates(parent_index, leaf_count)?;
+            tree.compute_local_merkle_coordinates(parent_index)?;
 
-        let mut is_leaf_local = false;
         if local_generalized_index < parent_index {
             // NOTE: need to recurse to children to find ultimate leaf
             let parent_depth = get_depth(parent_index)?;
@@ -90,16 +105,7 @@ impl Prover {
             data.prove_element(local_index, self)?;
             self.proof.index = parent_index;
         } else {
-            // NOTE: leaf is within the current object, set a flag to grab from merkle tree later
-            is_leaf_local = true;
-        }
-        let chunks = data.chunks()?;
-        let mut tree = compute_merkle_tree(&mut self.hasher, &chunks, leaf_count)?;
-        if let Some(decoration) = decoration {
-            tree.mix_in_decoration(decoration, &mut self.hasher)?;
-        }
-
-        if is_leaf_local {
+            // NOTE: leaf is within the current object
             self.set_leaf(&tree[parent_index]);
         }
 
@@ -115,37 +121,153 @@ impl Prover {
 
         Ok(())
     }
+
+    // Compute the Merkle tree of `data`, including any decoration.
+    fn compute_tree<T: Prove + ?Sized>(&mut self, data: &T) -> Result<MerkleTree, Error> {
+        let decoration = data.decoration_root::<H>()?;
+        let chunks = data.chunks::<H>()?;
+        if data.is_progressive() {
+            let tree = compute_progressive_merkle_tree(&mut self.hasher, &chunks, decoration)?;
+            return Ok(MerkleTree::Progressive(tree))
+        }
+
+        let chunk_count = data.value_chunk_count();
+        let mut leaf_count = chunk_count.next_power_of_two();
+        if decoration.is_some() {
+            // double to account for decoration layer
+            leaf_count *= 2;
+        }
+        let mut tree = compute_merkle_tree(&mut self.hasher, &chunks, leaf_count)?;
+        if let Some(decoration) = decoration {
+            tree.mix_in_decoration(decoration, &mut self.hasher)?;
+        }
+        Ok(MerkleTree::Complete { tree, leaf_count })
+    }
+}
+
+// The Merkle tree of a value being proven.
+enum MerkleTree {
+    Complete { tree: Tree, leaf_count: usize },
+    Progressive(ProgressiveTree),
 }
 
-impl From<Prover> for ProofAndWitness {
-    fn from(value: Prover) -> Self {
+impl MerkleTree {
+    fn compute_local_merkle_coordinates(
+        &self,
+        i: GeneralizedIndex,
+    ) -> Result<(u32, usize, GeneralizedIndex), Error> {
+        match self {
+            Self::Complete { leaf_count, .. } => compute_local_merkle_coordinates(i, *leaf_count),
+            Self::Progressive(tree) => tree.compute_local_merkle_coordinates(i),
+        }
+    }
+}
+
+impl Index<GeneralizedIndex> for MerkleTree {
+    type Output = [u8];
+
+    fn index(&self, index: GeneralizedIndex) -> &Self::Output {
+        match self {
+            Self::Complete { tree, .. } => &tree[index],
+            Self::Progressive(tree) => &tree[index],
+        }
+    }
+}
+
+impl<H: Hasher> Prover<H> {
+    // Collect the nodes of `data` at the generalized `targets` into `self.nodes`, recursing into
+    // the elements of `data` for any targets below its own Merkle tree.
+    fn collect_nodes<T: Prove + ?Sized>(
+        &mut self,
+        data: &T,
+        targets: Vec<GeneralizedIndex>,
+    ) -> Result<(), Error> {
+        let tree = self.compute_tree(data)?;
+
+        let mut local_targets = vec![];
+        let mut targets_by_element = HashMap::<usize, (GeneralizedIndex, Vec<_>)>::new();
+        for target in targets {
+            let (local_depth, local_index, local_generalized_index) =
+                tree.compute_local_merkle_coordinates(target)?;
+            if local_generalized_index < target {
+                // NOTE: target is within the element at `local_index`, so re-root the index there
+                let child_depth = get_depth(target)? - local_depth;
+                let node_count = 2usize.pow(child_depth);
+                let child_index = node_count + target % node_count;
+                targets_by_element
+                    .entry(local_index)
+                    .or_insert_with(|| (local_generalized_index, vec![]))
+                    .1
+                    .push(child_index);
+            } else {
+                local_targets.push(target);
+            }
+        }
+
+        for (local_index, (local_generalized_index, child_targets)) in targets_by_element {
+            let nodes = mem::take(&mut self.nodes);
+            self.targets = Some(child_targets);
+            data.prove_element(local_index, self)?;
+            let child_nodes = mem::replace(&mut self.nodes, nodes);
+            for (child_index, node) in child_nodes {
+                let node_count = 2usize.pow(get_depth(child_index)?);
+                let index = local_generalized_index * node_count + child_index % node_count;
+                self.nodes.push((index, node));
+            }
+        }
+
+        for target in local_targets {
+            self.nodes.push((target, tree[target].try_into().expect("is correct size")));
+        }
+        self.set_witness(&tree[1]);
+
+        Ok(())
+    }
+
+    /// Derive a Merkle multiproof of the nodes at the generalized `indices` of `data`.
+    pub fn compute_multiproof<T: Prove + ?Sized>(
+        data: &T,
+        indices: Vec<GeneralizedIndex>,
+    ) -> Result<MultiProofAndWitness, Error> {
+        let helper_indices = get_helper_indices(&indices);
+        let mut prover = Self::new(1);
+        prover.targets = Some(indices.iter().chain(helper_indices.iter()).copied().collect());
+        prover.compute_proof(data)?;
+
+        let nodes = prover.nodes.into_iter().collect::<HashMap<_, _>>();
+        let get_node = |index| nodes.get(index).copied().ok_or(Error::InvalidGeneralizedIndex);
+        let leaves = indices.iter().map(get_node).collect::<Result<Vec<_>, _>>()?;
+        let branch = helper_indices.iter().map(get_node).collect::<Result<Vec<_>, _>>()?;
+        Ok((MultiProof { leaves, branch, indices }, prover.witness))
+    }
+}
+
+impl<H: Hasher> From<Prover<H>> for ProofAndWitness {
+    fn from(value: Prover<H>) -> Self {
         (value.proof, value.witness)
     }
 }
 
 impl From<GeneralizedIndex> for Prover {
     fn from(index: GeneralizedIndex) -> Self {
-        Self {
-            hasher: Sha256::new(),
-            proof: Proof { leaf: Default::default(), branch: vec![], index },
-            witness: Default::default(),
-        }
+        Self::new(index)
     }
 }
 
 /// Required functionality to support computing Merkle proofs.
 pub trait Prove: GeneralizedIndexable {
-    /// Compute the "chunks" of this type as required for the SSZ merkle tree computation.
+    /// Compute the "chunks" of this type as required for the SSZ merkle tree computation, using
+    /// the hash function `H` for any inner Merkle trees.
     /// Default implementation signals an error. Implementing types should override
     /// to provide the correct behavior.
-    fn chunks(&self) -> Result<Vec<u8>, Error> {
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, Error> {
         Err(Error::NotChunkable)
     }
 
     /// Construct a proof of the member element located at the type-specific `index` assuming the
     /// context in `prover`.
     #[allow(unused)]
-    fn prove_element(&self, index: usize, prover: &mut Prover) -> Result<(), Error> {
+    fn prove_element<H: Hasher>(&self, index: usize, prover: &mut Prover<H>) -> Result<(), Error> {
         Err(Error::NoInnerElement)
     }
 
@@ -157,14 +279,57 @@ pub trait Prove: GeneralizedIndexable {
         None
     }
 
+    /// Returns the root of the "decoration" if this type has any in the Merkle tree, computed with
+    /// the hash function `H`.
+    /// Defaults to the root of `decoration`. Types decorated with something other than a single
+    /// `usize`, e.g. the `active_fields` of a `StableContainer`, should override this instead.
+    fn decoration_root<H: Hasher>(&self) -> Result<Option<Node>, Error> {
+        self.decoration().map(|decoration| decoration.hash_tree_root_with::<H>()).transpose()
+    }
+
+    /// Return the chunk count when merkleizing `self`.
+    /// Defaults to the `chunk_count` of `Self`. Types whose Merkle tree depends on the value
+    /// rather than the type, e.g. a `DynamicValue`, should override this.
+    fn value_chunk_count(&self) -> usize {
+        Self::chunk_count()
+    }
+
+    /// Indicate whether the chunks of `self` are merkleized progressively, following EIP-7916,
+    /// rather than as a perfect binary tree.
+    fn is_progressive(&self) -> bool {
+        false
+    }
+
     /// Compute a Merkle proof of `Self` at the type's `path`, along with the root of the Merkle
     /// tree as a witness value.
     fn prove(&self, path: Path) -> Result<ProofAndWitness, Error> {
+        self.prove_with::<Sha256>(path)
+    }
+
+    /// Compute a Merkle proof of `Self` at the type's `path`, along with the root of the Merkle
+    /// tree as a witness value, using the hash function `H`.
+    fn prove_with<H: Hasher>(&self, path: Path) -> Result<ProofAndWitness, Error> {
         let index = Self::generalized_index(path)?;
-        let mut prover = Prover::from(index);
+        let mut prover = Prover::<H>::new(index);
         prover.compute_proof(self)?;
         Ok(prover.into())
     }
+
+    /// Compute a Merkle multiproof of `Self` at each of the type's `paths`, along with the root of
+    /// the Merkle tree as a witness value.
+    fn prove_multi(&self, paths: &[Path]) -> Result<MultiProofAndWitness, Error> {
+        self.prove_multi_with::<Sha256>(paths)
+    }
+
+    /// Compute a Merkle multiproof of `Self` at each of the type's `paths`, along with the root of
+    /// the Merkle tree as a witness value, using the hash function `H`.
+    fn prove_multi_with<H: Hasher>(&self, paths: &[Path]) -> Result<MultiProofAndWitness, Error> {
+        let indices = paths
+            .iter()
+            .map(|path| Self::generalized_index(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        Prover::<H>::compute_multiproof(self, indices)
+    }
 }
 
 /// Contains data necessary to verify `leaf` was included under some witness "root" node
@@ -181,7 +346,18 @@ impl Proof {
     /// This `root` is the hash tree root of the SSZ object that produced the proof.
     /// See `Prover` for further information.
     pub fn verify(&self, root: Node) -> Result<(), Error> {
-        is_valid_merkle_branch_for_generalized_index(self.leaf, &self.branch, self.index, root)
+        self.verify_with::<Sha256>(root)
+    }
+
+    /// Verify `self` against the provided `root` witness node, where the Merkle tree was computed
+    /// with the hash function `H`.
+    pub fn verify_with<H: Hasher>(&self, root: Node) -> Result<(), Error> {
+        is_valid_merkle_branch_for_generalized_index_with::<H>(
+            self.leaf,
+            &self.branch,
+            self.index,
+            root,
+        )
     }
 }
 
@@ -192,10 +368,26 @@ pub fn is_valid_merkle_branch_for_generalized_index(
     branch: &[Node],
     generalized_index: GeneralizedIndex,
     root: Node,
+) -> Result<(), Error> {
+    is_valid_merkle_branch_for_generalized_index_with::<Sha256>(
+        leaf,
+        branch,
+        generalized_index,
+        root,
+    )
+}
+
+/// Verifies the Merkle proof against the `root` given the other metadata, assuming `leaf` occupies
+/// the `generalized_index` in a tree computed with the hash function `H`.
+pub fn is_valid_merkle_branch_for_generalized_index_with<H: Hasher>(
+    leaf: Node,
+    branch: &[Node],
+    generalized_index: GeneralizedIndex,
+    root: Node,
 ) -> Result<(), Error> {
     let depth = log_2(generalized_index).ok_or(Error::InvalidGeneralizedIndex)? as usize;
     let index = get_subtree_index(generalized_index)?;
-    is_valid_merkle_branch(leaf, branch, depth, index, root)
+    is_valid_merkle_branch_with::<H>(leaf, branch, depth, index, root)
 }
 
 /// `is_valid_merkle_branch` verifies the Merkle proof against the `root` given the other metadata.
@@ -205,23 +397,33 @@ pub fn is_valid_merkle_branch(
     depth: usize,
     index: usize,
     root: Node,
+) -> Result<(), Error> {
+    is_valid_merkle_branch_with::<Sha256>(leaf, branch, depth, index, root)
+}
+
+/// `is_valid_merkle_branch_with` verifies the Merkle proof against the `root` given the other
+/// metadata, where the Merkle tree was computed with the hash function `H`.
+pub fn is_valid_merkle_branch_with<H: Hasher>(
+    leaf: Node,
+    branch: &[Node],
+    depth: usize,
+    index: usize,
+    root: Node,
 ) -> Result<(), Error> {
     if branch.len() != depth {
         return Err(Error::InvalidProof)
     }
 
     let mut derived_root = leaf;
-    let mut hasher = Sha256::new();
+    let mut hasher = H::default();
 
     for (i, node) in branch.iter().enumerate() {
+        let current = derived_root;
         if (index / 2usize.pow(i as u32)) % 2 != 0 {
-            hasher.update(node);
-            hasher.update(derived_root);
+            hash_nodes(&mut hasher, node, current, derived_root.as_mut());
         } else {
-            hasher.update(derived_root);
-            hasher.update(node);
+            hash_nodes(&mut hasher, current, node, derived_root.as_mut());
         }
-        derived_root.copy_from_slice(&hasher.finalize_reset());
     }
 
     if derived_root == root {
@@ -234,7 +436,7 @@ pub fn is_valid_merkle_branch(
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
-    use crate::prelude::*;
+    use crate::{hasher::Keccak256, prelude::*};
     use alloy_primitives::hex::FromHex;
 
     pub(crate) fn decode_node_from_hex(hex: &str) -> Node {
@@ -248,6 +450,12 @@ pub(crate) mod tests {
         if let Err(err) = result {
             panic!("{err} for {proof:?} with witness {witness}")
         }
+
+        let (multiproof, multiproof_witness) = data.prove_multi(&[path]).unwrap();
+        assert_eq!(multiproof_witness, witness);
+        assert_eq!(multiproof.leaves, [proof.leaf]);
+        assert_eq!(multiproof.branch.len(), proof.branch.len());
+        assert!(multiproof.verify(witness).is_ok());
     }
 
     #[test]
@@ -295,6 +503,46 @@ pub(crate) mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_prove_multi() {
+        type Inner = List<u16, 40>;
+        let elements = (0..5u16)
+            .map(|i| Inner::try_from(vec![i; 10 * i as usize]).unwrap())
+            .collect::<Vec<_>>();
+        let data = List::<Inner, 7>::try_from(elements).unwrap();
+        let paths: &[Path] = &[
+            &[3.into(), 2.into()],
+            &[4.into(), 20.into()],
+            &[4.into(), PathElement::Length],
+            &[0.into()],
+            &[PathElement::Length],
+        ];
+        let (multiproof, witness) = data.prove_multi(paths).unwrap();
+        assert_eq!(witness, data.hash_tree_root().unwrap());
+        assert!(multiproof.verify(witness).is_ok());
+
+        for (i, path) in paths.iter().enumerate() {
+            let (proof, _) = data.prove(path).unwrap();
+            assert_eq!(multiproof.leaves[i], proof.leaf);
+            assert_eq!(multiproof.indices[i], proof.index);
+        }
+        assert_eq!(multiproof.branch.len(), get_helper_indices(&multiproof.indices).len());
+
+        let mut invalid = multiproof.clone();
+        invalid.branch[0] = Node::default();
+        assert!(invalid.verify(witness).is_err());
+        let mut invalid = multiproof;
+        invalid.leaves.swap(0, 1);
+        assert!(invalid.verify(witness).is_err());
+
+        let (multiproof, witness) = data.prove_multi_with::<Keccak256>(paths).unwrap();
+        assert_eq!(witness, data.hash_tree_root_with::<Keccak256>().unwrap());
+        assert!(multiproof.verify_with::<Keccak256>(witness).is_ok());
+        assert!(multiproof.verify(witness).is_err());
+
+        assert!(data.prove_multi(&[&[8.into()]]).is_err());
+    }
+
     #[test]
     fn test_proving_primitives_fails_with_bad_path() {
         let data = 8u8;
@@ -0,0 +1,452 @@
+use crate::{
+    de::{Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
+    error::{Error, InstanceError},
+    lib::*,
+    list::List,
+    merkleization::{
+        mix_in_length,
+        proofs::{Prove, Prover},
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, PersistentTree,
+    },
+    ser::{
+        homogeneous_encoded_len, serialize_homogeneous_into, serialize_reserved, Serialize,
+        SerializeError, Sink,
+    },
+    view::{ListView, Viewable},
+    Serializable, SimpleSerialize,
+};
+
+#[cfg(feature = "std")]
+use crate::de::Reader;
+
+/// A `List` backed by a persistent Merkle tree.
+///
+/// Clones share the nodes of the tree, so cloning is cheap and modifying an element of a clone
+/// only copies (and re-hashes) the path from that element to the root.
+///
+/// Elements that are basic types must pack evenly into chunks, e.g. a `PersistentList<[u8; 3], N>`
+/// can not be constructed.
+pub struct PersistentList<T: Serializable, const N: usize> {
+    tree: PersistentTree<T>,
+}
+
+impl<T, const N: usize> PersistentList<T, N>
+where
+    T: Serializable,
+{
+    /// Return the number of elements in the list.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Return `true` if the list has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return a reference to the element at `index`, if it exists.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.tree.get(index)
+    }
+
+    /// Return an iterator over the elements of the list.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.tree.iter()
+    }
+}
+
+impl<T, const N: usize> PersistentList<T, N>
+where
+    T: SimpleSerialize + Clone,
+{
+    /// Replace the element at `index` with `value`.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) -> Result<(), Error> {
+        assert!(index < self.len(), "index {index} out of bounds for length {}", self.len());
+        Ok(self.tree.set(index, value)?)
+    }
+
+    /// Append `value` to the end of the list.
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        if self.len() == N {
+            return Err(Error::Instance(InstanceError::Bounded { bound: N, provided: N + 1 }))
+        }
+        Ok(self.tree.push(value)?)
+    }
+
+    /// Remove the last element of the list, returning it if it exists.
+    pub fn pop(&mut self) -> Option<T> {
+        self.tree.pop()
+    }
+}
+
+impl<T, const N: usize> Clone for PersistentList<T, N>
+where
+    T: Serializable,
+{
+    fn clone(&self) -> Self {
+        Self { tree: self.tree.clone() }
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for PersistentList<T, N>
+where
+    T: Serializable + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(
+                f,
+                "PersistentList<{}, {}>(len={}){:#?}",
+                any::type_name::<T>(),
+                N,
+                self.len(),
+                self.tree
+            )
+        } else {
+            write!(
+                f,
+                "PersistentList<{}, {}>(len={}){:?}",
+                any::type_name::<T>(),
+                N,
+                self.len(),
+                self.tree
+            )
+        }
+    }
+}
+
+impl<T, const N: usize> Default for PersistentList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn default() -> Self {
+        let tree = PersistentTree::new(N)
+            .expect("any PersistentList over elements packing evenly into chunks can be empty");
+        Self { tree }
+    }
+}
+
+impl<T, const N: usize> PartialEq for PersistentList<T, N>
+where
+    T: Serializable + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T, const N: usize> Eq for PersistentList<T, N> where T: Serializable + Eq {}
+
+impl<T, const N: usize> TryFrom<Vec<T>> for PersistentList<T, N>
+where
+    T: SimpleSerialize,
+{
+    type Error = Error;
+
+    fn try_from(data: Vec<T>) -> Result<Self, Self::Error> {
+        let tree = PersistentTree::from_elements(data, N)?;
+        Ok(Self { tree })
+    }
+}
+
+impl<T, const N: usize> TryFrom<&[T]> for PersistentList<T, N>
+where
+    T: SimpleSerialize + Clone,
+{
+    type Error = Error;
+
+    fn try_from(data: &[T]) -> Result<Self, Self::Error> {
+        data.to_vec().try_into()
+    }
+}
+
+impl<T, const N: usize> Index<usize> for PersistentList<T, N>
+where
+    T: Serializable,
+{
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Some(element) => element,
+            None => panic!("index {index} out of bounds for length {}", self.len()),
+        }
+    }
+}
+
+impl<T, const N: usize> Serializable for PersistentList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn is_variable_size() -> bool {
+        true
+    }
+
+    fn size_hint() -> usize {
+        0
+    }
+
+    fn min_encoded_len() -> usize {
+        List::<T, N>::min_encoded_len()
+    }
+
+    fn max_encoded_len() -> usize {
+        List::<T, N>::max_encoded_len()
+    }
+}
+
+impl<T, const N: usize> Serialize for PersistentList<T, N>
+where
+    T: Serializable,
+{
+    fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+        serialize_reserved(self, buffer)
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        serialize_homogeneous_into(self.tree.iter(), sink)
+    }
+
+    fn encoded_len(&self) -> usize {
+        homogeneous_encoded_len(self.tree.iter())
+    }
+}
+
+impl<T, const N: usize> PersistentList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn from_deserialized(mut data: List<T, N>) -> Result<Self, DeserializeError> {
+        mem::take(&mut *data).try_into().map_err(|err| match err {
+            Error::Type(err) => DeserializeError::InvalidType(err),
+            Error::Instance(err) => DeserializeError::InvalidInstance(err),
+            // NOTE: merkleization of elements can only fail if they violate their bounds,
+            // which deserialization already checked
+            _ => unreachable!("no other error variant allowed here"),
+        })
+    }
+}
+
+impl<T, const N: usize> Deserialize for PersistentList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn deserialize(encoding: &[u8]) -> Result<Self, DeserializeError> {
+        List::deserialize(encoding).and_then(Self::from_deserialized)
+    }
+
+    #[cfg(feature = "std")]
+    fn deserialize_reader<R: std::io::Read>(
+        reader: &mut Reader<R>,
+        len: Option<usize>,
+    ) -> Result<Self, DeserializeError> {
+        List::deserialize_reader(reader, len).and_then(Self::from_deserialized)
+    }
+}
+
+impl<T, const N: usize> HashTreeRoot for PersistentList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        Ok(mix_in_length::<H>(self.tree.root_with::<H>()?, self.len()))
+    }
+}
+
+impl<T, const N: usize> GeneralizedIndexable for PersistentList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn chunk_count() -> usize {
+        <List<T, N> as GeneralizedIndexable>::chunk_count()
+    }
+
+    fn compute_generalized_index(
+        parent: GeneralizedIndex,
+        path: Path,
+    ) -> Result<GeneralizedIndex, MerkleizationError> {
+        List::<T, N>::compute_generalized_index(parent, path)
+    }
+}
+
+impl<T, const N: usize> Prove for PersistentList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        self.tree.chunks_with::<H>()
+    }
+
+    fn prove_element<H: Hasher>(
+        &self,
+        index: usize,
+        prover: &mut Prover<H>,
+    ) -> Result<(), MerkleizationError> {
+        let child = self.get(index).ok_or(MerkleizationError::InvalidInnerIndex)?;
+        prover.compute_proof(child)
+    }
+
+    fn decoration(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T, const N: usize> Viewable for PersistentList<T, N>
+where
+    T: SimpleSerialize,
+{
+    type View<'a> = ListView<'a, T, N>;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        ListView::new(encoding)
+    }
+}
+
+impl<T, const N: usize> SimpleSerialize for PersistentList<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<Node, Error> {
+        List::<T, N>::hash_tree_root_from_bytes(encoding)
+    }
+}
+
+impl<T, const N: usize> SszTypeInfo for PersistentList<T, N>
+where
+    T: Serializable + SszTypeInfo,
+{
+    fn type_info() -> SszType {
+        SszType::List(Box::new(T::type_info()), N)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const N: usize> serde::Serialize for PersistentList<T, N>
+where
+    T: Serializable + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const N: usize> serde::Deserialize<'de> for PersistentList<T, N>
+where
+    T: SimpleSerialize + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let data = <Vec<T> as serde::Deserialize>::deserialize(deserializer)?;
+        PersistentList::<T, N>::try_from(data).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{proofs::tests::compute_and_verify_proof_for_path, serialize, PathElement, U256};
+
+    #[test]
+    fn test_persistent_list_matches_list() {
+        let mut value = PersistentList::<u16, 100>::default();
+        let mut expected = List::<u16, 100>::default();
+        assert_eq!(value.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+
+        for i in 0..100 {
+            value.push(i).unwrap();
+            expected.push(i);
+            assert_eq!(value.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+        }
+        assert!(value.push(100).is_err());
+
+        let snapshot = value.clone();
+        value.set(47, 4747).unwrap();
+        expected[47] = 4747;
+        assert_eq!(value.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+        assert_eq!(serialize(&value).unwrap(), serialize(&expected).unwrap());
+        assert_eq!(snapshot[47], 47);
+        assert_ne!(snapshot, value);
+
+        for _ in 0..30 {
+            assert_eq!(value.pop(), expected.pop());
+        }
+        assert_eq!(value.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+    }
+
+    #[test]
+    fn test_persistent_list_of_composite_type() {
+        type Inner = List<U256, 3>;
+        let data =
+            (0..20).map(|i| Inner::try_from(vec![U256::from(i)]).unwrap()).collect::<Vec<_>>();
+        let mut value = PersistentList::<Inner, 32>::try_from(data.clone()).unwrap();
+        let mut expected = List::<Inner, 32>::try_from(data).unwrap();
+        assert_eq!(value.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+
+        let mut element = value[3].clone();
+        element.push(U256::from(3333));
+        value.set(3, element.clone()).unwrap();
+        expected[3] = element;
+        assert_eq!(value.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+
+        let paths: &[Path] = &[&[3.into(), 1.into()], &[19.into()], &[PathElement::Length]];
+        for &path in paths {
+            compute_and_verify_proof_for_path(&value, path);
+        }
+    }
+
+    #[test]
+    fn test_persistent_list_roundtrip() {
+        let value = PersistentList::<u64, 40>::try_from(vec![1u64, 2, 3, 4, 5]).unwrap();
+        let encoding = serialize(&value).unwrap();
+        let recovered = PersistentList::<u64, 40>::deserialize(&encoding).unwrap();
+        assert_eq!(value, recovered);
+
+        let result = PersistentList::<u64, 4>::deserialize(&encoding);
+        assert!(matches!(result, Err(DeserializeError::InvalidInstance(..))));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_persistent_list_serde() {
+        let value = PersistentList::<u8, 8>::try_from(vec![1u8, 2, 3]).unwrap();
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, "[1,2,3]");
+        let recovered: PersistentList<u8, 8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(value, recovered);
+        assert!(serde_json::from_str::<PersistentList<u8, 2>>(&json).is_err());
+    }
+
+    #[test]
+    fn test_persistent_list_in_container() {
+        use crate::prelude::*;
+
+        #[derive(Default, Debug, PartialEq, Eq, SimpleSerialize)]
+        struct Foo {
+            a: u8,
+            b: List<u32, 64>,
+        }
+
+        #[derive(Default, Debug, PartialEq, Eq, SimpleSerialize)]
+        struct Bar {
+            a: u8,
+            b: PersistentList<u32, 64>,
+        }
+
+        let data = vec![3u32, 2, 1];
+        let foo = Foo { a: 1, b: data.clone().try_into().unwrap() };
+        let bar = Bar { a: 1, b: data.try_into().unwrap() };
+        assert_eq!(foo.hash_tree_root().unwrap(), bar.hash_tree_root().unwrap());
+        assert_eq!(ssz_rs::serialize(&foo).unwrap(), ssz_rs::serialize(&bar).unwrap());
+        compute_and_verify_proof_for_path(&bar, &["b".into(), 2.into()]);
+    }
+}
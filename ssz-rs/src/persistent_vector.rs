@@ -0,0 +1,396 @@
+use crate::{
+    de::{Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
+    error::{Error, InstanceError, TypeError},
+    lib::*,
+    merkleization::{
+        proofs::{Prove, Prover},
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, PersistentTree,
+    },
+    ser::{
+        homogeneous_encoded_len, serialize_homogeneous_into, serialize_reserved, Serialize,
+        SerializeError, Sink,
+    },
+    vector::Vector,
+    view::{VectorView, Viewable},
+    Serializable, SimpleSerialize,
+};
+
+#[cfg(feature = "std")]
+use crate::de::Reader;
+
+/// A `Vector` backed by a persistent Merkle tree.
+///
+/// Clones share the nodes of the tree, so cloning is cheap and modifying an element of a clone
+/// only copies (and re-hashes) the path from that element to the root.
+///
+/// Elements that are basic types must pack evenly into chunks, e.g. a
+/// `PersistentVector<[u8; 3], N>` can not be constructed.
+///
+/// NOTE: a `PersistentVector` of length `0` is illegal.
+pub struct PersistentVector<T: Serializable, const N: usize> {
+    tree: PersistentTree<T>,
+}
+
+impl<T, const N: usize> PersistentVector<T, N>
+where
+    T: Serializable,
+{
+    /// Return the number of elements in the vector.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Return `true` if the vector has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return a reference to the element at `index`, if it exists.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.tree.get(index)
+    }
+
+    /// Return an iterator over the elements of the vector.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.tree.iter()
+    }
+}
+
+impl<T, const N: usize> PersistentVector<T, N>
+where
+    T: SimpleSerialize + Clone,
+{
+    /// Replace the element at `index` with `value`.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) -> Result<(), Error> {
+        assert!(index < self.len(), "index {index} out of bounds for length {}", self.len());
+        Ok(self.tree.set(index, value)?)
+    }
+}
+
+impl<T, const N: usize> Clone for PersistentVector<T, N>
+where
+    T: Serializable,
+{
+    fn clone(&self) -> Self {
+        Self { tree: self.tree.clone() }
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for PersistentVector<T, N>
+where
+    T: Serializable + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(f, "PersistentVector<{}, {}>{:#?}", any::type_name::<T>(), N, self.tree)
+        } else {
+            write!(f, "PersistentVector<{}, {}>{:?}", any::type_name::<T>(), N, self.tree)
+        }
+    }
+}
+
+impl<T, const N: usize> Default for PersistentVector<T, N>
+where
+    T: SimpleSerialize + Default,
+{
+    fn default() -> Self {
+        // SAFETY: there is currently no way to enforce statically
+        // that `N` is non-zero with const generics so panics are possible.
+        assert!(N > 0);
+
+        let mut data = Vec::with_capacity(N);
+        for _ in 0..N {
+            data.push(T::default());
+        }
+
+        data.try_into().expect(
+            "any PersistentVector over elements packing evenly into chunks can be constructed \
+             with nonzero default data",
+        )
+    }
+}
+
+impl<T, const N: usize> PartialEq for PersistentVector<T, N>
+where
+    T: Serializable + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T, const N: usize> Eq for PersistentVector<T, N> where T: Serializable + Eq {}
+
+impl<T, const N: usize> TryFrom<Vec<T>> for PersistentVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    type Error = Error;
+
+    fn try_from(data: Vec<T>) -> Result<Self, Self::Error> {
+        if N == 0 {
+            return Err(Error::Type(TypeError::InvalidBound(N)))
+        }
+        if data.len() != N {
+            return Err(Error::Instance(InstanceError::Exact { required: N, provided: data.len() }))
+        }
+        let tree = PersistentTree::from_elements(data, N)?;
+        Ok(Self { tree })
+    }
+}
+
+impl<T, const N: usize> TryFrom<&[T]> for PersistentVector<T, N>
+where
+    T: SimpleSerialize + Clone,
+{
+    type Error = Error;
+
+    fn try_from(data: &[T]) -> Result<Self, Self::Error> {
+        data.to_vec().try_into()
+    }
+}
+
+impl<T, const N: usize> Index<usize> for PersistentVector<T, N>
+where
+    T: Serializable,
+{
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Some(element) => element,
+            None => panic!("index {index} out of bounds for length {}", self.len()),
+        }
+    }
+}
+
+impl<T, const N: usize> Serializable for PersistentVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn is_variable_size() -> bool {
+        Vector::<T, N>::is_variable_size()
+    }
+
+    fn size_hint() -> usize {
+        Vector::<T, N>::size_hint()
+    }
+
+    fn min_encoded_len() -> usize {
+        Vector::<T, N>::min_encoded_len()
+    }
+
+    fn max_encoded_len() -> usize {
+        Vector::<T, N>::max_encoded_len()
+    }
+}
+
+impl<T, const N: usize> Serialize for PersistentVector<T, N>
+where
+    T: Serializable,
+{
+    fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+        if N == 0 {
+            return Err(TypeError::InvalidBound(N).into())
+        }
+        serialize_reserved(self, buffer)
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        if N == 0 {
+            return Err(TypeError::InvalidBound(N).into())
+        }
+        serialize_homogeneous_into(self.tree.iter(), sink)
+    }
+
+    fn encoded_len(&self) -> usize {
+        homogeneous_encoded_len(self.tree.iter())
+    }
+}
+
+impl<T, const N: usize> PersistentVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn from_deserialized(mut data: Vector<T, N>) -> Result<Self, DeserializeError> {
+        mem::take(&mut *data).try_into().map_err(|err| match err {
+            Error::Type(err) => DeserializeError::InvalidType(err),
+            Error::Instance(err) => DeserializeError::InvalidInstance(err),
+            // NOTE: merkleization of elements can only fail if they violate their bounds,
+            // which deserialization already checked
+            _ => unreachable!("no other error variant allowed here"),
+        })
+    }
+}
+
+impl<T, const N: usize> Deserialize for PersistentVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn deserialize(encoding: &[u8]) -> Result<Self, DeserializeError> {
+        Vector::deserialize(encoding).and_then(Self::from_deserialized)
+    }
+
+    #[cfg(feature = "std")]
+    fn deserialize_reader<R: std::io::Read>(
+        reader: &mut Reader<R>,
+        len: Option<usize>,
+    ) -> Result<Self, DeserializeError> {
+        Vector::deserialize_reader(reader, len).and_then(Self::from_deserialized)
+    }
+}
+
+impl<T, const N: usize> HashTreeRoot for PersistentVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        self.tree.root_with::<H>()
+    }
+}
+
+impl<T, const N: usize> GeneralizedIndexable for PersistentVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn chunk_count() -> usize {
+        <Vector<T, N> as GeneralizedIndexable>::chunk_count()
+    }
+
+    fn compute_generalized_index(
+        parent: GeneralizedIndex,
+        path: Path,
+    ) -> Result<GeneralizedIndex, MerkleizationError> {
+        Vector::<T, N>::compute_generalized_index(parent, path)
+    }
+}
+
+impl<T, const N: usize> Prove for PersistentVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        self.tree.chunks_with::<H>()
+    }
+
+    fn prove_element<H: Hasher>(
+        &self,
+        index: usize,
+        prover: &mut Prover<H>,
+    ) -> Result<(), MerkleizationError> {
+        let child = self.get(index).ok_or(MerkleizationError::InvalidInnerIndex)?;
+        prover.compute_proof(child)
+    }
+}
+
+impl<T, const N: usize> Viewable for PersistentVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    type View<'a> = VectorView<'a, T, N>;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        VectorView::new(encoding)
+    }
+}
+
+impl<T, const N: usize> SimpleSerialize for PersistentVector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<Node, Error> {
+        Vector::<T, N>::hash_tree_root_from_bytes(encoding)
+    }
+}
+
+impl<T, const N: usize> SszTypeInfo for PersistentVector<T, N>
+where
+    T: Serializable + SszTypeInfo,
+{
+    fn type_info() -> SszType {
+        SszType::Vector(Box::new(T::type_info()), N)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const N: usize> serde::Serialize for PersistentVector<T, N>
+where
+    T: Serializable + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const N: usize> serde::Deserialize<'de> for PersistentVector<T, N>
+where
+    T: SimpleSerialize + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let data = <Vec<T> as serde::Deserialize>::deserialize(deserializer)?;
+        PersistentVector::<T, N>::try_from(data).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{list::List, proofs::tests::compute_and_verify_proof_for_path, serialize};
+
+    #[test]
+    fn test_persistent_vector_matches_vector() {
+        let data = (0..37u64).collect::<Vec<_>>();
+        let mut value = PersistentVector::<u64, 37>::try_from(data.clone()).unwrap();
+        let mut expected = Vector::<u64, 37>::try_from(data).unwrap();
+        assert_eq!(value.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+
+        let snapshot = value.clone();
+        for i in [0, 5, 36] {
+            value.set(i, 1000 + i as u64).unwrap();
+            expected[i] = 1000 + i as u64;
+            assert_eq!(value.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+        }
+        assert_eq!(snapshot[5], 5);
+        assert_eq!(value[5], 1005);
+        assert_eq!(serialize(&value).unwrap(), serialize(&expected).unwrap());
+
+        let recovered = PersistentVector::<u64, 37>::deserialize(&serialize(&value).unwrap());
+        assert_eq!(recovered.unwrap(), value);
+    }
+
+    #[test]
+    fn test_persistent_vector_of_composite_type() {
+        let mut value = PersistentVector::<List<bool, 8>, 5>::default();
+        let mut expected = Vector::<List<bool, 8>, 5>::default();
+        assert_eq!(value.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+
+        let element = List::try_from(vec![true, false, true]).unwrap();
+        value.set(2, element.clone()).unwrap();
+        expected[2] = element;
+        assert_eq!(value.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+
+        let path = &[2.into(), 2.into()];
+        compute_and_verify_proof_for_path(&value, path);
+    }
+
+    #[test]
+    fn test_persistent_vector_try_from_invalid() {
+        let result = PersistentVector::<u8, 4>::try_from(vec![1u8, 2, 3]);
+        assert!(matches!(result, Err(Error::Instance(InstanceError::Exact { .. }))));
+        let result = PersistentVector::<u8, 0>::try_from(vec![]);
+        assert!(matches!(result, Err(Error::Type(TypeError::InvalidBound(0)))));
+        let result = PersistentVector::<[u8; 3], 2>::try_from(vec![[1u8; 3], [2u8; 3]]);
+        assert!(matches!(result, Err(Error::Type(TypeError::InvalidElementSize(3)))));
+    }
+}
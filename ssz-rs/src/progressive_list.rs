@@ -0,0 +1,458 @@
+use crate::{
+    de::{deserialize_homogeneous_composite, Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
+    error::Error,
+    lib::*,
+    merkleization::{
+        elements_to_chunks,
+        hasher::Sha256,
+        merkleize_progressive, merkleize_progressive_with, mix_in_length, pack,
+        progressive_chunk_generalized_index,
+        proofs::{Prove, Prover},
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, PathElement, BYTES_PER_CHUNK,
+    },
+    ser::{
+        homogeneous_encoded_len, serialize_homogeneous_into, serialize_reserved, Serialize,
+        SerializeError, Sink,
+    },
+    view::ListView,
+    Serializable, SimpleSerialize,
+};
+
+#[cfg(feature = "std")]
+use crate::de::{deserialize_homogeneous_composite_from_reader, Reader};
+#[cfg(feature = "rayon")]
+use crate::merkleization::{elements_to_chunks_in_parallel, ParallelHashTreeRoot};
+
+/// A homogenous collection of any number of values, following EIP-7916.
+///
+/// A `ProgressiveList` is serialized like a `List` but has no bound on its length. Its Merkle tree
+/// is built from successively larger subtrees as the list grows, so the generalized index of each
+/// element is stable no matter how many elements follow it.
+#[derive(PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct ProgressiveList<T: Serializable> {
+    data: Vec<T>,
+}
+
+impl<T: Serializable> AsRef<[T]> for ProgressiveList<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T> fmt::Debug for ProgressiveList<T>
+where
+    T: Serializable + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            write!(
+                f,
+                "ProgressiveList<{}>(len={}){:#?}",
+                any::type_name::<T>(),
+                self.len(),
+                self.data
+            )
+        } else {
+            write!(
+                f,
+                "ProgressiveList<{}>(len={}){:?}",
+                any::type_name::<T>(),
+                self.len(),
+                self.data
+            )
+        }
+    }
+}
+
+impl<T> Default for ProgressiveList<T>
+where
+    T: Serializable,
+{
+    fn default() -> Self {
+        Self { data: vec![] }
+    }
+}
+
+impl<T> PartialEq for ProgressiveList<T>
+where
+    T: Serializable + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<T> Eq for ProgressiveList<T> where T: Serializable + Eq {}
+
+impl<T> From<Vec<T>> for ProgressiveList<T>
+where
+    T: Serializable,
+{
+    fn from(data: Vec<T>) -> Self {
+        Self { data }
+    }
+}
+
+impl<T> From<&[T]> for ProgressiveList<T>
+where
+    T: Serializable + Clone,
+{
+    fn from(data: &[T]) -> Self {
+        Self { data: data.to_vec() }
+    }
+}
+
+impl<T> FromIterator<T> for ProgressiveList<T>
+where
+    T: Serializable,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self { data: iter.into_iter().collect() }
+    }
+}
+
+impl<T> Deref for ProgressiveList<T>
+where
+    T: Serializable,
+{
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<T> DerefMut for ProgressiveList<T>
+where
+    T: Serializable,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+impl<T, Idx: SliceIndex<[T]>> Index<Idx> for ProgressiveList<T>
+where
+    T: Serializable,
+{
+    type Output = <Idx as SliceIndex<[T]>>::Output;
+
+    fn index(&self, index: Idx) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl<T, Idx: SliceIndex<[T]>> IndexMut<Idx> for ProgressiveList<T>
+where
+    T: Serializable,
+{
+    fn index_mut(&mut self, index: Idx) -> &mut Self::Output {
+        &mut self.data[index]
+    }
+}
+
+impl<T> Serializable for ProgressiveList<T>
+where
+    T: Serializable,
+{
+    fn is_variable_size() -> bool {
+        true
+    }
+
+    fn size_hint() -> usize {
+        0
+    }
+
+    fn min_encoded_len() -> usize {
+        0
+    }
+
+    fn max_encoded_len() -> usize {
+        usize::MAX
+    }
+}
+
+impl<T> Serialize for ProgressiveList<T>
+where
+    T: Serializable,
+{
+    fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+        serialize_reserved(self, buffer)
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        serialize_homogeneous_into(self.data.iter(), sink)
+    }
+
+    fn encoded_len(&self) -> usize {
+        homogeneous_encoded_len(self.data.iter())
+    }
+}
+
+impl<T> Deserialize for ProgressiveList<T>
+where
+    T: Serializable,
+{
+    fn deserialize(encoding: &[u8]) -> Result<Self, DeserializeError> {
+        if !T::is_variable_size() {
+            let remainder = encoding.len() % T::size_hint();
+            if remainder != 0 {
+                return Err(DeserializeError::AdditionalInput {
+                    provided: encoding.len(),
+                    // SAFETY: checked subtraction is unnecessary, as encoding.len() > remainder;
+                    // qed
+                    expected: encoding.len() - remainder,
+                })
+            }
+        }
+
+        let data = deserialize_homogeneous_composite(encoding)?;
+        Ok(Self { data })
+    }
+
+    #[cfg(feature = "std")]
+    fn deserialize_reader<R: std::io::Read>(
+        reader: &mut Reader<R>,
+        len: Option<usize>,
+    ) -> Result<Self, DeserializeError> {
+        let data = deserialize_homogeneous_composite_from_reader(reader, len, usize::MAX)?;
+        Ok(Self { data })
+    }
+}
+
+impl<T> ProgressiveList<T>
+where
+    T: SimpleSerialize,
+{
+    fn assemble_chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        if T::is_composite_type() {
+            elements_to_chunks::<H, _>(&self.data)
+        } else {
+            pack(self)
+        }
+    }
+}
+
+impl<T> HashTreeRoot for ProgressiveList<T>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        let chunks = self.assemble_chunks::<H>()?;
+        let data_root = merkleize_progressive_with::<H>(&chunks)?;
+        Ok(mix_in_length::<H>(data_root, self.len()))
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T> ParallelHashTreeRoot for ProgressiveList<T>
+where
+    T: SimpleSerialize + Sync,
+{
+    fn par_hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        if !T::is_composite_type() {
+            return self.hash_tree_root_with::<H>()
+        }
+        let chunks = elements_to_chunks_in_parallel::<H, _>(&self.data)?;
+        let data_root = merkleize_progressive_with::<H>(&chunks)?;
+        Ok(mix_in_length::<H>(data_root, self.len()))
+    }
+}
+
+impl<T> GeneralizedIndexable for ProgressiveList<T>
+where
+    T: SimpleSerialize,
+{
+    fn compute_generalized_index(
+        parent: GeneralizedIndex,
+        path: Path,
+    ) -> Result<GeneralizedIndex, MerkleizationError> {
+        if let Some((next, rest)) = path.split_first() {
+            match next {
+                PathElement::Index(i) => {
+                    let chunk_position = i * T::item_length() / BYTES_PER_CHUNK;
+                    let chunk_index = progressive_chunk_generalized_index(chunk_position);
+                    let depth = chunk_index.ilog2();
+                    // SAFETY: checked subtraction is unnecessary, as `chunk_index >= 1 << depth`;
+                    // qed
+                    let child = ((parent * 2) << depth) + chunk_index - (1 << depth);
+                    T::compute_generalized_index(child, rest)
+                }
+                PathElement::Length => {
+                    if rest.is_empty() {
+                        Ok(parent * 2 + 1)
+                    } else {
+                        Err(MerkleizationError::InvalidPath(rest.to_vec()))
+                    }
+                }
+                elem => Err(MerkleizationError::InvalidPathElement(elem.clone())),
+            }
+        } else {
+            Ok(parent)
+        }
+    }
+}
+
+impl<T> Prove for ProgressiveList<T>
+where
+    T: SimpleSerialize,
+{
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        self.assemble_chunks::<H>()
+    }
+
+    fn prove_element<H: Hasher>(
+        &self,
+        index: usize,
+        prover: &mut Prover<H>,
+    ) -> Result<(), MerkleizationError> {
+        let child = self.get(index).ok_or(MerkleizationError::InvalidInnerIndex)?;
+        prover.compute_proof(child)
+    }
+
+    fn decoration(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn is_progressive(&self) -> bool {
+        true
+    }
+}
+
+impl<T> SimpleSerialize for ProgressiveList<T>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<Node, Error> {
+        // NOTE: a `ProgressiveList` is encoded like a `List` with no bound on its length
+        let view = ListView::<T, { usize::MAX }>::new(encoding)?;
+        let chunks = view.chunks()?;
+        let data_root = merkleize_progressive(&chunks)?;
+        Ok(mix_in_length::<Sha256>(data_root, view.len()))
+    }
+}
+
+impl<T> SszTypeInfo for ProgressiveList<T>
+where
+    T: Serializable + SszTypeInfo,
+{
+    fn type_info() -> SszType {
+        SszType::ProgressiveList(Box::new(T::type_info()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        merkleization::{hash_nodes, proofs::tests::decode_node_from_hex},
+        serialize, List, PathElement, U256,
+    };
+
+    #[test]
+    fn test_encoding_matches_list() {
+        let data = vec![1u16, 2, 3, 4, 5];
+        let value = ProgressiveList::<u16>::from(data.clone());
+        let list = List::<u16, 8>::try_from(data).unwrap();
+        let encoding = serialize(&value).unwrap();
+        assert_eq!(encoding, serialize(&list).unwrap());
+        assert_eq!(value.encoded_len(), encoding.len());
+        let recovered = ProgressiveList::<u16>::deserialize(&encoding).unwrap();
+        assert_eq!(value, recovered);
+
+        let inner = ProgressiveList::<u8>::from(vec![1u8, 2, 3]);
+        let value = ProgressiveList::from(vec![inner.clone(), Default::default(), inner]);
+        let encoding = serialize(&value).unwrap();
+        let recovered = ProgressiveList::<ProgressiveList<u8>>::deserialize(&encoding).unwrap();
+        assert_eq!(value, recovered);
+
+        // odd number of bytes for a list of `u16`
+        let result = ProgressiveList::<u16>::deserialize(&[1, 2, 3]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_hash_tree_root() {
+        let value = ProgressiveList::<u8>::default();
+        let expected = mix_in_length::<Sha256>(Node::default(), 0);
+        assert_eq!(value.hash_tree_root().unwrap(), expected);
+
+        // a single chunk hangs off the right of the root of the progressive tree
+        let value = ProgressiveList::<U256>::from(vec![U256::from(1)]);
+        let chunk = U256::from(1).hash_tree_root().unwrap();
+        let mut data_root = Node::default();
+        hash_nodes(&mut Sha256::default(), Node::default(), chunk, data_root.as_mut());
+        assert_eq!(value.hash_tree_root().unwrap(), mix_in_length::<Sha256>(data_root, 1));
+
+        let value = ProgressiveList::<u64>::from((0..100).collect::<Vec<_>>());
+        let expected = decode_node_from_hex(
+            "0x4eb1e3e82467d16862ffe3840f3f4cf7ad397bd196ef80de0d885bef27e2c0cd",
+        );
+        let root = value.hash_tree_root().unwrap();
+        assert_eq!(root, expected);
+
+        let encoding = serialize(&value).unwrap();
+        assert_eq!(ProgressiveList::<u64>::hash_tree_root_from_bytes(&encoding).unwrap(), root);
+    }
+
+    #[test]
+    fn test_generalized_index() {
+        type L = ProgressiveList<U256>;
+
+        assert_eq!(L::generalized_index(&[PathElement::Length]).unwrap(), 3);
+        // successive subtrees of 1, 4 and 16 chunks
+        assert_eq!(L::generalized_index(&[0.into()]).unwrap(), 5);
+        assert_eq!(L::generalized_index(&[1.into()]).unwrap(), 36);
+        assert_eq!(L::generalized_index(&[4.into()]).unwrap(), 39);
+        assert_eq!(L::generalized_index(&[5.into()]).unwrap(), 272);
+        assert_eq!(L::generalized_index(&[20.into()]).unwrap(), 287);
+        assert_eq!(L::generalized_index(&[21.into()]).unwrap(), 2112);
+
+        // basic types are packed into chunks
+        type M = ProgressiveList<u64>;
+        assert_eq!(M::generalized_index(&[3.into()]).unwrap(), 5);
+        assert_eq!(M::generalized_index(&[4.into()]).unwrap(), 36);
+    }
+
+    #[test]
+    fn test_prove_progressive_list() {
+        let data = ProgressiveList::<U256>::from((0..30).map(U256::from).collect::<Vec<_>>());
+        for i in [0, 1, 4, 5, 20, 21, 29] {
+            let path = &[i.into()];
+            crate::proofs::tests::compute_and_verify_proof_for_path(&data, path);
+        }
+        let path = &[PathElement::Length];
+        crate::proofs::tests::compute_and_verify_proof_for_path(&data, path);
+
+        let data = ProgressiveList::<u8>::from(vec![1u8, 2, 3]);
+        let path = &[2.into()];
+        crate::proofs::tests::compute_and_verify_proof_for_path(&data, path);
+
+        // no node exists for elements beyond the last subtree of the list
+        let path = &[200.into()];
+        assert!(data.prove(path).is_err());
+    }
+
+    #[test]
+    fn test_prove_nested_progressive_list() {
+        let inner = ProgressiveList::<u16>::from((0..40).collect::<Vec<_>>());
+        let data = ProgressiveList::from(vec![inner.clone(), Default::default(), inner]);
+        for path in [&[0.into(), 3.into()][..], &[2.into(), 39.into()], &[1.into()]] {
+            crate::proofs::tests::compute_and_verify_proof_for_path(&data, path);
+        }
+        let path = &[2.into(), PathElement::Length];
+        crate::proofs::tests::compute_and_verify_proof_for_path(&data, path);
+
+        // elements keep their generalized index as the list grows
+        let mut longer = data.clone();
+        longer.push(Default::default());
+        let path = &[2.into(), 39.into()];
+        let (proof, _) = data.prove(path).unwrap();
+        let (longer_proof, witness) = longer.prove(path).unwrap();
+        assert_eq!(proof.index, longer_proof.index);
+        assert!(longer_proof.verify(witness).is_ok());
+    }
+}
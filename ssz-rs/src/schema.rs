@@ -0,0 +1,872 @@
+//! Parse SSZ schemas written like the type definitions of the Python consensus specs into their
+//! runtime `SszType`s, e.g.:
+//!
+//! ```
+//! # use ssz_rs::prelude::*;
+//! let schema = Schema::parse(
+//!     r#"
+//! MAX_VALIDATORS_PER_COMMITTEE = 2**11
+//! Root = Bytes32
+//!
+//! class Checkpoint(Container):
+//!     epoch: uint64
+//!     root: Root
+//!
+//! class Votes(Container):
+//!     aggregation_bits: Bitlist[MAX_VALIDATORS_PER_COMMITTEE]
+//!     target: Checkpoint
+//! "#,
+//! )
+//! .unwrap();
+//! let ty = schema.get("Votes").unwrap();
+//! assert_eq!(ty.generalized_index(&["target".into(), "root".into()]).unwrap(), 7);
+//! ```
+//!
+//! Definitions may refer to types and constants defined anywhere in the schema. Besides
+//! `Container`s, classes may define `StableContainer[N]`s and `Profile[B]`s following EIP-7495,
+//! with `Optional[T]` fields, or alias another type, e.g. `class Root(Bytes32): pass`.
+use crate::{
+    dynamic::{ProfileField, SszType},
+    error::TypeError,
+    lib::*,
+};
+use core::str::FromStr;
+
+/// An error parsing a schema.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The schema is not well-formed at the given line.
+    Syntax { line: usize, message: String },
+    /// The definition at the given line refers to an undefined name.
+    Undefined { line: usize, name: String },
+    /// The definition at the given line is not meaningful, e.g. a type used as a bound.
+    Invalid { line: usize, message: String },
+    /// The type defined at the given line is not a legal SSZ type.
+    InvalidType { line: usize, error: TypeError },
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { line, message } => write!(f, "syntax error on line {line}: {message}"),
+            Self::Undefined { line, name } => write!(f, "undefined name `{name}` on line {line}"),
+            Self::Invalid { line, message } => {
+                write!(f, "invalid definition on line {line}: {message}")
+            }
+            Self::InvalidType { line, error } => write!(f, "invalid type on line {line}: {error}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SchemaError {}
+
+/// A set of named SSZ types and constants.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    // Each type in the order of its definition.
+    types: Vec<(String, SszType)>,
+    constants: HashMap<String, u128>,
+}
+
+impl Schema {
+    /// Parse a schema from its textual definition.
+    pub fn parse(input: &str) -> Result<Self, SchemaError> {
+        let definitions = parse_definitions(input)?;
+        let mut resolver = Resolver::new(&definitions);
+        let mut schema = Schema::default();
+        for definition in &definitions {
+            match resolver.resolve(&definition.name, definition.line)? {
+                Value::Type(ty) => schema.types.push((definition.name.clone(), ty)),
+                Value::Int(value) => {
+                    schema.constants.insert(definition.name.clone(), value);
+                }
+            }
+        }
+        Ok(schema)
+    }
+
+    /// Return the type named `name`.
+    pub fn get(&self, name: &str) -> Option<&SszType> {
+        self.types.iter().find(|(type_name, _)| type_name == name).map(|(_, ty)| ty)
+    }
+
+    /// Return the value of the constant named `name`.
+    pub fn constant(&self, name: &str) -> Option<u128> {
+        self.constants.get(name).copied()
+    }
+
+    /// Return each named type, in the order of its definition.
+    pub fn types(&self) -> impl Iterator<Item = (&str, &SszType)> {
+        self.types.iter().map(|(name, ty)| (name.as_str(), ty))
+    }
+
+    /// Define the type named `name`, e.g. `T::type_info()` of some type `T` defined in Rust,
+    /// replacing any existing type of the same name.
+    pub fn insert(&mut self, name: impl Into<String>, ty: SszType) {
+        let name = name.into();
+        match self.types.iter_mut().find(|(type_name, _)| *type_name == name) {
+            Some((_, existing)) => *existing = ty,
+            None => self.types.push((name, ty)),
+        }
+    }
+
+    /// Parse a type expression, e.g. `List[Checkpoint, 4]`, which may refer to the types and
+    /// constants of `self`.
+    pub fn parse_type(&self, input: &str) -> Result<SszType, SchemaError> {
+        let line = 1;
+        let expr = Parser::new(tokenize(input, line)?, line).parse_complete()?;
+        let definitions = self
+            .types
+            .iter()
+            .map(|(name, ty)| (name.clone(), Some(Value::Type(ty.clone()))))
+            .chain(
+                self.constants.iter().map(|(name, value)| (name.clone(), Some(Value::Int(*value)))),
+            )
+            .collect();
+        let mut resolver = Resolver { definitions: HashMap::new(), resolved: definitions };
+        resolver.eval_type(&expr, line)
+    }
+}
+
+impl FromStr for Schema {
+    type Err = SchemaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Int(u128),
+    Punct(&'static str),
+}
+
+fn tokenize(input: &str, line: usize) -> Result<Vec<Token>, SchemaError> {
+    const PUNCTUATION: [&str; 12] = ["**", "//", "[", "]", "(", ")", ",", ":", "=", "+", "-", "*"];
+
+    let mut tokens = vec![];
+    let mut rest = input.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if c.is_ascii_alphabetic() || c == '_' {
+            let end =
+                rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if c.is_ascii_digit() {
+            let end =
+                rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            let literal = rest[..end].replace('_', "");
+            let value = match literal.strip_prefix("0x") {
+                Some(hex) => u128::from_str_radix(hex, 16),
+                None => literal.parse(),
+            }
+            .map_err(|_| SchemaError::Syntax {
+                line,
+                message: format!("invalid integer `{}`", &rest[..end]),
+            })?;
+            tokens.push(Token::Int(value));
+            rest = &rest[end..];
+        } else if let Some(punct) = PUNCTUATION.iter().find(|punct| rest.starts_with(**punct)) {
+            tokens.push(Token::Punct(punct));
+            rest = &rest[punct.len()..];
+        } else {
+            return Err(SchemaError::Syntax { line, message: format!("unexpected character `{c}`") })
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Int(u128),
+    Name(String),
+    // A generic type, e.g. `List[uint64, 4]`.
+    Subscript(String, Vec<Expr>),
+    // A cast of an integer, e.g. `uint64(2**10)`.
+    Call(String, Box<Expr>),
+    BinaryOp(&'static str, Box<Expr>, Box<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>, line: usize) -> Self {
+        Self { tokens, position: 0, line }
+    }
+
+    fn error(&self, message: impl Into<String>) -> SchemaError {
+        SchemaError::Syntax { line: self.line, message: message.into() }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Punct(p)) if *p == punct) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), SchemaError> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{punct}`")))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, SchemaError> {
+        match self.next() {
+            Some(Token::Ident(ident)) => Ok(ident),
+            _ => Err(self.error("expected a name")),
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), SchemaError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error("unexpected input at the end of the line")),
+        }
+    }
+
+    // Parse all remaining tokens as a single expression.
+    fn parse_complete(mut self) -> Result<Expr, SchemaError> {
+        let expr = self.parse_expr()?;
+        self.expect_end()?;
+        Ok(expr)
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr, SchemaError> {
+        let mut lhs = self.parse_product()?;
+        loop {
+            let op = if self.eat("+") {
+                "+"
+            } else if self.eat("-") {
+                "-"
+            } else {
+                return Ok(lhs)
+            };
+            let rhs = self.parse_product()?;
+            lhs = Expr::BinaryOp(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Expr, SchemaError> {
+        let mut lhs = self.parse_power()?;
+        loop {
+            let op = if self.eat("*") {
+                "*"
+            } else if self.eat("//") {
+                "//"
+            } else {
+                return Ok(lhs)
+            };
+            let rhs = self.parse_power()?;
+            lhs = Expr::BinaryOp(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn parse_power(&mut self) -> Result<Expr, SchemaError> {
+        let base = self.parse_atom()?;
+        if self.eat("**") {
+            let exponent = self.parse_power()?;
+            return Ok(Expr::BinaryOp("**", Box::new(base), Box::new(exponent)))
+        }
+        Ok(base)
+    }
+
+    fn parse_atom(&mut self) -> Result<Expr, SchemaError> {
+        match self.next() {
+            Some(Token::Int(value)) => Ok(Expr::Int(value)),
+            Some(Token::Punct("(")) => {
+                let expr = self.parse_expr()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) => {
+                if self.eat("[") {
+                    let mut args = vec![self.parse_expr()?];
+                    while self.eat(",") {
+                        args.push(self.parse_expr()?);
+                    }
+                    self.expect("]")?;
+                    Ok(Expr::Subscript(name, args))
+                } else if self.eat("(") {
+                    let arg = self.parse_expr()?;
+                    self.expect(")")?;
+                    Ok(Expr::Call(name, Box::new(arg)))
+                } else {
+                    Ok(Expr::Name(name))
+                }
+            }
+            _ => Err(self.error("expected a type or an integer")),
+        }
+    }
+}
+
+// A named definition in a schema.
+struct Definition {
+    name: String,
+    line: usize,
+    body: Body,
+}
+
+enum Body {
+    // A constant or an alias of a type, e.g. `Epoch = uint64`.
+    Expr(Expr),
+    // A class with the given base, e.g. `Container`, and fields.
+    Class { base: Expr, fields: Vec<(String, Expr, usize)> },
+}
+
+fn parse_definitions(input: &str) -> Result<Vec<Definition>, SchemaError> {
+    let mut definitions: Vec<Definition> = vec![];
+    let mut in_docstring = false;
+    for (i, source) in input.lines().enumerate() {
+        let line = i + 1;
+        let trimmed = source.trim();
+        // NOTE: skip docstrings, which are only expected within classes
+        if in_docstring || trimmed.starts_with("\"\"\"") {
+            let quotes = trimmed.matches("\"\"\"").count();
+            if quotes % 2 == 1 {
+                in_docstring = !in_docstring;
+            }
+            continue
+        }
+        let content = match source.find('#') {
+            Some(comment) => &source[..comment],
+            None => source,
+        };
+        if content.trim().is_empty() {
+            continue
+        }
+
+        let mut parser = Parser::new(tokenize(content, line)?, line);
+        let is_indented = content.starts_with(char::is_whitespace);
+        if is_indented {
+            let Some(Definition { body: Body::Class { fields, .. }, .. }) = definitions.last_mut()
+            else {
+                return Err(SchemaError::Syntax { line, message: "unexpected indentation".into() })
+            };
+            if content.trim() == "pass" {
+                continue
+            }
+            let name = parser.expect_ident()?;
+            parser.expect(":")?;
+            fields.push((name, parser.parse_complete()?, line));
+            continue
+        }
+
+        match parser.next() {
+            Some(Token::Ident(keyword)) if keyword == "class" => {
+                let name = parser.expect_ident()?;
+                parser.expect("(")?;
+                let base = parser.parse_expr()?;
+                parser.expect(")")?;
+                parser.expect(":")?;
+                parser.expect_end()?;
+                definitions.push(Definition {
+                    name,
+                    line,
+                    body: Body::Class { base, fields: vec![] },
+                });
+            }
+            Some(Token::Ident(name)) => {
+                // NOTE: ignore any annotation, e.g. `MAX_ATTESTATIONS: uint64 = 128`
+                if parser.eat(":") {
+                    parser.parse_expr()?;
+                }
+                parser.expect("=")?;
+                let expr = parser.parse_complete()?;
+                definitions.push(Definition { name, line, body: Body::Expr(expr) });
+            }
+            _ => {
+                return Err(SchemaError::Syntax {
+                    line,
+                    message: "expected a class or a named definition".into(),
+                })
+            }
+        }
+    }
+
+    let mut names = HashSet::new();
+    for definition in &definitions {
+        if !names.insert(definition.name.as_str()) {
+            return Err(SchemaError::Invalid {
+                line: definition.line,
+                message: format!("`{}` is defined more than once", definition.name),
+            })
+        }
+    }
+    Ok(definitions)
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Int(u128),
+    Type(SszType),
+}
+
+// Resolves the definitions of a schema by name, so definitions can refer to each other in any
+// order.
+struct Resolver<'a> {
+    definitions: HashMap<&'a str, &'a Definition>,
+    // NOTE: `None` marks a definition being resolved, to detect cycles
+    resolved: HashMap<String, Option<Value>>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(definitions: &'a [Definition]) -> Self {
+        let definitions =
+            definitions.iter().map(|definition| (definition.name.as_str(), definition)).collect();
+        Self { definitions, resolved: HashMap::new() }
+    }
+
+    fn resolve(&mut self, name: &str, line: usize) -> Result<Value, SchemaError> {
+        match self.resolved.get(name) {
+            Some(Some(value)) => return Ok(value.clone()),
+            Some(None) => {
+                return Err(SchemaError::Invalid {
+                    line,
+                    message: format!("`{name}` is defined in terms of itself"),
+                })
+            }
+            None => {}
+        }
+        let Some(definition) = self.definitions.get(name).copied() else {
+            return Err(SchemaError::Undefined { line, name: name.to_string() })
+        };
+        self.resolved.insert(name.to_string(), None);
+        let line = definition.line;
+        let value = match &definition.body {
+            Body::Expr(expr) => self.eval(expr, line)?,
+            Body::Class { base, fields } => Value::Type(self.eval_class(base, fields, line)?),
+        };
+        if let Value::Type(ty) = &value {
+            ty.validate().map_err(|error| SchemaError::InvalidType { line, error })?;
+        }
+        self.resolved.insert(name.to_string(), Some(value.clone()));
+        Ok(value)
+    }
+
+    fn eval(&mut self, expr: &Expr, line: usize) -> Result<Value, SchemaError> {
+        let invalid = |message: String| SchemaError::Invalid { line, message };
+        match expr {
+            Expr::Int(value) => Ok(Value::Int(*value)),
+            Expr::BinaryOp(op, lhs, rhs) => {
+                let lhs = self.eval_int(lhs, line)?;
+                let rhs = self.eval_int(rhs, line)?;
+                let value = match *op {
+                    "+" => lhs.checked_add(rhs),
+                    "-" => lhs.checked_sub(rhs),
+                    "*" => lhs.checked_mul(rhs),
+                    "//" => lhs.checked_div(rhs),
+                    "**" => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_pow(rhs)),
+                    _ => unreachable!("parsed a known operator"),
+                };
+                let value =
+                    value.ok_or_else(|| invalid(format!("`{lhs} {op} {rhs}` overflows")))?;
+                Ok(Value::Int(value))
+            }
+            Expr::Call(name, arg) => {
+                let Some(SszType::Uint(bits)) = builtin_type(name) else {
+                    return Err(invalid(format!("`{name}` is not an integer type")))
+                };
+                let value = self.eval_int(arg, line)?;
+                if bits < 128 && value >> bits != 0 {
+                    return Err(invalid(format!("{value} does not fit in `{name}`")))
+                }
+                Ok(Value::Int(value))
+            }
+            Expr::Name(name) => match builtin_type(name) {
+                Some(ty) => Ok(Value::Type(ty)),
+                None => self.resolve(name, line),
+            },
+            Expr::Subscript(name, args) => {
+                let expect_args = |count: usize| {
+                    if args.len() == count {
+                        Ok(())
+                    } else {
+                        Err(invalid(format!("`{name}` expects {count} argument(s)")))
+                    }
+                };
+                let ty =
+                    match name.as_str() {
+                        "Vector" | "List" => {
+                            expect_args(2)?;
+                            let element_type = Box::new(self.eval_type(&args[0], line)?);
+                            let bound = self.eval_bound(&args[1], line)?;
+                            if name == "Vector" {
+                                SszType::Vector(element_type, bound)
+                            } else {
+                                SszType::List(element_type, bound)
+                            }
+                        }
+                        "ByteVector" | "ByteList" => {
+                            expect_args(1)?;
+                            let element_type = Box::new(SszType::Uint(8));
+                            let bound = self.eval_bound(&args[0], line)?;
+                            if name == "ByteVector" {
+                                SszType::Vector(element_type, bound)
+                            } else {
+                                SszType::List(element_type, bound)
+                            }
+                        }
+                        "Bitvector" => {
+                            expect_args(1)?;
+                            SszType::Bitvector(self.eval_bound(&args[0], line)?)
+                        }
+                        "Bitlist" => {
+                            expect_args(1)?;
+                            SszType::Bitlist(self.eval_bound(&args[0], line)?)
+                        }
+                        "ProgressiveList" => {
+                            expect_args(1)?;
+                            SszType::ProgressiveList(Box::new(self.eval_type(&args[0], line)?))
+                        }
+                        "Union" => {
+                            let variants = args
+                                .iter()
+                                .enumerate()
+                                .map(|(i, arg)| {
+                                    let variant_type = match arg {
+                                        Expr::Name(name) if name == "None" => None,
+                                        arg => Some(self.eval_type(arg, line)?),
+                                    };
+                                    let selector = u8::try_from(i)
+                                        .map_err(|_| invalid("too many variants".into()))?;
+                                    Ok((selector, variant_type))
+                                })
+                                .collect::<Result<_, SchemaError>>()?;
+                            SszType::Union(variants)
+                        }
+                        "Optional" => return Err(invalid(
+                            "`Optional` is only supported for the fields of stable containers and \
+                             profiles"
+                                .into(),
+                        )),
+                        _ => return Err(SchemaError::Undefined { line, name: name.clone() }),
+                    };
+                Ok(Value::Type(ty))
+            }
+        }
+    }
+
+    fn eval_int(&mut self, expr: &Expr, line: usize) -> Result<u128, SchemaError> {
+        match self.eval(expr, line)? {
+            Value::Int(value) => Ok(value),
+            Value::Type(_) => Err(SchemaError::Invalid {
+                line,
+                message: "expected an integer, not a type".into(),
+            }),
+        }
+    }
+
+    fn eval_bound(&mut self, expr: &Expr, line: usize) -> Result<usize, SchemaError> {
+        let value = self.eval_int(expr, line)?;
+        usize::try_from(value).map_err(|_| SchemaError::Invalid {
+            line,
+            message: format!("the bound {value} is too large"),
+        })
+    }
+
+    fn eval_type(&mut self, expr: &Expr, line: usize) -> Result<SszType, SchemaError> {
+        match self.eval(expr, line)? {
+            Value::Type(ty) => Ok(ty),
+            Value::Int(_) => Err(SchemaError::Invalid {
+                line,
+                message: "expected a type, not an integer".into(),
+            }),
+        }
+    }
+
+    // Evaluate the type of a field, which may be `Optional` in a stable container or profile.
+    fn eval_field(&mut self, expr: &Expr, line: usize) -> Result<(SszType, bool), SchemaError> {
+        match expr {
+            Expr::Subscript(name, args) if name == "Optional" && args.len() == 1 => {
+                Ok((self.eval_type(&args[0], line)?, true))
+            }
+            expr => Ok((self.eval_type(expr, line)?, false)),
+        }
+    }
+
+    fn eval_class(
+        &mut self,
+        base: &Expr,
+        fields: &[(String, Expr, usize)],
+        line: usize,
+    ) -> Result<SszType, SchemaError> {
+        let invalid =
+            |line: usize, message: &str| SchemaError::Invalid { line, message: message.into() };
+        match base {
+            Expr::Name(name) if name == "Container" => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, expr, line)| Ok((name.clone(), self.eval_type(expr, *line)?)))
+                    .collect::<Result<_, SchemaError>>()?;
+                Ok(SszType::Container(fields))
+            }
+            Expr::Subscript(name, args) if name == "StableContainer" && args.len() == 1 => {
+                let max_fields = self.eval_bound(&args[0], line)?;
+                let fields = fields
+                    .iter()
+                    .map(|(name, expr, line)| match self.eval_field(expr, *line)? {
+                        (ty, true) => Ok((name.clone(), ty)),
+                        (_, false) => Err(invalid(
+                            *line,
+                            "every field of a stable container must be `Optional`",
+                        )),
+                    })
+                    .collect::<Result<_, SchemaError>>()?;
+                Ok(SszType::StableContainer(max_fields, fields))
+            }
+            Expr::Subscript(name, args) if name == "Profile" && args.len() == 1 => {
+                let SszType::StableContainer(max_fields, base_fields) =
+                    self.eval_type(&args[0], line)?
+                else {
+                    return Err(invalid(line, "the base of a profile must be a stable container"))
+                };
+                let fields = fields
+                    .iter()
+                    .map(|(name, expr, line)| {
+                        let (ty, optional) = self.eval_field(expr, *line)?;
+                        let index = base_fields
+                            .iter()
+                            .position(|(base_name, _)| base_name == name)
+                            .ok_or_else(|| {
+                                invalid(
+                                    *line,
+                                    "every field of a profile must be a field of its base",
+                                )
+                            })?;
+                        Ok(ProfileField { name: name.clone(), ty, index, optional })
+                    })
+                    .collect::<Result<_, SchemaError>>()?;
+                Ok(SszType::Profile(max_fields, fields))
+            }
+            // NOTE: a class without fields aliases its base, e.g. `class Root(Bytes32): pass`
+            base if fields.is_empty() => self.eval_type(base, line),
+            _ => Err(invalid(
+                line,
+                "classes with fields must be containers, stable containers or profiles",
+            )),
+        }
+    }
+}
+
+// Return the type named `name` by the SSZ spec, if any.
+fn builtin_type(name: &str) -> Option<SszType> {
+    match name {
+        "boolean" | "bool" => Some(SszType::Boolean),
+        "byte" => Some(SszType::Uint(8)),
+        _ => {
+            if let Some(bits) = name.strip_prefix("uint") {
+                let bits = bits.parse().ok()?;
+                return matches!(bits, 8 | 16 | 32 | 64 | 128 | 256).then_some(SszType::Uint(bits))
+            }
+            // NOTE: `BytesN` aliases `ByteVector[N]`
+            let len = name.strip_prefix("Bytes")?.parse().ok()?;
+            Some(SszType::Vector(Box::new(SszType::Uint(8)), len))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    const PHASE0: &str = r#"
+# Constants
+MAX_VALIDATORS_PER_COMMITTEE = 2**11  # 2048
+SLOTS_PER_HISTORICAL_ROOT = uint64(2**13)
+JUSTIFICATION_BITS_LENGTH = 4
+
+# Custom types
+Slot = uint64
+Epoch = uint64
+class CommitteeIndex(uint64):
+    pass
+Root = Bytes32
+BLSSignature = Bytes96
+
+class Checkpoint(Container):
+    epoch: Epoch
+    root: Root
+
+class AttestationData(Container):
+    """
+    The data attested to.
+    """
+    slot: Slot
+    index: CommitteeIndex
+    # LMD GHOST vote
+    beacon_block_root: Root
+    # FFG vote
+    source: Checkpoint
+    target: Checkpoint
+
+class Attestation(Container):
+    aggregation_bits: Bitlist[MAX_VALIDATORS_PER_COMMITTEE]
+    data: AttestationData
+    signature: BLSSignature
+
+class HistoricalBatch(Container):
+    block_roots: Vector[Root, SLOTS_PER_HISTORICAL_ROOT]
+    state_roots: Vector[Root, SLOTS_PER_HISTORICAL_ROOT]
+
+class State(Container):
+    justification_bits: Bitvector[JUSTIFICATION_BITS_LENGTH]
+    attestations: List[Attestation, MAX_VALIDATORS_PER_COMMITTEE * 2]
+    extra: Union[None, uint16, ByteList[32]]
+    history: ProgressiveList[Root]
+"#;
+
+    #[derive(Debug, Default, SimpleSerialize)]
+    struct Checkpoint {
+        epoch: u64,
+        root: Node,
+    }
+
+    #[derive(Debug, Default, SimpleSerialize)]
+    struct AttestationData {
+        slot: u64,
+        index: u64,
+        beacon_block_root: Node,
+        source: Checkpoint,
+        target: Checkpoint,
+    }
+
+    #[derive(Debug, Default, SimpleSerialize)]
+    struct Attestation {
+        aggregation_bits: Bitlist<2048>,
+        data: AttestationData,
+        signature: Vector<u8, 96>,
+    }
+
+    #[derive(Debug, SimpleSerialize)]
+    enum Extra {
+        None,
+        A(u16),
+        B(List<u8, 32>),
+    }
+
+    #[derive(Debug, SimpleSerialize)]
+    struct State {
+        justification_bits: Bitvector<4>,
+        attestations: List<Attestation, 4096>,
+        extra: Extra,
+        history: ProgressiveList<Node>,
+    }
+
+    #[derive(Debug, Default, SimpleSerialize)]
+    #[ssz(stable_container(max_fields = 4))]
+    struct Shape {
+        side: Option<u16>,
+        color: Option<u8>,
+        radius: Option<u16>,
+    }
+
+    #[derive(Debug, SimpleSerialize)]
+    #[ssz(profile(Shape))]
+    struct Circle {
+        color: Option<u8>,
+        radius: u16,
+    }
+
+    #[test]
+    fn test_parse_phase0_types() {
+        let schema = Schema::parse(PHASE0).unwrap();
+        assert_eq!(schema.get("Checkpoint").unwrap(), &Checkpoint::type_info());
+        assert_eq!(schema.get("AttestationData").unwrap(), &AttestationData::type_info());
+        assert_eq!(schema.get("Attestation").unwrap(), &Attestation::type_info());
+        assert_eq!(schema.get("State").unwrap(), &State::type_info());
+        assert_eq!(schema.get("CommitteeIndex").unwrap(), &SszType::Uint(64));
+        assert_eq!(
+            schema.get("HistoricalBatch").unwrap(),
+            &SszType::Container(vec![
+                ("block_roots".into(), <Vector<Node, 8192>>::type_info()),
+                ("state_roots".into(), <Vector<Node, 8192>>::type_info()),
+            ])
+        );
+        assert_eq!(schema.constant("SLOTS_PER_HISTORICAL_ROOT"), Some(8192));
+        assert!(schema.get("MAX_VALIDATORS_PER_COMMITTEE").is_none());
+
+        let names = schema.types().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(names[..3], ["Slot", "Epoch", "CommitteeIndex"]);
+
+        let ty = schema.parse_type("List[Checkpoint, MAX_VALIDATORS_PER_COMMITTEE]").unwrap();
+        assert_eq!(ty, <List<Checkpoint, 2048>>::type_info());
+
+        let value = Attestation::default();
+        let encoding = serialize(&value).unwrap();
+        let dynamic_value = schema.get("Attestation").unwrap().deserialize(&encoding).unwrap();
+        assert_eq!(dynamic_value.hash_tree_root().unwrap(), value.hash_tree_root().unwrap());
+    }
+
+    #[test]
+    fn test_parse_stable_containers() {
+        let schema: Schema = r#"
+class Circle(Profile[Shape]):
+    color: Optional[uint8]
+    radius: uint16
+
+class Shape(StableContainer[4]):
+    side: Optional[uint16]
+    color: Optional[uint8]
+    radius: Optional[uint16]
+"#
+        .parse()
+        .unwrap();
+        assert_eq!(schema.get("Shape").unwrap(), &Shape::type_info());
+        assert_eq!(schema.get("Circle").unwrap(), &Circle::type_info());
+
+        let mut schema = schema;
+        schema.insert("Circles", <List<Circle, 4>>::type_info());
+        let ty = schema.parse_type("List[Circle, 4]").unwrap();
+        assert_eq!(schema.parse_type("Circles").unwrap(), ty);
+    }
+
+    #[test]
+    fn test_invalid_schemas() {
+        let cases = [
+            "class A(Container):\n    a: B",
+            "A = B\nB = List[A, 2]",
+            "class A(Container):\n    a: uint7",
+            "class A(Container):\n    a: Vector[uint8, 0]",
+            "class A(Container):\n    a: List[uint8, uint8]",
+            "class A(Container):\n    a: List[uint8]",
+            "class A(Container):\n    a: Optional[uint8]",
+            "class A(StableContainer[2]):\n    a: uint8",
+            "class A(Container)\n    a: uint8",
+            "    a: uint8",
+            "A = 2**200",
+            "A = uint8(256)",
+            "A = 1\nA = 2",
+            "A = $",
+        ];
+        for case in cases {
+            assert!(Schema::parse(case).is_err(), "{case}");
+        }
+
+        let err = Schema::parse("\nclass A(Container):\n    a: B").unwrap_err();
+        assert!(matches!(err, SchemaError::Undefined { line: 3, name } if name == "B"));
+    }
+}
@@ -17,6 +17,11 @@ pub enum SerializeError {
     InvalidInstance(InstanceError),
     /// An invalid type was encountered.
     InvalidType(TypeError),
+    /// The sink could not accept the encoding.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    /// The bytes written differ from the encoded length, e.g. of an incorrect `encoded_len`.
+    EncodedLenMismatch { expected: usize, written: usize },
 }
 
 impl From<InstanceError> for SerializeError {
@@ -40,6 +45,12 @@ impl Display for SerializeError {
             ),
             SerializeError::InvalidInstance(err) => write!(f, "invalid instance: {err}"),
             SerializeError::InvalidType(err) => write!(f, "invalid type: {err}"),
+            #[cfg(feature = "std")]
+            SerializeError::Io(err) => write!(f, "could not write to sink: {err}"),
+            SerializeError::EncodedLenMismatch { expected, written } => write!(
+                f,
+                "wrote {written} bytes for an encoded length of {expected} bytes",
+            ),
         }
     }
 }
@@ -47,72 +58,465 @@ impl Display for SerializeError {
 #[cfg(feature = "std")]
 impl std::error::Error for SerializeError {}
 
+/// A destination for the bytes of an encoding, e.g. any `std::io::Write`.
+pub trait Sink {
+    /// Write all of `bytes` to this sink.
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), SerializeError>;
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write + ?Sized> Sink for W {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), SerializeError> {
+        self.write_all(bytes).map_err(SerializeError::Io)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Sink for Vec<u8> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), SerializeError> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
 /// A data structure that can be serialized using SSZ.
 pub trait Serialize {
     /// Append an encoding of `self` to the `buffer`.
     ///
     /// Returns the number of bytes written.
     fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError>;
+
+    /// Write an encoding of `self` to the `sink`.
+    ///
+    /// Returns the number of bytes written.
+    ///
+    /// The default implementation buffers the entire encoding in memory before writing it. The
+    /// types of this crate holding other values, including `DynamicValue`, and the types deriving
+    /// `SimpleSerialize` override it to write each part as it is produced; other types holding
+    /// other values should override it as well.
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        let mut buffer = Vec::new();
+        let written = self.serialize(&mut buffer)?;
+        sink.write_bytes(&buffer)?;
+        Ok(written)
+    }
+
+    /// Return the number of bytes in the encoding of `self`.
+    ///
+    /// The default implementation serializes `self` into a scratch buffer, so an implementation
+    /// of `serialize` using `encoded_len` must override it. The types of this crate and the types
+    /// deriving `SimpleSerialize` compute it without serializing.
+    fn encoded_len(&self) -> usize {
+        let mut buffer = Vec::new();
+        // NOTE: any error is returned again when serializing `self`
+        let _ = self.serialize(&mut buffer);
+        buffer.len()
+    }
 }
 
-// Part represents either a fixed sized part of the serialization
-// or an offset pointing to a variably sized part of the serialization
-pub enum Part {
-    Fixed(Vec<u8>),
-    Offset(usize),
+// Number of bytes a value of type `T` occupies in the fixed part of an encoding containing it.
+pub fn fixed_part_len<T: Serializable>() -> usize {
+    if T::is_variable_size() {
+        BYTES_PER_LENGTH_OFFSET
+    } else {
+        T::size_hint()
+    }
 }
 
-#[derive(Default)]
-pub struct Serializer {
-    parts: Vec<Part>,
-    variable: Vec<u8>,
-    fixed_lengths_sum: usize,
-    variable_lengths_sum: usize,
+// Minimum number of bytes a value of type `T` occupies in an encoding containing it, including
+// any offset.
+pub fn min_part_len<T: Serializable>() -> usize {
+    if T::is_variable_size() {
+        T::min_encoded_len().saturating_add(BYTES_PER_LENGTH_OFFSET)
+    } else {
+        T::size_hint()
+    }
 }
 
-impl Serializer {
-    pub fn serialize(mut self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
-        let total_size = self.fixed_lengths_sum + self.variable_lengths_sum;
-        if total_size as u64 >= MAXIMUM_LENGTH {
-            return Err(SerializeError::MaximumEncodedLengthReached(total_size))
-        }
+// Maximum number of bytes a value of type `T` occupies in an encoding containing it, including
+// any offset.
+pub fn max_part_len<T: Serializable>() -> usize {
+    if T::is_variable_size() {
+        T::max_encoded_len().saturating_add(BYTES_PER_LENGTH_OFFSET)
+    } else {
+        T::size_hint()
+    }
+}
 
-        // SAFETY: `fixed_lengths_sum` fits in `u32` if the total size check holds
-        let mut running_length = self.fixed_lengths_sum as u32;
-        for part in self.parts {
-            match part {
-                Part::Fixed(mut data) => {
-                    buffer.append(&mut data);
-                }
-                Part::Offset(offset) => {
-                    let bytes_written = running_length.serialize(buffer)?;
-                    debug_assert_eq!(bytes_written, BYTES_PER_LENGTH_OFFSET);
+// Number of bytes `element` occupies in an encoding containing it, including any offset.
+pub fn element_encoded_len<T: Serializable>(element: &T) -> usize {
+    if T::is_variable_size() {
+        BYTES_PER_LENGTH_OFFSET + element.encoded_len()
+    } else {
+        T::size_hint()
+    }
+}
 
-                    // SAFETY: `offset` fits in `u32` if the total size check holds
-                    running_length += offset as u32;
-                }
-            }
-        }
+// Number of bytes a value of type `T` with an encoding of `encoded_len` bytes occupies in an
+// encoding containing it, including any offset.
+pub fn part_len<T: Serializable>(encoded_len: usize) -> usize {
+    if T::is_variable_size() {
+        BYTES_PER_LENGTH_OFFSET + encoded_len
+    } else {
+        encoded_len
+    }
+}
+
+// Check that an encoding of `len` bytes can be addressed by offsets.
+pub(crate) fn check_encoded_len(len: usize) -> Result<(), SerializeError> {
+    if len as u64 >= MAXIMUM_LENGTH {
+        return Err(SerializeError::MaximumEncodedLengthReached(len))
+    }
+    Ok(())
+}
+
+// Append the encoding of `value` to `buffer`, growing `buffer` at most once by reserving the
+// encoded length of `value` before writing it in place.
+pub fn serialize_reserved<T: Serialize + ?Sized>(
+    value: &T,
+    buffer: &mut Vec<u8>,
+) -> Result<usize, SerializeError> {
+    buffer.reserve(value.encoded_len());
+    value.serialize_into(buffer)
+}
 
-        buffer.append(&mut self.variable);
+// Number of bytes in the encoding of a homogeneous collection of `elements`.
+pub(crate) fn homogeneous_encoded_len<'a, T: Serializable + 'a>(
+    elements: impl Iterator<Item = &'a T>,
+) -> usize {
+    if T::is_variable_size() {
+        elements.map(element_encoded_len).sum()
+    } else {
+        elements.count() * T::size_hint()
+    }
+}
+
+// Write the encoding of a homogeneous collection of `elements` to `sink`.
+pub(crate) fn serialize_homogeneous_into<'a, T, S>(
+    elements: impl Iterator<Item = &'a T> + Clone,
+    sink: &mut S,
+) -> Result<usize, SerializeError>
+where
+    T: Serializable + 'a,
+    S: Sink + ?Sized,
+{
+    if !T::is_variable_size() {
+        let len = T::size_hint();
+        let fixed_len = elements.clone().count() * len;
+        let mut serializer = StreamSerializer::new(sink, fixed_len, fixed_len)?;
+        for element in elements {
+            serializer.write_fixed_with_len(element, len)?;
+        }
+        return serializer.finish()
+    }
 
-        Ok(total_size)
+    // NOTE: computing the length of an element may visit every value nested in it, so the length of
+    // each element is only computed once
+    let lens = elements.clone().map(Serialize::encoded_len).collect::<Vec<_>>();
+    let fixed_len = lens.len() * BYTES_PER_LENGTH_OFFSET;
+    let total_len = fixed_len + lens.iter().sum::<usize>();
+    let mut serializer = StreamSerializer::new(sink, fixed_len, total_len)?;
+    for (element, &len) in elements.clone().zip(&lens) {
+        serializer.write_fixed_with_len(element, len)?;
+    }
+    for element in elements {
+        serializer.write_variable(element)?;
     }
+    serializer.finish()
+}
+
+/// Writes an encoding to a `Sink` without buffering it, computing offsets from the encoded length
+/// of each variable-size element.
+///
+/// Every element is first passed (in order) to `write_fixed_with_len` and then again to
+/// `write_variable`.
+pub struct StreamSerializer<'a, S: Sink + ?Sized> {
+    sink: &'a mut S,
+    next_offset: usize,
+    total_len: usize,
+    written: usize,
+}
 
-    pub fn with_element<T: Serializable>(&mut self, element: &T) -> Result<(), SerializeError> {
-        let mut element_buffer = Vec::with_capacity(T::size_hint());
-        element.serialize(&mut element_buffer)?;
+impl<'a, S: Sink + ?Sized> StreamSerializer<'a, S> {
+    /// Prepare to write an encoding with a fixed part of `fixed_len` bytes and `total_len` bytes
+    /// overall.
+    pub fn new(
+        sink: &'a mut S,
+        fixed_len: usize,
+        total_len: usize,
+    ) -> Result<Self, SerializeError> {
+        check_encoded_len(total_len)?;
+        Ok(Self { sink, next_offset: fixed_len, total_len, written: 0 })
+    }
 
-        let element_buffer_len = element_buffer.len();
+    /// Write the fixed part of `element`, i.e. the element itself or an offset to it, where
+    /// `encoded_len` is the length of the encoding of `element`.
+    pub fn write_fixed_with_len<T: Serializable>(
+        &mut self,
+        element: &T,
+        encoded_len: usize,
+    ) -> Result<(), SerializeError> {
         if T::is_variable_size() {
-            self.parts.push(Part::Offset(element_buffer_len));
-            self.variable.append(&mut element_buffer);
-            self.fixed_lengths_sum += BYTES_PER_LENGTH_OFFSET;
-            self.variable_lengths_sum += element_buffer_len;
+            // SAFETY: `next_offset` fits in `u32` if the total size check holds
+            let offset = self.next_offset as u32;
+            self.sink.write_bytes(&offset.to_le_bytes())?;
+            self.written += BYTES_PER_LENGTH_OFFSET;
+            self.next_offset += encoded_len;
         } else {
-            self.parts.push(Part::Fixed(element_buffer));
-            self.fixed_lengths_sum += element_buffer_len;
+            self.written += element.serialize_into(self.sink)?;
         }
         Ok(())
     }
+
+    /// Write the variable part of `element`, if any.
+    pub fn write_variable<T: Serializable>(&mut self, element: &T) -> Result<(), SerializeError> {
+        if T::is_variable_size() {
+            self.written += element.serialize_into(self.sink)?;
+        }
+        Ok(())
+    }
+
+    /// Return the number of bytes written, checking that it is the total length of the encoding.
+    ///
+    /// NOTE: offsets are computed from the given lengths, so an encoding of another length is
+    /// corrupt, e.g. if the `encoded_len` of an element disagrees with its encoding.
+    pub fn finish(self) -> Result<usize, SerializeError> {
+        if self.written != self.total_len {
+            let (expected, written) = (self.total_len, self.written);
+            return Err(SerializeError::EncodedLenMismatch { expected, written })
+        }
+        Ok(self.written)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{
+        Bitlist, Bitvector, CachedList, CachedVector, Deserialize, DeserializeError, List,
+        PersistentList, PersistentVector, ProgressiveList, SszTypeInfo, Vector,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_serialize_into_writer() {
+        let value = Vector::<List<u16, 8>, 3>::try_from(vec![
+            List::try_from(vec![1u16, 2]).unwrap(),
+            List::default(),
+            List::try_from(vec![3u16]).unwrap(),
+        ])
+        .unwrap();
+        let expected = crate::serialize(&value).unwrap();
+        assert_eq!(value.encoded_len(), expected.len());
+
+        let mut buffer = [0u8; 18];
+        let mut writer = &mut buffer[..];
+        let written = value.serialize_into(&mut writer).unwrap();
+        assert_eq!(written, expected.len());
+        assert_eq!(&buffer[..], &expected[..]);
+
+        let mut buffer = [0u8; 17];
+        let mut writer = &mut buffer[..];
+        let result = value.serialize_into(&mut writer);
+        assert!(matches!(result, Err(SerializeError::Io(..))));
+    }
+
+    #[test]
+    fn test_serialize_reserves_exact_len() {
+        type Nested = Vector<Option<List<List<u16, 64>, 4>>, 3>;
+        let value = Nested::try_from(vec![
+            Some(List::try_from(vec![List::try_from(vec![7u16; 40]).unwrap()]).unwrap()),
+            None,
+            Some(List::default()),
+        ])
+        .unwrap();
+        let bits = Bitvector::<72>::default();
+        let mut marked_bits = Bitlist::<200>::default();
+        marked_bits.resize(150, true);
+
+        // NOTE: each buffer is grown once, to exactly the encoded length
+        let mut buffer = Vec::new();
+        value.serialize(&mut buffer).unwrap();
+        assert_eq!((buffer.len(), buffer.capacity()), (value.encoded_len(), value.encoded_len()));
+        let encoding = buffer;
+        for buffer in [crate::serialize(&bits).unwrap(), crate::serialize(&marked_bits).unwrap()] {
+            assert_eq!(buffer.capacity(), buffer.len());
+        }
+        let mut streamed = vec![];
+        marked_bits.serialize_into(&mut streamed).unwrap();
+        assert_eq!(streamed, crate::serialize(&marked_bits).unwrap());
+
+        let dynamic_value = Nested::type_info().deserialize(&encoding).unwrap();
+        let mut buffer = Vec::new();
+        dynamic_value.serialize(&mut buffer).unwrap();
+        assert_eq!(buffer, encoding);
+        assert_eq!(buffer.capacity(), buffer.len());
+    }
+
+    // A sink recording the length of each write.
+    #[derive(Default)]
+    struct RecordingSink {
+        bytes: Vec<u8>,
+        writes: Vec<usize>,
+    }
+
+    impl std::io::Write for RecordingSink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.bytes.extend_from_slice(buf);
+            self.writes.push(buf.len());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // Check that `value` is written in parts rather than buffered, as by the default
+    // `Serialize::serialize_into`.
+    fn assert_streamed<T: Serialize>(value: &T) {
+        let mut encoding = vec![];
+        value.serialize(&mut encoding).unwrap();
+        let mut sink = RecordingSink::default();
+        assert_eq!(value.serialize_into(&mut sink).unwrap(), encoding.len());
+        assert_eq!(sink.bytes, encoding);
+        assert!(sink.writes.iter().all(|&len| len < encoding.len()), "{:?}", sink.writes);
+    }
+
+    #[test]
+    fn test_composite_types_override_serialize_into() {
+        let elements = vec![List::<u16, 4>::try_from(vec![1u16, 2]).unwrap(), List::default()];
+        assert_streamed(&Vector::<_, 2>::try_from(elements.clone()).unwrap());
+        assert_streamed(&List::<_, 3>::try_from(elements.clone()).unwrap());
+        assert_streamed(&[elements[0].clone(), elements[1].clone()]);
+        assert_streamed(&ProgressiveList::try_from(elements.clone()).unwrap());
+        assert_streamed(&PersistentVector::<_, 2>::try_from(elements.clone()).unwrap());
+        assert_streamed(&PersistentList::<_, 3>::try_from(elements.clone()).unwrap());
+        assert_streamed(&CachedVector::<_, 2>::try_from(elements.clone()).unwrap());
+        assert_streamed(&CachedList::<_, 3>::try_from(elements.clone()).unwrap());
+        assert_streamed(&Some(elements[0].clone()));
+
+        let value = List::<_, 3>::try_from(elements).unwrap();
+        let dynamic_value =
+            List::<List<u16, 4>, 3>::type_info().deserialize(&crate::serialize(&value).unwrap());
+        assert_streamed(&dynamic_value.unwrap());
+    }
+
+    static ENCODED_LEN_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    // A variable-size value counting how often its encoded length is computed.
+    #[derive(Debug, Default)]
+    struct Probe;
+
+    impl Serializable for Probe {
+        fn is_variable_size() -> bool {
+            true
+        }
+
+        fn size_hint() -> usize {
+            0
+        }
+    }
+
+    impl Serialize for Probe {
+        fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+            buffer.push(0xAB);
+            Ok(1)
+        }
+
+        fn encoded_len(&self) -> usize {
+            ENCODED_LEN_CALLS.fetch_add(1, Ordering::Relaxed);
+            1
+        }
+    }
+
+    impl Deserialize for Probe {
+        fn deserialize(_: &[u8]) -> Result<Self, DeserializeError> {
+            Ok(Self)
+        }
+    }
+
+    #[test]
+    fn test_serialize_computes_each_len_once_per_level() {
+        let inner = || List::<Probe, 4>::try_from(vec![Probe, Probe, Probe]).unwrap();
+        let value = Vector::<List<Probe, 4>, 2>::try_from(vec![inner(), inner()]).unwrap();
+        let encoding = crate::serialize(&value).unwrap();
+        assert_eq!(encoding.len(), 2 * (4 + 3 * (4 + 1)));
+        // NOTE: once to reserve the buffer, then once by each collection holding the probe
+        assert_eq!(ENCODED_LEN_CALLS.load(Ordering::Relaxed), 6 * 3);
+    }
+
+    // A variable-size value with an incorrect encoded length.
+    #[derive(Debug, Default)]
+    struct Misreported;
+
+    impl Serializable for Misreported {
+        fn is_variable_size() -> bool {
+            true
+        }
+
+        fn size_hint() -> usize {
+            0
+        }
+    }
+
+    impl Serialize for Misreported {
+        fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+            buffer.extend_from_slice(&[0xCD; 2]);
+            Ok(2)
+        }
+
+        fn encoded_len(&self) -> usize {
+            3
+        }
+    }
+
+    impl Deserialize for Misreported {
+        fn deserialize(_: &[u8]) -> Result<Self, DeserializeError> {
+            Ok(Self)
+        }
+    }
+
+    // A variable-size value with the default encoded length.
+    #[derive(Debug, Default)]
+    struct Unreported;
+
+    impl Serializable for Unreported {
+        fn is_variable_size() -> bool {
+            true
+        }
+
+        fn size_hint() -> usize {
+            0
+        }
+    }
+
+    impl Serialize for Unreported {
+        fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+            buffer.extend_from_slice(&[0xCD; 2]);
+            Ok(2)
+        }
+    }
+
+    impl Deserialize for Unreported {
+        fn deserialize(_: &[u8]) -> Result<Self, DeserializeError> {
+            Ok(Self)
+        }
+    }
+
+    #[test]
+    fn test_serialize_checks_encoded_lens() {
+        assert_eq!(Unreported.encoded_len(), 2);
+        let value = List::<Unreported, 4>::try_from(vec![Unreported, Unreported]).unwrap();
+        let expected = [8, 0, 0, 0, 10, 0, 0, 0, 0xCD, 0xCD, 0xCD, 0xCD];
+        assert_eq!(crate::serialize(&value).unwrap(), expected);
+
+        // NOTE: the offsets would point past the end of the encoding
+        let value = List::<Misreported, 4>::try_from(vec![Misreported]).unwrap();
+        let result = crate::serialize(&value);
+        assert!(matches!(
+            result,
+            Err(SerializeError::EncodedLenMismatch { expected: 7, written: 6 })
+        ));
+    }
 }
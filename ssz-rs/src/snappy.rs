@@ -0,0 +1,421 @@
+//! Snappy-compressed SSZ encodings, as exchanged by consensus clients over libp2p.
+//!
+//! Gossip messages are raw snappy blocks of an encoding, with `encode_block` and `decode_block`.
+//! Req/resp payloads are `ssz_snappy`: the length of the encoding as an unsigned varint followed by
+//! the encoding in snappy frames, with `encode_framed`, `write_framed`, `decode_framed` and
+//! `read_framed`, e.g.:
+//!
+//! ```
+//! # use ssz_rs::prelude::*;
+//! # use ssz_rs::snappy;
+//! let value = List::<u64, 16>::try_from(vec![1, 2, 3]).unwrap();
+//! let payload = snappy::encode_framed(&value).unwrap();
+//! assert_eq!(payload[0], 24);
+//! assert_eq!(snappy::decode_framed::<List<u64, 16>>(&payload).unwrap(), value);
+//! assert!(snappy::decode_framed::<List<u64, 2>>(&payload).is_err());
+//! ```
+//!
+//! Decoding rejects any length outside the bounds of the encodings of the type before
+//! decompressing, and only ever reads as many bytes as the length prefix allows.
+//!
+//! For types only known at runtime, `compress_block`, `decompress_block`, `compress_framed` and
+//! `decompress_framed` work on encodings directly, with the bounds given by the `SszType`.
+use crate::{
+    de::DeserializeError,
+    lib::*,
+    ser::{Serialize, SerializeError},
+    Serializable,
+};
+use snap::{
+    raw::{decompress_len, Decoder, Encoder},
+    read::FrameDecoder,
+    write::FrameEncoder,
+};
+use std::io::{self, Read, Write};
+
+// Maximum number of bytes in the encoding of a `u64` as a varint.
+const MAX_VARINT_LEN: usize = 10;
+// Maximum number of uncompressed bytes in a snappy frame.
+const MAX_FRAME_LEN: usize = 1 << 16;
+// Number of bytes in the stream identifier of snappy frames.
+const STREAM_IDENTIFIER_LEN: usize = 10;
+// Number of bytes in the header and checksum of a snappy frame.
+const FRAME_HEADER_LEN: usize = 8;
+
+/// An error encoding or decoding a snappy-compressed encoding.
+#[derive(Debug)]
+pub enum SnappyError {
+    /// The value could not be serialized.
+    Serialize(SerializeError),
+    /// The decompressed encoding could not be deserialized.
+    Deserialize(DeserializeError),
+    /// The data is not valid snappy.
+    Snappy(snap::Error),
+    /// The data could not be read or written.
+    Io(io::Error),
+    /// The length of the encoding is outside the bounds of the encodings of the type.
+    InvalidLength { len: usize, min: usize, max: usize },
+    /// The length prefix is not a minimal varint of at most 10 bytes.
+    InvalidLengthPrefix,
+}
+
+impl Display for SnappyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serialize(err) => write!(f, "could not serialize: {err}"),
+            Self::Deserialize(err) => write!(f, "could not deserialize: {err}"),
+            Self::Snappy(err) => write!(f, "invalid snappy data: {err}"),
+            Self::Io(err) => write!(f, "could not read or write snappy data: {err}"),
+            Self::InvalidLength { len, min, max } => write!(
+                f,
+                "encoding of {len} byte(s) is outside of the bounds [{min}, {max}] for this type"
+            ),
+            Self::InvalidLengthPrefix => write!(f, "invalid varint length prefix"),
+        }
+    }
+}
+
+impl std::error::Error for SnappyError {}
+
+impl From<SerializeError> for SnappyError {
+    fn from(err: SerializeError) -> Self {
+        Self::Serialize(err)
+    }
+}
+
+impl From<DeserializeError> for SnappyError {
+    fn from(err: DeserializeError) -> Self {
+        Self::Deserialize(err)
+    }
+}
+
+impl From<snap::Error> for SnappyError {
+    fn from(err: snap::Error) -> Self {
+        Self::Snappy(err)
+    }
+}
+
+// NOTE: the snappy readers and writers surface their errors as `io::Error`s
+impl From<io::Error> for SnappyError {
+    fn from(err: io::Error) -> Self {
+        if err.get_ref().map_or(false, |inner| inner.is::<snap::Error>()) {
+            let inner = err.into_inner().expect("error has an inner error");
+            Self::Snappy(*inner.downcast::<snap::Error>().expect("inner error is a snappy error"))
+        } else {
+            Self::Io(err)
+        }
+    }
+}
+
+/// Compress the encoding of `value` into a raw snappy block.
+pub fn encode_block<T: Serialize>(value: &T) -> Result<Vec<u8>, SnappyError> {
+    let mut encoding = Vec::new();
+    value.serialize(&mut encoding)?;
+    compress_block(&encoding)
+}
+
+/// Decode a value of `T` from a raw snappy block of its encoding.
+pub fn decode_block<T: Serializable>(data: &[u8]) -> Result<T, SnappyError> {
+    let encoding = decompress_block(data, T::min_encoded_len(), T::max_encoded_len())?;
+    Ok(T::deserialize(&encoding)?)
+}
+
+/// Compress `encoding` into a raw snappy block.
+pub fn compress_block(encoding: &[u8]) -> Result<Vec<u8>, SnappyError> {
+    Ok(Encoder::new().compress_vec(encoding)?)
+}
+
+/// Decompress an encoding of between `min` and `max` bytes from a raw snappy block, e.g. with the
+/// bounds of an `SszType`.
+pub fn decompress_block(data: &[u8], min: usize, max: usize) -> Result<Vec<u8>, SnappyError> {
+    check_len(decompress_len(data)?, min, max)?;
+    Ok(Decoder::new().decompress_vec(data)?)
+}
+
+/// Encode `value` as `ssz_snappy`, i.e. the length of its encoding as a varint followed by the
+/// encoding in snappy frames.
+pub fn encode_framed<T: Serialize>(value: &T) -> Result<Vec<u8>, SnappyError> {
+    let mut buffer = Vec::new();
+    write_framed(&mut buffer, value)?;
+    Ok(buffer)
+}
+
+/// Write `value` as `ssz_snappy` to `writer`.
+pub fn write_framed<W: Write, T: Serialize>(writer: W, value: &T) -> Result<(), SnappyError> {
+    let mut encoding = Vec::new();
+    value.serialize(&mut encoding)?;
+    write_framed_encoding(writer, &encoding)
+}
+
+/// Compress `encoding` as `ssz_snappy`.
+pub fn compress_framed(encoding: &[u8]) -> Result<Vec<u8>, SnappyError> {
+    let mut buffer = Vec::new();
+    write_framed_encoding(&mut buffer, encoding)?;
+    Ok(buffer)
+}
+
+/// Decode a value of `T` from exactly the `ssz_snappy` bytes in `data`.
+pub fn decode_framed<T: Serializable>(data: &[u8]) -> Result<T, SnappyError> {
+    let encoding = decompress_framed(data, T::min_encoded_len(), T::max_encoded_len())?;
+    Ok(T::deserialize(&encoding)?)
+}
+
+/// Decompress an encoding of between `min` and `max` bytes from exactly the `ssz_snappy` bytes in
+/// `data`, e.g. with the bounds of an `SszType`.
+pub fn decompress_framed(data: &[u8], min: usize, max: usize) -> Result<Vec<u8>, SnappyError> {
+    let mut reader = data;
+    let encoding = read_framed_encoding(&mut reader, min, max)?;
+    if !reader.is_empty() {
+        let expected = data.len() - reader.len();
+        return Err(DeserializeError::AdditionalInput { provided: data.len(), expected }.into())
+    }
+    Ok(encoding)
+}
+
+/// Read a value of `T` as `ssz_snappy` from `reader`, leaving any bytes after its last frame
+/// unread.
+///
+/// NOTE: at most the maximum compressed length of an encoding of the given length is read, so
+/// padding beyond that is an error.
+pub fn read_framed<T: Serializable, R: Read>(reader: R) -> Result<T, SnappyError> {
+    let encoding = read_framed_encoding(reader, T::min_encoded_len(), T::max_encoded_len())?;
+    Ok(T::deserialize(&encoding)?)
+}
+
+fn write_framed_encoding<W: Write>(mut writer: W, encoding: &[u8]) -> Result<(), SnappyError> {
+    write_varint(&mut writer, encoding.len())?;
+    let mut encoder = FrameEncoder::new(&mut writer);
+    encoder.write_all(encoding)?;
+    encoder.flush()?;
+    Ok(())
+}
+
+fn read_framed_encoding<R: Read>(
+    mut reader: R,
+    min: usize,
+    max: usize,
+) -> Result<Vec<u8>, SnappyError> {
+    let len = read_varint(&mut reader)?;
+    check_len(len, min, max)?;
+    let mut decoder = FrameDecoder::new(reader.take(max_framed_len(len) as u64));
+
+    // NOTE: each read yields the rest of at most one frame, so any decompressed bytes beyond `len`
+    // are detected without reading past the frame that holds the end of the encoding
+    let mut encoding = Vec::new();
+    let mut buffer = vec![0u8; MAX_FRAME_LEN.min(len.saturating_add(1))];
+    while encoding.len() < len {
+        let remaining = len - encoding.len();
+        let available = buffer.len().min(remaining.saturating_add(1));
+        let read = decoder.read(&mut buffer[..available])?;
+        if read == 0 {
+            let provided = encoding.len();
+            return Err(DeserializeError::ExpectedFurtherInput { provided, expected: len }.into())
+        }
+        if read > remaining {
+            let provided = encoding.len() + read;
+            return Err(DeserializeError::AdditionalInput { provided, expected: len }.into())
+        }
+        encoding.extend_from_slice(&buffer[..read]);
+    }
+    Ok(encoding)
+}
+
+fn check_len(len: usize, min: usize, max: usize) -> Result<(), SnappyError> {
+    if len < min || len > max {
+        return Err(SnappyError::InvalidLength { len, min, max })
+    }
+    Ok(())
+}
+
+// Maximum number of bytes in snappy frames of `len` uncompressed bytes, following the bound of
+// snappy on the compressed length of each frame.
+//
+// NOTE: `len` comes from untrusted input and is only bounded by the type, possibly by `usize::MAX`
+fn max_framed_len(len: usize) -> usize {
+    let frame_count = len / MAX_FRAME_LEN + usize::from(len % MAX_FRAME_LEN != 0);
+    let max_compressed_len = len.saturating_add(len / 6);
+    frame_count
+        .saturating_mul(FRAME_HEADER_LEN + 32)
+        .saturating_add(max_compressed_len)
+        .saturating_add(STREAM_IDENTIFIER_LEN)
+}
+
+fn write_varint<W: Write>(writer: &mut W, mut value: usize) -> io::Result<()> {
+    let mut buffer = [0u8; MAX_VARINT_LEN];
+    let mut len = 0;
+    while value >= 0x80 {
+        buffer[len] = (value as u8) | 0x80;
+        value >>= 7;
+        len += 1;
+    }
+    buffer[len] = value as u8;
+    writer.write_all(&buffer[..=len])
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> Result<usize, SnappyError> {
+    let mut value = 0u64;
+    for i in 0..MAX_VARINT_LEN {
+        let mut byte = [0u8];
+        reader.read_exact(&mut byte)?;
+        let byte = byte[0];
+        let bits = (byte & 0x7f) as u64;
+        // NOTE: the last byte of a `u64` only holds its top bit, and a minimal varint does not end
+        // with a zero byte
+        if (i == MAX_VARINT_LEN - 1 && bits > 1) || (i > 0 && byte == 0) {
+            return Err(SnappyError::InvalidLengthPrefix)
+        }
+        value |= bits << (7 * i);
+        if byte & 0x80 == 0 {
+            return usize::try_from(value).map_err(|_| SnappyError::InvalidLengthPrefix)
+        }
+    }
+    Err(SnappyError::InvalidLengthPrefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+    struct Blocks {
+        slot: u64,
+        roots: List<Node, 4096>,
+    }
+
+    fn sample() -> Blocks {
+        let roots = (0..3000u16).map(|i| Node::repeat_byte((i % 7) as u8)).collect::<Vec<_>>();
+        Blocks { slot: 42, roots: roots.try_into().unwrap() }
+    }
+
+    #[test]
+    fn test_block_round_trip() {
+        let value = sample();
+        let data = encode_block(&value).unwrap();
+        assert!(data.len() < serialize(&value).unwrap().len());
+        assert_eq!(decode_block::<Blocks>(&data).unwrap(), value);
+
+        let result = decode_block::<u32>(&encode_block(&7u64).unwrap());
+        assert!(matches!(result, Err(SnappyError::InvalidLength { len: 8, min: 4, max: 4 })));
+        assert!(matches!(decode_block::<u64>(&[8, 1, 2]), Err(SnappyError::Snappy(..))));
+    }
+
+    #[test]
+    fn test_framed_round_trip() {
+        // NOTE: the encoding spans several frames
+        let value = sample();
+        let len = serialize(&value).unwrap().len();
+        assert!(len > MAX_FRAME_LEN);
+        let data = encode_framed(&value).unwrap();
+        let mut prefix = vec![];
+        write_varint(&mut prefix, len).unwrap();
+        assert_eq!(&data[..prefix.len()], prefix);
+        assert_eq!(decode_framed::<Blocks>(&data).unwrap(), value);
+
+        let empty = List::<u8, 4>::default();
+        let data = encode_framed(&empty).unwrap();
+        assert_eq!(data, [0]);
+        assert_eq!(decode_framed::<List<u8, 4>>(&data).unwrap(), empty);
+
+        // NOTE: a reader is left at the start of the next payload
+        let mut stream = encode_framed(&1u16).unwrap();
+        write_framed(&mut stream, &2u16).unwrap();
+        let mut reader = stream.as_slice();
+        assert_eq!(read_framed::<u16, _>(&mut reader).unwrap(), 1);
+        assert_eq!(read_framed::<u16, _>(&mut reader).unwrap(), 2);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_runtime_types() {
+        let value = sample();
+        let encoding = serialize(&value).unwrap();
+        let ty = Blocks::type_info();
+        let (min, max) = (ty.min_encoded_len(), ty.max_encoded_len());
+
+        let data = compress_block(&encoding).unwrap();
+        assert_eq!(data, encode_block(&value).unwrap());
+        assert_eq!(decompress_block(&data, min, max).unwrap(), encoding);
+        let result = decompress_block(&data, 0, 8);
+        assert!(matches!(result, Err(SnappyError::InvalidLength { min: 0, max: 8, .. })));
+
+        let data = compress_framed(&encoding).unwrap();
+        assert_eq!(data, encode_framed(&value).unwrap());
+        assert_eq!(decompress_framed(&data, min, max).unwrap(), encoding);
+        let result = decompress_framed(&data, 0, 8);
+        assert!(matches!(result, Err(SnappyError::InvalidLength { min: 0, max: 8, .. })));
+    }
+
+    #[test]
+    fn test_framed_invalid_lengths() {
+        let data = encode_framed(&sample()).unwrap();
+        let result = decode_framed::<List<Node, 4>>(&data);
+        assert!(matches!(result, Err(SnappyError::InvalidLength { min: 0, max: 128, .. })));
+        let result = decode_framed::<Node>(&encode_framed(&7u64).unwrap());
+        assert!(matches!(result, Err(SnappyError::InvalidLength { len: 8, min: 32, max: 32 })));
+
+        // NOTE: the length prefix disagrees with the frames
+        let mut data = encode_framed(&7u64).unwrap();
+        data[0] = 4;
+        let result = decode_framed::<List<u8, 8>>(&data);
+        assert!(matches!(
+            result,
+            Err(SnappyError::Deserialize(DeserializeError::AdditionalInput { expected: 4, .. }))
+        ));
+        data[0] = 12;
+        let result = decode_framed::<List<u8, 16>>(&data);
+        assert!(matches!(
+            result,
+            Err(SnappyError::Deserialize(DeserializeError::ExpectedFurtherInput {
+                provided: 8,
+                expected: 12
+            }))
+        ));
+
+        let mut data = encode_framed(&7u64).unwrap();
+        data.truncate(data.len() - 1);
+        assert!(matches!(decode_framed::<u64>(&data), Err(SnappyError::Io(..))));
+        let mut data = encode_framed(&7u64).unwrap();
+        data.extend_from_slice(&[7, 0]);
+        let result = decode_framed::<u64>(&data);
+        assert!(matches!(
+            result,
+            Err(SnappyError::Deserialize(DeserializeError::AdditionalInput { .. }))
+        ));
+
+        // NOTE: the largest length prefix is within the unbounded lengths of these types
+        let mut data = vec![0xff; MAX_VARINT_LEN - 1];
+        data.push(0x01);
+        let result = decode_framed::<List<List<u8, { 1 << 62 }>, { 1 << 62 }>>(&data);
+        assert!(matches!(
+            result,
+            Err(SnappyError::Deserialize(DeserializeError::ExpectedFurtherInput {
+                provided: 0,
+                expected: usize::MAX
+            }))
+        ));
+        let result = decode_framed::<ProgressiveList<u8>>(&data);
+        assert!(matches!(
+            result,
+            Err(SnappyError::Deserialize(DeserializeError::ExpectedFurtherInput { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_varints() {
+        for value in [0, 1, 127, 128, 300, u32::MAX as usize, usize::MAX] {
+            let mut buffer = vec![];
+            write_varint(&mut buffer, value).unwrap();
+            assert_eq!(read_varint(&mut buffer.as_slice()).unwrap(), value);
+        }
+        let result = read_varint(&mut [0x80, 0].as_slice());
+        assert!(matches!(result, Err(SnappyError::InvalidLengthPrefix)));
+        let result = read_varint(&mut [0xff; 10].as_slice());
+        assert!(matches!(result, Err(SnappyError::InvalidLengthPrefix)));
+        let mut data = [0xff; 11];
+        data[9] = 0x81;
+        let result = read_varint(&mut data.as_slice());
+        assert!(matches!(result, Err(SnappyError::InvalidLengthPrefix)));
+        assert!(matches!(read_varint(&mut [0x80].as_slice()), Err(SnappyError::Io(..))));
+    }
+}
@@ -0,0 +1,939 @@
+//! The canonical JSON (and YAML) representation of SSZ values used by the consensus specs and the
+//! beacon API.
+//!
+//! The representation differs from the default `serde` implementations of this crate:
+//! - unsigned integers of any width are decimal strings (numbers are also accepted when
+//!   deserializing),
+//! - lists and vectors of bytes are `0x`-prefixed hex strings, like bitfields and roots,
+//! - unions are objects with a `selector` and a `value`, where the value is `null` for `None`.
+//!
+//! Values of any type implementing `SpecSerialize` use this representation when wrapped in a
+//! `Spec`, or in a field annotated with `#[serde(with = "ssz_rs::spec_serde")]`. Deriving
+//! `SimpleSerialize` with the attribute `ssz(serde = "spec")` implements the traits of this module,
+//! along with `serde::Serialize` and `serde::Deserialize` in this representation, for a container
+//! or union.
+//!
+//! A `DynamicValue` is serialized in this representation when wrapped in a `Spec`, and deserialized
+//! given its `SszType` with a `DynamicSeed`.
+use crate::{
+    bitlist::Bitlist,
+    bitvector::Bitvector,
+    byte_list::ByteList,
+    byte_vector::ByteVector,
+    cached_list::CachedList,
+    cached_vector::CachedVector,
+    dynamic::{DynamicValue, SszType},
+    error::InstanceError,
+    lib::*,
+    list::List,
+    merkleization::Node,
+    persistent_list::PersistentList,
+    persistent_vector::PersistentVector,
+    progressive_list::ProgressiveList,
+    uint::U256,
+    vector::Vector,
+    Serializable, Serialize, SimpleSerialize,
+};
+use serde::{
+    de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor},
+    ser::{self, SerializeStruct, Serializer},
+};
+
+/// A type which can be serialized in the representation of the consensus specs.
+pub trait SpecSerialize {
+    /// Serialize this value with `serializer`.
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    /// Serialize a list or vector of `elements` of this type with `serializer`.
+    ///
+    /// By default, this is a sequence of the representations of each element; a sequence of bytes
+    /// is a hex string instead.
+    fn spec_serialize_elements<'a, S, I>(elements: I, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        Self: 'a,
+        S: Serializer,
+        I: IntoIterator<Item = &'a Self>,
+    {
+        serializer.collect_seq(elements.into_iter().map(Spec))
+    }
+}
+
+/// A type which can be deserialized from the representation of the consensus specs.
+pub trait SpecDeserialize: Sized {
+    /// Deserialize a value of this type from `deserializer`.
+    fn spec_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+
+    /// Deserialize the elements of a list or vector of this type from `deserializer`.
+    fn spec_deserialize_elements<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Self>, D::Error> {
+        let elements = <Vec<Spec<Self>> as serde::Deserialize>::deserialize(deserializer)?;
+        Ok(elements.into_iter().map(|element| element.0).collect())
+    }
+}
+
+/// A union which can be deserialized from the representation of the consensus specs, given its
+/// selector.
+pub trait SpecUnion: Sized {
+    /// Deserialize the value of the variant with `selector` from `deserializer`.
+    ///
+    /// The value of a `None` variant is `null`, i.e. a unit.
+    fn spec_deserialize_variant<'de, D: Deserializer<'de>>(
+        selector: u8,
+        deserializer: D,
+    ) -> Result<Self, D::Error>;
+}
+
+/// A wrapper to (de)serialize a value with `serde` in the representation of the consensus specs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Spec<T>(pub T);
+
+impl<T: SpecSerialize> serde::Serialize for Spec<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.spec_serialize(serializer)
+    }
+}
+
+impl<'de, T: SpecDeserialize> serde::Deserialize<'de> for Spec<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::spec_deserialize(deserializer).map(Spec)
+    }
+}
+
+/// Serialize `value` in the representation of the consensus specs, for use as
+/// `#[serde(with = "ssz_rs::spec_serde")]`.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: SpecSerialize + ?Sized,
+    S: Serializer,
+{
+    value.spec_serialize(serializer)
+}
+
+/// Deserialize a value in the representation of the consensus specs, for use as
+/// `#[serde(with = "ssz_rs::spec_serde")]`.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: SpecDeserialize,
+    D: Deserializer<'de>,
+{
+    T::spec_deserialize(deserializer)
+}
+
+/// Serialize the variant of a union with `selector` and the given `value`, which is `&()` for a
+/// `None` variant.
+pub fn serialize_union<S, T>(serializer: S, selector: u8, value: &T) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: serde::Serialize + ?Sized,
+{
+    let mut state = serializer.serialize_struct("Union", 2)?;
+    state.serialize_field("selector", &selector)?;
+    state.serialize_field("value", value)?;
+    state.end()
+}
+
+/// Deserialize a union from an object with a `selector` and a `value`.
+///
+/// NOTE: the value can only be decoded once the selector is known, so `selector` must precede
+/// `value`; `value` can be omitted for a `None` variant.
+pub fn deserialize_union<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: SpecUnion,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_struct("Union", &["selector", "value"], UnionVisitor(PhantomData))
+}
+
+// Return the name of the next field of a map.
+#[doc(hidden)]
+pub fn next_field<'de, A: MapAccess<'de>>(map: &mut A) -> Result<Option<String>, A::Error> {
+    map.next_key()
+}
+
+struct UnionVisitor<T>(PhantomData<fn() -> T>);
+
+impl<'de, T: SpecUnion> Visitor<'de> for UnionVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a union with a `selector` and a `value`")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut selector = None;
+        let mut value = None;
+        while let Some(key) = next_field(&mut map)? {
+            match key.as_str() {
+                "selector" if selector.is_some() => {
+                    return Err(de::Error::duplicate_field("selector"))
+                }
+                "selector" => {
+                    selector = Some(map.next_value::<Spec<u8>>()?.0);
+                }
+                "value" if value.is_some() => return Err(de::Error::duplicate_field("value")),
+                "value" => {
+                    let selector = selector
+                        .ok_or_else(|| de::Error::custom("`selector` must precede `value`"))?;
+                    value = Some(map.next_value_seed(VariantSeed(selector, PhantomData))?);
+                }
+                key => return Err(de::Error::unknown_field(key, &["selector", "value"])),
+            }
+        }
+        match (selector, value) {
+            (_, Some(value)) => Ok(value),
+            (Some(selector), None) => {
+                T::spec_deserialize_variant(selector, de::value::UnitDeserializer::new())
+            }
+            (None, None) => Err(de::Error::missing_field("selector")),
+        }
+    }
+}
+
+struct VariantSeed<T>(u8, PhantomData<fn() -> T>);
+
+impl<'de, T: SpecUnion> DeserializeSeed<'de> for VariantSeed<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        T::spec_deserialize_variant(self.0, deserializer)
+    }
+}
+
+impl<T: SpecSerialize + ?Sized> SpecSerialize for &T {
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).spec_serialize(serializer)
+    }
+}
+
+struct UintVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for UintVisitor<T>
+where
+    T: TryFrom<u64> + FromStr,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a decimal string of a `{}`", any::type_name::<T>())
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+        T::try_from(value).map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        // NOTE: `FromStr` for `U256` also accepts hex strings, so restrict to decimal digits
+        if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+        value.parse().map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+macro_rules! define_spec_uint {
+    ($uint:ty) => {
+        impl SpecSerialize for $uint {
+            fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl SpecDeserialize for $uint {
+            fn spec_deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                deserializer.deserialize_any(UintVisitor(PhantomData))
+            }
+        }
+    };
+}
+
+define_spec_uint!(u16);
+define_spec_uint!(u32);
+define_spec_uint!(u64);
+define_spec_uint!(u128);
+define_spec_uint!(usize);
+define_spec_uint!(U256);
+
+impl SpecSerialize for u8 {
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+
+    fn spec_serialize_elements<'a, S, I>(elements: I, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        I: IntoIterator<Item = &'a Self>,
+    {
+        let bytes = elements.into_iter().copied().collect::<Vec<_>>();
+        alloy_primitives::serde_hex::serialize(bytes, serializer)
+    }
+}
+
+impl SpecDeserialize for u8 {
+    fn spec_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(UintVisitor(PhantomData))
+    }
+
+    fn spec_deserialize_elements<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Self>, D::Error> {
+        alloy_primitives::serde_hex::deserialize(deserializer)
+    }
+}
+
+// NOTE: these types already use the representation of the consensus specs with `serde`
+macro_rules! define_spec_with_serde {
+    ($ty:ty $(, const $n:ident)?) => {
+        impl$(<const $n: usize>)? SpecSerialize for $ty {
+            fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serde::Serialize::serialize(self, serializer)
+            }
+        }
+
+        impl$(<const $n: usize>)? SpecDeserialize for $ty {
+            fn spec_deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                serde::Deserialize::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+define_spec_with_serde!(bool);
+define_spec_with_serde!(Node);
+define_spec_with_serde!(Bitlist<N>, const N);
+define_spec_with_serde!(Bitvector<N>, const N);
+define_spec_with_serde!(ByteList<N>, const N);
+define_spec_with_serde!(ByteVector<N>, const N);
+
+impl<T, const N: usize> SpecSerialize for List<T, N>
+where
+    T: Serializable + SpecSerialize,
+{
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::spec_serialize_elements(self.iter(), serializer)
+    }
+}
+
+impl<T, const N: usize> SpecDeserialize for List<T, N>
+where
+    T: Serializable + SpecDeserialize,
+{
+    fn spec_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let elements = T::spec_deserialize_elements(deserializer)?;
+        Self::try_from(elements).map_err(|(_, err)| de::Error::custom(err))
+    }
+}
+
+impl<T, const N: usize> SpecSerialize for Vector<T, N>
+where
+    T: Serializable + SpecSerialize,
+{
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::spec_serialize_elements(self.iter(), serializer)
+    }
+}
+
+impl<T, const N: usize> SpecDeserialize for Vector<T, N>
+where
+    T: Serializable + SpecDeserialize,
+{
+    fn spec_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let elements = T::spec_deserialize_elements(deserializer)?;
+        Self::try_from(elements).map_err(|(_, err)| de::Error::custom(err))
+    }
+}
+
+impl<T, const N: usize> SpecSerialize for [T; N]
+where
+    T: SpecSerialize,
+{
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::spec_serialize_elements(self.iter(), serializer)
+    }
+}
+
+impl<T, const N: usize> SpecDeserialize for [T; N]
+where
+    T: SpecDeserialize,
+{
+    fn spec_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let elements = T::spec_deserialize_elements(deserializer)?;
+        let len = elements.len();
+        elements.try_into().map_err(|_| de::Error::invalid_length(len, &"an array of `N` elements"))
+    }
+}
+
+impl<T> SpecSerialize for ProgressiveList<T>
+where
+    T: Serializable + SpecSerialize,
+{
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::spec_serialize_elements(self.iter(), serializer)
+    }
+}
+
+impl<T> SpecDeserialize for ProgressiveList<T>
+where
+    T: Serializable + SpecDeserialize,
+{
+    fn spec_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::spec_deserialize_elements(deserializer).map(Self::from)
+    }
+}
+
+impl<T, const N: usize> SpecSerialize for PersistentList<T, N>
+where
+    T: Serializable + SpecSerialize,
+{
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::spec_serialize_elements(self.iter(), serializer)
+    }
+}
+
+impl<T, const N: usize> SpecDeserialize for PersistentList<T, N>
+where
+    T: SimpleSerialize + SpecDeserialize,
+{
+    fn spec_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let elements = T::spec_deserialize_elements(deserializer)?;
+        Self::try_from(elements).map_err(de::Error::custom)
+    }
+}
+
+impl<T, const N: usize> SpecSerialize for PersistentVector<T, N>
+where
+    T: Serializable + SpecSerialize,
+{
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::spec_serialize_elements(self.iter(), serializer)
+    }
+}
+
+impl<T, const N: usize> SpecDeserialize for PersistentVector<T, N>
+where
+    T: SimpleSerialize + SpecDeserialize,
+{
+    fn spec_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let elements = T::spec_deserialize_elements(deserializer)?;
+        Self::try_from(elements).map_err(de::Error::custom)
+    }
+}
+
+impl<T, const N: usize> SpecSerialize for CachedList<T, N>
+where
+    T: Serializable + SpecSerialize,
+{
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::spec_serialize_elements(self.iter(), serializer)
+    }
+}
+
+impl<T, const N: usize> SpecDeserialize for CachedList<T, N>
+where
+    T: SimpleSerialize + SpecDeserialize,
+{
+    fn spec_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        List::<T, N>::spec_deserialize(deserializer).map(Into::into)
+    }
+}
+
+impl<T, const N: usize> SpecSerialize for CachedVector<T, N>
+where
+    T: Serializable + SpecSerialize,
+{
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::spec_serialize_elements(self.iter(), serializer)
+    }
+}
+
+impl<T, const N: usize> SpecDeserialize for CachedVector<T, N>
+where
+    T: SimpleSerialize + SpecDeserialize,
+{
+    fn spec_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vector::<T, N>::spec_deserialize(deserializer).map(Into::into)
+    }
+}
+
+impl<T: SpecSerialize> SpecSerialize for Option<T> {
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            None => serialize_union(serializer, 0, &()),
+            Some(value) => serialize_union(serializer, 1, &Spec(value)),
+        }
+    }
+}
+
+impl<T: SpecDeserialize> SpecDeserialize for Option<T> {
+    fn spec_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_union(deserializer)
+    }
+}
+
+impl<T: SpecDeserialize> SpecUnion for Option<T> {
+    fn spec_deserialize_variant<'de, D: Deserializer<'de>>(
+        selector: u8,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        match selector {
+            0 => <() as serde::Deserialize>::deserialize(deserializer).map(|_| None),
+            1 => T::spec_deserialize(deserializer).map(Some),
+            selector => Err(de::Error::custom(format!("invalid selector {selector}"))),
+        }
+    }
+}
+
+// NOTE: byte collections and bitfields are the hex string of their encoding
+impl SpecSerialize for DynamicValue {
+    fn spec_serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Uint { value, .. } => serializer.collect_str(value),
+            Self::Boolean(value) => serializer.serialize_bool(*value),
+            Self::Vector { element_type, elements } |
+            Self::List { element_type, elements, .. } |
+            Self::ProgressiveList { element_type, elements }
+                if *element_type != SszType::Uint(8) =>
+            {
+                serializer.collect_seq(elements.iter().map(Spec))
+            }
+            Self::Vector { .. } |
+            Self::List { .. } |
+            Self::ProgressiveList { .. } |
+            Self::Bitvector(..) |
+            Self::Bitlist { .. } => {
+                let mut encoding = vec![];
+                self.serialize(&mut encoding).map_err(ser::Error::custom)?;
+                alloy_primitives::serde_hex::serialize(encoding, serializer)
+            }
+            Self::Container(fields) => {
+                serializer.collect_map(fields.iter().map(|(name, value)| (name, Spec(value))))
+            }
+            Self::StableContainer { fields, values, .. } => {
+                let names = fields.iter().map(|(name, _)| name);
+                serializer
+                    .collect_map(names.zip(values.iter().map(|value| value.as_ref().map(Spec))))
+            }
+            Self::Profile { fields, values, .. } => {
+                let names = fields.iter().map(|field| &field.name);
+                serializer
+                    .collect_map(names.zip(values.iter().map(|value| value.as_ref().map(Spec))))
+            }
+            Self::Union { selector, value, .. } => {
+                serialize_union(serializer, *selector, &value.as_deref().map(Spec))
+            }
+        }
+    }
+}
+
+/// Deserialize a `DynamicValue` of the given type from the representation of the consensus specs,
+/// e.g. with `DeserializeSeed::deserialize(DynamicSeed(&ty), deserializer)`.
+///
+/// The lengths of collections and the widths of integers are checked against the type.
+#[derive(Debug, Clone, Copy)]
+pub struct DynamicSeed<'a>(pub &'a SszType);
+
+impl<'de> DeserializeSeed<'de> for DynamicSeed<'_> {
+    type Value = DynamicValue;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<DynamicValue, D::Error> {
+        let value = match self.0 {
+            SszType::Uint(bits) => {
+                let value: U256 = deserializer.deserialize_any(UintVisitor(PhantomData))?;
+                if value.bit_len() > *bits {
+                    let err = InstanceError::Bounded { bound: *bits, provided: value.bit_len() };
+                    return Err(de::Error::custom(err))
+                }
+                DynamicValue::Uint { bits: *bits, value }
+            }
+            SszType::Boolean => {
+                DynamicValue::Boolean(serde::Deserialize::deserialize(deserializer)?)
+            }
+            SszType::Vector(element_type, len) => {
+                let elements = deserialize_elements(element_type, deserializer)?;
+                if elements.len() != *len {
+                    let err = InstanceError::Exact { required: *len, provided: elements.len() };
+                    return Err(de::Error::custom(err))
+                }
+                DynamicValue::Vector { element_type: (**element_type).clone(), elements }
+            }
+            SszType::List(element_type, limit) => {
+                let elements = deserialize_elements(element_type, deserializer)?;
+                if elements.len() > *limit {
+                    let err = InstanceError::Bounded { bound: *limit, provided: elements.len() };
+                    return Err(de::Error::custom(err))
+                }
+                DynamicValue::List {
+                    element_type: (**element_type).clone(),
+                    limit: *limit,
+                    elements,
+                }
+            }
+            SszType::ProgressiveList(element_type) => DynamicValue::ProgressiveList {
+                element_type: (**element_type).clone(),
+                elements: deserialize_elements(element_type, deserializer)?,
+            },
+            ty @ (SszType::Bitvector(..) | SszType::Bitlist(..)) => {
+                let encoding: Vec<u8> = alloy_primitives::serde_hex::deserialize(deserializer)?;
+                ty.deserialize(&encoding).map_err(de::Error::custom)?
+            }
+            ty @ (SszType::Container(..) | SszType::StableContainer(..) | SszType::Profile(..)) => {
+                deserializer.deserialize_map(FieldsVisitor(ty))?
+            }
+            SszType::Union(variants) => {
+                deserializer.deserialize_map(DynamicUnionVisitor(variants))?
+            }
+        };
+        Ok(value)
+    }
+}
+
+fn deserialize_elements<'de, D: Deserializer<'de>>(
+    element_type: &SszType,
+    deserializer: D,
+) -> Result<Vec<DynamicValue>, D::Error> {
+    if *element_type == SszType::Uint(8) {
+        let bytes: Vec<u8> = alloy_primitives::serde_hex::deserialize(deserializer)?;
+        let elements =
+            bytes.into_iter().map(|byte| DynamicValue::Uint { bits: 8, value: U256::from(byte) });
+        return Ok(elements.collect())
+    }
+    deserializer.deserialize_seq(ElementsVisitor(element_type))
+}
+
+struct ElementsVisitor<'a>(&'a SszType);
+
+impl<'de> Visitor<'de> for ElementsVisitor<'_> {
+    type Value = Vec<DynamicValue>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of `{}`", self.0)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(element) = seq.next_element_seed(DynamicSeed(self.0))? {
+            elements.push(element);
+        }
+        Ok(elements)
+    }
+}
+
+// Deserialize an optional field, where `null` is an absent field.
+struct OptionalSeed<'a>(&'a SszType);
+
+impl<'de> DeserializeSeed<'de> for OptionalSeed<'_> {
+    type Value = Option<DynamicValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_option(self)
+    }
+}
+
+impl<'de> Visitor<'de> for OptionalSeed<'_> {
+    type Value = Option<DynamicValue>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an optional `{}`", self.0)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        DynamicSeed(self.0).deserialize(deserializer).map(Some)
+    }
+}
+
+// Deserialize the fields of a `Container`, `StableContainer` or `Profile`.
+struct FieldsVisitor<'a>(&'a SszType);
+
+impl<'de> Visitor<'de> for FieldsVisitor<'_> {
+    type Value = DynamicValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an object of the fields of `{}`", self.0)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        // NOTE: the name and type of each field, and whether it is optional
+        let fields = match self.0 {
+            SszType::Container(fields) => {
+                fields.iter().map(|(name, ty)| (name, ty, false)).collect::<Vec<_>>()
+            }
+            SszType::StableContainer(_, fields) => {
+                fields.iter().map(|(name, ty)| (name, ty, true)).collect()
+            }
+            SszType::Profile(_, fields) => {
+                fields.iter().map(|field| (&field.name, &field.ty, field.optional)).collect()
+            }
+            _ => unreachable!("only visits types with fields"),
+        };
+
+        let mut slots = vec![None; fields.len()];
+        while let Some(key) = next_field(&mut map)? {
+            let index = fields
+                .iter()
+                .position(|(name, ..)| **name == key)
+                .ok_or_else(|| de::Error::custom(format!("unknown field `{key}`")))?;
+            if slots[index].is_some() {
+                return Err(de::Error::custom(format!("duplicate field `{key}`")))
+            }
+            let (_, ty, is_optional) = fields[index];
+            slots[index] = Some(if is_optional {
+                map.next_value_seed(OptionalSeed(ty))?
+            } else {
+                map.next_value_seed(DynamicSeed(ty)).map(Some)?
+            });
+        }
+
+        let mut values = Vec::with_capacity(fields.len());
+        for ((name, _, is_optional), slot) in fields.iter().zip(slots) {
+            match slot.flatten() {
+                None if !is_optional => {
+                    return Err(de::Error::custom(format!("missing field `{name}`")))
+                }
+                value => values.push(value),
+            }
+        }
+        let value = match self.0 {
+            SszType::Container(fields) => DynamicValue::Container(
+                fields
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .zip(values.into_iter().flatten())
+                    .collect(),
+            ),
+            SszType::StableContainer(max_fields, fields) => DynamicValue::StableContainer {
+                max_fields: *max_fields,
+                fields: fields.clone(),
+                values,
+            },
+            SszType::Profile(max_fields, fields) => {
+                DynamicValue::Profile { max_fields: *max_fields, fields: fields.clone(), values }
+            }
+            _ => unreachable!("only visits types with fields"),
+        };
+        Ok(value)
+    }
+}
+
+// Deserialize a union like `UnionVisitor`, with the variants of an `SszType::Union`.
+struct DynamicUnionVisitor<'a>(&'a [(u8, Option<SszType>)]);
+
+impl DynamicUnionVisitor<'_> {
+    fn variant<E: de::Error>(&self, selector: u8) -> Result<Option<&SszType>, E> {
+        self.0
+            .iter()
+            .find(|(variant_selector, _)| *variant_selector == selector)
+            .map(|(_, variant_type)| variant_type.as_ref())
+            .ok_or_else(|| E::custom(format!("invalid selector {selector}")))
+    }
+}
+
+impl<'de> Visitor<'de> for DynamicUnionVisitor<'_> {
+    type Value = DynamicValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a union with a `selector` and a `value`")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut selector = None;
+        let mut value = None;
+        while let Some(key) = next_field(&mut map)? {
+            match key.as_str() {
+                "selector" if selector.is_some() => {
+                    return Err(de::Error::duplicate_field("selector"))
+                }
+                "selector" => {
+                    selector = Some(map.next_value::<Spec<u8>>()?.0);
+                }
+                "value" if value.is_some() => return Err(de::Error::duplicate_field("value")),
+                "value" => {
+                    let selector = selector
+                        .ok_or_else(|| de::Error::custom("`selector` must precede `value`"))?;
+                    value = Some(match self.variant(selector)? {
+                        Some(variant_type) => Some(map.next_value_seed(DynamicSeed(variant_type))?),
+                        None => map.next_value::<()>().map(|_| None)?,
+                    });
+                }
+                key => return Err(de::Error::unknown_field(key, &["selector", "value"])),
+            }
+        }
+        let selector = selector.ok_or_else(|| de::Error::missing_field("selector"))?;
+        let value = match (value, self.variant(selector)?) {
+            (Some(value), _) => value,
+            (None, None) => None,
+            (None, Some(_)) => return Err(de::Error::missing_field("value")),
+        };
+        Ok(DynamicValue::Union { variants: self.0.to_vec(), selector, value: value.map(Box::new) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_basic_types() {
+        assert_eq!(serde_json::to_value(Spec(12u8)).unwrap(), json!("12"));
+        assert_eq!(serde_json::to_value(Spec(u64::MAX)).unwrap(), json!("18446744073709551615"));
+        let value = U256::from(1u8) << 200usize;
+        let json = serde_json::to_value(Spec(value)).unwrap();
+        assert_eq!(json, json!(value.to_string()));
+        assert_eq!(serde_json::from_value::<Spec<U256>>(json).unwrap().0, value);
+        assert_eq!(serde_json::to_value(Spec(true)).unwrap(), json!(true));
+
+        assert_eq!(serde_json::from_value::<Spec<u64>>(json!("42")).unwrap().0, 42);
+        assert_eq!(serde_json::from_value::<Spec<u64>>(json!(42)).unwrap().0, 42);
+        assert!(serde_json::from_value::<Spec<u8>>(json!("256")).is_err());
+        assert!(serde_json::from_value::<Spec<u8>>(json!(256)).is_err());
+        assert!(serde_json::from_value::<Spec<u64>>(json!("0x2a")).is_err());
+        assert!(serde_json::from_value::<Spec<U256>>(json!("0x2a")).is_err());
+        assert!(serde_json::from_value::<Spec<u64>>(json!("")).is_err());
+        assert!(serde_json::from_value::<Spec<u64>>(json!(-1)).is_err());
+    }
+
+    #[test]
+    fn test_collections() {
+        let bytes = List::<u8, 8>::try_from(vec![0xde, 0xad]).unwrap();
+        assert_eq!(serde_json::to_value(Spec(&bytes)).unwrap(), json!("0xdead"));
+        let recovered = serde_json::from_value::<Spec<List<u8, 8>>>(json!("0xdead")).unwrap();
+        assert_eq!(recovered.0, bytes);
+        assert!(serde_json::from_value::<Spec<List<u8, 1>>>(json!("0xdead")).is_err());
+
+        let root = Vector::<u8, 4>::try_from(vec![1, 2, 3, 4]).unwrap();
+        assert_eq!(serde_json::to_value(Spec(&root)).unwrap(), json!("0x01020304"));
+        assert!(serde_json::from_value::<Spec<Vector<u8, 4>>>(json!("0x010203")).is_err());
+        assert_eq!(serde_json::to_value(Spec([7u8; 2])).unwrap(), json!("0x0707"));
+        assert_eq!(
+            serde_json::to_value(Spec(Node::repeat_byte(1))).unwrap(),
+            json!(format!("0x{}", "01".repeat(32)))
+        );
+
+        let numbers = List::<u64, 8>::try_from(vec![1, 2]).unwrap();
+        let json = serde_json::to_value(Spec(&numbers)).unwrap();
+        assert_eq!(json, json!(["1", "2"]));
+        assert_eq!(serde_json::from_value::<Spec<List<u64, 8>>>(json).unwrap().0, numbers);
+        assert_eq!(
+            serde_json::from_value::<Spec<PersistentList<u64, 8>>>(json!(["1", 2])).unwrap().0,
+            PersistentList::try_from(vec![1, 2]).unwrap()
+        );
+
+        let nested =
+            List::<List<u8, 4>, 2>::try_from(vec![List::try_from(vec![1]).unwrap()]).unwrap();
+        let json = serde_json::to_value(Spec(&nested)).unwrap();
+        assert_eq!(json, json!(["0x01"]));
+        assert_eq!(serde_json::from_value::<Spec<List<List<u8, 4>, 2>>>(json).unwrap().0, nested);
+
+        let bits = Bitlist::<8>::try_from([true, false, true].as_ref()).unwrap();
+        assert_eq!(serde_json::to_value(Spec(&bits)).unwrap(), json!("0x0d"));
+    }
+
+    #[test]
+    fn test_unions() {
+        let json = serde_json::to_value(Spec(Some(3u16))).unwrap();
+        assert_eq!(json, json!({"selector": 1, "value": "3"}));
+        assert_eq!(serde_json::from_value::<Spec<Option<u16>>>(json).unwrap().0, Some(3));
+
+        let json = serde_json::to_value(Spec(None::<u16>)).unwrap();
+        assert_eq!(json, json!({"selector": 0, "value": null}));
+        assert_eq!(serde_json::from_value::<Spec<Option<u16>>>(json).unwrap().0, None);
+        let value = serde_json::from_value::<Spec<Option<u16>>>(json!({"selector": 0}));
+        assert_eq!(value.unwrap().0, None);
+
+        let value: Spec<Option<u16>> =
+            serde_json::from_str(r#"{"selector": "1", "value": "3"}"#).unwrap();
+        assert_eq!(value.0, Some(3));
+        let invalid = [
+            r#"{"value": "3", "selector": 1}"#,
+            r#"{"selector": 1}"#,
+            r#"{"selector": 2, "value": "3"}"#,
+            r#"{"selector": 0, "value": "3"}"#,
+            r#"{"selector": 1, "value": "3", "other": 1}"#,
+            r#"{"selector": 1, "selector": 1, "value": "3"}"#,
+        ];
+        for json in invalid {
+            assert!(serde_json::from_str::<Spec<Option<u16>>>(json).is_err(), "{json}");
+        }
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+    #[ssz(stable_container(max_fields = 4), serde = "spec")]
+    struct Shape {
+        side: Option<u16>,
+        color: Option<u8>,
+        radius: Option<u16>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+    #[ssz(profile(Shape), serde = "spec")]
+    struct Circle {
+        color: Option<u8>,
+        radius: u16,
+    }
+
+    #[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+    #[ssz(serde = "spec")]
+    struct Sample {
+        slot: u64,
+        data: List<u8, 8>,
+        bits: Bitlist<8>,
+        pair: Vector<u16, 2>,
+        extra: Option<U256>,
+        shape: Shape,
+        circle: Circle,
+    }
+
+    #[test]
+    fn test_dynamic_values() {
+        let value = Sample {
+            slot: 32,
+            data: List::try_from(vec![0xde, 0xad]).unwrap(),
+            bits: Bitlist::try_from([true, false, true].as_ref()).unwrap(),
+            pair: Vector::try_from(vec![1, 2]).unwrap(),
+            extra: Some(U256::from(1u8) << 200usize),
+            shape: Shape { side: Some(3), color: None, radius: None },
+            circle: Circle { color: None, radius: 4 },
+        };
+        let ty = Sample::type_info();
+        let dynamic = ty.deserialize(&crate::serialize(&value).unwrap()).unwrap();
+
+        let json = serde_json::to_value(Spec(&dynamic)).unwrap();
+        assert_eq!(json, serde_json::to_value(&value).unwrap());
+        assert_eq!(DynamicSeed(&ty).deserialize(json.clone()).unwrap(), dynamic);
+
+        let mut without_optional = json.clone();
+        without_optional["shape"].as_object_mut().unwrap().remove("color");
+        assert_eq!(DynamicSeed(&ty).deserialize(without_optional).unwrap(), dynamic);
+
+        let invalid = [
+            ("slot", json!("18446744073709551616")),
+            ("data", json!("0x000102030405060708")),
+            ("bits", json!("0x00")),
+            ("pair", json!(["1"])),
+            ("extra", json!({"selector": 2, "value": "1"})),
+            ("shape", json!({"side": "3", "other": null})),
+            ("circle", json!({"color": null, "radius": null})),
+        ];
+        for (field, field_value) in invalid {
+            let mut json = json.clone();
+            json[field] = field_value;
+            assert!(DynamicSeed(&ty).deserialize(json).is_err(), "{field}");
+        }
+        let mut missing = json;
+        missing.as_object_mut().unwrap().remove("slot");
+        assert!(DynamicSeed(&ty).deserialize(missing).is_err());
+    }
+}
@@ -0,0 +1,281 @@
+//! Support for the `StableContainer` and `Profile` types of [EIP-7495][eip].
+//!
+//! [eip]: https://eips.ethereum.org/EIPS/eip-7495
+use crate::{
+    lib::*,
+    merkleization::{
+        merkleize_with, mix_in_aux, pack_bytes, Hasher, MerkleizationError, Node, BITS_PER_CHUNK,
+        BYTES_PER_CHUNK,
+    },
+};
+
+/// A container with capacity for `MAX_FIELDS` optional fields, where the generalized index of
+/// each field is stable as fields are added or removed over time, i.e. the `StableContainer[N]` of
+/// [EIP-7495](https://eips.ethereum.org/EIPS/eip-7495).
+///
+/// Implemented for structs deriving `SimpleSerialize` with `#[ssz(stable_container(max_fields =
+/// N))]`, where every field is an `Option`:
+///
+/// ```
+/// # use ssz_rs::prelude::*;
+/// #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+/// #[ssz(stable_container(max_fields = 4))]
+/// struct Shape {
+///     side: Option<u16>,
+///     color: Option<u8>,
+///     radius: Option<u16>,
+/// }
+///
+/// let shape = Shape { side: Some(0x42), color: Some(1), radius: None };
+/// assert_eq!(serialize(&shape).unwrap(), [0b011, 0x42, 0x00, 0x01]);
+/// ```
+pub trait StableContainer {
+    /// The maximum number of fields `N` of this `StableContainer[N]`.
+    const MAX_FIELDS: usize;
+}
+
+/// A container holding a subset of the fields of the `StableContainer` `Self::Base`, merkleized
+/// at the same generalized indices as in `Self::Base`, i.e. the `Profile[B]` of
+/// [EIP-7495](https://eips.ethereum.org/EIPS/eip-7495).
+///
+/// Implemented for structs deriving `SimpleSerialize` with `#[ssz(profile(Base))]`, where each
+/// field is a field of `Base` with the same name, in the same order. Fields that are `Option`s are
+/// optional in the profile and the remaining fields are required:
+///
+/// ```
+/// # use ssz_rs::prelude::*;
+/// # #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+/// # #[ssz(stable_container(max_fields = 4))]
+/// # struct Shape {
+/// #     side: Option<u16>,
+/// #     color: Option<u8>,
+/// #     radius: Option<u16>,
+/// # }
+/// #[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+/// #[ssz(profile(Shape))]
+/// struct Circle {
+///     color: Option<u8>,
+///     radius: u16,
+/// }
+///
+/// let circle = Circle { color: Some(1), radius: 0x42 };
+/// let shape = Shape { side: None, color: Some(1), radius: Some(0x42) };
+/// assert_eq!(circle.hash_tree_root().unwrap(), shape.hash_tree_root().unwrap());
+/// ```
+pub trait Profile {
+    /// The `StableContainer` this type is a profile of.
+    type Base: StableContainer;
+}
+
+// Return the chunks of a `StableContainer` with capacity for `max_fields` fields, given the roots
+// of its active `fields` by index.
+pub fn active_field_chunks(fields: &[(usize, Node)], max_fields: usize) -> Vec<u8> {
+    let mut chunks = vec![0u8; max_fields * BYTES_PER_CHUNK];
+    for (index, root) in fields {
+        chunks[index * BYTES_PER_CHUNK..(index + 1) * BYTES_PER_CHUNK]
+            .copy_from_slice(root.as_ref());
+    }
+    chunks
+}
+
+// Return the root of the `active_fields` bitvector of a `StableContainer` with capacity for
+// `max_fields` fields, given the `indices` of its active fields.
+pub fn active_fields_root<H: Hasher>(
+    indices: impl IntoIterator<Item = usize>,
+    max_fields: usize,
+) -> Result<Node, MerkleizationError> {
+    let mut active_fields = vec![0u8; (max_fields + 7) / 8];
+    for index in indices {
+        active_fields[index / 8] |= 1 << (index % 8);
+    }
+    pack_bytes(&mut active_fields);
+    let chunk_count = (max_fields + BITS_PER_CHUNK - 1) / BITS_PER_CHUNK;
+    merkleize_with::<H>(&active_fields, Some(chunk_count))
+}
+
+// Return the root of a `StableContainer` with capacity for `max_fields` fields, or of a `Profile`
+// of it, given the roots of its active `fields` by index.
+pub fn merkleize_active_fields<H: Hasher>(
+    fields: &[(usize, Node)],
+    max_fields: usize,
+) -> Result<Node, MerkleizationError> {
+    let chunks = active_field_chunks(fields, max_fields);
+    let root = merkleize_with::<H>(&chunks, Some(max_fields))?;
+    let indices = fields.iter().map(|(index, _)| *index);
+    Ok(mix_in_aux::<H>(root, active_fields_root::<H>(indices, max_fields)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{prelude::*, proofs::tests::compute_and_verify_proof_for_path};
+    use alloy_primitives::hex::{self, FromHex};
+
+    #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+    #[ssz(stable_container(max_fields = 4))]
+    struct Shape {
+        side: Option<u16>,
+        color: Option<u8>,
+        radius: Option<u16>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+    #[ssz(profile(Shape))]
+    struct Square {
+        side: u16,
+        color: u8,
+    }
+
+    #[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+    #[ssz(profile(Shape))]
+    struct Circle {
+        color: u8,
+        radius: u16,
+    }
+
+    #[derive(Debug, PartialEq, Eq, SimpleSerialize)]
+    #[ssz(profile(Shape))]
+    struct Disc {
+        color: Option<u8>,
+        radius: u16,
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, SimpleSerialize)]
+    #[ssz(stable_container(max_fields = 8))]
+    struct Block {
+        slot: Option<u64>,
+        transactions: Option<List<List<u8, 16>, 4>>,
+        proposer: Option<u32>,
+    }
+
+    fn root(hex: &str) -> Node {
+        Node::from_hex(hex).unwrap()
+    }
+
+    fn assert_encoding<T: SimpleSerialize + PartialEq + core::fmt::Debug>(value: &T, hex: &str) {
+        let encoding = serialize(value).unwrap();
+        assert_eq!(hex::encode(&encoding), hex);
+        assert_eq!(value.encoded_len(), encoding.len());
+        let mut streamed = vec![];
+        value.serialize_into(&mut streamed).unwrap();
+        assert_eq!(streamed, encoding);
+        assert_eq!(&T::deserialize(&encoding).unwrap(), value);
+        let root = value.hash_tree_root().unwrap();
+        assert_eq!(hash_tree_root_from_bytes::<T>(&encoding).unwrap(), root);
+    }
+
+    #[test]
+    fn test_stable_container() {
+        let shape = Shape { side: Some(0x42), color: Some(1), radius: None };
+        assert_encoding(&shape, "03420001");
+        let expected = root("bfdb6fda9d02805e640c0f5767b8d1bb9ff4211498a5e2d7c0f36e1b88ce57ff");
+        assert_eq!(shape.hash_tree_root().unwrap(), expected);
+
+        let shape = Shape { side: None, color: Some(1), radius: Some(0x42) };
+        assert_encoding(&shape, "06014200");
+        let expected = root("f66d2c38c8d2afbd409e86c529dff728e9a4208215ca20ee44e49c3d11e145d8");
+        assert_eq!(shape.hash_tree_root().unwrap(), expected);
+
+        let shape = Shape { side: Some(0x42), color: None, radius: None };
+        assert_encoding(&shape, "014200");
+        let expected = root("7ee06d29b02f4ec2f778a7c5404f9c033d0695633d858521c1b533d6692225f2");
+        assert_eq!(shape.hash_tree_root().unwrap(), expected);
+
+        assert_encoding(&Shape::default(), "00");
+        assert!(Shape::is_variable_size());
+        assert_eq!(<Shape as StableContainer>::MAX_FIELDS, 4);
+    }
+
+    #[test]
+    fn test_stable_container_with_variable_size_fields() {
+        let transactions = vec![List::try_from(vec![1, 2]).unwrap(), List::default()];
+        let block = Block {
+            slot: Some(23),
+            transactions: Some(List::try_from(transactions).unwrap()),
+            proposer: Some(5),
+        };
+        assert_encoding(&block, "0717000000000000001000000005000000080000000a0000000102");
+        let block = Block { proposer: None, ..block };
+        assert_encoding(&block, "0317000000000000000c000000080000000a0000000102");
+        assert_encoding(&Block { slot: Some(1), ..Default::default() }, "010100000000000000");
+    }
+
+    #[test]
+    fn test_profile() {
+        let square = Square { side: 0x42, color: 1 };
+        assert_encoding(&square, "420001");
+        let shape = Shape { side: Some(0x42), color: Some(1), radius: None };
+        assert_eq!(square.hash_tree_root().unwrap(), shape.hash_tree_root().unwrap());
+        assert!(!Square::is_variable_size());
+        assert_eq!(Square::size_hint(), 3);
+
+        let circle = Circle { color: 1, radius: 0x42 };
+        assert_encoding(&circle, "014200");
+        let shape = Shape { side: None, color: Some(1), radius: Some(0x42) };
+        assert_eq!(circle.hash_tree_root().unwrap(), shape.hash_tree_root().unwrap());
+
+        let disc = Disc { color: Some(1), radius: 0x42 };
+        assert_encoding(&disc, "01014200");
+        assert_eq!(disc.hash_tree_root().unwrap(), circle.hash_tree_root().unwrap());
+        let disc = Disc { color: None, radius: 0x42 };
+        assert_encoding(&disc, "004200");
+        let shape = Shape { side: None, color: None, radius: Some(0x42) };
+        assert_eq!(disc.hash_tree_root().unwrap(), shape.hash_tree_root().unwrap());
+        assert!(Disc::is_variable_size());
+    }
+
+    #[test]
+    fn test_invalid_encodings() {
+        // the field at index 3 is not defined
+        assert!(Shape::deserialize(&hex::decode("0b420001").unwrap()).is_err());
+        // the bitvector has length 4
+        assert!(Shape::deserialize(&hex::decode("13420001").unwrap()).is_err());
+        assert!(Shape::deserialize(&[]).is_err());
+        assert!(Shape::deserialize(&hex::decode("034200").unwrap()).is_err());
+        assert!(Shape::deserialize(&hex::decode("0342000100").unwrap()).is_err());
+        assert!(Disc::deserialize(&hex::decode("02014200").unwrap()).is_err());
+        assert!(Square::deserialize(&hex::decode("4200").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_stable_generalized_indices() {
+        for path in [&["side".into()][..], &["color".into()], &["radius".into()]] {
+            let index = Shape::generalized_index(path).unwrap();
+            if path != [PathElement::from("side")] {
+                assert_eq!(Circle::generalized_index(path).unwrap(), index);
+                assert_eq!(Disc::generalized_index(path).unwrap(), index);
+            }
+            if path != [PathElement::from("radius")] {
+                assert_eq!(Square::generalized_index(path).unwrap(), index);
+            }
+        }
+        assert_eq!(Shape::generalized_index(&["radius".into()]).unwrap(), 10);
+        assert_eq!(Block::generalized_index(&["transactions".into(), 1.into()]).unwrap(), 137);
+        assert!(Square::generalized_index(&["radius".into()]).is_err());
+    }
+
+    #[test]
+    fn test_prove_stable_container() {
+        let shape = Shape { side: None, color: Some(1), radius: Some(0x42) };
+        for field in ["side", "color", "radius"] {
+            compute_and_verify_proof_for_path(&shape, &[field.into()]);
+        }
+        let circle = Circle { color: 1, radius: 0x42 };
+        for field in ["color", "radius"] {
+            compute_and_verify_proof_for_path(&circle, &[field.into()]);
+            assert_eq!(
+                circle.prove(&[field.into()]).unwrap(),
+                shape.prove(&[field.into()]).unwrap()
+            );
+        }
+
+        let transactions = vec![List::try_from(vec![1, 2]).unwrap(), List::default()];
+        let block = Block {
+            slot: Some(23),
+            transactions: Some(List::try_from(transactions).unwrap()),
+            proposer: None,
+        };
+        compute_and_verify_proof_for_path(&block, &["transactions".into(), 0.into(), 1.into()]);
+        compute_and_verify_proof_for_path(&block, &["proposer".into()]);
+        assert!(Block::default().prove(&["transactions".into(), 0.into()]).is_err());
+    }
+}
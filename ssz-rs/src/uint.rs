@@ -1,11 +1,13 @@
 use crate::{
     de::{Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
     lib::*,
     merkleization::{
-        pack_bytes, proofs::Prove, GeneralizedIndexable, HashTreeRoot, MerkleizationError, Node,
-        BYTES_PER_CHUNK,
+        pack_bytes, proofs::Prove, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError,
+        Node, BYTES_PER_CHUNK,
     },
-    ser::{Serialize, SerializeError},
+    ser::{Serialize, SerializeError, Sink},
+    view::Viewable,
     Serializable, SimpleSerialize, BITS_PER_BYTE,
 };
 
@@ -31,6 +33,18 @@ macro_rules! define_uint {
                 buffer.extend_from_slice(&self.to_le_bytes());
                 Ok(bits_to_bytes(<$uint>::BITS))
             }
+
+            fn serialize_into<S: Sink + ?Sized>(
+                &self,
+                sink: &mut S,
+            ) -> Result<usize, SerializeError> {
+                sink.write_bytes(&self.to_le_bytes())?;
+                Ok(bits_to_bytes(<$uint>::BITS))
+            }
+
+            fn encoded_len(&self) -> usize {
+                bits_to_bytes(<$uint>::BITS)
+            }
         }
 
         impl Deserialize for $uint {
@@ -56,8 +70,8 @@ macro_rules! define_uint {
         }
 
         impl HashTreeRoot for $uint {
-            fn hash_tree_root(&self) -> Result<Node, MerkleizationError> {
-                let root = self.chunks()?;
+            fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+                let root = self.chunks::<H>()?;
                 Ok(root.as_slice().try_into().expect("is valid root"))
             }
 
@@ -73,7 +87,7 @@ macro_rules! define_uint {
         }
 
         impl Prove for $uint {
-            fn chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
+            fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
                 let mut root = Vec::with_capacity(BYTES_PER_CHUNK);
                 let _ = self.serialize(&mut root)?;
                 pack_bytes(&mut root);
@@ -81,7 +95,21 @@ macro_rules! define_uint {
             }
         }
 
+        impl Viewable for $uint {
+            type View<'a> = Self;
+
+            fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+                Self::deserialize(encoding)
+            }
+        }
+
         impl SimpleSerialize for $uint {}
+
+        impl SszTypeInfo for $uint {
+            fn type_info() -> SszType {
+                SszType::Uint(<$uint>::BITS as usize)
+            }
+        }
     };
 }
 
@@ -112,6 +140,15 @@ impl Serialize for U256 {
         buffer.extend_from_slice(self.as_le_slice());
         Ok(Self::size_hint())
     }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        sink.write_bytes(self.as_le_slice())?;
+        Ok(Self::size_hint())
+    }
+
+    fn encoded_len(&self) -> usize {
+        Self::size_hint()
+    }
 }
 
 impl Deserialize for U256 {
@@ -137,8 +174,8 @@ impl Deserialize for U256 {
 }
 
 impl HashTreeRoot for U256 {
-    fn hash_tree_root(&self) -> Result<Node, MerkleizationError> {
-        let chunks = self.chunks()?;
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        let chunks = self.chunks::<H>()?;
         Ok(Node::try_from(chunks.as_slice()).expect("is right size"))
     }
 
@@ -154,13 +191,27 @@ impl GeneralizedIndexable for U256 {
 }
 
 impl Prove for U256 {
-    fn chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
         Ok(self.as_le_bytes().to_vec())
     }
 }
 
+impl Viewable for U256 {
+    type View<'a> = Self;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        Self::deserialize(encoding)
+    }
+}
+
 impl SimpleSerialize for U256 {}
 
+impl SszTypeInfo for U256 {
+    fn type_info() -> SszType {
+        SszType::Uint(256)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -279,6 +330,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn test_serde() {
         let x = U256::from(23);
         let x_str = serde_json::to_string(&x).unwrap();
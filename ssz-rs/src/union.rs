@@ -1,16 +1,22 @@
 use crate::{
     de::{Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
+    error::Error,
     lib::*,
     merkleization::{
-        mix_in_selector,
+        mix_in_selector, mix_in_selector_with,
         proofs::{Prove, Prover},
-        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, MerkleizationError, Node, Path,
-        PathElement, BYTES_PER_CHUNK,
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, PathElement, BYTES_PER_CHUNK,
     },
-    ser::{Serialize, SerializeError},
+    ser::{serialize_reserved, Serialize, SerializeError, Sink},
+    view::Viewable,
     Serializable, SimpleSerialize,
 };
 
+#[cfg(feature = "std")]
+use crate::de::{read_none_value, read_selector, Reader};
+
 /// `SimpleSerialize` is implemented for `Option` as a convenience
 /// when the schema is equivalent to one described by:
 /// enum Option<T: SimpleSerialize> {
@@ -26,6 +32,14 @@ impl<T: Serializable> Serializable for Option<T> {
     fn size_hint() -> usize {
         0
     }
+
+    fn min_encoded_len() -> usize {
+        1
+    }
+
+    fn max_encoded_len() -> usize {
+        T::max_encoded_len().saturating_add(1)
+    }
 }
 
 impl<T> Serialize for Option<T>
@@ -33,13 +47,24 @@ where
     T: Serializable,
 {
     fn serialize(&self, buffer: &mut Vec<u8>) -> Result<usize, SerializeError> {
+        serialize_reserved(self, buffer)
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
         match self {
             Some(data) => {
-                let selector_bytes = 1u8.serialize(buffer)?;
-                let value_bytes = data.serialize(buffer)?;
+                let selector_bytes = 1u8.serialize_into(sink)?;
+                let value_bytes = data.serialize_into(sink)?;
                 Ok(selector_bytes + value_bytes)
             }
-            None => 0u8.serialize(buffer),
+            None => 0u8.serialize_into(sink),
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        match self {
+            Some(data) => 1 + data.encoded_len(),
+            None => 1,
         }
     }
 }
@@ -66,23 +91,39 @@ where
             }
             1 => {
                 // SAFETY: index is safe because encoding is not empty; qed
-                let inner = T::deserialize(&encoding[1..])?;
+                let inner = T::deserialize(&encoding[1..]).map_err(|err| err.at(1))?;
                 Ok(Some(inner))
             }
             b => Err(DeserializeError::InvalidByte(b)),
         }
     }
+
+    #[cfg(feature = "std")]
+    fn deserialize_reader<R: std::io::Read>(
+        reader: &mut Reader<R>,
+        len: Option<usize>,
+    ) -> Result<Self, DeserializeError> {
+        let (selector, len) = read_selector(reader, len)?;
+        match selector {
+            0 => {
+                read_none_value(reader, len)?;
+                Ok(None)
+            }
+            1 => T::deserialize_reader(reader, len).map(Some).map_err(|err| err.at(1)),
+            b => Err(DeserializeError::InvalidByte(b)),
+        }
+    }
 }
 
 impl<T> HashTreeRoot for Option<T>
 where
     T: SimpleSerialize,
 {
-    fn hash_tree_root(&self) -> Result<Node, MerkleizationError> {
-        let chunks = Node::try_from(self.chunks()?.as_slice()).expect("is correct size");
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        let chunks = Node::try_from(self.chunks::<H>()?.as_slice()).expect("is correct size");
         match self {
-            Some(_) => Ok(mix_in_selector(chunks, 1)),
-            None => Ok(mix_in_selector(chunks, 0)),
+            Some(_) => Ok(mix_in_selector_with::<H>(chunks, 1)),
+            None => Ok(mix_in_selector_with::<H>(chunks, 0)),
         }
     }
 }
@@ -133,17 +174,21 @@ impl<T> Prove for Option<T>
 where
     T: SimpleSerialize,
 {
-    fn chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
         match self {
             Some(value) => {
-                let root = value.hash_tree_root()?;
+                let root = value.hash_tree_root_with::<H>()?;
                 Ok(root.to_vec())
             }
             None => Ok(vec![0u8; BYTES_PER_CHUNK]),
         }
     }
 
-    fn prove_element(&self, index: usize, prover: &mut Prover) -> Result<(), MerkleizationError> {
+    fn prove_element<H: Hasher>(
+        &self,
+        index: usize,
+        prover: &mut Prover<H>,
+    ) -> Result<(), MerkleizationError> {
         if index >= 2 {
             Err(MerkleizationError::InvalidInnerIndex)
         } else {
@@ -165,7 +210,74 @@ where
     }
 }
 
-impl<T> SimpleSerialize for Option<T> where T: SimpleSerialize {}
+impl<T> Viewable for Option<T>
+where
+    T: Viewable,
+{
+    type View<'a> = Option<T::View<'a>>;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        if encoding.is_empty() {
+            return Err(DeserializeError::ExpectedFurtherInput { provided: 0, expected: 1 })
+        }
+
+        // SAFETY: index is safe because encoding is not empty; qed
+        match encoding[0] {
+            0 => {
+                if encoding.len() != 1 {
+                    return Err(DeserializeError::AdditionalInput {
+                        provided: encoding.len(),
+                        expected: 1,
+                    })
+                }
+                Ok(None)
+            }
+            // SAFETY: index is safe because encoding is not empty; qed
+            1 => T::view(&encoding[1..]).map(Some),
+            b => Err(DeserializeError::InvalidByte(b)),
+        }
+    }
+}
+
+impl<T> SimpleSerialize for Option<T>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<Node, Error> {
+        if encoding.is_empty() {
+            return Err(DeserializeError::ExpectedFurtherInput { provided: 0, expected: 1 }.into())
+        }
+
+        // SAFETY: index is safe because encoding is not empty; qed
+        match encoding[0] {
+            0 => {
+                if encoding.len() != 1 {
+                    return Err(DeserializeError::AdditionalInput {
+                        provided: encoding.len(),
+                        expected: 1,
+                    }
+                    .into())
+                }
+                Ok(mix_in_selector(Node::default(), 0))
+            }
+            1 => {
+                // SAFETY: index is safe because encoding is not empty; qed
+                let root = T::hash_tree_root_from_bytes(&encoding[1..])?;
+                Ok(mix_in_selector(root, 1))
+            }
+            b => Err(DeserializeError::InvalidByte(b).into()),
+        }
+    }
+}
+
+impl<T> SszTypeInfo for Option<T>
+where
+    T: SszTypeInfo,
+{
+    fn type_info() -> SszType {
+        SszType::Union(vec![(0, None), (1, Some(T::type_info()))])
+    }
+}
 
 #[cfg(test)]
 mod tests {
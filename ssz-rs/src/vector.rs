@@ -1,17 +1,27 @@
 use crate::{
     de::{deserialize_homogeneous_composite, Deserialize, DeserializeError},
+    dynamic::{SszType, SszTypeInfo},
     error::{Error, InstanceError, TypeError},
     lib::*,
     merkleization::{
-        elements_to_chunks, get_power_of_two_ceil, merkleize, pack,
+        elements_to_chunks, get_power_of_two_ceil, merkleize, merkleize_with, pack,
         proofs::{Prove, Prover},
-        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, MerkleizationError, Node, Path,
-        PathElement,
+        GeneralizedIndex, GeneralizedIndexable, HashTreeRoot, Hasher, MerkleizationError, Node,
+        Path, PathElement,
     },
-    ser::{Serialize, SerializeError, Serializer},
+    ser::{
+        homogeneous_encoded_len, max_part_len, min_part_len, serialize_homogeneous_into,
+        serialize_reserved, Serialize, SerializeError, Sink,
+    },
+    view::VectorView,
     Serializable, SimpleSerialize,
 };
 
+#[cfg(feature = "std")]
+use crate::de::{deserialize_homogeneous_composite_from_reader, Reader};
+#[cfg(feature = "rayon")]
+use crate::merkleization::{elements_to_chunks_in_parallel, ParallelHashTreeRoot};
+
 /// A homogenous collection of a fixed number of values.
 ///
 /// NOTE: a `Vector` of length `0` is illegal.
@@ -156,6 +166,14 @@ where
     fn size_hint() -> usize {
         T::size_hint() * N
     }
+
+    fn min_encoded_len() -> usize {
+        N.saturating_mul(min_part_len::<T>())
+    }
+
+    fn max_encoded_len() -> usize {
+        N.saturating_mul(max_part_len::<T>())
+    }
 }
 
 impl<T, const N: usize> Serialize for Vector<T, N>
@@ -166,11 +184,18 @@ where
         if N == 0 {
             return Err(TypeError::InvalidBound(N).into())
         }
-        let mut serializer = Serializer::default();
-        for element in &self.data {
-            serializer.with_element(element)?;
+        serialize_reserved(self, buffer)
+    }
+
+    fn serialize_into<S: Sink + ?Sized>(&self, sink: &mut S) -> Result<usize, SerializeError> {
+        if N == 0 {
+            return Err(TypeError::InvalidBound(N).into())
         }
-        serializer.serialize(buffer)
+        serialize_homogeneous_into(self.data.iter(), sink)
+    }
+
+    fn encoded_len(&self) -> usize {
+        homogeneous_encoded_len(self.data.iter())
     }
 }
 
@@ -205,24 +230,77 @@ where
             _ => unreachable!("no other error variant can be returned at this point"),
         })
     }
+
+    #[cfg(feature = "std")]
+    fn deserialize_reader<R: std::io::Read>(
+        reader: &mut Reader<R>,
+        len: Option<usize>,
+    ) -> Result<Self, DeserializeError> {
+        if N == 0 {
+            return Err(TypeError::InvalidBound(N).into())
+        }
+        let expected_length = N * T::size_hint();
+        if !T::is_variable_size() {
+            if let Some(len) = len {
+                if len < expected_length {
+                    return Err(DeserializeError::ExpectedFurtherInput {
+                        provided: len,
+                        expected: expected_length,
+                    })
+                }
+                if len > expected_length {
+                    return Err(DeserializeError::AdditionalInput {
+                        provided: len,
+                        expected: expected_length,
+                    })
+                }
+            }
+        }
+        let inner = deserialize_homogeneous_composite_from_reader(reader, len, N).map_err(
+            |err| match err {
+                DeserializeError::InvalidInstance(InstanceError::Bounded { provided, .. }) => {
+                    if T::is_variable_size() {
+                        InstanceError::Exact { required: N, provided }.into()
+                    } else {
+                        DeserializeError::AdditionalInput {
+                            provided: provided * T::size_hint(),
+                            expected: expected_length,
+                        }
+                    }
+                }
+                err => err,
+            },
+        )?;
+        if !T::is_variable_size() && inner.len() < N {
+            return Err(DeserializeError::ExpectedFurtherInput {
+                provided: inner.len() * T::size_hint(),
+                expected: expected_length,
+            })
+        }
+        inner.try_into().map_err(|(_, err)| match err {
+            Error::Deserialize(err) => err,
+            Error::Instance(err) => DeserializeError::InvalidInstance(err),
+            Error::Type(err) => DeserializeError::InvalidType(err),
+            _ => unreachable!("no other error variant can be returned at this point"),
+        })
+    }
 }
 
 impl<T, const N: usize> Vector<T, N>
 where
     T: SimpleSerialize,
 {
-    fn assemble_chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
+    fn assemble_chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
         if T::is_composite_type() {
-            let count = self.len();
-            elements_to_chunks(self.data.iter().enumerate(), count)
+            elements_to_chunks::<H, _>(&self.data)
         } else {
             pack(&self.data)
         }
     }
 
-    fn compute_hash_tree_root(&self) -> Result<Node, MerkleizationError> {
-        let chunks = self.assemble_chunks()?;
-        merkleize(&chunks, None)
+    fn compute_hash_tree_root<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        let chunks = self.assemble_chunks::<H>()?;
+        merkleize_with::<H>(&chunks, None)
     }
 }
 
@@ -230,8 +308,22 @@ impl<T, const N: usize> HashTreeRoot for Vector<T, N>
 where
     T: SimpleSerialize,
 {
-    fn hash_tree_root(&self) -> Result<Node, MerkleizationError> {
-        self.compute_hash_tree_root()
+    fn hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        self.compute_hash_tree_root::<H>()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T, const N: usize> ParallelHashTreeRoot for Vector<T, N>
+where
+    T: SimpleSerialize + Sync,
+{
+    fn par_hash_tree_root_with<H: Hasher>(&self) -> Result<Node, MerkleizationError> {
+        if !T::is_composite_type() {
+            return self.compute_hash_tree_root::<H>()
+        }
+        let chunks = elements_to_chunks_in_parallel::<H, _>(&self.data)?;
+        merkleize_with::<H>(&chunks, None)
     }
 }
 
@@ -270,11 +362,15 @@ impl<T, const N: usize> Prove for Vector<T, N>
 where
     T: SimpleSerialize,
 {
-    fn chunks(&self) -> Result<Vec<u8>, MerkleizationError> {
-        self.assemble_chunks()
+    fn chunks<H: Hasher>(&self) -> Result<Vec<u8>, MerkleizationError> {
+        self.assemble_chunks::<H>()
     }
 
-    fn prove_element(&self, index: usize, prover: &mut Prover) -> Result<(), MerkleizationError> {
+    fn prove_element<H: Hasher>(
+        &self,
+        index: usize,
+        prover: &mut Prover<H>,
+    ) -> Result<(), MerkleizationError> {
         if index >= N {
             Err(MerkleizationError::InvalidInnerIndex)
         } else {
@@ -284,7 +380,24 @@ where
     }
 }
 
-impl<T, const N: usize> SimpleSerialize for Vector<T, N> where T: SimpleSerialize {}
+impl<T, const N: usize> SimpleSerialize for Vector<T, N>
+where
+    T: SimpleSerialize,
+{
+    fn hash_tree_root_from_bytes(encoding: &[u8]) -> Result<Node, Error> {
+        let chunks = VectorView::<T, N>::new(encoding)?.chunks()?;
+        Ok(merkleize(&chunks, None)?)
+    }
+}
+
+impl<T, const N: usize> SszTypeInfo for Vector<T, N>
+where
+    T: Serializable + SszTypeInfo,
+{
+    fn type_info() -> SszType {
+        SszType::Vector(Box::new(T::type_info()), N)
+    }
+}
 
 #[cfg(feature = "serde")]
 struct VectorVisitor<T: Serializable>(PhantomData<Vec<T>>);
@@ -437,6 +550,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn test_serde() {
         type V = Vector<u8, 4>;
         let data = vec![1u8, 0, 22, 33];
@@ -447,6 +561,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     #[should_panic]
     fn test_illegal_serde() {
         type V = Vector<u8, 4>;
@@ -0,0 +1,433 @@
+use crate::{
+    de::{Deserialize, DeserializeError},
+    error::{Error, InstanceError, TypeError},
+    lib::*,
+    list::List,
+    merkleization::{pack_bytes, BYTES_PER_CHUNK},
+    ser::BYTES_PER_LENGTH_OFFSET,
+    vector::Vector,
+    Serializable, SimpleSerialize,
+};
+
+/// A type which can be read directly from its SSZ encoding without decoding it entirely.
+///
+/// Constructing a view validates the layout of the encoding (e.g. the offsets of a container or
+/// list) following the same rules as `Deserialize`, but the contents of any inner elements are
+/// only validated once they are read from the view.
+///
+/// Basic types (and other types which are cheap to decode, like unions) are their own view.
+pub trait Viewable: Serializable + Sized {
+    /// The view over an encoding of this type.
+    type View<'a>;
+
+    /// Validate the layout of `encoding` and return a view over it.
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError>;
+}
+
+// The validated layout of a homogeneous sequence of `T` within some encoding.
+struct Elements<'a, T> {
+    encoding: &'a [u8],
+    len: usize,
+    _element: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> Clone for Elements<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Elements<'a, T> {}
+
+impl<'a, T: Serializable> Elements<'a, T> {
+    fn parse(encoding: &'a [u8]) -> Result<Self, DeserializeError> {
+        let len = if T::is_variable_size() {
+            Self::validate_offsets(encoding)?
+        } else {
+            let remainder = encoding.len() % T::size_hint();
+            if remainder != 0 {
+                return Err(DeserializeError::AdditionalInput {
+                    provided: encoding.len(),
+                    // SAFETY: checked subtraction is unnecessary, as encoding.len() > remainder;
+                    // qed
+                    expected: encoding.len() - remainder,
+                })
+            }
+            encoding.len() / T::size_hint()
+        };
+        Ok(Self { encoding, len, _element: PhantomData })
+    }
+
+    // Validates the offsets of variable-size elements, returning the number of elements.
+    fn validate_offsets(encoding: &[u8]) -> Result<usize, DeserializeError> {
+        if encoding.is_empty() {
+            return Ok(0)
+        }
+
+        let offsets_len = encoding.get(..BYTES_PER_LENGTH_OFFSET).ok_or({
+            DeserializeError::ExpectedFurtherInput {
+                provided: encoding.len(),
+                expected: BYTES_PER_LENGTH_OFFSET,
+            }
+        })?;
+        let offsets_len = u32::deserialize(offsets_len)? as usize;
+        if encoding.len() < offsets_len {
+            return Err(DeserializeError::ExpectedFurtherInput {
+                provided: encoding.len(),
+                expected: offsets_len,
+            })
+        }
+        if offsets_len % BYTES_PER_LENGTH_OFFSET != 0 {
+            return Err(DeserializeError::InvalidOffsetsLength(offsets_len))
+        }
+
+        let len = offsets_len / BYTES_PER_LENGTH_OFFSET;
+        let mut start = read_offset(encoding, 0);
+        for index in 1..=len {
+            let end = if index == len { encoding.len() } else { read_offset(encoding, index) };
+            if start > end {
+                return Err(DeserializeError::OffsetNotIncreasing { start, end })
+            }
+            start = end;
+        }
+        Ok(len)
+    }
+
+    fn element(&self, index: usize) -> Option<&'a [u8]> {
+        if index >= self.len {
+            return None
+        }
+        let range = if T::is_variable_size() {
+            let start = read_offset(self.encoding, index);
+            let end = if index + 1 == self.len {
+                self.encoding.len()
+            } else {
+                read_offset(self.encoding, index + 1)
+            };
+            start..end
+        } else {
+            let size = T::size_hint();
+            index * size..(index + 1) * size
+        };
+        // SAFETY: the range was validated when parsing; qed
+        Some(&self.encoding[range])
+    }
+}
+
+impl<'a, T: Viewable> Elements<'a, T> {
+    fn get(&self, index: usize) -> Option<Result<T::View<'a>, DeserializeError>> {
+        self.element(index).map(T::view)
+    }
+
+    fn iter(self) -> impl Iterator<Item = Result<T::View<'a>, DeserializeError>> {
+        (0..self.len).map(move |index| {
+            // SAFETY: index is in bounds; qed
+            T::view(self.element(index).expect("index is in bounds"))
+        })
+    }
+}
+
+impl<'a, T: SimpleSerialize> Elements<'a, T> {
+    // Assembles the Merkle chunks of the elements directly from their encodings.
+    fn chunks(&self) -> Result<Vec<u8>, Error> {
+        if T::is_composite_type() {
+            let mut chunks = vec![0u8; self.len * BYTES_PER_CHUNK];
+            for (index, chunk) in chunks.chunks_exact_mut(BYTES_PER_CHUNK).enumerate() {
+                // SAFETY: index is in bounds; qed
+                let element = self.element(index).expect("index is in bounds");
+                let root = T::hash_tree_root_from_bytes(element)?;
+                chunk.copy_from_slice(root.as_ref());
+            }
+            Ok(chunks)
+        } else {
+            // NOTE: elements are deserialized to ensure the encoding is valid, e.g. for `bool`
+            for index in 0..self.len {
+                // SAFETY: index is in bounds; qed
+                T::deserialize(self.element(index).expect("index is in bounds"))?;
+            }
+            let mut chunks = self.encoding.to_vec();
+            pack_bytes(&mut chunks);
+            Ok(chunks)
+        }
+    }
+}
+
+// Reads the offset at `index` from an encoding whose offsets were already validated.
+fn read_offset(encoding: &[u8], index: usize) -> usize {
+    let start = index * BYTES_PER_LENGTH_OFFSET;
+    let mut bytes = [0u8; BYTES_PER_LENGTH_OFFSET];
+    bytes.copy_from_slice(&encoding[start..start + BYTES_PER_LENGTH_OFFSET]);
+    u32::from_le_bytes(bytes) as usize
+}
+
+/// A view over the SSZ encoding of a `List<T, N>`.
+pub struct ListView<'a, T, const N: usize> {
+    elements: Elements<'a, T>,
+}
+
+impl<'a, T, const N: usize> ListView<'a, T, N>
+where
+    T: Serializable,
+{
+    /// Validate the layout of `encoding` and return a view over it.
+    pub fn new(encoding: &'a [u8]) -> Result<Self, DeserializeError> {
+        let elements = Elements::parse(encoding)?;
+        if elements.len > N {
+            return Err(InstanceError::Bounded { bound: N, provided: elements.len }.into())
+        }
+        Ok(Self { elements })
+    }
+
+    /// Return the number of elements in the list.
+    pub fn len(&self) -> usize {
+        self.elements.len
+    }
+
+    /// Return `true` if the list has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the underlying encoding.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.elements.encoding
+    }
+
+    /// Decode the entire list.
+    pub fn deserialize(&self) -> Result<List<T, N>, DeserializeError> {
+        List::deserialize(self.as_bytes())
+    }
+}
+
+impl<'a, T, const N: usize> ListView<'a, T, N>
+where
+    T: Viewable,
+{
+    /// Return a view of the element at `index`, if it exists.
+    pub fn get(&self, index: usize) -> Option<Result<T::View<'a>, DeserializeError>> {
+        self.elements.get(index)
+    }
+
+    /// Return an iterator over views of the elements of the list.
+    pub fn iter(&self) -> impl Iterator<Item = Result<T::View<'a>, DeserializeError>> {
+        self.elements.iter()
+    }
+}
+
+impl<'a, T, const N: usize> ListView<'a, T, N>
+where
+    T: SimpleSerialize,
+{
+    pub(crate) fn chunks(&self) -> Result<Vec<u8>, Error> {
+        self.elements.chunks()
+    }
+}
+
+impl<'a, T, const N: usize> Clone for ListView<'a, T, N> {
+    fn clone(&self) -> Self {
+        Self { elements: self.elements }
+    }
+}
+
+impl<'a, T, const N: usize> fmt::Debug for ListView<'a, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "ListView<{}, {}>(len={})", any::type_name::<T>(), N, self.elements.len)
+    }
+}
+
+impl<T, const N: usize> Viewable for List<T, N>
+where
+    T: Serializable,
+{
+    type View<'a> = ListView<'a, T, N>;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        ListView::new(encoding)
+    }
+}
+
+/// A view over the SSZ encoding of a `Vector<T, N>`.
+pub struct VectorView<'a, T, const N: usize> {
+    elements: Elements<'a, T>,
+}
+
+impl<'a, T, const N: usize> VectorView<'a, T, N>
+where
+    T: Serializable,
+{
+    /// Validate the layout of `encoding` and return a view over it.
+    pub fn new(encoding: &'a [u8]) -> Result<Self, DeserializeError> {
+        if N == 0 {
+            return Err(TypeError::InvalidBound(N).into())
+        }
+        if !T::is_variable_size() {
+            let expected_length = N * T::size_hint();
+            if encoding.len() < expected_length {
+                return Err(DeserializeError::ExpectedFurtherInput {
+                    provided: encoding.len(),
+                    expected: expected_length,
+                })
+            }
+            if encoding.len() > expected_length {
+                return Err(DeserializeError::AdditionalInput {
+                    provided: encoding.len(),
+                    expected: expected_length,
+                })
+            }
+        }
+        let elements = Elements::parse(encoding)?;
+        if elements.len != N {
+            return Err(InstanceError::Exact { required: N, provided: elements.len }.into())
+        }
+        Ok(Self { elements })
+    }
+
+    /// Return the number of elements in the vector.
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    /// Return `true` if the vector has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the underlying encoding.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.elements.encoding
+    }
+
+    /// Decode the entire vector.
+    pub fn deserialize(&self) -> Result<Vector<T, N>, DeserializeError> {
+        Vector::deserialize(self.as_bytes())
+    }
+}
+
+impl<'a, T, const N: usize> VectorView<'a, T, N>
+where
+    T: Viewable,
+{
+    /// Return a view of the element at `index`, if it exists.
+    pub fn get(&self, index: usize) -> Option<Result<T::View<'a>, DeserializeError>> {
+        self.elements.get(index)
+    }
+
+    /// Return an iterator over views of the elements of the vector.
+    pub fn iter(&self) -> impl Iterator<Item = Result<T::View<'a>, DeserializeError>> {
+        self.elements.iter()
+    }
+}
+
+impl<'a, T, const N: usize> VectorView<'a, T, N>
+where
+    T: SimpleSerialize,
+{
+    pub(crate) fn chunks(&self) -> Result<Vec<u8>, Error> {
+        self.elements.chunks()
+    }
+}
+
+impl<'a, T, const N: usize> Clone for VectorView<'a, T, N> {
+    fn clone(&self) -> Self {
+        Self { elements: self.elements }
+    }
+}
+
+impl<'a, T, const N: usize> fmt::Debug for VectorView<'a, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "VectorView<{}, {}>", any::type_name::<T>(), N)
+    }
+}
+
+impl<T, const N: usize> Viewable for Vector<T, N>
+where
+    T: Serializable,
+{
+    type View<'a> = VectorView<'a, T, N>;
+
+    fn view(encoding: &[u8]) -> Result<Self::View<'_>, DeserializeError> {
+        VectorView::new(encoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{serialize, Bitlist, U256};
+
+    #[test]
+    fn test_list_view_of_basic_type() {
+        let value = List::<u16, 8>::try_from(vec![1u16, 2, 3]).unwrap();
+        let encoding = serialize(&value).unwrap();
+        let view = List::<u16, 8>::view(&encoding).unwrap();
+        assert_eq!(view.len(), 3);
+        assert_eq!(view.get(1).unwrap().unwrap(), 2);
+        assert!(view.get(3).is_none());
+        let elements = view.iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(elements, vec![1, 2, 3]);
+        assert_eq!(view.deserialize().unwrap(), value);
+
+        let result = List::<u16, 2>::view(&encoding);
+        assert!(matches!(
+            result,
+            Err(DeserializeError::InvalidInstance(InstanceError::Bounded {
+                bound: 2,
+                provided: 3
+            }))
+        ));
+        let result = List::<u16, 8>::view(&encoding[..5]);
+        assert!(matches!(result, Err(DeserializeError::AdditionalInput { .. })));
+    }
+
+    #[test]
+    fn test_list_view_of_composite_type() {
+        type Inner = List<U256, 4>;
+        let value = List::<Inner, 3>::try_from(vec![
+            Inner::try_from(vec![U256::from(1)]).unwrap(),
+            Inner::default(),
+            Inner::try_from(vec![U256::from(2), U256::from(3)]).unwrap(),
+        ])
+        .unwrap();
+        let encoding = serialize(&value).unwrap();
+        let view = List::<Inner, 3>::view(&encoding).unwrap();
+        assert_eq!(view.len(), 3);
+        let inner = view.get(2).unwrap().unwrap();
+        assert_eq!(inner.len(), 2);
+        assert_eq!(inner.get(1).unwrap().unwrap(), U256::from(3));
+        assert!(view.get(1).unwrap().unwrap().is_empty());
+        assert_eq!(view.deserialize().unwrap(), value);
+
+        let mut invalid = encoding.clone();
+        // swap the first two offsets
+        invalid[4..8].copy_from_slice(&encoding[..4]);
+        invalid[..4].copy_from_slice(&encoding[4..8]);
+        let result = List::<Inner, 3>::view(&invalid);
+        assert!(matches!(result, Err(DeserializeError::OffsetNotIncreasing { .. })));
+    }
+
+    #[test]
+    fn test_vector_view() {
+        let value = Vector::<Bitlist<8>, 2>::try_from(vec![
+            Bitlist::try_from(&[true, false][..]).unwrap(),
+            Bitlist::try_from(&[false; 7][..]).unwrap(),
+        ])
+        .unwrap();
+        let encoding = serialize(&value).unwrap();
+        let view = Vector::<Bitlist<8>, 2>::view(&encoding).unwrap();
+        assert_eq!(view.len(), 2);
+        assert_eq!(view.get(0).unwrap().unwrap(), value[0]);
+        assert_eq!(view.get(1).unwrap().unwrap(), value[1]);
+        assert_eq!(view.deserialize().unwrap(), value);
+
+        let result = Vector::<Bitlist<8>, 3>::view(&encoding);
+        assert!(matches!(
+            result,
+            Err(DeserializeError::InvalidInstance(InstanceError::Exact {
+                required: 3,
+                provided: 2
+            }))
+        ));
+        let result = Vector::<u32, 2>::view(&[0u8; 7]);
+        assert!(matches!(result, Err(DeserializeError::ExpectedFurtherInput { .. })));
+    }
+}
@@ -6,49 +6,49 @@ use test_utils::{
     deserialize, hash_tree_root, read_ssz_snappy_from_test_data, root_from_hex, serialize,
 };
 
-#[derive(PartialEq, Eq, Debug, Default, SimpleSerialize)]
-struct SingleFieldTestStruct {
-    a: u8,
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct SingleFieldTestStruct {
+    pub a: u8,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct SmallTestStruct {
+    pub a: u16,
+    pub b: u16,
 }
 
-#[derive(PartialEq, Eq, Debug, Default, SimpleSerialize)]
-struct SmallTestStruct {
-    a: u16,
-    b: u16,
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct FixedTestStruct {
+    pub a: u8,
+    pub b: u64,
+    pub c: u32,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
+pub struct VarTestStruct {
+    pub a: u16,
+    pub b: List<u16, 1024>,
+    pub c: u8,
 }
 
 #[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
-struct FixedTestStruct {
-    a: u8,
-    b: u64,
-    c: u32,
+pub struct ComplexTestStruct {
+    pub a: u16,
+    pub b: List<u16, 128>,
+    pub c: u8,
+    pub d: List<u8, 256>,
+    pub e: VarTestStruct,
+    pub f: Vector<FixedTestStruct, 4>,
+    pub g: Vector<VarTestStruct, 2>,
 }
 
 #[derive(PartialEq, Eq, Debug, Default, Clone, SimpleSerialize)]
-struct VarTestStruct {
-    a: u16,
-    b: List<u16, 1024>,
-    c: u8,
-}
-
-#[derive(PartialEq, Eq, Debug, Default, SimpleSerialize)]
-struct ComplexTestStruct {
-    a: u16,
-    b: List<u16, 128>,
-    c: u8,
-    d: List<u8, 256>,
-    e: VarTestStruct,
-    f: Vector<FixedTestStruct, 4>,
-    g: Vector<VarTestStruct, 2>,
-}
-
-#[derive(PartialEq, Eq, Debug, Default, SimpleSerialize)]
-struct BitsStruct {
-    a: Bitlist<5>,
-    b: Bitvector<2>,
-    c: Bitvector<1>,
-    d: Bitlist<6>,
-    e: Bitvector<8>,
+pub struct BitsStruct {
+    pub a: Bitlist<5>,
+    pub b: Bitvector<2>,
+    pub c: Bitvector<1>,
+    pub d: Bitlist<6>,
+    pub e: Bitvector<8>,
 }
 
 #[test]
@@ -7,15 +7,35 @@ pub fn root_from_hex(hex_str: &str) -> Node {
 }
 
 pub fn serialize<T: SimpleSerialize>(value: &T) -> Vec<u8> {
-    ssz_rs::serialize(value).expect("can serialize")
+    let encoding = ssz_rs::serialize(value).expect("can serialize");
+    assert_eq!(value.encoded_len(), encoding.len());
+    let mut streamed = vec![];
+    let _ = value.serialize_into(&mut streamed).expect("can serialize into sink");
+    assert_eq!(streamed, encoding);
+    encoding
 }
 
+#[cfg(feature = "std")]
 pub fn deserialize<T: SimpleSerialize>(encoding: &[u8]) -> T {
-    ssz_rs::deserialize(encoding).expect("can deserialize")
+    let value = ssz_rs::deserialize::<T>(encoding).expect("can deserialize");
+    let streamed = ssz_rs::deserialize_reader::<T, _>(encoding, encoding.len())
+        .expect("can deserialize from reader");
+    assert_eq!(serialize(&streamed), serialize(&value));
+    value
+}
+
+#[cfg(not(feature = "std"))]
+pub fn deserialize<T: SimpleSerialize>(encoding: &[u8]) -> T {
+    ssz_rs::deserialize::<T>(encoding).expect("can deserialize")
 }
 
 pub fn hash_tree_root<T: SimpleSerialize>(value: &T) -> Node {
-    value.hash_tree_root().expect("can compute root")
+    let root = value.hash_tree_root().expect("can compute root");
+    let encoding = serialize(value);
+    let root_from_bytes =
+        ssz_rs::hash_tree_root_from_bytes::<T>(&encoding).expect("can compute root from bytes");
+    assert_eq!(root_from_bytes, root);
+    root
 }
 
 // Return SSZ-encoded bytes from test file at `target_path`